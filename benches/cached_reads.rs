@@ -0,0 +1,45 @@
+//! Times reading random indices through a `ReadHandle` and through a `CachedReadHandle`
+//!
+//! Run with `cargo bench --bench cached_reads`. Without optimizations, such as under `cargo test --all-targets`,
+//! it only reads a few elements to check that it still works
+
+#[cfg(not(loom))]
+fn main() {
+    let (len, reads) = if cfg!(any(debug_assertions, miri)) {
+        (1 << 10, 1 << 12)
+    } else {
+        (1 << 22, 1 << 24)
+    };
+    let (wh, rh) = stele::Stele::new();
+    for n in 0..len {
+        wh.push(n);
+    }
+    let cached = rh.cached();
+    time("ReadHandle", &rh, len, reads);
+    time("CachedReadHandle", &cached, len, reads);
+}
+
+#[cfg(not(loom))]
+fn time(name: &str, reader: &impl stele::SteleReaderCopied<usize>, len: usize, reads: usize) {
+    use std::{hint::black_box, time::Instant};
+
+    let mut state = 0x2545_f491_usize;
+    let mut sum = 0_usize;
+    let start = Instant::now();
+    for _ in 0..reads {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        sum = sum.wrapping_add(reader.get(black_box(state % len)));
+    }
+    let elapsed = start.elapsed();
+    black_box(sum);
+    println!(
+        "{name}: {:.2} ns per read",
+        elapsed.as_secs_f64() * 1e9 / reads as f64
+    );
+}
+
+//loom's atomics only work inside a model
+#[cfg(loom)]
+fn main() {}
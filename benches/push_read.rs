@@ -0,0 +1,75 @@
+//! Times pushing to a `WriteHandle` and reading through a `ReadHandle`, in order and at random indices
+//!
+//! Run with `cargo bench --bench push_read`. Without optimizations, such as under `cargo test --all-targets`,
+//! it only pushes and reads a few elements to check that it still works
+
+#[cfg(not(loom))]
+fn main() {
+    let (len, rounds) = if cfg!(any(debug_assertions, miri)) {
+        (1 << 10, 1)
+    } else {
+        (1 << 22, 10)
+    };
+    //The fastest of several rounds, as every round does the same work
+    let best = |name: &str, mut round: Box<dyn FnMut() -> f64>| {
+        let nanos = (0..rounds).map(|_| round()).fold(f64::INFINITY, f64::min);
+        println!("{name}: {nanos:.2} ns per element");
+    };
+    best(
+        "push",
+        Box::new(move || {
+            let (wh, rh) = stele::Stele::new();
+            let start = std::time::Instant::now();
+            for n in 0..len {
+                wh.push(std::hint::black_box(n));
+            }
+            let elapsed = start.elapsed();
+            assert_eq!(rh.len(), len);
+            elapsed.as_secs_f64() * 1e9 / len as f64
+        }),
+    );
+    let (wh, rh) = stele::Stele::new();
+    for n in 0..len {
+        wh.push(n);
+    }
+    let reader = rh.clone();
+    best(
+        "read in order",
+        Box::new(move || time(&reader, len, (0..len).map(std::hint::black_box))),
+    );
+    best(
+        "read at random",
+        Box::new(move || {
+            let mut state = 0x2545_f491_usize;
+            let indices = (0..len).map(move |_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                std::hint::black_box(state % len)
+            });
+            time(&rh, len, indices)
+        }),
+    );
+}
+
+#[cfg(not(loom))]
+fn time(
+    reader: &stele::ReadHandle<usize>,
+    len: usize,
+    indices: impl Iterator<Item = usize>,
+) -> f64 {
+    use std::{hint::black_box, time::Instant};
+
+    let mut sum = 0_usize;
+    let start = Instant::now();
+    for idx in indices {
+        sum = sum.wrapping_add(*reader.read(idx));
+    }
+    let elapsed = start.elapsed();
+    black_box(sum);
+    elapsed.as_secs_f64() * 1e9 / len as f64
+}
+
+//loom's atomics only work inside a model
+#[cfg(loom)]
+fn main() {}
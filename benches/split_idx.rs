@@ -0,0 +1,47 @@
+//! Times splitting indices into a block and an offset with the lookup table against the saturating formulation
+//! it replaced
+//!
+//! Run with `cargo bench --bench split_idx`. Without optimizations, such as under `cargo test --all-targets`,
+//! it only splits a few indices to check that it still works
+
+use std::{hint::black_box, time::Instant};
+use stele::layout::{block_of, offset_in_block};
+
+fn saturating(idx: usize) -> (usize, usize) {
+    let outer_idx = 32_usize.saturating_sub(
+        (idx.leading_zeros() as usize).saturating_sub(usize::BITS.saturating_sub(32) as usize),
+    );
+    let inner_idx = idx.saturating_sub(1 << (outer_idx.saturating_sub(1)));
+    (outer_idx, inner_idx)
+}
+
+fn time(name: &str, split: impl Fn(usize) -> (usize, usize), splits: usize) {
+    let mut state = 0x2545_f491_usize;
+    let mut sum = 0_usize;
+    let start = Instant::now();
+    for _ in 0..splits {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        //Spread the indices over every block rather than mostly the last ones
+        let idx = state >> (state % 32);
+        let (outer, inner) = split(black_box(idx));
+        sum = sum.wrapping_add(outer).wrapping_add(inner);
+    }
+    let elapsed = start.elapsed();
+    black_box(sum);
+    println!(
+        "{name}: {:.2} ns per split",
+        elapsed.as_secs_f64() * 1e9 / splits as f64
+    );
+}
+
+fn main() {
+    let splits = if cfg!(any(debug_assertions, miri)) {
+        1 << 10
+    } else {
+        1 << 26
+    };
+    time("saturating", saturating, splits);
+    time("table", |idx| (block_of(idx), offset_in_block(idx)), splits);
+}
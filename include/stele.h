@@ -0,0 +1,75 @@
+/*
+ * Read-only access to a Stele exported from Rust with `ReadHandle::as_raw_parts`.
+ *
+ * The structs mirror `stele::ffi::RawBlock` and `stele::ffi::RawParts`. Elements are
+ * addressed through the exported blocks: element `idx` lives in the first block whose
+ * slots, counted from the start of block 0, reach past `idx`. `stele_locate` walks the
+ * blocks to find it, which works whatever growth policy the Stele was built with.
+ *
+ * With the default growth policy the block of an element can also be computed directly:
+ *
+ *   block 0 holds 1 element at index 0
+ *   block n > 0 holds 2^(n-1) elements starting at index 2^(n-1)
+ *
+ *   block(idx)  = idx == 0 ? 0 : floor(log2(idx)) + 1
+ *   offset(idx) = idx == 0 ? 0 : idx - 2^(block(idx) - 1)
+ *
+ * A Stele built with `GrowthPolicy::doubling(e)` multiplies every block size and start
+ * index by 2^e, so shift `idx` right by `e` to find the block and subtract the start of
+ * that block from `idx` to find the offset. `GrowthPolicy::uniform(n)` gives every block
+ * `n` elements, so the block is `idx / n` and the offset `idx % n`.
+ *
+ * Elements below `len` are initialized and never change or move, so they can be read
+ * from any thread without synchronization for as long as the Stele is alive. A Stele
+ * exported after `ReadHandle::leak_raw` stays alive until the process exits.
+ */
+#ifndef STELE_H
+#define STELE_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define STELE_MAX_BLOCKS 32
+
+typedef struct stele_raw_block {
+    /* The first slot of the block, or NULL if the block is not part of the export */
+    const void *ptr;
+    /* The number of slots in the block, or 0 if the block is not part of the export */
+    size_t len;
+} stele_raw_block;
+
+typedef struct stele_raw_parts {
+    /* The number of leading elements that were initialized when the snapshot was taken */
+    size_t len;
+    /* The number of leading entries of `blocks` needed to hold `len` elements */
+    size_t block_count;
+    /* The blocks in order, of which only the first `block_count` are filled in */
+    stele_raw_block blocks[STELE_MAX_BLOCKS];
+} stele_raw_parts;
+
+/*
+ * Returns the address of element `idx` of a Stele whose elements are `elem_size` bytes,
+ * or NULL if `idx` is not below `parts->len`
+ */
+static inline const void *stele_locate(const stele_raw_parts *parts, size_t idx, size_t elem_size) {
+    size_t block;
+    if (idx >= parts->len) {
+        return NULL;
+    }
+    for (block = 0; block < parts->block_count; block++) {
+        if (idx < parts->blocks[block].len) {
+            return (const char *)parts->blocks[block].ptr + idx * elem_size;
+        }
+        idx -= parts->blocks[block].len;
+    }
+    return NULL;
+}
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* STELE_H */
@@ -1,64 +1,439 @@
-use core::{cmp::max, fmt::Debug, marker::PhantomData, ptr::null_mut, sync::atomic::Ordering};
+use core::{
+    cell::UnsafeCell,
+    fmt::Debug,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    panic::{RefUnwindSafe, UnwindSafe},
+    ptr::{null_mut, NonNull},
+    sync::atomic::Ordering,
+};
 extern crate alloc;
+use alloc::{alloc::Layout, boxed::Box, vec::Vec};
 
-use self::{reader::ReadHandle, writer::WriteHandle};
+use self::{
+    builder::{Prealloc, SteleBuilder},
+    iter::{BlocksMut, IterMut, RefIterator},
+    reader::ReadHandle,
+    static_handle::{StaticReadHandle, StaticWriteHandle},
+    writer::WriteHandle,
+};
 use crate::{
-    max_len, split_idx,
-    sync::{Arc, AtomicPtr, AtomicUsize},
+    error::{PushError, SteleError},
+    layout::GrowthPolicy,
+    mem::{
+        initial_blocks, AllocErrorHook, BufferStorage, DefaultStorage, Placement, PlacementHook,
+        RetryOrFail, Storage,
+    },
+    raw::RawStele,
+    sync::{ord, Arc, AtomicBool, AtomicPtr, AtomicUsize, Notify},
     Inner,
 };
+
+///Configure every construction option of a Stele in one place
+pub mod builder;
+///Fill reserved blocks of a Stele from several threads and publish them all at once
+pub mod bulk;
+///A reader that remembers where each block starts, so that random access skips loading the block pointer
+pub mod cached;
+#[cfg(all(feature = "bytemuck", not(any(loom, feature = "loom", shuttle))))]
+mod cast;
+///Flatten a Stele of strings or vectors into a single collection
+pub mod concat;
+#[cfg(feature = "std")]
+mod drain;
+///Read and write the bytes of a Stele asynchronously with tokio's I/O traits
+#[cfg(feature = "tokio-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-io")))]
+pub mod io;
+///A writer that can be shared with interrupt handlers by pushing inside a critical section
+#[cfg(feature = "critical-section")]
+#[cfg_attr(docsrs, doc(cfg(feature = "critical-section")))]
+pub mod isr;
 ///Iterate over a Stele by Reference or by Value (for copy types)
 pub mod iter;
+///Typed indices into a Stele, which keep reading the same element however much it grows
+pub mod key;
+#[cfg(feature = "metrics")]
+mod meter;
 ///Implementation details for [`ReadHandle`]
 pub mod reader;
+#[cfg(feature = "rand")]
+mod sample;
+///SIMD reductions over the blocks of a Stele of numbers, built on the nightly `core::simd`
+#[cfg(feature = "nightly-simd")]
+#[cfg_attr(docsrs, doc(cfg(feature = "nightly-simd")))]
+pub mod simd;
+///Push the items of an asynchronous sink on to a Stele
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+pub mod sink;
+///Reserved elements that are initialized later, possibly on another thread
+pub mod slot;
+///Write straight into the unused slots past the end of a Stele and publish them once they are initialized
+pub mod spare;
+///Handles to a Stele stored in a `static`, which borrow it instead of sharing ownership
+pub mod static_handle;
+///Stream the elements of a Stele as they are pushed
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+pub mod stream;
+///Block or wait asynchronously until an element matching a predicate is pushed
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod wait;
 ///Implementation details for [`WriteHandle`]
 pub mod writer;
 
+//The number of elements whose initialized flags share a word
+const BITS: usize = usize::BITS as usize;
+
+//Set in `Stele::slow_paths` once an element has been reserved, after which blocks may have initialized flags
+const RESERVED: usize = 1;
+//Set in `Stele::slow_paths` once a notifier has been set, which every push has to wake
+const NOTIFIED: usize = 2;
+
+/// A hook called with every element just before it is dropped
+///
+/// The element type is erased, so that the layout of a Stele does not depend on the hook and it can be viewed as a Stele of
+/// a transparent wrapper of its elements. The hook is a boxed closure along with the functions that call and free it
+struct DropHook {
+    hook: NonNull<()>,
+    call: unsafe fn(NonNull<()>, *const ()),
+    free: unsafe fn(NonNull<()>),
+}
+
+//SAFETY: Only ever created from a closure that is Send and Sync
+unsafe impl Send for DropHook {}
+unsafe impl Sync for DropHook {}
+
+impl DropHook {
+    fn new<T, F: Fn(&T) + Send + Sync + 'static>(hook: F) -> Self {
+        /// SAFETY: `hook` must come from `DropHook::new::<T, F>`, and `val` must point to a `T` or a transparent wrapper of one
+        unsafe fn call<T, F: Fn(&T)>(hook: NonNull<()>, val: *const ()) {
+            //SAFETY: By the safety contract the hook is a live `F`, and a transparent wrapper has the same layout as a `T`
+            unsafe { hook.cast::<F>().as_ref()(&*val.cast::<T>()) }
+        }
+        /// SAFETY: `hook` must come from `DropHook::new::<_, F>` and not be used again
+        unsafe fn free<F>(hook: NonNull<()>) {
+            //SAFETY: By the safety contract the hook was leaked from a `Box<F>` and nothing else frees it
+            drop(unsafe { Box::from_raw(hook.cast::<F>().as_ptr()) });
+        }
+        DropHook {
+            hook: NonNull::from(Box::leak(Box::new(hook))).cast(),
+            call: call::<T, F>,
+            free: free::<F>,
+        }
+    }
+
+    /// SAFETY: `val` must be the element type the hook was created for or a transparent wrapper of it
+    unsafe fn call<T>(&self, val: &T) {
+        //SAFETY: The hook is alive until `self` is dropped, and the element is valid by the safety contract
+        unsafe { (self.call)(self.hook, core::ptr::from_ref(val).cast()) }
+    }
+}
+
+impl Drop for DropHook {
+    fn drop(&mut self) {
+        //SAFETY: The hook was created by `new` with the function that frees it, and is not used after this
+        unsafe { (self.free)(self.hook) }
+    }
+}
+
 /// A [`Stele`] is an append-only data structure that allows for zero copying after by having a set of
 /// pointers to power-of-two sized blocks of `T` such that the capacity still doubles each time but
-/// there is no need to copy the old data over.
+/// there is no need to copy the old data over. Other block sizes can be chosen with a [`GrowthPolicy`].
 ///
 /// The trade-off for this is that the [`Stele`] must hold a slot for up to 32
 /// pointers, which does increase the memory footprint.
+///
+/// # Unwind safety
+///
+/// A Stele and every handle to it are [`UnwindSafe`] and [`RefUnwindSafe`] whenever the elements and the storage are,
+/// so they can be used across `std::panic::catch_unwind` without wrapping them in
+/// [`AssertUnwindSafe`](core::panic::AssertUnwindSafe). A push only becomes visible once the length is stored,
+/// so a panic part way through pushing one or more elements leaves every element before it readable and nothing after it
 #[derive(Debug)]
-pub struct Stele<T> {
-    inners: [AtomicPtr<Inner<T>>; 32],
-    len: AtomicUsize,
+//`repr(C)` so that the layout only depends on the layouts of the fields, see `cast.rs`
+#[repr(C)]
+pub struct Stele<T, S: Storage = DefaultStorage> {
+    //The blocks and the number of elements pushed or reserved
+    pub(crate) raw: RawStele<T, S>,
+    //One bit per element for every block that has held a reservation, set once the element is initialized.
+    //Blocks without one have never held a reservation, so every element in them below `len` was pushed
+    filled: [AtomicPtr<AtomicUsize>; 32],
+    //The number of reservations that have not been filled yet
+    pending: AtomicUsize,
+    //A length up to which every element is known to be initialized, which only ever grows until the Stele is recycled
+    initialized: AtomicUsize,
+    //Which optional work pushes and reads have to do, so that without reservations or a notifier they do none of it.
+    //Bits are only set by the writer or with `&mut self`, always before the length that needs them is published,
+    //and only cleared with `&mut self`
+    slow_paths: AtomicUsize,
+    //The most elements the Stele may hold, if it is bounded or its growth policy limits it
+    bound: Option<usize>,
+    //Which blocks the first push allocates
+    prealloc: Prealloc,
+    //Only ever accessed by the writer
+    alloc_error_hook: UnsafeCell<Option<Box<AllocErrorHook>>>,
+    //Only ever accessed by the writer
+    block_placement: UnsafeCell<Option<Box<PlacementHook>>>,
+    //Only ever accessed by the writer, or once no handle is left to read the elements
+    drop_hook: UnsafeCell<Option<DropHook>>,
+    notifier: Option<Box<dyn Notify + Send + Sync>>,
+    //Set while there is no writer, so that blocked readers know nothing more is coming until a reader is promoted
+    closed: AtomicBool,
+    #[cfg(feature = "futures")]
+    wakers: crate::sync::Mutex<stream::WakerSlot>,
+    //The number of registered wakers, so that pushes only take the lock when a stream is waiting
+    #[cfg(feature = "futures")]
+    waiting: AtomicUsize,
+    //Correlates the tracing events of this Stele, or 0 until the first event assigns it one
+    #[cfg(feature = "tracing")]
+    trace_id: AtomicUsize,
+    //Registered when the Stele is built, and left out of those created in a `const` context or rebuilt from existing blocks
+    #[cfg(feature = "metrics")]
+    meter: Option<meter::Meter>,
 }
 
 //SAFETY: If `T` is both `Send` and `Sync`, it is safe to both move the
 //array of inners and hand out references to the contained elements.
-unsafe impl<T> Send for Stele<T> where T: Send + Sync {}
-unsafe impl<T> Sync for Stele<T> where T: Send + Sync {}
+//The storage is moved along with the Stele and shared by every handle, so it must be `Send` and `Sync` respectively
+unsafe impl<T, S: Storage + Send> Send for Stele<T, S> where T: Send + Sync {}
+unsafe impl<T, S: Storage + Sync> Sync for Stele<T, S> where T: Send + Sync {}
 
-impl<T> Stele<T> {
-    //Taken from the standard libraries small vector optimization
-    const INITIAL_SIZE: usize = {
-        match core::mem::size_of::<T>() {
-            1 => 3,
-            2..=1023 => 2,
-            _ => 1,
-        }
-    };
+//A panic never leaves a Stele half-updated. Every push commits its element with the store to the length, so an element written
+//before a panic but not published stays past the end where no reader can see it, and dropping the elements resets the length
+//before running any destructor or drop hook. The hooks and the notifier are only ever called, never replaced part way,
+//so the only state a panic can break is that of the elements and the storage, which is why those are required to be unwind safe.
+//This also makes every handle unwind safe, as they only hold the Stele behind an `Arc` or a shared reference
+impl<T: UnwindSafe, S: Storage + UnwindSafe> UnwindSafe for Stele<T, S> {}
+impl<T: RefUnwindSafe, S: Storage + RefUnwindSafe> RefUnwindSafe for Stele<T, S> {}
 
+impl<T> Stele<T> {
     #[allow(clippy::new_ret_no_self)]
     #[must_use]
     /// Creates a new Stele returns a [`WriteHandle`] and [`ReadHandle`]
     pub fn new() -> (WriteHandle<T>, ReadHandle<T>) {
-        let s = Arc::new(Self {
-            inners: [(); 32].map(|()| crate::sync::AtomicPtr::new(null_mut())),
-            len: AtomicUsize::new(0),
-        });
-        let h = WriteHandle {
-            handle: Arc::clone(&s),
+        Self::new_in(DefaultStorage::default())
+    }
+
+    #[allow(clippy::new_ret_no_self)]
+    #[must_use]
+    /// Creates a new Stele whose first block holds 2<sup>`first_block_exp`</sup> elements and returns a [`WriteHandle`] and [`ReadHandle`]
+    ///
+    /// See [`with_first_block_exp_in`](Stele::with_first_block_exp_in) for details
+    pub fn with_first_block_exp(first_block_exp: u32) -> (WriteHandle<T>, ReadHandle<T>) {
+        Self::with_first_block_exp_in(first_block_exp, DefaultStorage::default())
+    }
+
+    #[allow(clippy::new_ret_no_self)]
+    #[must_use]
+    /// Creates a new Stele whose blocks are sized by `growth` and returns a [`WriteHandle`] and [`ReadHandle`]
+    ///
+    /// See [`with_growth_in`](Stele::with_growth_in) for details
+    pub fn with_growth(growth: GrowthPolicy) -> (WriteHandle<T>, ReadHandle<T>) {
+        Self::with_growth_in(growth, DefaultStorage::default())
+    }
+
+    #[must_use]
+    /// Creates a new Stele that holds at most `max_len` elements and returns a [`WriteHandle`] and [`ReadHandle`]
+    ///
+    /// See [`bounded_in`](Stele::bounded_in) for details
+    pub fn bounded(max_len: usize) -> (WriteHandle<T>, ReadHandle<T>) {
+        Self::bounded_in(max_len, DefaultStorage::default())
+    }
+
+    /// Creates an empty Stele in a `const` context, so that it can be stored directly in a `static`
+    ///
+    /// It starts without a writer: use [`claim_writer`](Stele::claim_writer) to get the only [`StaticWriteHandle`]
+    /// and [`reader`](Stele::reader) to read from it
+    #[cfg(not(any(loom, feature = "loom", shuttle)))]
+    #[must_use]
+    pub const fn const_new() -> Self {
+        Stele {
+            raw: RawStele::const_new(),
+            filled: [Self::NULL_FLAGS; 32],
+            pending: AtomicUsize::new(0),
+            initialized: AtomicUsize::new(0),
+            slow_paths: AtomicUsize::new(0),
+            bound: None,
+            prealloc: Prealloc::Lazy,
+            alloc_error_hook: UnsafeCell::new(None),
+            block_placement: UnsafeCell::new(None),
+            drop_hook: UnsafeCell::new(None),
+            notifier: None,
+            //There is no writer until one is claimed
+            closed: AtomicBool::new(true),
+            #[cfg(feature = "futures")]
+            wakers: crate::sync::Mutex::new(stream::WakerSlot::new()),
+            #[cfg(feature = "futures")]
+            waiting: AtomicUsize::new(0),
+            #[cfg(feature = "tracing")]
+            trace_id: AtomicUsize::new(0),
+            #[cfg(feature = "metrics")]
+            meter: None,
+        }
+    }
+
+    //Only used to repeat in an array, where each use is a fresh value
+    #[cfg(not(any(loom, feature = "loom", shuttle)))]
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NULL_FLAGS: AtomicPtr<AtomicUsize> = AtomicPtr::new(null_mut());
+}
+
+impl<T, S: Storage> Stele<T, S> {
+    /// Creates a new Stele with the given allocator and returns a [`WriteHandle`] and [`ReadHandle`]
+    pub fn new_in(storage: S) -> (WriteHandle<T, S>, ReadHandle<T, S>) {
+        SteleBuilder::new_in(storage).finish().to_handles()
+    }
+
+    /// Creates a new Stele with the given allocator whose first block holds 2<sup>`first_block_exp`</sup> elements,
+    /// and returns a [`WriteHandle`] and [`ReadHandle`]
+    ///
+    /// Every later block still doubles in size, so a Stele that is known to grow large can skip the tiny blocks at the start.
+    /// `first_block_exp` is clamped to at most 16, and 0 gives the same layout as [`new_in`](Stele::new_in)
+    pub fn with_first_block_exp_in(
+        first_block_exp: u32,
+        storage: S,
+    ) -> (WriteHandle<T, S>, ReadHandle<T, S>) {
+        SteleBuilder::new_in(storage)
+            .first_block_exp(first_block_exp)
+            .finish()
+            .to_handles()
+    }
+
+    /// Creates a new Stele with the given allocator whose blocks are sized by `growth`, and returns a [`WriteHandle`] and [`ReadHandle`]
+    ///
+    /// A [uniform](GrowthPolicy::uniform) policy bounds the Stele to the elements its 32 blocks can hold,
+    /// so it reports what is [`remaining`](ReadHandle::remaining) and fills up just like a [bounded](Stele::bounded_in) one
+    pub fn with_growth_in(
+        growth: GrowthPolicy,
+        storage: S,
+    ) -> (WriteHandle<T, S>, ReadHandle<T, S>) {
+        SteleBuilder::new_in(storage)
+            .growth(growth)
+            .finish()
+            .to_handles()
+    }
+
+    /// Creates a new Stele with the given allocator that holds at most `max_len` elements, and returns a [`WriteHandle`] and [`ReadHandle`]
+    ///
+    /// Once full, [`try_push`](WriteHandle::try_push) returns the value and [`push`](WriteHandle::push) panics.
+    /// Elements are never removed, so the only way to make room again is to [`recycle`](Stele::recycle) the Stele
+    pub fn bounded_in(max_len: usize, storage: S) -> (WriteHandle<T, S>, ReadHandle<T, S>) {
+        SteleBuilder::new_in(storage)
+            .bound(max_len)
+            .finish()
+            .to_handles()
+    }
+
+    /// Creates a Stele with the given allocator from the contents of an iterator,
+    /// mirroring [`FromIterator`](core::iter::FromIterator) for custom allocators
+    #[must_use]
+    pub fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, storage: S) -> Self {
+        let s = SteleBuilder::new_in(storage).finish();
+        for item in iter {
+            //SAFETY: We are the only writer since we just created the Stele
+            unsafe { s.push(item) };
+        }
+        s
+    }
+
+    pub(crate) fn empty_in(growth: GrowthPolicy, storage: S) -> Self {
+        Stele {
+            raw: RawStele::with_growth_in(growth, storage),
+            filled: [(); 32].map(|()| AtomicPtr::new(null_mut())),
+            pending: AtomicUsize::new(0),
+            initialized: AtomicUsize::new(0),
+            slow_paths: AtomicUsize::new(0),
+            bound: growth.max_len(),
+            prealloc: Prealloc::Lazy,
+            alloc_error_hook: UnsafeCell::new(None),
+            block_placement: UnsafeCell::new(None),
+            drop_hook: UnsafeCell::new(None),
+            notifier: None,
+            //There is no writer until the handles are created or one is claimed
+            closed: AtomicBool::new(true),
+            #[cfg(feature = "futures")]
+            wakers: crate::sync::Mutex::new(stream::WakerSlot::default()),
+            #[cfg(feature = "futures")]
+            waiting: AtomicUsize::new(0),
+            #[cfg(feature = "tracing")]
+            trace_id: AtomicUsize::new(0),
+            #[cfg(feature = "metrics")]
+            meter: None,
+        }
+    }
+
+    /// Returns the only [`StaticWriteHandle`] to a Stele that lives forever, such as one created with
+    /// [`const_new`](Stele::const_new)
+    ///
+    /// Write access is never given back, even once the handle is dropped, so this only ever succeeds once
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SteleError::WriterExists`] if the writer was already claimed
+    pub fn claim_writer(&'static self) -> Result<StaticWriteHandle<'static, T, S>, SteleError> {
+        if !self.reopen() {
+            return Err(SteleError::WriterExists);
+        }
+        Ok(StaticWriteHandle {
+            handle: self,
             _unsync: PhantomData,
-        };
-        let r = ReadHandle { handle: s };
-        (h, r)
+        })
+    }
+
+    /// Returns a [`StaticReadHandle`] to a Stele that lives forever, such as one created with [`const_new`](Stele::const_new)
+    #[must_use]
+    pub fn reader(&'static self) -> StaticReadHandle<'static, T, S> {
+        StaticReadHandle { handle: self }
+    }
+
+    /// Returns a reference to the allocator backing this Stele
+    #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "allocator_api", feature = "allocator-api2")))
+    )]
+    #[must_use]
+    pub fn allocator(&self) -> &S {
+        &self.raw.storage
+    }
+
+    /// Rebuilds a Stele from blocks that already hold `len` elements and returns a [`ReadHandle`] to it
+    ///
+    /// There is no writer until the handle is [promoted](ReadHandle::try_promote)
+    ///
+    /// SAFETY: `blocks` must hold a block of the right length for every block up to the one holding `len - 1`,
+    /// each of which must be freeable by `storage` and have its first `len` elements initialized
+    #[cfg(all(feature = "mmap", unix))]
+    pub(crate) unsafe fn from_blocks(
+        first_block_exp: u32,
+        storage: S,
+        blocks: &[*mut Inner<T>],
+        len: usize,
+    ) -> ReadHandle<T, S> {
+        let s = Self::empty_in(GrowthPolicy::doubling(first_block_exp), storage);
+        debug_assert_eq!(s.raw.growth.first_block_exp(), Some(first_block_exp));
+        debug_assert!(s.raw.blocks_for_len(len) <= blocks.len());
+        for (inner, &block) in s.raw.inners.iter().zip(blocks) {
+            inner.store(block, ord::RLX);
+        }
+        s.raw.len.store(len, ord::RLX);
+        ReadHandle {
+            handle: Arc::new(s),
+        }
+    }
+
+    /// Returns the storage the blocks are allocated from
+    #[cfg(all(feature = "mmap", unix))]
+    pub(crate) fn storage(&self) -> &S {
+        &self.raw.storage
     }
 
     /// Creates a pair of handles from an owned Stele after using [`FromIterator`](core::iter::FromIterator)
-    pub fn to_handles(self) -> (WriteHandle<T>, ReadHandle<T>) {
+    pub fn to_handles(self) -> (WriteHandle<T, S>, ReadHandle<T, S>) {
+        self.closed.store(false, ord::RLX);
         let s = Arc::new(self);
         let h = WriteHandle {
             handle: Arc::clone(&s),
@@ -68,124 +443,999 @@ impl<T> Stele<T> {
         (h, r)
     }
 
+    /// Pushes a new item on to the end of a Stele that has not been shared yet, allocating a new block if necessary
+    ///
+    /// Holding `&mut self` proves there is no other writer, so unlike pushing through a shared Stele this needs no handle
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Stele is [bounded](Stele::bounded) and already full
+    pub fn push_mut(&mut self, val: T) {
+        assert!(!self.is_full(), "Pushed to a full Stele");
+        //SAFETY: `&mut self` means nothing else can push at the same time
+        unsafe { self.push(val) };
+    }
+
+    /// Returns a reference to the element at `idx`, or `None` if it is out of bounds or a reservation that has not been filled
+    #[must_use]
+    pub fn read(&self, idx: usize) -> Option<&T> {
+        if idx >= self.len() || !self.is_initialized(idx) {
+            None
+        } else {
+            //SAFETY: Null pointers return None from mut_ptr::as_ref()
+            unsafe { Some(self.raw.read_raw(idx).as_ref()?.read()) }
+        }
+    }
+
+    /// Returns a mutable reference to the element at `idx`, or `None` if it is out of bounds or a reservation that has not been filled
+    ///
+    /// This requires `T: Unpin`, as the element may have been [pinned](ReadHandle::read_pinned) through a handle before the Stele was unwrapped
+    #[must_use]
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut T>
+    where
+        T: Unpin,
+    {
+        if idx >= self.len() || !self.is_initialized(idx) {
+            return None;
+        }
+        //SAFETY: The element is initialized, and holding `&mut self` means no handle can read it while it is borrowed
+        unsafe { Some((*self.raw.read_raw(idx)).read_mut()) }
+    }
+
+    /// Returns the number of elements in the Stele
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.raw.len.load(ord::ACQ)
+    }
+
+    /// Returns `true` if the Stele holds no elements
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the elements that are initialized when this is called, by reference
+    #[must_use]
+    pub fn iter(&self) -> RefIterator<'_, T, S> {
+        RefIterator::from_stele(self)
+    }
+
+    /// Returns an iterator that allows modifying the elements up to the first reservation that has not been filled
+    ///
+    /// This requires `T: Unpin` for the same reason as [`get_mut`](Stele::get_mut)
+    #[must_use]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, S>
+    where
+        T: Unpin,
+    {
+        IterMut::new(self)
+    }
+
+    /// Returns an iterator over the elements up to the first reservation that has not been filled as one mutable slice per block
+    ///
+    /// This requires `T: Unpin` for the same reason as [`get_mut`](Stele::get_mut)
+    #[must_use]
+    pub fn make_mut_slice_blocks(&mut self) -> BlocksMut<'_, T, S>
+    where
+        T: Unpin,
+    {
+        BlocksMut::new(self)
+    }
+
+    /// Drops every element and resets the length to zero while keeping all allocated blocks,
+    /// so that pushing again does not need to allocate until the previous capacity is exceeded
+    pub fn recycle(&mut self) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            name: "stele.recycle",
+            id = self.trace_id(),
+            len = self.len(),
+            capacity = self.capacity()
+        );
+        self.drop_elements();
+    }
+
+    /// Moves every element into a [`Vec`] with a capacity of exactly [`len`](ReadHandle::len) and frees all blocks
+    ///
+    /// This requires `T: Unpin`, as moving the elements out would break the guarantee of [`read_pinned`](ReadHandle::read_pinned)
+    #[must_use]
+    pub fn into_vec(self) -> Vec<T>
+    where
+        T: Unpin,
+    {
+        #[cfg(feature = "metrics")]
+        self.report_pushes();
+        //Resetting the length first means the blocks are freed without dropping the moved out elements again
+        let len = self.raw.len.swap(0, ord::ACQREL);
+        let mut v = Vec::with_capacity(len);
+        for idx in (0..len).filter(|&idx| self.is_initialized(idx)) {
+            //SAFETY: The element is initialized, and since the length is now zero it will not be read or dropped again
+            v.push(unsafe { (*self.raw.read_raw(idx)).take() });
+        }
+        v
+    }
+
+    /// Moves every element into a boxed slice and frees all blocks, see [`into_vec`](Stele::into_vec)
+    #[must_use]
+    pub fn into_boxed_slice(self) -> Box<[T]>
+    where
+        T: Unpin,
+    {
+        self.into_vec().into_boxed_slice()
+    }
+
+    /// Sets a hook that is consulted whenever allocating a block fails
+    ///
+    /// If the hook returns [`RetryOrFail::Retry`], usually after freeing some memory, the allocation is attempted again,
+    /// up to a bounded number of times, before falling back to [`handle_alloc_error`](alloc::alloc::handle_alloc_error)
+    pub fn set_alloc_error_hook(
+        &mut self,
+        hook: impl Fn(Layout) -> RetryOrFail + Send + Sync + 'static,
+    ) {
+        *self.alloc_error_hook.get_mut() = Some(Box::new(hook));
+    }
+
+    /// Sets a hook that picks where every block allocated from now on is placed, see [`WriteHandle::set_block_placement`]
+    pub fn set_block_placement(
+        &mut self,
+        hook: impl Fn(usize, Layout) -> Placement + Send + Sync + 'static,
+    ) {
+        *self.block_placement.get_mut() = Some(Box::new(hook));
+    }
+
+    /// Sets a hook that is called with every element just before it is dropped, see [`WriteHandle::set_drop_hook`]
+    pub fn set_drop_hook(&mut self, hook: impl Fn(&T) + Send + Sync + 'static) {
+        *self.drop_hook.get_mut() = Some(DropHook::new(hook));
+    }
+
+    /// Sets the [`Notify`] used to wake readers blocked in [`wait_for_len`](ReadHandle::wait_for_len) after every push
+    ///
+    /// Without one, pushing does no extra work and readers cannot block
+    pub fn set_notifier(&mut self, notifier: impl Notify + Send + Sync + 'static) {
+        self.notifier = Some(Box::new(notifier));
+        self.slow_paths.fetch_or(NOTIFIED, ord::RLX);
+    }
+
+    /// Blocks until the Stele holds at least `len` elements, returning `false` if the writer was dropped first
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`Notify`] was set with [`set_notifier`](Stele::set_notifier)
+    #[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+    pub(crate) fn wait_for_len(&self, len: usize) -> bool {
+        let notifier = self
+            .notifier
+            .as_ref()
+            .expect("Waiting requires a notifier to be set");
+        notifier.wait_until(&mut || self.len() >= len || self.closed.load(ord::ACQ));
+        self.len() >= len
+    }
+
+    /// Marks the writer as gone and wakes everything waiting on it
+    fn close(&self) {
+        //Streams are closed before `closed` is set, as a reader may be promoted as soon as it is
+        //and must not have its streams closed afterwards
+        #[cfg(feature = "futures")]
+        self.close_streams();
+        self.closed.store(true, ord::REL);
+        if let Some(notifier) = &self.notifier {
+            notifier.notify_all();
+        }
+    }
+
+    /// Claims the writer role if the previous writer is gone, returning whether it succeeded
+    ///
+    /// Only one caller can succeed each time a writer is dropped
+    fn reopen(&self) -> bool {
+        //Acquire pairs with the Release in `close`, so everything the previous writer did, including setting the
+        //allocation error hook, is visible to the new one
+        if self
+            .closed
+            .compare_exchange(true, false, ord::ACQ, ord::RLX)
+            .is_err()
+        {
+            return false;
+        }
+        #[cfg(feature = "futures")]
+        self.reopen_streams();
+        true
+    }
+
+    /// SAFETY: You must be the only writer
+    unsafe fn set_alloc_error_hook_unchecked(&self, hook: Box<AllocErrorHook>) {
+        //SAFETY: The hook is only accessed by the writer, and by the safety contract we are the only writer
+        unsafe { *self.alloc_error_hook.get() = Some(hook) };
+    }
+
+    fn alloc_error_hook(&self) -> Option<&AllocErrorHook> {
+        //SAFETY: The hook is only accessed by the writer, which is the only caller of `allocate`
+        unsafe { (*self.alloc_error_hook.get()).as_deref() }
+    }
+
+    /// SAFETY: You must be the only writer
+    unsafe fn set_block_placement_unchecked(&self, hook: Box<PlacementHook>) {
+        //SAFETY: The hook is only accessed by the writer, and by the safety contract we are the only writer
+        unsafe { *self.block_placement.get() = Some(hook) };
+    }
+
+    /// SAFETY: You must be the only writer
+    unsafe fn set_drop_hook_unchecked(&self, hook: impl Fn(&T) + Send + Sync + 'static) {
+        //SAFETY: The hook is only accessed by the writer or with `&mut self`, and by the safety contract we are the only writer
+        unsafe { *self.drop_hook.get() = Some(DropHook::new(hook)) };
+    }
+
+    /// Consults the placement hook, if there is one, about block `idx` at `block`, which must not have been published yet
+    fn place(&self, idx: usize, block: *mut Inner<T>) {
+        //SAFETY: The hook is only accessed by the writer, which is the only caller of `allocate`
+        let Some(hook) = (unsafe { (*self.block_placement.get()).as_deref() }) else {
+            return;
+        };
+        //Zero sized elements never take up any memory
+        if core::mem::size_of::<T>() == 0 {
+            return;
+        }
+        let layout = self
+            .raw
+            .block_layout(idx)
+            .expect("The block was just allocated with this layout");
+        crate::mem::place_block(block.cast(), layout, hook(idx, layout));
+    }
+
     /// SAFETY: You must only call `push` once at a time to avoid write-write conflicts
-    unsafe fn push(&self, val: T) {
-        let idx = self.len.load(Ordering::Acquire);
-        let (outer_idx, inner_idx) = split_idx(idx);
+    pub(crate) unsafe fn push(&self, val: T) {
+        let idx = self.raw.len.load(ord::ACQ);
+        let (outer_idx, inner_idx) = self.raw.split_idx(idx);
+        let mut block = self.raw.block(outer_idx);
+        if block.is_null() {
+            block = self.allocate(outer_idx);
+        }
+        //SAFETY: The block holding `idx` has just been loaded or allocated and we are the only writer
+        unsafe { self.write(block, idx, inner_idx, val) };
+    }
+
+    /// SAFETY: You must only call `try_push` once at a time to avoid write-write conflicts
+    unsafe fn try_push(&self, val: T) -> Result<(), Full<T>> {
+        if self.is_full() {
+            return Err(Full(val));
+        }
+        //SAFETY: By the safety contract of `try_push` we are the only writer
+        unsafe { self.push(val) };
+        Ok(())
+    }
+
+    /// SAFETY: You must only call `push_within_capacity` once at a time to avoid write-write conflicts
+    unsafe fn push_within_capacity(&self, val: T) -> Result<(), PushError<T>> {
+        let idx = self.raw.len.load(ord::ACQ);
+        if let Some(bound) = self.bound.filter(|&bound| idx >= bound) {
+            return Err(PushError {
+                value: val,
+                error: SteleError::CapacityExceeded {
+                    requested: idx + 1,
+                    max: bound,
+                },
+            });
+        }
+        let (outer_idx, inner_idx) = self.raw.split_idx(idx);
+        let block = self.raw.block(outer_idx);
+        if block.is_null() {
+            return Err(PushError {
+                value: val,
+                error: SteleError::CapacityExceeded {
+                    requested: idx + 1,
+                    max: self.capacity(),
+                },
+            });
+        }
+        //SAFETY: The block holding `idx` is allocated and we are the only writer
+        unsafe { self.write(block, idx, inner_idx, val) };
+        Ok(())
+    }
+
+    /// Moves every element of `items` into the next slots, allocating blocks as necessary, and publishes the new length once
+    /// so that readers see either all of them or none, returning the index of the first one
+    ///
+    /// # Panics
+    ///
+    /// Panics without pushing anything if the Stele is [bounded](Stele::bounded) and does not have room for all of them
+    ///
+    /// SAFETY: You must be the only writer
+    unsafe fn push_array<const N: usize>(&self, items: [T; N]) -> usize {
+        let start = self.raw.len.load(ord::ACQ);
+        assert!(
+            !matches!(self.remaining(), Some(remaining) if remaining < N),
+            "Pushed to a full Stele"
+        );
+        //Every block is allocated before anything is moved out of `items`, so an allocation failure cannot lose elements
+        //SAFETY: By the safety contract of `push_array` we are the only writer
+        unsafe { self.reserve_blocks(N) };
+        //The elements are moved into the blocks below, so the array must not drop them as well
+        let items = core::mem::ManuallyDrop::new(items);
+        let end = start + N;
+        let mut idx = start;
+        while idx < end {
+            let (outer_idx, inner_idx) = self.raw.split_idx(idx);
+            let run = (self.raw.block_len(outer_idx) - inner_idx).min(end - idx);
+            //SAFETY: The run lies within its allocated block and past the end of the Stele, so no reader can see it
+            //until the length is published below, and `Inner<T>` has the same layout as `T`
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    items.as_ptr().add(idx - start),
+                    self.raw.block(outer_idx).add(inner_idx).cast::<T>(),
+                    run,
+                );
+            }
+            (idx..idx + run).for_each(|idx| self.mark_initialized(idx, ord::RLX));
+            idx += run;
+        }
+        self.raw.len.store(end, ord::REL);
+        self.notify_readers();
+        start
+    }
+
+    /// Allocates every block needed to hold `additional` more elements, or as many as the bound allows
+    ///
+    /// SAFETY: You must be the only writer
+    pub(crate) unsafe fn reserve_blocks(&self, additional: usize) {
+        let mut len = self.len().saturating_add(additional);
+        if let Some(bound) = self.bound {
+            len = len.min(bound);
+        }
+        for block in 0..self.raw.blocks_for_len(len).min(self.raw.inners.len()) {
+            if self.raw.block(block).is_null() {
+                self.allocate(block);
+            }
+        }
+    }
+
+    /// SAFETY: `block` must be the allocated block holding `idx`, `idx` must be the current length,
+    /// and you must be the only writer
+    unsafe fn write(&self, block: *mut Inner<T>, idx: usize, inner_idx: usize, val: T) {
+        //Pushes are counted in batches, one block at a time
+        #[cfg(feature = "metrics")]
+        if inner_idx == 0 {
+            self.report_pushes();
+        }
         //SAFETY: By only incrementing the index after appending the element we ensure that we never allow reads to access unwritten memory
-        //and by the safety contract of `push` we know we aren't writing to the same spot multiple times
+        //and by the safety contract of `write` we know we aren't writing to the same spot multiple times
         unsafe {
-            if (idx.is_power_of_two() && outer_idx > Self::INITIAL_SIZE)
-                || (outer_idx <= Self::INITIAL_SIZE && self.is_empty())
-            {
-                self.allocate(outer_idx);
-            }
-            *self.inners[outer_idx]
-                .load(Ordering::Acquire)
-                .add(inner_idx) = crate::Inner::new(val);
-        }
-        self.len.store(idx + 1, Ordering::Release);
-    }
-
-    pub(crate) fn allocate(&self, idx: usize) {
-        if idx == 0 {
-            (0..=Self::INITIAL_SIZE).for_each(|i| {
-                self.inners[i].compare_exchange(
-                    core::ptr::null_mut(),
-                        unsafe { crate::mem::alloc_inner(max_len(i)) },
-                    Ordering::AcqRel,
-                    Ordering::Relaxed)
-                    .expect("The pointer is null because we have just incremented the cap to the head of this pointer");
-            });
-        } else {
-            self.inners[idx]
-            .compare_exchange(
-                core::ptr::null_mut(),
-                unsafe { crate::mem::alloc_inner(max_len(idx)) },
-                Ordering::AcqRel,
-                Ordering::Relaxed,
-            )
-            .expect("The pointer is null because we have just incremented the cap to the head of this pointer");
+            *block.add(inner_idx) = crate::Inner::new(val);
         }
+        //Loaded once, so that a push without reservations or a notifier only adds this load and the branches below
+        let slow_paths = self.slow_paths.load(ord::RLX);
+        if slow_paths & RESERVED != 0 {
+            //Publishing the new length publishes the flag as well
+            self.mark_initialized(idx, ord::RLX);
+        }
+        self.raw.len.store(idx + 1, ord::REL);
+        if slow_paths & NOTIFIED != 0 {
+            self.notify_notifier();
+        }
+        //See `notify_readers`
+        #[cfg(feature = "futures")]
+        self.wake_waiting();
     }
 
-    pub(crate) fn read(&self, idx: usize) -> &T {
-        debug_assert!(self.len.load(Ordering::Acquire) > idx);
-        unsafe { (*self.read_raw(idx)).read() }
+    /// Wakes every reader waiting for new elements
+    fn notify_readers(&self) {
+        if self.slow_paths.load(ord::RLX) & NOTIFIED != 0 {
+            self.notify_notifier();
+        }
+        //Streams are not behind a bit: one can start waiting at any time from another thread, and the writer
+        //can only be sure to see that through the fence in `wake_waiting`, which is what makes it cost anything
+        #[cfg(feature = "futures")]
+        self.wake_waiting();
     }
 
-    pub(crate) fn try_read(&self, idx: usize) -> Option<&T> {
-        //SAFETY: Null pointers return None from mut_ptr::as_ref()
-        if idx >= self.len() {
-            None
+    fn notify_notifier(&self) {
+        if let Some(notifier) = &self.notifier {
+            notifier.notify_all();
+        }
+    }
+
+    /// Reserves the next index without initializing it, allocating its block if necessary
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Stele is bounded and full
+    ///
+    /// SAFETY: You must be the only writer
+    unsafe fn reserve(&self) -> usize {
+        assert!(!self.is_full(), "Pushed to a full Stele");
+        let idx = self.raw.len.load(ord::ACQ);
+        let (outer_idx, inner_idx) = self.raw.split_idx(idx);
+        if self.raw.block(outer_idx).is_null() {
+            self.allocate(outer_idx);
+        }
+        //Set before the length is published below, so that every reader that can see the reservation checks the flags
+        self.slow_paths.fetch_or(RESERVED, ord::RLX);
+        if self.filled[outer_idx].load(ord::ACQ).is_null() {
+            //Everything before `idx` in this block was pushed, as any earlier reservation would have created the flags
+            let flags = (0..self.raw.block_len(outer_idx).div_ceil(BITS))
+                .map(|word| {
+                    let start = word * BITS;
+                    AtomicUsize::new(match inner_idx.saturating_sub(start) {
+                        0 => 0,
+                        n if n >= BITS => usize::MAX,
+                        n => (1 << n) - 1,
+                    })
+                })
+                .collect::<Box<[_]>>();
+            self.filled[outer_idx].store(Box::into_raw(flags).cast(), ord::REL);
+        }
+        self.pending.fetch_add(1, ord::RLX);
+        //Readers only look for flags after loading a length that includes `idx`, so they always find the ones just created
+        self.raw.len.store(idx + 1, ord::REL);
+        idx
+    }
+
+    /// Initializes the reserved element at `idx` and wakes every reader waiting for it
+    ///
+    /// SAFETY: `idx` must have been returned by `reserve` and must only be filled once
+    pub(crate) unsafe fn fill(&self, idx: usize, val: T) {
+        //SAFETY: The block was allocated by `reserve` and by the safety contract nothing else writes to `idx`
+        unsafe { *self.raw.read_raw(idx) = crate::Inner::new(val) };
+        self.mark_initialized(idx, ord::REL);
+        self.pending.fetch_sub(1, ord::REL);
+        self.notify_readers();
+    }
+
+    /// Sets the flag for `idx` if its block has flags
+    fn mark_initialized(&self, idx: usize, order: Ordering) {
+        if self.slow_paths.load(ord::RLX) & RESERVED == 0 {
+            return;
+        }
+        let (outer_idx, inner_idx) = self.raw.split_idx(idx);
+        let flags = self.filled[outer_idx].load(ord::ACQ);
+        if !flags.is_null() {
+            //SAFETY: Flags are created with one bit for every element of their block and only freed with `&mut self`
+            unsafe { (*flags.add(inner_idx / BITS)).fetch_or(1 << (inner_idx % BITS), order) };
+        }
+    }
+
+    /// Returns whether the element at `idx`, which must be below the length, has been initialized
+    fn is_initialized(&self, idx: usize) -> bool {
+        //Any reservation below a length the caller has seen set the bit before publishing that length
+        if self.slow_paths.load(ord::RLX) & RESERVED == 0 {
+            return true;
+        }
+        let (outer_idx, inner_idx) = self.raw.split_idx(idx);
+        let flags = self.filled[outer_idx].load(ord::ACQ);
+        //SAFETY: Flags are created with one bit for every element of their block and only freed with `&mut self`
+        flags.is_null()
+            || unsafe { (*flags.add(inner_idx / BITS)).load(ord::ACQ) } & (1 << (inner_idx % BITS))
+                != 0
+    }
+
+    /// Returns the length of the longest prefix whose elements are all initialized
+    pub(crate) fn initialized_len(&self) -> usize {
+        let len = self.len();
+        //A fill decrements `pending` only after setting its flag, so seeing no pending reservations
+        //means every element below `len` is initialized
+        if self.pending.load(ord::ACQ) == 0 {
+            return len;
+        }
+        let mut prefix = self.initialized.load(ord::ACQ).min(len);
+        while prefix < len && self.is_initialized(prefix) {
+            prefix += 1;
+        }
+        self.initialized.fetch_max(prefix, ord::RLX);
+        prefix
+    }
+
+    /// Allocates the block at `idx` and returns it, also allocating every block up to [`initial_blocks`] when `idx` is 0
+    /// and the Stele was built with [`Prealloc::SmallBlocks`]
+    ///
+    /// Blocks that are already allocated, such as those kept by `recycle`, are left as they are
+    fn allocate(&self, idx: usize) -> *mut Inner<T> {
+        let blocks = if idx == 0 && self.prealloc == Prealloc::SmallBlocks {
+            0..=initial_blocks::<T>()
         } else {
-            //SAFETY: Null pointers return None from mut_ptr::as_ref()
-            unsafe { Some(self.read_raw(idx).as_ref()?.read()) }
+            idx..=idx
+        };
+        for i in blocks {
+            if self.raw.block(i).is_null() {
+                //Placed before the block is published, so that no reader touches its pages first
+                self.raw
+                    .allocate_block(i, self.alloc_error_hook(), |block| self.place(i, block));
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    name: "stele.alloc",
+                    id = self.trace_id(),
+                    block = i,
+                    elements = self.raw.block_len(i),
+                    bytes = self.raw.block_len(i) * core::mem::size_of::<T>(),
+                    capacity = self.capacity()
+                );
+                #[cfg(feature = "metrics")]
+                if let Some(meter) = &self.meter {
+                    meter.report_pushes(self.len());
+                    meter.allocated(self.raw.block_len(i) * core::mem::size_of::<T>());
+                }
+            }
+        }
+        self.raw.block(idx)
+    }
+
+    /// SAFETY: You must be the only writer and there must be no calls to `push` afterwards
+    unsafe fn shrink_unused(&self) {
+        let len = self.len();
+        #[cfg(feature = "tracing")]
+        let blocks = self.block_count();
+        #[cfg(any(feature = "tracing", feature = "metrics"))]
+        let bytes = self.allocated_bytes();
+        (self.raw.blocks_for_len(len)..self.raw.inners.len()).for_each(|i| {
+            //SAFETY: Readers only dereference blocks holding an index below `len`, and this block starts
+            //at or beyond `len`, so no reader can be using it or any element in it
+            unsafe { self.raw.free_block(i) };
+        });
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            name: "stele.shrink",
+            id = self.trace_id(),
+            blocks = blocks - self.block_count(),
+            bytes = bytes - self.allocated_bytes(),
+            len
+        );
+        #[cfg(feature = "metrics")]
+        if let Some(meter) = &self.meter {
+            meter.freed(bytes - self.allocated_bytes());
         }
     }
 
-    pub(crate) fn len(&self) -> usize {
-        self.len.load(Ordering::Acquire)
+    /// Adds the pushes since they were last reported to the `stele.pushes` counter
+    #[cfg(feature = "metrics")]
+    fn report_pushes(&self) {
+        if let Some(meter) = &self.meter {
+            meter.report_pushes(self.len());
+        }
     }
 
-    pub(crate) fn is_empty(&self) -> bool {
-        self.len() == 0
+    /// Returns the id that correlates the tracing events of this Stele, assigning the next free one on first use
+    #[cfg(feature = "tracing")]
+    fn trace_id(&self) -> usize {
+        //Ids only need to be unique rather than be part of a loom model, so this is always a real atomic
+        static NEXT_ID: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(1);
+        match self.trace_id.load(ord::RLX) {
+            0 => {
+                let id = NEXT_ID.fetch_add(1, ord::RLX);
+                //Another handle may have assigned one in the meantime, in which case that one sticks
+                match self.trace_id.compare_exchange(0, id, ord::RLX, ord::RLX) {
+                    Ok(_) => id,
+                    Err(assigned) => assigned,
+                }
+            }
+            id => id,
+        }
     }
 
-    //SAFETY: idx must be less than self.len
-    unsafe fn read_raw(&self, idx: usize) -> *mut crate::Inner<T> {
-        let (outer_idx, inner_idx) = crate::split_idx(idx);
-        unsafe {
-            self.inners[outer_idx]
-                .load(Ordering::Acquire)
-                .add(inner_idx)
+    /// Drops every initialized element in index order, after passing it to the drop hook if there is one, and sets the length to zero
+    ///
+    /// The length is reset before any destructor runs so that a panicking destructor or hook can only leak
+    /// the remaining elements rather than leave them reachable after being dropped
+    fn drop_elements(&mut self) {
+        #[cfg(feature = "metrics")]
+        self.report_pushes();
+        let len = self.raw.len.swap(0, ord::ACQREL);
+        //SAFETY: Holding `&mut self` means nothing else can access the hook
+        let hook = unsafe { (*self.drop_hook.get()).as_ref() };
+        if core::mem::needs_drop::<T>() || hook.is_some() {
+            for idx in (0..len).filter(|&idx| self.is_initialized(idx)) {
+                //SAFETY: The element is initialized, and holding `&mut self` means nothing else can read it
+                //while or after it is dropped. The hook was set through a handle of either this element type or
+                //one it is a transparent wrapper of or wrapped by, which all share a layout
+                unsafe {
+                    let inner = self.raw.read_raw(idx);
+                    if let Some(hook) = hook {
+                        hook.call((*inner).read());
+                    }
+                    (*inner).drop_in_place();
+                }
+            }
+        }
+        for block in 0..self.filled.len() {
+            let flags = self.filled[block].swap(null_mut(), ord::RLX);
+            if !flags.is_null() {
+                let words = self.raw.block_len(block).div_ceil(BITS);
+                //SAFETY: The flags were created by `reserve` as a boxed slice of exactly this length
+                drop(unsafe { Box::from_raw(core::ptr::slice_from_raw_parts_mut(flags, words)) });
+            }
+        }
+        self.pending.store(0, ord::RLX);
+        self.initialized.store(0, ord::RLX);
+        //Every flag was freed above, and a notifier stays set
+        self.slow_paths.fetch_and(!RESERVED, ord::RLX);
+        #[cfg(feature = "metrics")]
+        if let Some(meter) = &self.meter {
+            meter.reset_pushes();
         }
     }
+
+    pub(crate) fn read_at(&self, idx: usize) -> &T {
+        debug_assert!(self.raw.len.load(ord::ACQ) > idx);
+        assert!(
+            self.is_initialized(idx),
+            "Read a reserved element that has not been filled"
+        );
+        unsafe { (*self.raw.read_raw(idx)).read() }
+    }
+
+    pub(crate) fn remaining(&self) -> Option<usize> {
+        self.bound.map(|bound| bound.saturating_sub(self.len()))
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.remaining() == Some(0)
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.raw.capacity()
+    }
+
+    pub(crate) fn allocated_bytes(&self) -> usize {
+        self.capacity() * core::mem::size_of::<T>()
+    }
+
+    pub(crate) fn block_count(&self) -> usize {
+        self.raw.allocated_blocks().count()
+    }
+
+    pub(crate) fn overhead_bytes(&self) -> usize {
+        core::mem::size_of_val(self)
+    }
+
+    /// Returns the bytes taken up by the initialized flags of the blocks that have held a reservation
+    #[cfg(feature = "deepsize")]
+    pub(crate) fn flag_bytes(&self) -> usize {
+        (0..self.filled.len())
+            .filter(|&block| !self.filled[block].load(ord::ACQ).is_null())
+            .map(|block| self.raw.block_len(block).div_ceil(BITS) * core::mem::size_of::<usize>())
+            .sum()
+    }
+
+    pub(crate) fn growth(&self) -> GrowthPolicy {
+        self.raw.growth
+    }
+
+    /// Returns the elements from `idx` up to `end` or the end of the block holding `idx`, whichever comes first
+    ///
+    /// SAFETY: `idx` must be below `end`, and every element below `end` must be initialized
+    pub(crate) unsafe fn block_slice(&self, idx: usize, end: usize) -> &[T] {
+        let (outer_idx, inner_idx) = self.raw.split_idx(idx);
+        let len = end.min(idx - inner_idx + self.raw.block_len(outer_idx)) - idx;
+        //SAFETY: The elements are initialized and within one block, and `Inner<T>` has the same layout as `T`
+        unsafe { core::slice::from_raw_parts(self.raw.read_raw(idx).cast::<T>(), len) }
+    }
+
+    /// Folds the elements from `idx` up to `end` a block at a time, prefetching ahead of the one being read
+    ///
+    /// SAFETY: Every element below `end` must be initialized
+    pub(crate) unsafe fn fold_blocks<'a, B>(
+        &'a self,
+        mut idx: usize,
+        end: usize,
+        init: B,
+        mut f: impl FnMut(B, &'a T) -> B,
+    ) -> B {
+        let mut acc = init;
+        while idx < end {
+            //SAFETY: By the safety contract every element below `end` is initialized
+            let block = unsafe { self.block_slice(idx, end) };
+            idx += block.len();
+            //SAFETY: The block holding `idx` is allocated since it is below `end`
+            let next =
+                (idx < end).then(|| unsafe { self.raw.read_raw(idx).cast_const().cast::<T>() });
+            acc = crate::prefetch::fold_prefetched(block, next, acc, &mut f);
+        }
+        acc
+    }
+
+    /// Creates a Stele in `storage` with the same growth policy, bound and block alignment as this one, holding the first `len` elements
+    ///
+    /// Every block is allocated up front, then `fill` is given each run of elements within one block of both Steles along with the slots
+    /// to fill from it, and every block of the new Stele is published once it is full
+    ///
+    /// SAFETY: Every element below `len` must be initialized, and `fill` must initialize every slot it is given
+    pub(crate) unsafe fn migrate_in<S2: Storage>(
+        &self,
+        len: usize,
+        storage: S2,
+        mut fill: impl FnMut(&[T], &mut [MaybeUninit<T>]),
+    ) -> Stele<T, S2> {
+        let mut builder = SteleBuilder::new_in(storage)
+            .growth(self.raw.growth)
+            .align(self.raw.block_align);
+        if let Some(bound) = self.bound {
+            builder = builder.bound(bound);
+        }
+        let stele = builder.finish();
+        let mut idx = 0;
+        //SAFETY: The Stele was just created, so there is no other writer
+        for slots in unsafe { stele.spare_capacity(len) } {
+            let mut filled = 0;
+            while filled < slots.len() {
+                //SAFETY: `idx` is below `len` since there are slots left, and by the safety contract everything below `len` is initialized
+                let block = unsafe { self.block_slice(idx, len) };
+                let run = block.len().min(slots.len() - filled);
+                fill(&block[..run], &mut slots[filled..filled + run]);
+                filled += run;
+                idx += run;
+            }
+            //SAFETY: By the safety contract of `migrate_in` every slot of the block was just initialized
+            unsafe { stele.publish_spare(slots.len()) };
+        }
+        stele
+    }
+
+    /// Asserts that the slot at `idx`, which must be past the end of the Stele, still holds the pattern fresh blocks are poisoned with
+    #[cfg(all(
+        test,
+        feature = "debug-poison",
+        not(any(loom, feature = "loom", shuttle, target_arch = "wasm32"))
+    ))]
+    pub(crate) fn poison_check(&self, idx: usize) {
+        assert!(
+            idx >= self.len(),
+            "Only slots past the end are left poisoned"
+        );
+        if core::mem::size_of::<T>() == 0 {
+            return;
+        }
+        let (outer_idx, _) = self.raw.split_idx(idx);
+        assert!(
+            !self.raw.block(outer_idx).is_null(),
+            "The block holding {} has not been allocated",
+            idx
+        );
+        //SAFETY: The block is allocated and, being past the end, the slot is only ever written by `alloc_inner`
+        let bytes = unsafe {
+            core::slice::from_raw_parts(
+                self.raw.read_raw(idx).cast::<u8>(),
+                core::mem::size_of::<T>(),
+            )
+        };
+        assert!(
+            bytes.iter().all(|&b| b == crate::mem::POISON_FRESH),
+            "Slot {} past the end was written to",
+            idx
+        );
+    }
 }
 
-impl<T: Copy> Stele<T> {
+/// The error returned when pushing to a bounded [`Stele`] that is already full, which hands back the value that did not fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Full<T>(pub T);
+
+impl<T> Full<T> {
+    /// Returns the value that did not fit
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> core::fmt::Display for Full<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("the Stele is full")
+    }
+}
+
+impl<T: Debug> core::error::Error for Full<T> {}
+
+impl<T> Stele<T, BufferStorage> {
+    /// Creates a new Stele that stores its elements in `buf` instead of allocating, and returns a [`WriteHandle`] and [`ReadHandle`]
+    ///
+    /// The buffer is split up front into the same power-of-two blocks an allocated Stele would use, so only the longest prefix of `buf`
+    /// made up of whole blocks is used. The Stele is [bounded](Stele::bounded) to that prefix, so once it is full
+    /// [`try_push`](WriteHandle::try_push) and [`push_within_capacity`](WriteHandle::push_within_capacity) return an error
+    /// and [`push`](WriteHandle::push) panics, instead of anything trying to allocate
+    #[must_use]
+    pub fn new_in_buffer(
+        buf: &'static mut [MaybeUninit<T>],
+    ) -> (WriteHandle<T, BufferStorage>, ReadHandle<T, BufferStorage>) {
+        let mut s = Self::from_iter_in(core::iter::empty(), BufferStorage);
+        let base = buf.as_mut_ptr().cast::<Inner<T>>();
+        for (block, inner) in s.raw.inners.iter().enumerate() {
+            let first = s.raw.growth.first_index_of_block(block);
+            if first + s.raw.block_len(block) > buf.len() {
+                s.bound = Some(s.bound.map_or(first, |bound| bound.min(first)));
+                break;
+            }
+            //SAFETY: The whole block lies within `buf`, which has the same layout as a block of `Inner<T>`
+            inner.store(unsafe { base.add(first) }, ord::REL);
+        }
+        s.to_handles()
+    }
+}
+
+impl<T: Copy, S: Storage> Stele<T, S> {
     pub(crate) fn get(&self, idx: usize) -> T {
-        debug_assert!(self.len.load(Ordering::Acquire) > idx);
-        unsafe { (*self.read_raw(idx)).get() }
+        debug_assert!(self.raw.len.load(ord::ACQ) > idx);
+        assert!(
+            self.is_initialized(idx),
+            "Read a reserved element that has not been filled"
+        );
+        unsafe { (*self.raw.read_raw(idx)).get() }
+    }
+
+    /// Copies the elements from `idx` onwards into `dst` a block at a time, prefetching the start of each next block
+    ///
+    /// SAFETY: Every element below `idx + dst.len()` must be initialized
+    pub(crate) unsafe fn copy_blocks(&self, mut idx: usize, dst: &mut [T]) {
+        let end = idx + dst.len();
+        let mut copied = 0;
+        while idx < end {
+            //SAFETY: By the safety contract every element below `end` is initialized
+            let block = unsafe { self.block_slice(idx, end) };
+            idx += block.len();
+            if idx < end {
+                //SAFETY: The block holding `idx` is allocated since it is below `end`
+                crate::prefetch::prefetch(unsafe { self.raw.read_raw(idx) });
+            }
+            dst[copied..copied + block.len()].copy_from_slice(block);
+            copied += block.len();
+        }
+    }
+
+    /// Pushes every element of every slice in `parts`, allocating blocks as necessary, and publishes the new length once
+    /// so that readers see either all of them or none
+    ///
+    /// # Panics
+    ///
+    /// Panics without pushing anything if the Stele is [bounded](Stele::bounded) and does not have room for all of them
+    ///
+    /// SAFETY: You must be the only writer
+    pub(crate) unsafe fn extend_from_slices(&self, parts: &[&[T]]) {
+        let start = self.raw.len.load(ord::ACQ);
+        let count = parts.iter().map(|part| part.len()).sum::<usize>();
+        assert!(
+            !matches!(self.remaining(), Some(remaining) if remaining < count),
+            "Pushed to a full Stele"
+        );
+        let mut current = None;
+        for (idx, &val) in (start..).zip(parts.iter().copied().flatten()) {
+            let (outer_idx, inner_idx) = self.raw.split_idx(idx);
+            let block = match current {
+                Some((outer, block)) if outer == outer_idx => block,
+                _ => {
+                    let mut block = self.raw.block(outer_idx);
+                    if block.is_null() {
+                        block = self.allocate(outer_idx);
+                    }
+                    current = Some((outer_idx, block));
+                    block
+                }
+            };
+            //SAFETY: The slot is within its allocated block and past the end of the Stele,
+            //so no reader can see it until the length is published below
+            unsafe { *block.add(inner_idx) = crate::Inner::new(val) };
+            self.mark_initialized(idx, ord::RLX);
+        }
+        self.raw.len.store(start + count, ord::REL);
+        self.notify_readers();
+    }
+
+    /// Pushes as many leading elements of `vals` as fit in the block holding the current length, allocating it if necessary,
+    /// and returns how many were pushed
+    ///
+    /// The new length is published once for all of them. Only a full [bounded](Stele::bounded) Stele or an empty `vals` pushes nothing
+    ///
+    /// SAFETY: You must be the only writer
+    #[cfg(feature = "tokio-io")]
+    unsafe fn extend_within_block(&self, vals: &[T]) -> usize {
+        let idx = self.raw.len.load(ord::ACQ);
+        let (outer_idx, inner_idx) = self.raw.split_idx(idx);
+        let count = vals
+            .len()
+            .min(self.raw.block_len(outer_idx) - inner_idx)
+            .min(self.remaining().unwrap_or(usize::MAX));
+        if count == 0 {
+            return 0;
+        }
+        let mut block = self.raw.block(outer_idx);
+        if block.is_null() {
+            block = self.allocate(outer_idx);
+        }
+        for (offset, &val) in vals[..count].iter().enumerate() {
+            //SAFETY: Every slot from `inner_idx` to `inner_idx + count` is within the block and past the end of the Stele,
+            //so no reader can see it until the length is published below
+            unsafe { *block.add(inner_idx + offset) = crate::Inner::new(val) };
+            self.mark_initialized(idx + offset, ord::RLX);
+        }
+        self.raw.len.store(idx + count, ord::REL);
+        self.notify_readers();
+        count
     }
 }
 
 impl<T> core::iter::FromIterator<T> for Stele<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let s = Stele {
-            inners: [(); 32].map(|()| AtomicPtr::new(null_mut())),
-            len: AtomicUsize::new(0),
-        };
-        for item in iter {
-            //SAFETY: We are the only writer since we just created the Stele
-            unsafe { s.push(item) };
+        Self::from_iter_in(iter, DefaultStorage::default())
+    }
+}
+
+impl<T> From<Vec<T>> for Stele<T> {
+    /// Converts a [`Vec`] into a Stele, leaving most of the elements where they are
+    ///
+    /// Once the Vec holds more elements than the small blocks a Stele starts out with, its buffer is adopted as
+    /// every leading block that fits in its capacity, and only the elements past those are moved into blocks of their own.
+    /// The adopted buffer is freed as a whole when the Stele is dropped, so [`shrink_unused`](WriteHandle::shrink_unused)
+    /// does not return any of it. Smaller Vecs are pushed an element at a time
+    fn from(vec: Vec<T>) -> Self {
+        //SAFETY: The Vec allocated its buffer from the global allocator, which the default storage frees to
+        unsafe { Self::from_vec_in(vec, DefaultStorage::default()) }
+    }
+}
+
+impl<T, S: Storage> Stele<T, S> {
+    /// Converts a [`Vec`] into a Stele in `storage` the way [`From<Vec<T>>`](Stele::from) does
+    ///
+    /// SAFETY: `storage` must be able to free the buffer of `vec` as a block of its capacity
+    pub(crate) unsafe fn from_vec_in(vec: Vec<T>, storage: S) -> Self {
+        let mut s = SteleBuilder::new_in(storage).finish();
+        if core::mem::size_of::<T>() == 0
+            || vec.len() < s.raw.growth.first_index_of_block(initial_blocks::<T>() + 1)
+        {
+            for item in vec {
+                //SAFETY: We are the only writer since we just created the Stele
+                unsafe { s.push(item) };
+            }
+            return s;
+        }
+        let mut vec = core::mem::ManuallyDrop::new(vec);
+        let (ptr, len) = (vec.as_mut_ptr(), vec.len());
+        //SAFETY: Nothing is allocated yet, the storage can free the buffer by the safety contract of `from_vec_in`,
+        //and the Vec allocated it for exactly its capacity and will not touch it again
+        let adopted = unsafe { s.raw.adopt(ptr.cast(), vec.capacity()) };
+        let kept = len.min(adopted);
+        #[cfg(feature = "debug-poison")]
+        //SAFETY: The slots past the kept elements up to the end of the adopted blocks lie within the buffer
+        unsafe {
+            ptr.add(kept).cast::<u8>().write_bytes(
+                crate::mem::POISON_FRESH,
+                (adopted - kept) * core::mem::size_of::<T>(),
+            );
+        }
+        //The kept elements were pushed as far as the flags are concerned, as none of them was reserved
+        s.raw.len.store(kept, ord::RLX);
+        for idx in kept..len {
+            //SAFETY: Each element past the adopted blocks is moved out exactly once, and the Vec will not drop it,
+            //and we are the only writer since we just created the Stele
+            unsafe { s.push(ptr.add(idx).read()) };
         }
         s
     }
 }
 
-impl<T> Drop for Stele<T> {
+impl<'a, T, S: Storage> IntoIterator for &'a Stele<T, S> {
+    type Item = &'a T;
+
+    type IntoIter = RefIterator<'a, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: Unpin, S: Storage> IntoIterator for &'a mut Stele<T, S> {
+    type Item = &'a mut T;
+
+    type IntoIter = IterMut<'a, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, S: Storage> Drop for Stele<T, S> {
     fn drop(&mut self) {
-        #[cfg(not(loom))]
-        let size = *self.len.get_mut();
-        #[cfg(loom)]
-        let size = unsafe { self.len.unsync_load() };
-        if size == 0 {
-            return;
-        }
-        let num_inners = max(
-            (usize::BITS as usize) - (size.next_power_of_two().leading_zeros() as usize),
-            Self::INITIAL_SIZE + 1,
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            name: "stele.drop",
+            id = self.trace_id(),
+            blocks = self.block_count(),
+            bytes = self.allocated_bytes(),
+            len = self.len()
         );
-        for idx in 0..num_inners {
-            #[cfg(not(loom))]
-            unsafe {
-                crate::mem::dealloc_inner(*self.inners[idx].get_mut(), max_len(idx));
-            }
-            #[cfg(loom)]
-            unsafe {
-                crate::mem::dealloc_inner(self.inners[idx].unsync_load(), max_len(idx));
-            }
-        }
+        //The blocks themselves are freed when `raw` is dropped
+        self.drop_elements();
     }
 }
@@ -0,0 +1,240 @@
+use alloc::alloc::Layout;
+#[cfg(feature = "metrics")]
+use alloc::borrow::Cow;
+use core::marker::PhantomData;
+
+use super::{ReadHandle, Stele, WriteHandle};
+use crate::{
+    error::SteleError,
+    layout::GrowthPolicy,
+    mem::{DefaultStorage, Storage},
+};
+
+//The handles to a new Stele
+type Handles<T, S> = (WriteHandle<T, S>, ReadHandle<T, S>);
+
+/// Which blocks the first push allocates, see [`SteleBuilder::prealloc`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prealloc {
+    /// Every block is allocated by the push that first needs it, which is the default
+    Lazy,
+    /// The first push also allocates the next few small blocks, so that a handful of small pushes do not each allocate
+    ///
+    /// This is only available with the default [`GrowthPolicy`]
+    SmallBlocks,
+}
+
+/// Collects the options of a new [`Stele`] in one place, created by [`Stele::builder`]
+///
+/// The setters can be chained in any order, and options that cannot be combined are reported by [`build`](SteleBuilder::build)
+///
+/// ```
+/// use stele::Stele;
+///
+/// let (wh, rh) = Stele::<u64>::builder()
+///     .first_block_exp(4)
+///     .capacity(100)
+///     .bound(1000)
+///     .build()
+///     .unwrap();
+/// assert!(rh.capacity() >= 100);
+/// assert_eq!(rh.remaining(), Some(1000));
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct SteleBuilder<T, S: Storage = DefaultStorage> {
+    storage: S,
+    growth: Option<GrowthPolicy>,
+    first_block_exp: Option<u32>,
+    capacity: usize,
+    bound: Option<usize>,
+    align: usize,
+    prealloc: Option<Prealloc>,
+    #[cfg(feature = "metrics")]
+    metrics_label: Option<Cow<'static, str>>,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> Stele<T> {
+    /// Returns a [`SteleBuilder`] to configure a new Stele with
+    pub fn builder() -> SteleBuilder<T> {
+        SteleBuilder::new_in(DefaultStorage::default())
+    }
+}
+
+impl<T, S: Storage> SteleBuilder<T, S> {
+    pub(crate) fn new_in(storage: S) -> Self {
+        SteleBuilder {
+            storage,
+            growth: None,
+            first_block_exp: None,
+            capacity: 0,
+            bound: None,
+            align: 1,
+            prealloc: None,
+            #[cfg(feature = "metrics")]
+            metrics_label: None,
+            _type: PhantomData,
+        }
+    }
+
+    /// Allocates every block needed to hold `capacity` elements when the Stele is built, see [`WriteHandle::reserve`]
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Limits the Stele to at most `max_len` elements, see [`Stele::bounded`]
+    pub fn bound(mut self, max_len: usize) -> Self {
+        self.bound = Some(max_len);
+        self
+    }
+
+    /// Makes the first block hold 2<sup>`first_block_exp`</sup> elements, see [`Stele::with_first_block_exp_in`]
+    ///
+    /// This is a shorthand for [`GrowthPolicy::doubling`], so it cannot be combined with [`growth`](SteleBuilder::growth)
+    pub fn first_block_exp(mut self, first_block_exp: u32) -> Self {
+        self.first_block_exp = Some(first_block_exp);
+        self
+    }
+
+    /// Sizes the blocks by `growth`, see [`Stele::with_growth_in`]
+    pub fn growth(mut self, growth: GrowthPolicy) -> Self {
+        self.growth = Some(growth);
+        self
+    }
+
+    /// Aligns every block to at least `align` bytes, such as a cache line or a page, which must be a power of two
+    ///
+    /// Only the start of each block is aligned, the elements within it are laid out as in a slice
+    pub fn align(mut self, align: usize) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Chooses which blocks the first push allocates
+    pub fn prealloc(mut self, prealloc: Prealloc) -> Self {
+        self.prealloc = Some(prealloc);
+        self
+    }
+
+    /// Labels the metrics this Stele emits with `name = label`, so that they can be told apart from those of other Steles
+    ///
+    /// Every Stele built with the `metrics` feature counts its pushes in `stele.pushes` and the blocks it allocates in
+    /// `stele.blocks_allocated`, records the size of each of those blocks in the `stele.block_alloc_bytes` histogram,
+    /// and adds the bytes it holds to the `stele.allocated_bytes` gauge. Steles with the same label, or without one,
+    /// add up to the same metrics. Pushes are reported a block at a time and when the Stele is recycled or dropped,
+    /// so `stele.pushes` can lag behind the length by up to a block
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+    pub fn metrics_label(mut self, label: impl Into<Cow<'static, str>>) -> Self {
+        self.metrics_label = Some(label.into());
+        self
+    }
+
+    /// Allocates the blocks from `allocator` instead
+    #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "allocator_api", feature = "allocator-api2")))
+    )]
+    pub fn allocator<A: Storage>(self, allocator: A) -> SteleBuilder<T, A> {
+        SteleBuilder {
+            storage: allocator,
+            growth: self.growth,
+            first_block_exp: self.first_block_exp,
+            capacity: self.capacity,
+            bound: self.bound,
+            align: self.align,
+            prealloc: self.prealloc,
+            #[cfg(feature = "metrics")]
+            metrics_label: self.metrics_label,
+            _type: PhantomData,
+        }
+    }
+
+    /// Creates the Stele and returns a [`WriteHandle`] and [`ReadHandle`] to it
+    ///
+    /// # Errors
+    ///
+    /// See [`build_owned`](SteleBuilder::build_owned)
+    pub fn build(self) -> Result<Handles<T, S>, SteleError> {
+        self.build_owned().map(Stele::to_handles)
+    }
+
+    /// Creates the Stele without sharing it yet, so that it can be filled through `&mut` before calling [`to_handles`](Stele::to_handles)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SteleError::InvalidOptions`] if both [`first_block_exp`](SteleBuilder::first_block_exp) and [`growth`](SteleBuilder::growth) are set,
+    /// if [`Prealloc::SmallBlocks`] is combined with a growth policy other than the default, or if `align` is not a power of two.
+    ///
+    /// Returns [`SteleError::CapacityExceeded`] if the bound or the capacity is larger than the Stele can hold
+    pub fn build_owned(self) -> Result<Stele<T, S>, SteleError> {
+        self.check()?;
+        Ok(self.finish())
+    }
+
+    fn resolved_growth(&self) -> GrowthPolicy {
+        self.growth
+            .or_else(|| self.first_block_exp.map(GrowthPolicy::doubling))
+            .unwrap_or_default()
+    }
+
+    fn check(&self) -> Result<(), SteleError> {
+        if self.growth.is_some() && self.first_block_exp.is_some() {
+            return Err(SteleError::InvalidOptions {
+                reason: "`first_block_exp` and `growth` both choose the block sizes",
+            });
+        }
+        let growth = self.resolved_growth();
+        if self.prealloc == Some(Prealloc::SmallBlocks) && !growth.is_default() {
+            return Err(SteleError::InvalidOptions {
+                reason: "small blocks are only preallocated with the default growth policy",
+            });
+        }
+        if Layout::from_size_align(0, self.align).is_err() {
+            return Err(SteleError::InvalidOptions {
+                reason: "`align` must be a power of two",
+            });
+        }
+        if let (Some(bound), Some(max)) = (self.bound, growth.max_len()) {
+            if bound > max {
+                return Err(SteleError::CapacityExceeded {
+                    requested: bound,
+                    max,
+                });
+            }
+        }
+        if let Some(max) = self.bound.or_else(|| growth.max_len()) {
+            if self.capacity > max {
+                return Err(SteleError::CapacityExceeded {
+                    requested: self.capacity,
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the Stele, which the options must have been checked for unless they cannot conflict
+    pub(crate) fn finish(self) -> Stele<T, S> {
+        let mut s = Stele::empty_in(self.resolved_growth(), self.storage);
+        #[cfg(feature = "metrics")]
+        {
+            s.meter = Some(super::meter::Meter::new(self.metrics_label));
+        }
+        if self.bound.is_some() {
+            s.bound = self.bound;
+        }
+        s.raw.block_align = self.align;
+        if let Some(prealloc) = self.prealloc {
+            s.prealloc = prealloc;
+        }
+        if self.capacity > 0 {
+            //SAFETY: The Stele was just created, so there is no other writer
+            unsafe { s.reserve_blocks(self.capacity) };
+        }
+        s
+    }
+}
@@ -0,0 +1,201 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::Stele;
+use crate::{
+    error::SteleError,
+    mem::{DefaultStorage, Storage},
+    sync::ord,
+    Inner,
+};
+
+/// A part of the elements reserved with [`WriteHandle::reserve_blocks`](crate::WriteHandle::reserve_blocks),
+/// which lies within a single block and can be filled on its own thread
+///
+/// Nothing it writes is visible to readers until every [`BlockWriter`] of the reservation is handed to [`publish`](BlockWriter::publish)
+/// at once. Dropping one, including while unwinding from a panic, drops the elements it wrote and means the reservation can no longer be published
+#[derive(Debug)]
+pub struct BlockWriter<'w, T, S: Storage = DefaultStorage> {
+    stele: &'w Stele<T, S>,
+    //The first slot of the range, which lies within one allocated block
+    base: *mut Inner<T>,
+    range: Range<usize>,
+    //The end of the whole reservation, so that `publish` can tell whether any writer is missing
+    end: usize,
+    //One bit per element of the range, set once the element is written
+    written: Vec<usize>,
+    filled: usize,
+}
+
+//SAFETY: A BlockWriter only ever writes to the slots of its own range, which nothing else can reach until it is published,
+//so as long as the Stele, and therefore its items and storage, is both Send and Sync it can be moved to another thread
+unsafe impl<T, S: Storage> Send for BlockWriter<'_, T, S> where Stele<T, S>: Send + Sync {}
+
+impl<T, S: Storage> Stele<T, S> {
+    /// Allocates every block needed to hold `total` more elements and splits them into one [`BlockWriter`] per block
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Stele is bounded and does not have room for `total` more elements
+    ///
+    /// SAFETY: You must be the only writer, and nothing may be pushed until the writers are published or dropped
+    pub(crate) unsafe fn reserve_block_writers(&self, total: usize) -> Vec<BlockWriter<'_, T, S>> {
+        assert!(
+            !matches!(self.remaining(), Some(remaining) if remaining < total),
+            "Pushed to a full Stele"
+        );
+        let start = self.raw.len.load(ord::ACQ);
+        let end = start + total;
+        //SAFETY: By the safety contract we are the only writer
+        unsafe { self.reserve_blocks(total) };
+        let mut writers = Vec::new();
+        let mut idx = start;
+        while idx < end {
+            let (outer_idx, inner_idx) = self.raw.split_idx(idx);
+            let range = idx..end.min(idx - inner_idx + self.raw.block_len(outer_idx));
+            let words = range.len().div_ceil(super::BITS);
+            writers.push(BlockWriter {
+                stele: self,
+                //SAFETY: The block holding `idx` was allocated above
+                base: unsafe { self.raw.read_raw(idx) },
+                range: range.clone(),
+                end,
+                written: alloc::vec![0; words],
+                filled: 0,
+            });
+            idx = range.end;
+        }
+        writers
+    }
+}
+
+impl<T, S: Storage> BlockWriter<'_, T, S> {
+    /// Returns the indices of the [`Stele`] this writer fills
+    #[must_use]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the number of elements this writer has to fill
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    /// Returns `true` if this writer has no elements to fill
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    /// Returns `true` once every element of the range has been written
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.filled == self.len()
+    }
+
+    /// Writes `val` to the element `offset` places into the range, dropping any value written there before
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is not below [`len`](BlockWriter::len)
+    pub fn write(&mut self, offset: usize, val: T) {
+        assert!(
+            offset < self.len(),
+            "Offset {} is out of range for a block writer of length {}",
+            offset,
+            self.len()
+        );
+        let (word, bit) = (offset / super::BITS, 1 << (offset % super::BITS));
+        //SAFETY: The slot is within the range, which lies in one allocated block and is only reachable through this writer
+        let slot = unsafe { &mut *self.base.add(offset) };
+        if self.written[word] & bit == 0 {
+            *slot = Inner::new(val);
+            self.written[word] |= bit;
+            self.filled += 1;
+        } else {
+            //SAFETY: The bit is only set once the slot holds a value
+            unsafe { slot.drop_in_place() };
+            *slot = Inner::new(val);
+        }
+    }
+
+    /// Writes the items of `iter` to the range in order, starting at its first element, and returns how many were written
+    ///
+    /// Writing stops once the range is full, leaving the rest of `iter` untouched
+    pub fn fill_from_iter<I: IntoIterator<Item = T>>(&mut self, iter: I) -> usize {
+        let mut count = 0;
+        for val in iter.into_iter().take(self.len()) {
+            self.write(count, val);
+            count += 1;
+        }
+        count
+    }
+
+    /// Makes the elements of every writer of a reservation visible to readers by publishing the new length once
+    ///
+    /// # Errors
+    ///
+    /// If any writer of the reservation is missing or has not finished, nothing is published and every written element is dropped.
+    /// The error reports how many of the reserved elements had been written
+    ///
+    /// # Panics
+    ///
+    /// Panics if the writers do not all belong to the same [`Stele`]
+    pub fn publish(mut writers: Vec<Self>) -> Result<(), SteleError> {
+        let Some(first) = writers.first() else {
+            return Ok(());
+        };
+        let stele = first.stele;
+        assert!(
+            writers
+                .iter()
+                .all(|writer| core::ptr::eq(writer.stele, stele)),
+            "Published block writers of different Steles together"
+        );
+        writers.sort_unstable_by_key(|writer| writer.range.start);
+        let start = stele.raw.len.load(ord::ACQ);
+        let end = writers[0].end;
+        let mut next = start;
+        let mut complete = true;
+        for writer in &writers {
+            complete &= writer.range.start == next && writer.is_finished();
+            next = writer.range.end;
+        }
+        if !complete || next != end {
+            return Err(SteleError::Unfilled {
+                filled: writers.iter().map(|writer| writer.filled).sum(),
+                reserved: end - start,
+            });
+        }
+        for mut writer in writers {
+            //Only blocks that have held a reservation from `push_uninit` track which elements are initialized
+            let (outer_idx, _) = stele.raw.split_idx(writer.range.start);
+            if !stele.filled[outer_idx].load(ord::ACQ).is_null() {
+                for idx in writer.range.clone() {
+                    stele.mark_initialized(idx, ord::RLX);
+                }
+            }
+            //The elements now belong to the Stele
+            writer.filled = 0;
+            writer.written.clear();
+        }
+        stele.raw.len.store(end, ord::REL);
+        stele.notify_readers();
+        Ok(())
+    }
+}
+
+impl<T, S: Storage> Drop for BlockWriter<'_, T, S> {
+    fn drop(&mut self) {
+        if !core::mem::needs_drop::<T>() {
+            return;
+        }
+        for (word, &bits) in self.written.iter().enumerate() {
+            for bit in (0..super::BITS).filter(|bit| bits & (1 << bit) != 0) {
+                //SAFETY: The bit is only set once the slot holds a value, which nothing else can reach
+                unsafe { (*self.base.add(word * super::BITS + bit)).drop_in_place() };
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use core::{
+    cell::Cell,
+    ops::Index,
+    panic::{RefUnwindSafe, UnwindSafe},
+    ptr::null_mut,
+};
+
+use super::{reader::ReadHandle, Stele};
+use crate::{
+    mem::{DefaultStorage, Storage},
+    Inner,
+};
+
+/// A [`ReadHandle`] that remembers the start of every block it has read from, created by [`ReadHandle::cached`]
+///
+/// Reading an element normally loads the pointer to its block from the [`Stele`] every time, which adds up for
+/// random access such as binary searches or following links between elements. This handle keeps its own copy of
+/// every block pointer it has loaded, so reading from a block it has seen before only loads the length.
+///
+/// The copies need no synchronization: a block holding an element below the length is never freed or replaced while
+/// any handle is alive, as only blocks past the length are freed by [`shrink_unused`](crate::WriteHandle::shrink_unused)
+/// and everything else that frees blocks needs the [`Stele`] itself. Since the copies are kept in [`Cell`]s the handle
+/// can be sent to another thread but not shared between them, so every thread needs its own, and [`Clone`] starts over
+/// with none remembered
+#[derive(Debug)]
+pub struct CachedReadHandle<T, S: Storage = DefaultStorage> {
+    pub(crate) handle: ReadHandle<T, S>,
+    //The start of every block an element was read from, or null for the blocks that have not been read from yet
+    blocks: [Cell<*mut Inner<T>>; 32],
+}
+
+//SAFETY: The block pointers are only ever copies of the ones in the Stele, which this handle shares like a ReadHandle,
+//and the cells keep it from being Sync
+unsafe impl<T, S: Storage> Send for CachedReadHandle<T, S> where Stele<T, S>: Send + Sync {}
+
+//Every cell only ever goes from null to the pointer the Stele holds for its block, so a panic cannot leave one wrong
+impl<T: RefUnwindSafe, S: Storage + RefUnwindSafe> UnwindSafe for CachedReadHandle<T, S> {}
+impl<T: RefUnwindSafe, S: Storage + RefUnwindSafe> RefUnwindSafe for CachedReadHandle<T, S> {}
+
+impl<T, S: Storage> CachedReadHandle<T, S> {
+    pub(crate) fn new(handle: ReadHandle<T, S>) -> Self {
+        CachedReadHandle {
+            handle,
+            blocks: [(); 32].map(|()| Cell::new(null_mut())),
+        }
+    }
+
+    /// Reads the value at the given index
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds or the element is a reservation that has not been filled.
+    /// Unlike [`ReadHandle::read`], the bound is checked in every build
+    #[must_use]
+    pub fn read(&self, idx: usize) -> &T {
+        self.try_read(idx)
+            .expect("Read past the initialized elements")
+    }
+
+    /// Attempts to read the value at the index and returns [`Some`] if the value exists, and [`None`] otherwise
+    #[must_use]
+    pub fn try_read(&self, idx: usize) -> Option<&T> {
+        if idx >= self.handle.len() || !self.handle.handle.is_initialized(idx) {
+            return None;
+        }
+        //SAFETY: The element is below the length and initialized
+        Some(unsafe { self.read_unchecked(idx) })
+    }
+
+    /// Returns the number of elements in the [`Stele`], see [`ReadHandle::len`]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handle.len()
+    }
+
+    /// Returns `true` if the [`Stele`] holds no elements
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.handle.is_empty()
+    }
+
+    /// Returns the [`ReadHandle`] this reads through, forgetting every block pointer
+    #[must_use]
+    pub fn into_inner(self) -> ReadHandle<T, S> {
+        self.handle
+    }
+
+    /// SAFETY: `idx` must be below the length and initialized
+    unsafe fn read_unchecked(&self, idx: usize) -> &T {
+        let (outer_idx, inner_idx) = self.handle.handle.raw.split_idx(idx);
+        let mut block = self.blocks[outer_idx].get();
+        if block.is_null() {
+            //Having seen a length past `idx` means the block is allocated, and it stays the same from now on
+            block = self.handle.handle.raw.block(outer_idx);
+            self.blocks[outer_idx].set(block);
+        }
+        //SAFETY: The block holds `idx`, which is initialized by the safety contract
+        unsafe { (*block.add(inner_idx)).read() }
+    }
+}
+
+impl<T: Copy, S: Storage> CachedReadHandle<T, S> {
+    /// Returns a copy of the value at the given index, see [`read`](CachedReadHandle::read)
+    ///
+    /// # Panic
+    ///
+    /// Panics like [`read`](CachedReadHandle::read)
+    #[must_use]
+    pub fn get(&self, idx: usize) -> T {
+        *self.read(idx)
+    }
+}
+
+impl<T, S: Storage> Clone for CachedReadHandle<T, S> {
+    fn clone(&self) -> Self {
+        Self::new(self.handle.clone())
+    }
+}
+
+impl<T, S: Storage> Index<usize> for CachedReadHandle<T, S> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.read(index)
+    }
+}
+
+impl<T, S: Storage> From<ReadHandle<T, S>> for CachedReadHandle<T, S> {
+    fn from(handle: ReadHandle<T, S>) -> Self {
+        Self::new(handle)
+    }
+}
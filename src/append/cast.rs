@@ -0,0 +1,144 @@
+use core::{
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ptr::{addr_of, from_ref, read},
+};
+
+use bytemuck::TransparentWrapper;
+
+use super::{reader::ReadHandle, writer::WriteHandle, Stele};
+use crate::{mem::Storage, sync::Arc};
+
+//Viewing a `Stele<T, S>` as a `Stele<U, S>` when `U: TransparentWrapper<T>` is sound because:
+//
+//- By the safety contract of `TransparentWrapper`, `U` has the same size, alignment and validity as `T`,
+//  and any `T` may be wrapped into a `U` or a `U` peeled into a `T` by value or by reference.
+//- `Inner<T>` is `repr(transparent)` over `MaybeUninit<UnsafeCell<T>>`, so `Inner<U>` has the same layout as `Inner<T>`.
+//  Block `idx` holds the same number of elements either way, since the `GrowthPolicy`, `block_align`, the preallocated
+//  blocks and the `Layout` of a block only depend on the index and on the size and alignment of the element. Every block
+//  is therefore read, written and freed at the same addresses and with the same layout through either type.
+//- `Stele`, `RawStele` and `Adopted` are `repr(C)`, so their layouts only depend on the layouts of their fields,
+//  and the only fields that mention the element type are pointers to `Inner<T>`, which do not depend on it.
+//  The drop hook is type erased for this reason, as a `dyn Fn(&T)` would carry a vtable for `T`,
+//  and it casts the element back to the type it was set for before calling the closure.
+//- `ReadHandle` and `WriteHandle` are `repr(transparent)` over the `Arc`, so a reference to a handle
+//  can be cast along with the `Stele` it points to. Owned handles go through `Arc::into_raw` and `Arc::from_raw`,
+//  which allow the pointee type to change to one with the same size and alignment.
+//- Any thread holding a `&T` could already wrap it into a `&U` with `TransparentWrapper::wrap_ref`, so readers of one
+//  type sharing elements with readers of the other allows nothing that `bytemuck` does not. For the same reason,
+//  the elements may be dropped as either type, whichever the last handle to go has.
+//
+//The `Arc`s of loom and shuttle do not document casting the pointee, so this is left out of their models
+
+/// SAFETY: `U` must have the same size, alignment and validity as `T`
+unsafe fn cast_arc<T, U, S: Storage>(handle: Arc<Stele<T, S>>) -> Arc<Stele<U, S>> {
+    //SAFETY: `Stele<U, S>` has the same layout as `Stele<T, S>`, see above
+    unsafe { Arc::from_raw(Arc::into_raw(handle).cast::<Stele<U, S>>()) }
+}
+
+/// SAFETY: `U` must have the same size, alignment and validity as `T`
+unsafe fn cast_writer<T, U, S: Storage>(writer: WriteHandle<T, S>) -> WriteHandle<U, S> {
+    //The writer is moved into the new handle, so it must not be closed by dropping the old one
+    let writer = ManuallyDrop::new(writer);
+    //SAFETY: The handle is only read once and the old writer is never dropped, so the count of the `Arc` stays the same
+    let handle = unsafe { read(addr_of!(writer.handle)) };
+    WriteHandle {
+        //SAFETY: By the safety contract `U` has the same layout as `T`
+        handle: unsafe { cast_arc(handle) },
+        _unsync: PhantomData,
+    }
+}
+
+impl<T, S: Storage> ReadHandle<T, S> {
+    /// Turns this into a reader of `U`, which is a [`TransparentWrapper`] of `T`, without copying any elements
+    ///
+    /// Every other handle to the Stele keeps its own type, so the same elements can be read as `T` through them
+    /// and as `U` through this one. The elements are dropped as whichever type the last handle to be dropped has
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    #[must_use]
+    pub fn cast<U: TransparentWrapper<T>>(self) -> ReadHandle<U, S> {
+        ReadHandle {
+            //SAFETY: `U` has the same layout and validity as `T` by the safety contract of `TransparentWrapper`
+            handle: unsafe { cast_arc(self.handle) },
+        }
+    }
+
+    /// Views this as a reader of `U`, which is a [`TransparentWrapper`] of `T`, see [`cast`](ReadHandle::cast)
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    #[must_use]
+    pub fn cast_ref<U: TransparentWrapper<T>>(&self) -> &ReadHandle<U, S> {
+        //SAFETY: `ReadHandle` is transparent over an `Arc` of a `Stele`, which has the same layout for `U` as for `T`,
+        //and `U` has the same validity as `T`
+        unsafe { &*from_ref(self).cast::<ReadHandle<U, S>>() }
+    }
+
+    /// Turns this into a reader of `I`, which `T` is a [`TransparentWrapper`] of, undoing [`cast`](ReadHandle::cast)
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    #[must_use]
+    pub fn peel<I>(self) -> ReadHandle<I, S>
+    where
+        T: TransparentWrapper<I>,
+    {
+        ReadHandle {
+            //SAFETY: `T` has the same layout and validity as `I` by the safety contract of `TransparentWrapper`
+            handle: unsafe { cast_arc(self.handle) },
+        }
+    }
+
+    /// Views this as a reader of `I`, which `T` is a [`TransparentWrapper`] of, undoing [`cast_ref`](ReadHandle::cast_ref)
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    #[must_use]
+    pub fn peel_ref<I>(&self) -> &ReadHandle<I, S>
+    where
+        T: TransparentWrapper<I>,
+    {
+        //SAFETY: As in `cast_ref`, with the roles of the types swapped
+        unsafe { &*from_ref(self).cast::<ReadHandle<I, S>>() }
+    }
+}
+
+impl<T, S: Storage> WriteHandle<T, S> {
+    /// Turns this into the writer of `U`, which is a [`TransparentWrapper`] of `T`, without copying any elements
+    ///
+    /// Pushes of `U` are read as `T` by the readers that were created before, and the other way around.
+    /// The elements are dropped as whichever type the last handle to be dropped has, and a drop hook set through
+    /// either type keeps being called with the type it was set for
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    #[must_use]
+    pub fn cast<U: TransparentWrapper<T>>(self) -> WriteHandle<U, S> {
+        //SAFETY: `U` has the same layout and validity as `T` by the safety contract of `TransparentWrapper`
+        unsafe { cast_writer(self) }
+    }
+
+    /// Views this as the writer of `U`, which is a [`TransparentWrapper`] of `T`, so that pushes can go through either type,
+    /// see [`cast`](WriteHandle::cast)
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    #[must_use]
+    pub fn cast_ref<U: TransparentWrapper<T>>(&self) -> &WriteHandle<U, S> {
+        //SAFETY: `WriteHandle` is transparent over an `Arc` of a `Stele`, which has the same layout for `U` as for `T`,
+        //and `U` has the same validity as `T`. The view borrows this writer, so there is still only one at a time
+        unsafe { &*from_ref(self).cast::<WriteHandle<U, S>>() }
+    }
+
+    /// Turns this into the writer of `I`, which `T` is a [`TransparentWrapper`] of, undoing [`cast`](WriteHandle::cast)
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    #[must_use]
+    pub fn peel<I>(self) -> WriteHandle<I, S>
+    where
+        T: TransparentWrapper<I>,
+    {
+        //SAFETY: `T` has the same layout and validity as `I` by the safety contract of `TransparentWrapper`
+        unsafe { cast_writer(self) }
+    }
+
+    /// Views this as the writer of `I`, which `T` is a [`TransparentWrapper`] of, undoing [`cast_ref`](WriteHandle::cast_ref)
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    #[must_use]
+    pub fn peel_ref<I>(&self) -> &WriteHandle<I, S>
+    where
+        T: TransparentWrapper<I>,
+    {
+        //SAFETY: As in `cast_ref`, with the roles of the types swapped
+        unsafe { &*from_ref(self).cast::<WriteHandle<I, S>>() }
+    }
+}
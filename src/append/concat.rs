@@ -0,0 +1,165 @@
+use alloc::{string::String, vec::Vec};
+
+use super::reader::ReadHandle;
+use crate::mem::Storage;
+
+/// Element types that [`ReadHandle::concat`] and [`ReadHandle::join`] can flatten into a single collection,
+/// mirroring what [`[T]::concat`](slice::concat) and [`[T]::join`](slice::join) accept
+///
+/// Strings are flattened into a [`String`] and vectors and slices into a [`Vec`]
+pub trait Concat {
+    /// The collection every element is appended to
+    type Output;
+    /// The separator [`join`](ReadHandle::join) puts between elements
+    type Separator: ?Sized;
+
+    /// Returns the length this element adds to the output
+    fn output_len(&self) -> usize;
+
+    /// Returns the length the separator adds to the output
+    fn separator_len(sep: &Self::Separator) -> usize;
+
+    /// Creates an empty output with room for `capacity`
+    fn with_capacity(capacity: usize) -> Self::Output;
+
+    /// Appends this element to the output
+    fn extend_output(&self, output: &mut Self::Output);
+
+    /// Appends the separator to the output
+    fn extend_separator(sep: &Self::Separator, output: &mut Self::Output);
+}
+
+impl Concat for String {
+    type Output = String;
+    type Separator = str;
+
+    fn output_len(&self) -> usize {
+        self.len()
+    }
+
+    fn separator_len(sep: &str) -> usize {
+        sep.len()
+    }
+
+    fn with_capacity(capacity: usize) -> String {
+        String::with_capacity(capacity)
+    }
+
+    fn extend_output(&self, output: &mut String) {
+        output.push_str(self);
+    }
+
+    fn extend_separator(sep: &str, output: &mut String) {
+        output.push_str(sep);
+    }
+}
+
+impl Concat for &str {
+    type Output = String;
+    type Separator = str;
+
+    fn output_len(&self) -> usize {
+        self.len()
+    }
+
+    fn separator_len(sep: &str) -> usize {
+        sep.len()
+    }
+
+    fn with_capacity(capacity: usize) -> String {
+        String::with_capacity(capacity)
+    }
+
+    fn extend_output(&self, output: &mut String) {
+        output.push_str(self);
+    }
+
+    fn extend_separator(sep: &str, output: &mut String) {
+        output.push_str(sep);
+    }
+}
+
+impl<T: Clone> Concat for Vec<T> {
+    type Output = Vec<T>;
+    type Separator = [T];
+
+    fn output_len(&self) -> usize {
+        self.len()
+    }
+
+    fn separator_len(sep: &[T]) -> usize {
+        sep.len()
+    }
+
+    fn with_capacity(capacity: usize) -> Vec<T> {
+        Vec::with_capacity(capacity)
+    }
+
+    fn extend_output(&self, output: &mut Vec<T>) {
+        output.extend_from_slice(self);
+    }
+
+    fn extend_separator(sep: &[T], output: &mut Vec<T>) {
+        output.extend_from_slice(sep);
+    }
+}
+
+impl<T: Clone> Concat for &[T] {
+    type Output = Vec<T>;
+    type Separator = [T];
+
+    fn output_len(&self) -> usize {
+        self.len()
+    }
+
+    fn separator_len(sep: &[T]) -> usize {
+        sep.len()
+    }
+
+    fn with_capacity(capacity: usize) -> Vec<T> {
+        Vec::with_capacity(capacity)
+    }
+
+    fn extend_output(&self, output: &mut Vec<T>) {
+        output.extend_from_slice(self);
+    }
+
+    fn extend_separator(sep: &[T], output: &mut Vec<T>) {
+        output.extend_from_slice(sep);
+    }
+}
+
+impl<T: Concat, S: Storage> ReadHandle<T, S> {
+    /// Flattens every element into one collection, like [`[T]::concat`](slice::concat)
+    ///
+    /// Only the elements initialized when this is called are included. Their lengths are summed first,
+    /// so the output is allocated exactly once
+    #[must_use]
+    pub fn concat(&self) -> T::Output {
+        let len = self.initialized_len();
+        let capacity = self.iter_range(..len).map(T::output_len).sum();
+        let mut output = T::with_capacity(capacity);
+        self.iter_range(..len)
+            .for_each(|element| element.extend_output(&mut output));
+        output
+    }
+
+    /// Flattens every element into one collection with `sep` between each of them, like [`[T]::join`](slice::join)
+    ///
+    /// Only the elements initialized when this is called are included. Their lengths are summed first,
+    /// so the output is allocated exactly once
+    #[must_use]
+    pub fn join(&self, sep: &T::Separator) -> T::Output {
+        let len = self.initialized_len();
+        let capacity = self.iter_range(..len).map(T::output_len).sum::<usize>()
+            + len.saturating_sub(1) * T::separator_len(sep);
+        let mut output = T::with_capacity(capacity);
+        for (idx, element) in self.iter_range(..len).enumerate() {
+            if idx > 0 {
+                T::extend_separator(sep, &mut output);
+            }
+            element.extend_output(&mut output);
+        }
+        output
+    }
+}
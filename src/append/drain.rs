@@ -0,0 +1,72 @@
+use std::{sync::mpsc::Receiver, time::Instant};
+
+use super::writer::WriteHandle;
+use crate::mem::Storage;
+
+//Draining is done for the pushes, so the counts are there to be looked at rather than to be used
+#[allow(clippy::must_use_candidate)]
+impl<T, S: Storage> WriteHandle<T, S> {
+    /// Pushes every message waiting in `rx` without blocking, returning how many were pushed
+    ///
+    /// The length is published once per block the messages fill rather than once per message.
+    /// If the [`Stele`](super::Stele) is [bounded](super::Stele::bounded), messages that do not fit are left in the channel
+    pub fn drain_channel(&self, rx: &Receiver<T>) -> usize {
+        //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+        unsafe { self.handle.push_batched(|| rx.try_recv().ok()) }
+    }
+
+    /// Pushes the messages from `rx` as they arrive until every sender is dropped or `deadline` passes,
+    /// returning how many were pushed
+    ///
+    /// Each wait is followed by pushing every message that is already waiting as one batch, see
+    /// [`drain_channel`](WriteHandle::drain_channel). This also returns once a [bounded](super::Stele::bounded)
+    /// [`Stele`](super::Stele) is full, leaving the rest of the messages in the channel
+    pub fn drain_channel_blocking(&self, rx: &Receiver<T>, deadline: Instant) -> usize {
+        let mut pushed = 0;
+        while !self.is_full() {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let mut first = match rx.recv_timeout(timeout) {
+                Ok(first) => Some(first),
+                Err(_) => break,
+            };
+            //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+            pushed += unsafe {
+                self.handle
+                    .push_batched(|| first.take().or_else(|| rx.try_recv().ok()))
+            };
+        }
+        pushed
+    }
+
+    /// Pushes every message waiting in the crossbeam channel `rx` without blocking, see [`drain_channel`](WriteHandle::drain_channel)
+    #[cfg(feature = "crossbeam-channel")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "crossbeam-channel")))]
+    pub fn drain_crossbeam(&self, rx: &crossbeam_channel::Receiver<T>) -> usize {
+        //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+        unsafe { self.handle.push_batched(|| rx.try_recv().ok()) }
+    }
+
+    /// Pushes the messages from the crossbeam channel `rx` as they arrive until every sender is dropped or `deadline` passes,
+    /// see [`drain_channel_blocking`](WriteHandle::drain_channel_blocking)
+    #[cfg(feature = "crossbeam-channel")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "crossbeam-channel")))]
+    pub fn drain_crossbeam_blocking(
+        &self,
+        rx: &crossbeam_channel::Receiver<T>,
+        deadline: Instant,
+    ) -> usize {
+        let mut pushed = 0;
+        while !self.is_full() {
+            let mut first = match rx.recv_deadline(deadline) {
+                Ok(first) => Some(first),
+                Err(_) => break,
+            };
+            //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+            pushed += unsafe {
+                self.handle
+                    .push_batched(|| first.take().or_else(|| rx.try_recv().ok()))
+            };
+        }
+        pushed
+    }
+}
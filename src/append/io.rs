@@ -0,0 +1,142 @@
+extern crate std;
+
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io;
+
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncWrite, ReadBuf};
+
+use super::{reader::ReadHandle, writer::WriteHandle};
+use crate::mem::{DefaultStorage, Storage};
+
+///An [`AsyncRead`] and [`AsyncBufRead`] over the bytes of a [`Stele`](super::Stele), starting at the first byte
+///
+///Reaching the end does not end the reader: it waits for more bytes to be pushed like a [`SteleStream`](super::stream::SteleStream),
+///and only reports the end once the [`WriteHandle`] is dropped or [shut down](AsyncWrite::poll_shutdown)
+#[derive(Debug)]
+pub struct SteleReader<S: Storage = DefaultStorage> {
+    handle: ReadHandle<u8, S>,
+    pos: usize,
+}
+
+impl<S: Storage> SteleReader<S> {
+    ///Creates a new [`SteleReader`] starting at the first byte
+    #[must_use]
+    pub fn new(handle: ReadHandle<u8, S>) -> Self {
+        Self { handle, pos: 0 }
+    }
+
+    ///Returns the number of bytes read so far
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    ///Returns the [`ReadHandle`] the bytes are read through
+    #[must_use]
+    pub fn into_inner(self) -> ReadHandle<u8, S> {
+        self.handle
+    }
+
+    /// Returns the bytes from the current position up to the end of its block, waiting for more if there are none
+    ///
+    /// An empty slice means the writer is gone and everything has been read
+    fn poll_available(&mut self, cx: &mut Context<'_>) -> Poll<&[u8]> {
+        let stele = &*self.handle.handle;
+        let end = stele.initialized_len();
+        if self.pos < end {
+            //SAFETY: Everything below the initialized length is initialized
+            return Poll::Ready(unsafe { stele.block_slice(self.pos, end) });
+        }
+        let closed = stele.register_waker(cx.waker());
+        //Check again now that the waker is registered, in case a push landed in between
+        let end = stele.initialized_len();
+        if self.pos < end {
+            //SAFETY: Everything below the initialized length is initialized
+            Poll::Ready(unsafe { stele.block_slice(self.pos, end) })
+        } else if closed {
+            Poll::Ready(&[])
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<S: Storage> AsyncRead for SteleReader<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let mut read = 0;
+        //Keep copying across block boundaries for as long as bytes are available without waiting
+        while buf.remaining() > 0 {
+            let available = match this.poll_available(cx) {
+                Poll::Ready(available) => available,
+                Poll::Pending if read > 0 => break,
+                Poll::Pending => return Poll::Pending,
+            };
+            if available.is_empty() {
+                break;
+            }
+            let len = available.len().min(buf.remaining());
+            buf.put_slice(&available[..len]);
+            this.pos += len;
+            read += len;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S: Storage> AsyncBufRead for SteleReader<S> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.get_mut().poll_available(cx).map(Ok)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().pos += amt;
+    }
+}
+
+///Pushing never has to wait, so writes are always ready. Each call to `poll_write` copies the bytes block by block and
+///publishes the new length once per block
+///
+///Shutting down tells every [`SteleReader`] and [`SteleStream`](super::stream::SteleStream) that nothing more is coming,
+///after which writes fail with [`BrokenPipe`](io::ErrorKind::BrokenPipe). Once a [bounded](super::Stele::bounded) [`Stele`](super::Stele)
+///is full, writes return `Ok(0)`
+impl<S: Storage> AsyncWrite for WriteHandle<u8, S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.handle.streams_closed() {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "wrote to a WriteHandle that was shut down",
+            )));
+        }
+        let mut written = 0;
+        while written < buf.len() {
+            //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+            //and can only be used by one thread at a time
+            match unsafe { self.handle.extend_within_block(&buf[written..]) } {
+                0 => break,
+                pushed => written += pushed,
+            }
+        }
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.handle.close_streams();
+        Poll::Ready(Ok(()))
+    }
+}
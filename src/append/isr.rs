@@ -0,0 +1,100 @@
+use super::{ReadHandle, Stele, WriteHandle};
+use crate::{
+    mem::{DefaultStorage, Storage},
+    PushError,
+};
+
+/// A writer for a [`Stele`] that can be shared between thread and interrupt context, returned by
+/// [`WriteHandle::into_isr_shared`]
+///
+/// Unlike [`WriteHandle`] this is `Sync`, as every push happens inside [`critical_section::with`], so pushes from the
+/// main loop and from interrupt handlers never overlap. Reads never enter a critical section and stay lock-free.
+///
+/// - ## How long is the critical section held?
+///
+/// Each push holds it for one slot write and the store of the new length, plus waking any
+/// [notifier](Stele::set_notifier) and streams. It never allocates: pushing past the allocated capacity fails instead,
+/// so the blocks have to be allocated up front with [`WriteHandle::reserve`] before the handle is shared.
+#[derive(Debug)]
+pub struct IsrWriteHandle<T, S: Storage = DefaultStorage> {
+    pub(crate) writer: WriteHandle<T, S>,
+}
+
+//SAFETY: The only way to write through a shared IsrWriteHandle is `push_within_capacity`, which runs inside a
+//critical section so that there is only ever one writer at a time, and everything else only reads
+unsafe impl<T, S: Storage> Sync for IsrWriteHandle<T, S> where Stele<T, S>: Send + Sync {}
+
+impl<T, S: Storage> IsrWriteHandle<T, S> {
+    /// Pushes a new item on to the end of the [`Stele`] inside a critical section if that does not require allocating a new block,
+    /// returning the item otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns `val` in a [`PushError`] with [`SteleError::CapacityExceeded`](crate::SteleError::CapacityExceeded) if the block it belongs in has not been allocated
+    /// or the [`Stele`] is [bounded](Stele::bounded) and full
+    pub fn push_within_capacity(&self, val: T) -> Result<(), PushError<T>> {
+        critical_section::with(|_| self.writer.push_within_capacity(val))
+    }
+
+    /// Creates a new [`ReadHandle`]
+    #[must_use]
+    pub fn new_read_handle(&self) -> ReadHandle<T, S> {
+        self.writer.new_read_handle()
+    }
+
+    /// Reads the value at the given index
+    ///
+    /// # Panic
+    ///
+    /// This function panics in debug if the given index is out of bounds.
+    #[must_use]
+    pub fn read(&self, idx: usize) -> &T {
+        self.writer.read(idx)
+    }
+
+    /// Attempts to read the value at the index and returns [`Some`] if the value exists, and [`None`] otherwise
+    #[must_use]
+    pub fn try_read(&self, idx: usize) -> Option<&T> {
+        self.writer.try_read(idx)
+    }
+
+    /// Returns the current length of the underlying [`Stele`]
+    ///
+    /// Note:
+    /// Unlike with a [`WriteHandle`], an interrupt handler may push at any time, so this can be outdated by the time it is used
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.writer.len()
+    }
+
+    /// Returns whether the underlying [`Stele`] is empty or not
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.writer.is_empty()
+    }
+
+    /// Returns the number of elements the currently allocated blocks can hold, which no push through this handle can go beyond
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.writer.capacity()
+    }
+
+    /// Turns the handle back into a [`WriteHandle`], which can allocate again
+    #[must_use]
+    pub fn into_writer(self) -> WriteHandle<T, S> {
+        self.writer
+    }
+}
+
+impl<T: Copy, S: Storage> IsrWriteHandle<T, S> {
+    /// Get provides a way to get an owned copy of a value inside a [`Stele`]
+    /// provided the type `T` implements [`Copy`]
+    ///
+    /// # Panic
+    ///
+    /// This function panics in debug if the given index is out of bounds
+    #[must_use]
+    pub fn get(&self, idx: usize) -> T {
+        self.writer.get(idx)
+    }
+}
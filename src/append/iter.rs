@@ -1,49 +1,465 @@
-use super::reader::ReadHandle;
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{
+    marker::PhantomData,
+    ops::{Bound, Range, RangeBounds},
+};
+
+use super::{key::Key, reader::ReadHandle, Stele};
+use crate::mem::{DefaultStorage, Storage};
 
 ///An iterator that yields items by reference
 #[derive(Debug)]
-pub struct RefIterator<'rh, T> {
-    handle: &'rh ReadHandle<T>,
+pub struct RefIterator<'rh, T, S: Storage = DefaultStorage> {
+    handle: &'rh Stele<T, S>,
     pos: usize,
     len: usize,
 }
 
-impl<'rh, T> RefIterator<'rh, T> {
+impl<'rh, T, S: Storage> RefIterator<'rh, T, S> {
     ///Creates a new [`RefIterator`], borrowing the handle until dropped
     #[must_use]
-    pub fn new(handle: &'rh ReadHandle<T>) -> Self {
+    pub fn new(handle: &'rh ReadHandle<T, S>) -> Self {
+        Self::from_stele(&handle.handle)
+    }
+
+    pub(crate) fn from_stele(handle: &'rh Stele<T, S>) -> Self {
+        RefIterator {
+            handle,
+            pos: 0,
+            len: handle.initialized_len(),
+        }
+    }
+
+    ///Creates a [`RefIterator`] over the first `len` elements without loading the length
+    ///
+    ///SAFETY: The first `len` elements must be initialized
+    pub(crate) unsafe fn assume_len(handle: &'rh Stele<T, S>, len: usize) -> Self {
         RefIterator {
             handle,
             pos: 0,
-            len: handle.len(),
+            len,
+        }
+    }
+
+    ///Creates a new [`RefIterator`] over the elements of `range` that are initialized when this is called
+    ///
+    ///Both ends of the range are clamped to the initialized length, so a range past the end yields nothing instead of panicking
+    #[must_use]
+    pub fn new_range(handle: &'rh ReadHandle<T, S>, range: impl RangeBounds<usize>) -> Self {
+        let len = handle.initialized_len();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        let end = end.min(len);
+        RefIterator {
+            handle: &handle.handle,
+            pos: start.min(end),
+            len: end,
+        }
+    }
+
+    ///Returns the indices of the elements that have not been yielded yet
+    pub(crate) fn remaining_range(&self) -> Range<usize> {
+        self.pos..self.len
+    }
+
+    ///Groups runs of adjacent elements for which `pred` returns `true` into [`Group`]s, like [`slice::chunk_by`]
+    ///
+    ///`pred` is called with every pair of neighbouring elements, and a new group starts wherever it returns `false`.
+    ///Groups are ranges of indices, so they cost nothing extra when they straddle block boundaries
+    pub fn chunk_by<F>(self, pred: F) -> ChunkBy<'rh, T, S, F>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        ChunkBy { iter: self, pred }
+    }
+}
+
+impl<'rh, T, S: Storage> Iterator for RefIterator<'rh, T, S> {
+    type Item = &'rh T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.len > self.pos).then(|| {
+            self.pos += 1;
+            self.handle.read_at(self.pos - 1)
+        })
+    }
+
+    fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        //SAFETY: The iterator never goes past the initialized length it was created with
+        unsafe { self.handle.fold_blocks(self.pos, self.len, init, f) }
+    }
+}
+
+///An iterator that yields every element by reference along with its [`Key`], created by [`ReadHandle::iter_keyed`]
+///
+///Only the elements initialized when the iterator was created are covered
+#[derive(Debug)]
+pub struct KeyedIter<'rh, T, S: Storage = DefaultStorage> {
+    iter: RefIterator<'rh, T, S>,
+}
+
+impl<'rh, T, S: Storage> KeyedIter<'rh, T, S> {
+    pub(crate) fn new(iter: RefIterator<'rh, T, S>) -> Self {
+        Self { iter }
+    }
+}
+
+impl<'rh, T, S: Storage> Iterator for KeyedIter<'rh, T, S> {
+    type Item = (Key<T>, &'rh T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.iter.pos;
+        self.iter.next().map(|val| (Key::new(idx), val))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.iter.len - self.iter.pos;
+        (remaining, Some(remaining))
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let mut idx = self.iter.pos;
+        self.iter.fold(init, |acc, val| {
+            idx += 1;
+            f(acc, (Key::new(idx - 1), val))
+        })
+    }
+}
+
+impl<T, S: Storage> ExactSizeIterator for KeyedIter<'_, T, S> {}
+
+///An iterator over the elements of a [`Stele`] of boxed [`Any`](core::any::Any) values whose concrete type is `E`,
+///created by [`ReadHandle::iter_downcast`]
+///
+///Only the elements initialized when the iterator was created are covered
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub struct Downcast<'rh, E, S: Storage = DefaultStorage> {
+    iter: RefIterator<'rh, alloc::boxed::Box<dyn core::any::Any + Send + Sync>, S>,
+    _elem: PhantomData<fn() -> E>,
+}
+
+#[cfg(feature = "std")]
+impl<'rh, E, S: Storage> Downcast<'rh, E, S> {
+    pub(crate) fn new(
+        iter: RefIterator<'rh, alloc::boxed::Box<dyn core::any::Any + Send + Sync>, S>,
+    ) -> Self {
+        Self {
+            iter,
+            _elem: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E, S: Storage + core::fmt::Debug> core::fmt::Debug for Downcast<'_, E, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Downcast")
+            .field("iter", &self.iter)
+            .field("type", &core::any::type_name::<E>())
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'rh, E: core::any::Any, S: Storage> Iterator for Downcast<'rh, E, S> {
+    type Item = &'rh E;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        //Downcasting the box itself rather than what it holds would only ever match `E = Box<dyn Any + Send + Sync>`
+        self.iter.find_map(|val| (**val).downcast_ref())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.iter.len - self.iter.pos))
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.iter
+            .fold(init, |acc, val| match (**val).downcast_ref() {
+                Some(val) => f(acc, val),
+                None => acc,
+            })
+    }
+}
+
+///An iterator over the scalars of a [`Stele<[T; N]>`](Stele) of fixed-size records, created by [`ReadHandle::iter_flat`]
+///
+///Only the records initialized when the iterator was created are covered. Folding goes through the records a block at a time
+#[derive(Debug)]
+pub struct FlatIter<'rh, T, S: Storage = DefaultStorage, const N: usize = 1> {
+    records: RefIterator<'rh, [T; N], S>,
+    //The rest of the record currently being yielded
+    front: core::slice::Iter<'rh, T>,
+}
+
+impl<'rh, T, S: Storage, const N: usize> FlatIter<'rh, T, S, N> {
+    pub(crate) fn new(records: RefIterator<'rh, [T; N], S>) -> Self {
+        Self {
+            records,
+            front: [].iter(),
         }
     }
 }
 
-impl<'rh, T> Iterator for RefIterator<'rh, T> {
+impl<'rh, T, S: Storage, const N: usize> Iterator for FlatIter<'rh, T, S, N> {
     type Item = &'rh T;
 
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(scalar) = self.front.next() {
+                return Some(scalar);
+            }
+            self.front = self.records.next()?.iter();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.front.len() + (self.records.len - self.records.pos) * N;
+        (remaining, Some(remaining))
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let acc = self.front.fold(init, &mut f);
+        self.records
+            .fold(acc, |acc, record| record.iter().fold(acc, &mut f))
+    }
+}
+
+impl<T, S: Storage, const N: usize> ExactSizeIterator for FlatIter<'_, T, S, N> {}
+
+///An iterator that yields items by mutable reference, created by [`Stele::iter_mut`]
+#[derive(Debug)]
+pub struct IterMut<'s, T, S: Storage = DefaultStorage> {
+    stele: &'s Stele<T, S>,
+    pos: usize,
+    len: usize,
+    _mut: PhantomData<&'s mut T>,
+}
+
+impl<'s, T, S: Storage> IterMut<'s, T, S> {
+    pub(crate) fn new(stele: &'s mut Stele<T, S>) -> Self {
+        let len = stele.initialized_len();
+        IterMut {
+            stele,
+            pos: 0,
+            len,
+            _mut: PhantomData,
+        }
+    }
+}
+
+impl<'s, T, S: Storage> Iterator for IterMut<'s, T, S> {
+    type Item = &'s mut T;
+
     fn next(&mut self) -> Option<Self::Item> {
         (self.len > self.pos).then(|| {
             self.pos += 1;
-            self.handle.read(self.pos - 1)
+            //SAFETY: The element is below the initialized length, the Stele is mutably borrowed for `'s`,
+            //and every element is yielded at most once
+            unsafe { (*self.stele.raw.read_raw(self.pos - 1)).read_mut() }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, S: Storage> ExactSizeIterator for IterMut<'_, T, S> {}
+
+///An iterator that yields the initialized part of every block as a mutable slice, created by [`Stele::make_mut_slice_blocks`]
+#[derive(Debug)]
+pub struct BlocksMut<'s, T, S: Storage = DefaultStorage> {
+    stele: &'s Stele<T, S>,
+    pos: usize,
+    len: usize,
+    _mut: PhantomData<&'s mut T>,
+}
+
+impl<'s, T, S: Storage> BlocksMut<'s, T, S> {
+    pub(crate) fn new(stele: &'s mut Stele<T, S>) -> Self {
+        let len = stele.initialized_len();
+        BlocksMut {
+            stele,
+            pos: 0,
+            len,
+            _mut: PhantomData,
+        }
+    }
+}
+
+impl<'s, T, S: Storage> Iterator for BlocksMut<'s, T, S> {
+    type Item = &'s mut [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.len > self.pos).then(|| {
+            let (outer_idx, inner_idx) = self.stele.raw.split_idx(self.pos);
+            let len = (self.stele.raw.block_len(outer_idx) - inner_idx).min(self.len - self.pos);
+            self.pos += len;
+            //SAFETY: The elements are below the initialized length and within one block, `Inner<T>` has the same layout as `T`,
+            //the Stele is mutably borrowed for `'s` and every element is yielded at most once
+            unsafe {
+                core::slice::from_raw_parts_mut(
+                    self.stele.raw.read_raw(self.pos - len).cast::<T>(),
+                    len,
+                )
+            }
+        })
+    }
+}
+
+///An iterator over runs of adjacent elements, created by [`RefIterator::chunk_by`] and [`ReadHandle::chunk_by`]
+pub struct ChunkBy<'rh, T, S: Storage, F> {
+    iter: RefIterator<'rh, T, S>,
+    pred: F,
+}
+
+impl<T: core::fmt::Debug, S: Storage + core::fmt::Debug, F> core::fmt::Debug
+    for ChunkBy<'_, T, S, F>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ChunkBy")
+            .field("iter", &self.iter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'rh, T, S: Storage, F> Iterator for ChunkBy<'rh, T, S, F>
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    type Item = Group<'rh, T, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let RefIterator { handle, pos, len } = self.iter;
+        if pos >= len {
+            return None;
+        }
+        let mut end = pos + 1;
+        while end < len && (self.pred)(handle.read_at(end - 1), handle.read_at(end)) {
+            end += 1;
+        }
+        self.iter.pos = end;
+        Some(Group {
+            handle,
+            range: pos..end,
         })
     }
 }
 
+///A run of adjacent elements yielded by [`ChunkBy`], which is never empty
+#[derive(Debug)]
+pub struct Group<'rh, T, S: Storage = DefaultStorage> {
+    handle: &'rh Stele<T, S>,
+    range: Range<usize>,
+}
+
+impl<'rh, T, S: Storage> Group<'rh, T, S> {
+    ///Returns the indices of the elements in the group
+    #[must_use]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    ///Returns the number of elements in the group
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    ///Returns whether the group is empty, which it never is
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    ///Returns the first element of the group
+    #[must_use]
+    pub fn first(&self) -> &'rh T {
+        self.handle.read_at(self.range.start)
+    }
+
+    ///Returns the last element of the group
+    #[must_use]
+    pub fn last(&self) -> &'rh T {
+        self.handle.read_at(self.range.end - 1)
+    }
+
+    ///Returns an iterator over the elements of the group
+    #[must_use]
+    pub fn iter(&self) -> RefIterator<'rh, T, S> {
+        RefIterator {
+            handle: self.handle,
+            pos: self.range.start,
+            len: self.range.end,
+        }
+    }
+}
+
+impl<T, S: Storage> Clone for Group<'_, T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.handle,
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl<'rh, T, S: Storage> IntoIterator for Group<'rh, T, S> {
+    type Item = &'rh T;
+
+    type IntoIter = RefIterator<'rh, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'rh, T, S: Storage> IntoIterator for &Group<'rh, T, S> {
+    type Item = &'rh T;
+
+    type IntoIter = RefIterator<'rh, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 ///An iterator that yields items by value if the type implements copy
 #[derive(Debug)]
-pub struct CopyIterator<T: Copy> {
-    handle: ReadHandle<T>,
+pub struct CopyIterator<T: Copy, S: Storage = DefaultStorage> {
+    handle: ReadHandle<T, S>,
     pos: usize,
     len: usize,
 }
 
-impl<T: Copy> CopyIterator<T> {
+impl<T: Copy, S: Storage> CopyIterator<T, S> {
     ///Creates a new [`CopyIterator`], consuming the [`ReadHandle`]
     #[must_use]
-    pub fn new(handle: ReadHandle<T>) -> Self {
-        let len = handle.len();
+    pub fn new(handle: ReadHandle<T, S>) -> Self {
+        let len = handle.initialized_len();
         Self {
             handle,
             pos: 0,
@@ -52,7 +468,7 @@ impl<T: Copy> CopyIterator<T> {
     }
 }
 
-impl<T: Copy> Iterator for CopyIterator<T> {
+impl<T: Copy, S: Storage> Iterator for CopyIterator<T, S> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -61,4 +477,353 @@ impl<T: Copy> Iterator for CopyIterator<T> {
             self.handle.get(self.pos - 1)
         })
     }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        //SAFETY: The iterator never goes past the initialized length it was created with
+        unsafe {
+            self.handle
+                .handle
+                .fold_blocks(self.pos, self.len, init, |acc, &item| f(acc, item))
+        }
+    }
+}
+
+///An iterator over the fields of a [`Stele<u8>`](Stele) separated by a delimiter, created by [`ReadHandle::split`]
+///
+///Like [`slice::split`], every delimiter ends a field, so a leading or trailing delimiter or two in a row yield empty fields,
+///and a [`Stele`] without any delimiter yields everything as a single field. Only the bytes initialized when the iterator
+///was created are split
+#[derive(Debug)]
+pub struct Split<'rh, S: Storage = DefaultStorage> {
+    handle: &'rh Stele<u8, S>,
+    pos: usize,
+    len: usize,
+    delim: u8,
+    finished: bool,
+}
+
+impl<'rh, S: Storage> Split<'rh, S> {
+    pub(crate) fn new(handle: &'rh ReadHandle<u8, S>, delim: u8, start: usize) -> Self {
+        let len = handle.initialized_len();
+        Self {
+            handle: &handle.handle,
+            pos: start.min(len),
+            len,
+            delim,
+            finished: false,
+        }
+    }
+}
+
+impl<'rh, S: Storage> Iterator for Split<'rh, S> {
+    type Item = SegSlice<'rh, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let start = self.pos;
+        let mut scanned = start;
+        //Delimiters are searched for one block at a time, so a field can end in any block after the one it starts in
+        while scanned < self.len {
+            //SAFETY: `scanned` is below `len`, and everything below the initialized length is initialized
+            let block = unsafe { self.handle.block_slice(scanned, self.len) };
+            if let Some(found) = memchr::memchr(self.delim, block) {
+                self.pos = scanned + found + 1;
+                return Some(SegSlice {
+                    handle: self.handle,
+                    range: start..scanned + found,
+                });
+            }
+            scanned += block.len();
+        }
+        self.finished = true;
+        Some(SegSlice {
+            handle: self.handle,
+            range: start..self.len,
+        })
+    }
+}
+
+///An iterator over the lines of a [`Stele<u8>`](Stele) holding UTF-8 text, created by [`ReadHandle::lines`] and [`ReadHandle::lines_from`]
+///
+///Lines end with `\n` or `\r\n`, which is not included. Each line is borrowed if it lies within a single block and is valid UTF-8,
+///and copied if it crosses a block boundary or has invalid UTF-8 replaced with [`U+FFFD`](char::REPLACEMENT_CHARACTER)
+#[derive(Debug)]
+pub struct Lines<'rh, S: Storage = DefaultStorage> {
+    split: Split<'rh, S>,
+    //Whether a last line without a line ending is left for later instead of being yielded
+    complete_only: bool,
+    //The offset just past the last line yielded
+    consumed: usize,
+}
+
+impl<'rh, S: Storage> Lines<'rh, S> {
+    pub(crate) fn new(handle: &'rh ReadHandle<u8, S>, start: usize, complete_only: bool) -> Self {
+        let split = Split::new(handle, b'\n', start);
+        Self {
+            consumed: split.pos,
+            split,
+            complete_only,
+        }
+    }
+
+    ///Returns the offset just past the last line yielded, which is where [`lines_from`](ReadHandle::lines_from) should continue from
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.consumed
+    }
+}
+
+impl<'rh, S: Storage> Iterator for Lines<'rh, S> {
+    type Item = Cow<'rh, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = self.split.next()?;
+        if self.split.finished {
+            //There is no line ending after the last field, which is either empty or a line that may still be growing
+            if line.is_empty() || self.complete_only {
+                return None;
+            }
+            self.consumed = line.range.end;
+        } else {
+            self.consumed = line.range.end + 1;
+            if !line.is_empty() && self.split.handle.get(line.range.end - 1) == b'\r' {
+                line.range.end -= 1;
+            }
+        }
+        Some(match line.as_slice() {
+            Some(bytes) => String::from_utf8_lossy(bytes),
+            None => Cow::Owned(
+                String::from_utf8(line.to_vec())
+                    .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned()),
+            ),
+        })
+    }
+}
+
+///A run of bytes in a [`Stele<u8>`](Stele) that may span several blocks, yielded by [`Split`]
+///
+///Use [`as_slice`](SegSlice::as_slice) to borrow it when it lies within a single block,
+///and [`iter_bytes`](SegSlice::iter_bytes) or [`to_vec`](SegSlice::to_vec) otherwise
+#[derive(Debug)]
+pub struct SegSlice<'rh, S: Storage = DefaultStorage> {
+    handle: &'rh Stele<u8, S>,
+    range: Range<usize>,
+}
+
+impl<'rh, S: Storage> SegSlice<'rh, S> {
+    ///Returns the indices of the bytes in the Stele
+    #[must_use]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    ///Returns the number of bytes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    ///Returns whether there are no bytes
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    ///Returns the bytes as a slice if they lie within a single block, and [`None`] if they cross a block boundary
+    #[must_use]
+    pub fn as_slice(&self) -> Option<&'rh [u8]> {
+        let mut chunks = self.chunks();
+        match (chunks.next(), chunks.next()) {
+            (None, _) => Some(&[]),
+            (Some(chunk), None) => Some(chunk),
+            (Some(_), Some(_)) => None,
+        }
+    }
+
+    ///Returns an iterator over the parts of the bytes in each block they cover
+    #[must_use]
+    pub fn chunks(&self) -> SegChunks<'rh, S> {
+        SegChunks {
+            handle: self.handle,
+            range: self.range.clone(),
+        }
+    }
+
+    ///Returns an iterator over the bytes
+    pub fn iter_bytes(&self) -> impl Iterator<Item = u8> + 'rh {
+        self.chunks().flat_map(|chunk| chunk.iter().copied())
+    }
+
+    ///Copies the bytes into a [`Vec`]
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.len());
+        self.chunks()
+            .for_each(|chunk| bytes.extend_from_slice(chunk));
+        bytes
+    }
+}
+
+impl<S: Storage> Clone for SegSlice<'_, S> {
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.handle,
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl<S: Storage> PartialEq<[u8]> for SegSlice<'_, S> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.len() == other.len() && self.iter_bytes().eq(other.iter().copied())
+    }
+}
+
+///An iterator over the parts of a [`SegSlice`] in each block it covers
+#[derive(Debug)]
+pub struct SegChunks<'rh, S: Storage = DefaultStorage> {
+    handle: &'rh Stele<u8, S>,
+    range: Range<usize>,
+}
+
+impl<'rh, S: Storage> Iterator for SegChunks<'rh, S> {
+    type Item = &'rh [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        //SAFETY: The range is non-empty and was initialized when the `Split` that produced it was created
+        let chunk = unsafe { self.handle.block_slice(self.range.start, self.range.end) };
+        self.range.start += chunk.len();
+        Some(chunk)
+    }
+}
+
+///A piece of the UTF-8 text in a [`Stele<u8>`](Stele), yielded by [`Utf8Chunks`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Utf8Chunk<'rh> {
+    ///A run of valid UTF-8 within a single block
+    Valid(&'rh str),
+    ///An invalid sequence within a single block, which [`to_string_lossy`](ReadHandle::to_string_lossy)
+    ///replaces with a single [`U+FFFD`](char::REPLACEMENT_CHARACTER)
+    Invalid(&'rh [u8]),
+    ///A sequence that crosses a block boundary, copied out as it cannot be borrowed
+    Straddling(Straddling),
+}
+
+///A sequence of up to 4 bytes that crosses a block boundary, yielded by [`Utf8Chunks`] as [`Utf8Chunk::Straddling`]
+///
+///It is either a whole character or an invalid sequence, which includes one cut short by the end of the [`Stele`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Straddling {
+    bytes: [u8; 4],
+    len: u8,
+}
+
+impl Straddling {
+    ///Returns the bytes of the sequence
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..usize::from(self.len)]
+    }
+
+    ///Returns the character the bytes encode, or [`None`] if they are an invalid sequence
+    #[must_use]
+    pub fn to_char(&self) -> Option<char> {
+        core::str::from_utf8(self.as_bytes())
+            .ok()
+            .and_then(|s| s.chars().next())
+    }
+}
+
+///An iterator over the valid and invalid runs of UTF-8 in a [`Stele<u8>`](Stele), like [`Utf8Chunks`](core::str::Utf8Chunks) but without
+///copying the bytes out first, created by [`ReadHandle::utf8_chunks`]
+///
+///Every valid run and invalid sequence is borrowed from the block it lies in. Runs are cut at block boundaries,
+///and the one character or invalid sequence that crosses a boundary is copied out as a [`Utf8Chunk::Straddling`].
+///Each invalid sequence is split up the same way [`String::from_utf8_lossy`] does, so replacing each with one
+///[`U+FFFD`](char::REPLACEMENT_CHARACTER) gives the same text. Only the bytes initialized when the iterator was created are covered
+#[derive(Debug)]
+pub struct Utf8Chunks<'rh, S: Storage = DefaultStorage> {
+    handle: &'rh Stele<u8, S>,
+    pos: usize,
+    len: usize,
+}
+
+impl<'rh, S: Storage> Utf8Chunks<'rh, S> {
+    pub(crate) fn new(handle: &'rh ReadHandle<u8, S>) -> Self {
+        Self {
+            handle: &handle.handle,
+            pos: 0,
+            len: handle.initialized_len(),
+        }
+    }
+
+    ///Copies out the sequence starting at `pos` that the end of its block cut short
+    fn straddling(&mut self) -> Straddling {
+        let lead = self.handle.get(self.pos);
+        //The width the leading byte announces, which for an invalid leading byte is never reached anyway
+        let width = match lead {
+            0xf0..=0xff => 4,
+            0xe0..=0xef => 3,
+            _ => 2,
+        };
+        let mut bytes = [0; 4];
+        let available = width.min(self.len - self.pos);
+        (0..available).for_each(|i| bytes[i] = self.handle.get(self.pos + i));
+        let len = match core::str::from_utf8(&bytes[..available]) {
+            Ok(_) => available,
+            Err(err) => err.error_len().unwrap_or(available),
+        };
+        self.pos += len;
+        Straddling {
+            bytes,
+            #[allow(clippy::cast_possible_truncation)]
+            len: len as u8,
+        }
+    }
+}
+
+impl<'rh, S: Storage> Iterator for Utf8Chunks<'rh, S> {
+    type Item = Utf8Chunk<'rh>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.len {
+            return None;
+        }
+        //SAFETY: `pos` is below `len`, and everything below the initialized length is initialized
+        let block = unsafe { self.handle.block_slice(self.pos, self.len) };
+        match core::str::from_utf8(block) {
+            Ok(valid) => {
+                self.pos += block.len();
+                Some(Utf8Chunk::Valid(valid))
+            }
+            Err(err) if err.valid_up_to() > 0 => {
+                let (valid, _) = block.split_at(err.valid_up_to());
+                self.pos += valid.len();
+                //SAFETY: `from_utf8` checked that everything up to here is valid
+                Some(Utf8Chunk::Valid(unsafe {
+                    core::str::from_utf8_unchecked(valid)
+                }))
+            }
+            Err(err) => match err.error_len() {
+                Some(len) => {
+                    self.pos += len;
+                    Some(Utf8Chunk::Invalid(&block[..len]))
+                }
+                //The sequence was cut short by the end of the Stele rather than of the block
+                None if self.pos + block.len() == self.len => {
+                    self.pos = self.len;
+                    Some(Utf8Chunk::Invalid(block))
+                }
+                None => Some(Utf8Chunk::Straddling(self.straddling())),
+            },
+        }
+    }
 }
@@ -0,0 +1,125 @@
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::PhantomData,
+    ops::Range,
+};
+
+/// The index of an element in a [`Stele<T>`](super::Stele), typed by the element so that keys into different Steles
+/// cannot be mixed up
+///
+/// Elements never move or get removed, so a key handed out for an element keeps reading that same element through
+/// [`ReadHandle::read_key`](crate::ReadHandle::read_key) however much the Stele grows afterwards.
+/// A key does not remember which Stele it came from, so it only means something to the Stele that handed it out
+pub struct Key<T> {
+    idx: usize,
+    //Keeps the key Send, Sync and Copy whatever `T` is
+    _elem: PhantomData<fn() -> T>,
+}
+
+impl<T> Key<T> {
+    pub(crate) fn new(idx: usize) -> Self {
+        Key {
+            idx,
+            _elem: PhantomData,
+        }
+    }
+
+    /// Returns the index of the element
+    #[must_use]
+    pub fn index(self) -> usize {
+        self.idx
+    }
+}
+
+impl<T> Clone for Key<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Key<T> {}
+
+impl<T> PartialEq for Key<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.idx == other.idx
+    }
+}
+
+impl<T> Eq for Key<T> {}
+
+impl<T> PartialOrd for Key<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Key<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.idx.cmp(&other.idx)
+    }
+}
+
+impl<T> Hash for Key<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.idx.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Key<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Key").field(&self.idx).finish()
+    }
+}
+
+/// An iterator over the [`Key`]s of the elements that were initialized when it was created,
+/// created by [`ReadHandle::keys`](crate::ReadHandle::keys)
+pub struct Keys<T> {
+    range: Range<usize>,
+    _elem: PhantomData<fn() -> T>,
+}
+
+impl<T> Keys<T> {
+    pub(crate) fn new(range: Range<usize>) -> Self {
+        Keys {
+            range,
+            _elem: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for Keys<T> {
+    fn clone(&self) -> Self {
+        Keys::new(self.range.clone())
+    }
+}
+
+impl<T> fmt::Debug for Keys<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Keys").field(&self.range).finish()
+    }
+}
+
+impl<T> Iterator for Keys<T> {
+    type Item = Key<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(Key::new)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Keys<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(Key::new)
+    }
+}
+
+impl<T> ExactSizeIterator for Keys<T> {}
+
+impl<T> FusedIterator for Keys<T> {}
@@ -0,0 +1,94 @@
+use alloc::borrow::Cow;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use metrics::{counter, gauge, histogram, Counter, Gauge, Histogram};
+
+/// The handles a [`Stele`](super::Stele) emits its operational metrics through, registered once when it is built
+///
+/// Resolving the handles up front keeps the registry lookup off the hot path, and pushes are only counted by
+/// how far the length has moved since they were last reported, so a push that does not start a block touches nothing here
+#[derive(Debug)]
+pub(crate) struct Meter {
+    pushes: Counter,
+    blocks_allocated: Counter,
+    allocated_bytes: Gauge,
+    block_alloc_bytes: Histogram,
+    //The length up to which pushes have been added to `pushes`. Only ever accessed by the writer or with `&mut`,
+    //and never part of a loom model, so this is always a real atomic
+    reported: AtomicUsize,
+    //The bytes this Stele has added to `allocated_bytes`, which it takes back when it is dropped
+    allocated: AtomicUsize,
+}
+
+impl Meter {
+    /// Registers the handles with the current recorder, labeled with `name` if there is one
+    pub(crate) fn new(name: Option<Cow<'static, str>>) -> Self {
+        match name {
+            Some(name) => Meter::with_handles(
+                counter!("stele.pushes", "name" => name.clone()),
+                counter!("stele.blocks_allocated", "name" => name.clone()),
+                gauge!("stele.allocated_bytes", "name" => name.clone()),
+                histogram!("stele.block_alloc_bytes", "name" => name),
+            ),
+            None => Meter::with_handles(
+                counter!("stele.pushes"),
+                counter!("stele.blocks_allocated"),
+                gauge!("stele.allocated_bytes"),
+                histogram!("stele.block_alloc_bytes"),
+            ),
+        }
+    }
+
+    fn with_handles(
+        pushes: Counter,
+        blocks_allocated: Counter,
+        allocated_bytes: Gauge,
+        block_alloc_bytes: Histogram,
+    ) -> Self {
+        Meter {
+            pushes,
+            blocks_allocated,
+            allocated_bytes,
+            block_alloc_bytes,
+            reported: AtomicUsize::new(0),
+            allocated: AtomicUsize::new(0),
+        }
+    }
+
+    /// Adds every push up to the length `len` that has not been reported yet
+    pub(crate) fn report_pushes(&self, len: usize) {
+        let reported = self.reported.swap(len, Ordering::Relaxed);
+        if len > reported {
+            self.pushes.increment((len - reported) as u64);
+        }
+    }
+
+    /// Starts counting pushes from zero again, once the length has been reset
+    pub(crate) fn reset_pushes(&self) {
+        self.reported.store(0, Ordering::Relaxed);
+    }
+
+    /// Reports a newly allocated block of `bytes` bytes
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn allocated(&self, bytes: usize) {
+        self.blocks_allocated.increment(1);
+        self.block_alloc_bytes.record(bytes as f64);
+        self.allocated_bytes.increment(bytes as f64);
+        self.allocated.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Reports that `bytes` bytes of blocks were freed
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn freed(&self, bytes: usize) {
+        self.allocated_bytes.decrement(bytes as f64);
+        self.allocated.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+impl Drop for Meter {
+    //Every block this Stele allocated is freed along with it
+    fn drop(&mut self) {
+        let bytes = *self.allocated.get_mut();
+        self.freed(bytes);
+    }
+}
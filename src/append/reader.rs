@@ -1,22 +1,44 @@
-use super::Stele;
+use super::{
+    cached::CachedReadHandle, static_handle::StaticReadHandle, writer::WriteHandle, Stele,
+};
+#[cfg(feature = "std")]
+use crate::append::iter::Downcast;
 use crate::{
-    append::iter::{CopyIterator, RefIterator},
+    append::iter::{
+        ChunkBy, CopyIterator, FlatIter, KeyedIter, Lines, RefIterator, Split, Utf8Chunk,
+        Utf8Chunks,
+    },
+    append::key::{Key, Keys},
+    mem::{DefaultStorage, Storage},
     sync::Arc,
+    SteleError,
+};
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+use alloc::{borrow::Cow, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use core::any::Any;
+use core::{
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ops::{Index, RangeBounds},
+    pin::Pin,
+    ptr::NonNull,
 };
-use core::ops::Index;
 
 ///The reader for a [`Stele`]
 #[derive(Debug)]
-pub struct ReadHandle<T> {
-    pub(crate) handle: Arc<Stele<T>>,
+#[repr(transparent)]
+pub struct ReadHandle<T, S: Storage = DefaultStorage> {
+    pub(crate) handle: Arc<Stele<T, S>>,
 }
 
 //SAFETY: ReadHandle only provides immutable references to its contents and does not perform
 //any mutable operations internally
-unsafe impl<T> Send for ReadHandle<T> where Stele<T>: Send + Sync {}
-unsafe impl<T> Sync for ReadHandle<T> where Stele<T>: Send + Sync {}
+unsafe impl<T, S: Storage> Send for ReadHandle<T, S> where Stele<T, S>: Send + Sync {}
+unsafe impl<T, S: Storage> Sync for ReadHandle<T, S> where Stele<T, S>: Send + Sync {}
 
-impl<T> ReadHandle<T> {
+impl<T, S: Storage> ReadHandle<T, S> {
     /// Reads the value at the given index
     ///
     /// # Panic
@@ -25,16 +47,112 @@ impl<T> ReadHandle<T> {
     /// Since [`Index`] operates through this function, this same caveat also applies when indexing
     #[must_use]
     pub fn read(&self, idx: usize) -> &T {
-        self.handle.read(idx)
+        self.handle.read_at(idx)
     }
 
     /// Attempts to read the value at the index and returns [`Some`] if the value exists, and [`None`] otherwise
     #[must_use]
     pub fn try_read(&self, idx: usize) -> Option<&T> {
-        self.handle.try_read(idx)
+        self.handle.read(idx)
     }
 
-    /// Returns the current length of the underlying [`Stele`]
+    /// Reads the element at `key`, see [`read`](ReadHandle::read)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is out of bounds, which it can only be if it came from [`WriteHandle::next_key`] before
+    /// the element was pushed or from another [`Stele`]. Unlike [`read`](ReadHandle::read), this is checked in every build
+    #[must_use]
+    pub fn read_key(&self, key: Key<T>) -> &T {
+        self.try_read(key.index())
+            .expect("Read a key past the initialized elements")
+    }
+
+    /// Reads the values at every index in `idxs`, returning [`None`] if any of them does not exist
+    ///
+    /// The length is loaded once for all of them rather than once per index as with [`try_read`](ReadHandle::try_read).
+    /// Indices may repeat, and indices at or past a [reservation](WriteHandle::push_uninit) that has not been filled count as out of bounds
+    #[must_use]
+    pub fn get_disjoint<const N: usize>(&self, idxs: [usize; N]) -> Option<[&T; N]> {
+        let len = self.handle.initialized_len();
+        if idxs.iter().any(|&idx| idx >= len) {
+            return None;
+        }
+        //SAFETY: Everything below the initialized length is initialized
+        Some(idxs.map(|idx| unsafe { (*self.handle.raw.read_raw(idx)).read() }))
+    }
+
+    /// Appends the values at every index in `idxs` to `out`, see [`get_disjoint`](ReadHandle::get_disjoint)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SteleError::OutOfBounds`] for the first index that does not exist, in which case nothing is appended
+    pub fn read_many<'a>(&'a self, idxs: &[usize], out: &mut Vec<&'a T>) -> Result<(), SteleError> {
+        let len = self.handle.initialized_len();
+        if let Some(&index) = idxs.iter().find(|&&idx| idx >= len) {
+            return Err(SteleError::OutOfBounds { index, len });
+        }
+        //SAFETY: Everything below the initialized length is initialized
+        out.extend(
+            idxs.iter()
+                .map(|&idx| unsafe { (*self.handle.raw.read_raw(idx)).read() }),
+        );
+        Ok(())
+    }
+
+    /// Attempts to read the value at the index pinned in place, returning [`None`] if it does not exist
+    ///
+    /// # Pinning
+    ///
+    /// An element of a [`Stele`] never moves once it is pushed: blocks are never reallocated, elements are only dropped
+    /// once the last handle is gone or the [`Stele`] is [recycled](Stele::recycle) with exclusive access, and they are dropped in place.
+    /// The only ways to move an element, such as [`Stele::into_vec`] and [`Stele::get_mut`], require `T: Unpin`,
+    /// so `!Unpin` types like self-referential or intrusive ones can rely on their address until they are dropped
+    #[must_use]
+    pub fn read_pinned(&self, idx: usize) -> Option<Pin<&T>> {
+        //SAFETY: Elements never move once pushed, see above
+        self.try_read(idx)
+            .map(|val| unsafe { Pin::new_unchecked(val) })
+    }
+
+    /// Returns the address of the element at the index, or [`None`] if it does not exist
+    ///
+    /// The address is stable, as elements never move once pushed (see [`read_pinned`](ReadHandle::read_pinned)), which makes
+    /// it suitable for linking elements to one another when the lifetime of [`read`](ReadHandle::read) gets in the way.
+    /// It stays valid for reads until the element is dropped or borrowed mutably: once the last handle is gone, or once the
+    /// [`Stele`] is [recycled](Stele::recycle) or accessed through [`Stele::get_mut`] or [`Stele::iter_mut`].
+    /// It must never be written through, and [`element_at`](ReadHandle::element_at) turns it back into a reference
+    #[must_use]
+    pub fn address_of(&self, idx: usize) -> Option<NonNull<T>> {
+        self.try_read(idx).map(NonNull::from)
+    }
+
+    /// Returns the element at an address returned by [`address_of`](ReadHandle::address_of)
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `address_of` on a handle to the same [`Stele`] as this one, and since then the
+    /// [`Stele`] must not have been [recycled](Stele::recycle) (including by [`WriteHandle::try_recycle`]) or accessed mutably
+    /// through [`Stele::get_mut`], [`Stele::iter_mut`] or [`Stele::make_mut_slice_blocks`]
+    #[must_use]
+    pub unsafe fn element_at(&self, ptr: NonNull<T>) -> &T {
+        //SAFETY: By the safety contract the element is in this Stele and has been neither dropped nor borrowed mutably since
+        //its address was taken, so it lives at least as long as this handle
+        unsafe { ptr.as_ref() }
+    }
+
+    /// Returns a reference to the allocator backing the underlying [`Stele`]
+    #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "allocator_api", feature = "allocator-api2")))
+    )]
+    #[must_use]
+    pub fn allocator(&self) -> &S {
+        self.handle.allocator()
+    }
+
+    /// Returns the current length of the underlying [`Stele`], including elements [reserved](WriteHandle::push_uninit) but not filled yet
     ///
     /// Note: this is an optimistic operation and the length may be changing under you
     #[must_use]
@@ -42,6 +160,16 @@ impl<T> ReadHandle<T> {
         self.handle.len()
     }
 
+    /// Returns the number of leading elements that are all initialized, which is what iterators cover
+    ///
+    /// This is the same as [`len`](ReadHandle::len) unless some [reserved](WriteHandle::push_uninit) elements have not been filled yet.
+    ///
+    /// Note: this is an optimistic operation and the length may be changing under you
+    #[must_use]
+    pub fn initialized_len(&self) -> usize {
+        self.handle.initialized_len()
+    }
+
     /// Returns the current length of the underlying [`Stele`]
     ///
     /// Note: This is an optimistic operation as a write may happen between the operation returning and making use of the information provided
@@ -51,16 +179,409 @@ impl<T> ReadHandle<T> {
         self.handle.is_empty()
     }
 
+    /// Returns how many more elements can be pushed before a [bounded](Stele::bounded) [`Stele`] is full,
+    /// or [`None`] if it is unbounded
+    ///
+    /// Note: this is an optimistic operation and may be decreasing under you
+    #[must_use]
+    pub fn remaining(&self) -> Option<usize> {
+        self.handle.remaining()
+    }
+
+    /// Returns whether the [`Stele`] is [bounded](Stele::bounded) and full
+    ///
+    /// Note: once this returns `true` it always will, as recycling requires every [`ReadHandle`] to be gone
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.handle.is_full()
+    }
+
+    /// Returns the number of elements the currently allocated blocks can hold without allocating
+    ///
+    /// This includes blocks that were preallocated but have not been written to yet
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.handle.capacity()
+    }
+
+    /// Returns the number of bytes held by the currently allocated blocks
+    ///
+    /// Zero sized types always report `0`
+    #[must_use]
+    pub fn allocated_bytes(&self) -> usize {
+        self.handle.allocated_bytes()
+    }
+
+    /// Returns the number of blocks that are currently allocated
+    #[must_use]
+    pub fn block_count(&self) -> usize {
+        self.handle.block_count()
+    }
+
+    /// Returns a [`CachedReadHandle`] to the same [`Stele`], which is faster for reading elements in random order
+    /// but cannot be shared between threads
+    #[must_use]
+    pub fn cached(&self) -> CachedReadHandle<T, S> {
+        CachedReadHandle::new(self.clone())
+    }
+
+    /// Returns the [`GrowthPolicy`](crate::layout::GrowthPolicy) that decides how large each block is
+    #[must_use]
+    pub fn growth(&self) -> crate::layout::GrowthPolicy {
+        self.handle.growth()
+    }
+
+    /// Returns the size in bytes of the [`Stele`] itself, which holds the block pointers and the length
+    /// regardless of how many blocks are allocated
+    #[must_use]
+    pub fn overhead_bytes(&self) -> usize {
+        self.handle.overhead_bytes()
+    }
+
+    /// Creates a [`SteleStream`](super::stream::SteleStream) that yields clones of every element,
+    /// waiting for new ones to be pushed until the [`WriteHandle`] is dropped
+    #[cfg(feature = "futures")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+    #[must_use]
+    pub fn stream(&self) -> super::stream::SteleStream<T, S>
+    where
+        T: Clone,
+    {
+        super::stream::SteleStream::new(self.clone())
+    }
+
+    /// Blocks until the [`Stele`] holds at least `len` elements, returning `false` if the [`WriteHandle`]
+    /// was dropped before that happened
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`Notify`](crate::Notify) was set with [`Stele::set_notifier`]
+    #[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics")))))
+    )]
+    #[must_use]
+    pub fn wait_for_len(&self, len: usize) -> bool {
+        self.handle.wait_for_len(len)
+    }
+
+    /// Blocks until an element at `start` or after matches `pred`, and returns it along with its index,
+    /// or [`None`] if the [`WriteHandle`] was dropped before one was pushed
+    ///
+    /// Elements are checked in order, each one once, and the search waits for a reservation to be filled before
+    /// checking anything after it
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`Notify`](crate::Notify) was set with [`Stele::set_notifier`]
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(
+            feature = "std",
+            not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+        )))
+    )]
+    #[must_use]
+    pub fn wait_for_match(
+        &self,
+        start: usize,
+        mut pred: impl FnMut(&T) -> bool,
+    ) -> Option<(usize, &T)> {
+        self.handle
+            .wait_for_match(start, &mut pred, None)
+            .unwrap_or_else(|_| unreachable!("Waiting without a deadline never times out"))
+    }
+
+    /// Like [`wait_for_match`](ReadHandle::wait_for_match), but gives up once `timeout` has passed
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SteleError::TimedOut`] if nothing matched in time, with the index to resume the search from
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`Notify`](crate::Notify) was set with [`Stele::set_notifier`]
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(
+            feature = "std",
+            not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+        )))
+    )]
+    pub fn wait_for_match_timeout(
+        &self,
+        start: usize,
+        mut pred: impl FnMut(&T) -> bool,
+        timeout: core::time::Duration,
+    ) -> Result<Option<(usize, &T)>, SteleError> {
+        let deadline = std::time::Instant::now() + timeout;
+        self.handle
+            .wait_for_match(start, &mut pred, Some(deadline))
+            .map_err(|next| SteleError::TimedOut { next })
+    }
+
+    /// Returns a [`WaitForMatch`](super::wait::WaitForMatch) future that resolves to the first element at `start` or after
+    /// that matches `pred`, like [`wait_for_match`](ReadHandle::wait_for_match) without blocking the thread
+    ///
+    /// This is woken by the pushes themselves, so unlike the blocking version it does not need a [`Notify`](crate::Notify)
+    #[cfg(feature = "futures")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+    pub fn wait_for_match_async<F>(
+        &self,
+        start: usize,
+        pred: F,
+    ) -> super::wait::WaitForMatch<'_, T, F, S>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        super::wait::WaitForMatch::new(&self.handle, start, pred)
+    }
+
+    /// Turns this [`ReadHandle`] into the [`WriteHandle`] of its [`Stele`] once the previous writer has been dropped,
+    /// so that another thread can continue appending
+    ///
+    /// Only one reader can be promoted each time a writer is dropped, so there is still never more than one [`WriteHandle`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`ReadHandle`] unchanged if a [`WriteHandle`] still exists, including one just promoted by another reader
+    pub fn try_promote(self) -> Result<WriteHandle<T, S>, Self> {
+        if self.handle.reopen() {
+            Ok(WriteHandle {
+                handle: self.handle,
+                _unsync: PhantomData,
+            })
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Returns a view of the first `n` elements whose reads skip loading the length
+    ///
+    /// Indices are checked against `n` instead, which needs no atomic load, so this is meant for hot loops where the length was already validated
+    ///
+    /// # Safety
+    ///
+    /// The caller must have observed [`initialized_len`](ReadHandle::initialized_len) to be at least `n` through this
+    /// [`Stele`] beforehand, which is the same as [`len`](ReadHandle::len) unless [reserved](WriteHandle::push_uninit) elements are involved
+    #[must_use]
+    pub unsafe fn assume_len(&self, n: usize) -> LenAssumed<'_, T, S> {
+        debug_assert!(
+            self.initialized_len() >= n,
+            "Assumed a length of {} but only {} elements are initialized",
+            n,
+            self.initialized_len()
+        );
+        LenAssumed {
+            handle: &self.handle,
+            len: n,
+        }
+    }
+
+    /// Returns a snapshot of the initialized length and the blocks holding those elements, laid out for foreign readers
+    ///
+    /// The pointers stay valid for as long as any handle to the [`Stele`] is alive, see [`RawParts`](crate::ffi::RawParts)
+    #[must_use]
+    pub fn as_raw_parts(&self) -> crate::ffi::RawParts<T> {
+        self.handle.raw_parts()
+    }
+
+    /// Leaks this [`ReadHandle`] so that the [`Stele`] and the pointers it [exports](ReadHandle::as_raw_parts)
+    /// stay valid for the rest of the process, returning a [`StaticReadHandle`] to it
+    ///
+    /// The elements and blocks are never freed, even once every other handle is dropped
+    #[must_use]
+    pub fn leak_raw(self) -> StaticReadHandle<'static, T, S>
+    where
+        T: 'static,
+        S: 'static,
+    {
+        let stele = core::ptr::from_ref::<Stele<T, S>>(&self.handle);
+        //The reference count this handle held is never given back, so the Stele is never dropped
+        core::mem::forget(self);
+        //SAFETY: The Stele lives as long as a reference count is held, and one now is forever
+        StaticReadHandle {
+            handle: unsafe { &*stele },
+        }
+    }
+
     /// Creates a [`RefIterator`]
     ///
     /// This is primarily used to ensure the creation of a [`RefIterator`] when T is Copy
     #[must_use]
-    pub fn iter(&self) -> RefIterator<'_, T> {
+    pub fn iter(&self) -> RefIterator<'_, T, S> {
         self.into_iter()
     }
+
+    /// Creates a [`RefIterator`] over the elements in `range`, clamped to the initialized length
+    ///
+    /// See [`RefIterator::new_range`]
+    #[must_use]
+    pub fn iter_range(&self, range: impl RangeBounds<usize>) -> RefIterator<'_, T, S> {
+        RefIterator::new_range(self, range)
+    }
+
+    /// Creates a [`KeyedIter`] that yields every element initialized when this is called along with its [`Key`]
+    #[must_use]
+    pub fn iter_keyed(&self) -> KeyedIter<'_, T, S> {
+        KeyedIter::new(self.iter())
+    }
+
+    /// Returns an iterator over the [`Key`]s of the elements initialized when this is called
+    #[must_use]
+    pub fn keys(&self) -> Keys<T> {
+        Keys::new(0..self.initialized_len())
+    }
+
+    /// Clones every element initialized when this is called into a new [`Stele`] allocated from `storage`, and returns handles to it
+    ///
+    /// The new Stele has the same growth policy, bound and block alignment as this one. Every block it needs is allocated up front
+    /// and filled a block at a time, so this is the way to move the contents out of an allocator that is about to be freed,
+    /// such as an arena, while this Stele stays untouched. See [`copy_to_stele_in`](ReadHandle::copy_to_stele_in) for `Copy` elements
+    pub fn to_stele_in<S2: Storage>(&self, storage: S2) -> (WriteHandle<T, S2>, ReadHandle<T, S2>)
+    where
+        T: Clone,
+    {
+        let fill = |block: &[T], slots: &mut [MaybeUninit<T>]| {
+            for (slot, val) in slots.iter_mut().zip(block) {
+                slot.write(val.clone());
+            }
+        };
+        //SAFETY: Everything below the initialized length is initialized, and `fill` writes every slot it is given
+        unsafe {
+            self.handle
+                .migrate_in(self.initialized_len(), storage, fill)
+                .to_handles()
+        }
+    }
+
+    /// Copies every element initialized when this is called into a new [`Stele`] allocated from `storage`, and returns handles to it
+    ///
+    /// This is the same as [`to_stele_in`](ReadHandle::to_stele_in), but copies the elements with one `memcpy` per block
+    pub fn copy_to_stele_in<S2: Storage>(
+        &self,
+        storage: S2,
+    ) -> (WriteHandle<T, S2>, ReadHandle<T, S2>)
+    where
+        T: Copy,
+    {
+        let fill = |block: &[T], slots: &mut [MaybeUninit<T>]| {
+            //SAFETY: Both are runs of `T` of the same length, and the slots belong to another Stele so they cannot overlap
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    block.as_ptr(),
+                    slots.as_mut_ptr().cast::<T>(),
+                    block.len(),
+                );
+            }
+        };
+        //SAFETY: Everything below the initialized length is initialized, and `fill` writes every slot it is given
+        unsafe {
+            self.handle
+                .migrate_in(self.initialized_len(), storage, fill)
+                .to_handles()
+        }
+    }
+
+    /// Returns the number of leading elements that are equal in both [`Stele`]s, comparing up to the shorter of their initialized lengths
+    ///
+    /// The elements are compared a block slice at a time, which both [`Stele`]s can be split into even if their blocks have different sizes
+    #[must_use]
+    pub fn common_prefix_len<S2: Storage>(&self, other: &ReadHandle<T, S2>) -> usize
+    where
+        T: PartialEq,
+    {
+        let end = self.initialized_len().min(other.initialized_len());
+        let mut idx = 0;
+        while idx < end {
+            //SAFETY: Everything below both initialized lengths is initialized
+            let (ours, theirs) = unsafe {
+                (
+                    self.handle.block_slice(idx, end),
+                    other.handle.block_slice(idx, end),
+                )
+            };
+            let len = ours.len().min(theirs.len());
+            if let Some(at) = mismatch(&ours[..len], &theirs[..len]) {
+                return idx + at;
+            }
+            idx += len;
+        }
+        end
+    }
+
+    /// Returns the number of leading elements of the [`Stele`] that are equal to those of `other`,
+    /// see [`common_prefix_len`](ReadHandle::common_prefix_len)
+    #[must_use]
+    pub fn common_prefix_len_slice(&self, other: &[T]) -> usize
+    where
+        T: PartialEq,
+    {
+        let end = self.initialized_len().min(other.len());
+        let mut idx = 0;
+        while idx < end {
+            //SAFETY: Everything below the initialized length is initialized
+            let ours = unsafe { self.handle.block_slice(idx, end) };
+            if let Some(at) = mismatch(ours, &other[idx..idx + ours.len()]) {
+                return idx + at;
+            }
+            idx += ours.len();
+        }
+        end
+    }
+
+    /// Returns an iterator over the elements of this [`Stele`] that `other` is missing, which are all of them after the
+    /// [common prefix](ReadHandle::common_prefix_len)
+    ///
+    /// For replication, these are the elements to send a follower that holds `other`
+    #[must_use]
+    pub fn diff_suffix<S2: Storage>(&self, other: &ReadHandle<T, S2>) -> RefIterator<'_, T, S>
+    where
+        T: PartialEq,
+    {
+        self.iter_range(self.common_prefix_len(other)..)
+    }
+
+    /// Returns `true` if every initialized element is less than or equal to the one after it, like [`slice::is_sorted`]
+    ///
+    /// Elements that cannot be compared, such as a floating point NaN, count as out of order
+    #[must_use]
+    pub fn is_sorted(&self) -> bool
+    where
+        T: PartialOrd,
+    {
+        let mut iter = self.iter();
+        let Some(mut prev) = iter.next() else {
+            return true;
+        };
+        iter.all(|next| {
+            let ordered = prev <= next;
+            prev = next;
+            ordered
+        })
+    }
+
+    /// Groups runs of adjacent elements for which `pred` returns `true`, like [`slice::chunk_by`]
+    ///
+    /// Use [`iter_range`](ReadHandle::iter_range) and [`RefIterator::chunk_by`] to only group part of the [`Stele`]
+    pub fn chunk_by<F>(&self, pred: F) -> ChunkBy<'_, T, S, F>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        self.iter().chunk_by(pred)
+    }
 }
 
-impl<T: Copy> ReadHandle<T> {
+impl<T: Copy, S: Storage> ReadHandle<T, S> {
     /// Get provides a way to get an owned copy of a value inside a [`Stele`]
     /// provided the `T` implements [`Copy`]
     ///
@@ -71,9 +592,275 @@ impl<T: Copy> ReadHandle<T> {
     pub fn get(&self, idx: usize) -> T {
         self.handle.get(idx)
     }
+
+    /// Copies the elements from `start` onwards into `dst`, a block at a time
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `dst.len()` elements from `start` onwards are initialized
+    pub fn copy_to_slice(&self, start: usize, dst: &mut [T]) {
+        let len = self.initialized_len();
+        assert!(
+            start.checked_add(dst.len()).is_some_and(|end| end <= len),
+            "Copied {} elements from index {} of a Stele with {} initialized elements",
+            dst.len(),
+            start,
+            len
+        );
+        //SAFETY: The whole range was just checked to be initialized
+        unsafe { self.handle.copy_blocks(start, dst) };
+    }
+}
+
+impl<S: Storage> ReadHandle<u8, S> {
+    /// Returns an iterator over the fields separated by `delim`, like [`slice::split`] but without copying the bytes out first
+    ///
+    /// Each field is a [`SegSlice`](super::iter::SegSlice) that can be borrowed directly when it lies within a single block
+    #[must_use]
+    pub fn split(&self, delim: u8) -> Split<'_, S> {
+        Split::new(self, delim, 0)
+    }
+
+    /// Writes every initialized byte to `w` with one [`write_all`](std::io::Write::write_all) per block, returning the number of bytes written
+    ///
+    /// The length is read once up front, so bytes pushed while writing are left for the next call.
+    /// Short writes and [`Interrupted`](std::io::ErrorKind::Interrupted) errors are retried like [`std::io::copy`] does
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error returned by `w` other than [`Interrupted`](std::io::ErrorKind::Interrupted)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn write_all_to(&self, w: &mut impl std::io::Write) -> std::io::Result<u64> {
+        self.write_range_to(.., w)
+    }
+
+    /// Writes the initialized bytes in `range` to `w`, see [`write_all_to`](ReadHandle::write_all_to)
+    ///
+    /// Both ends of the range are clamped to the initialized length like [`iter_range`](ReadHandle::iter_range)
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error returned by `w` other than [`Interrupted`](std::io::ErrorKind::Interrupted)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn write_range_to(
+        &self,
+        range: impl RangeBounds<usize>,
+        w: &mut impl std::io::Write,
+    ) -> std::io::Result<u64> {
+        let range = self.iter_range(range).remaining_range();
+        let mut idx = range.start;
+        while idx < range.end {
+            //SAFETY: The range was clamped to the initialized length
+            let block = unsafe { self.handle.block_slice(idx, range.end) };
+            w.write_all(block)?;
+            idx += block.len();
+        }
+        Ok(range.len() as u64)
+    }
+
+    /// Returns an iterator over the lines of UTF-8 text, like [`str::lines`]
+    ///
+    /// Lines that lie within a single block and are valid UTF-8 are borrowed, see [`Lines`]
+    #[must_use]
+    pub fn lines(&self) -> Lines<'_, S> {
+        Lines::new(self, 0, false)
+    }
+
+    /// Returns an iterator over the complete lines starting at the byte offset `start`, for tailing a log as it grows
+    ///
+    /// A last line without a line ending is not yielded, as the rest of it may not have been pushed yet.
+    /// Pass [`Lines::position`] as `start` the next time to only get the lines pushed since
+    #[must_use]
+    pub fn lines_from(&self, start: usize) -> Lines<'_, S> {
+        Lines::new(self, start, true)
+    }
+
+    /// Returns an iterator over the runs of valid UTF-8 and the invalid sequences in between, like [`core::str::Utf8Chunks`]
+    ///
+    /// Runs are borrowed and never cross a block boundary, see [`Utf8Chunks`]
+    #[must_use]
+    pub fn utf8_chunks(&self) -> Utf8Chunks<'_, S> {
+        Utf8Chunks::new(self)
+    }
+
+    /// Returns the initialized bytes as text with every invalid sequence replaced by [`U+FFFD`](char::REPLACEMENT_CHARACTER),
+    /// like [`String::from_utf8_lossy`]
+    ///
+    /// The text is borrowed if it lies within a single block and is valid UTF-8, and copied otherwise
+    #[must_use]
+    pub fn to_string_lossy(&self) -> Cow<'_, str> {
+        let mut chunks = self.utf8_chunks();
+        let (first, second) = match (chunks.next(), chunks.next()) {
+            (None, _) => return Cow::Borrowed(""),
+            (Some(Utf8Chunk::Valid(valid)), None) => return Cow::Borrowed(valid),
+            (Some(first), second) => (first, second),
+        };
+        let mut text = String::with_capacity(self.initialized_len());
+        for chunk in core::iter::once(first).chain(second).chain(chunks) {
+            match chunk {
+                Utf8Chunk::Valid(valid) => text.push_str(valid),
+                Utf8Chunk::Invalid(_) => text.push(char::REPLACEMENT_CHARACTER),
+                Utf8Chunk::Straddling(seq) => {
+                    text.push(seq.to_char().unwrap_or(char::REPLACEMENT_CHARACTER));
+                }
+            }
+        }
+        Cow::Owned(text)
+    }
+}
+
+impl<T, S: Storage, const N: usize> ReadHandle<[T; N], S> {
+    /// Returns the number of scalars in the initialized records, which is `N` for every record
+    #[must_use]
+    pub fn flat_len(&self) -> usize {
+        self.initialized_len() * N
+    }
+
+    /// Reads the scalar at flat index `i`, which is element `i % N` of record `i / N`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not below [`flat_len`](ReadHandle::flat_len)
+    #[must_use]
+    pub fn flat_read(&self, i: usize) -> &T {
+        //Every record is initialized up to the flat length, so only the record index needs checking
+        let record = i
+            .checked_div(N)
+            .and_then(|record| self.handle.read(record))
+            .unwrap_or_else(|| {
+                panic!(
+                    "Flat index {} is out of bounds for a Stele with {} scalars",
+                    i,
+                    self.flat_len()
+                )
+            });
+        &record[i % N]
+    }
+
+    /// Returns an iterator over the scalars of every initialized record in order, crossing record and block boundaries
+    #[must_use]
+    pub fn iter_flat(&self) -> FlatIter<'_, T, S, N> {
+        FlatIter::new(self.iter())
+    }
+}
+
+impl<T: Copy, S: Storage, const N: usize> ReadHandle<[T; N], S> {
+    /// Returns a copy of the scalar at flat index `i`, see [`flat_read`](ReadHandle::flat_read)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not below [`flat_len`](ReadHandle::flat_len)
+    #[must_use]
+    pub fn flat_get(&self, i: usize) -> T {
+        *self.flat_read(i)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<S: Storage> ReadHandle<Box<dyn Any + Send + Sync>, S> {
+    /// Returns an iterator over the initialized elements whose concrete type is `E`, skipping every other one
+    #[must_use]
+    pub fn iter_downcast<E: Any>(&self) -> Downcast<'_, E, S> {
+        Downcast::new(self.iter())
+    }
+
+    /// Reads the element at the index as an `E`, returning [`None`] if it does not exist or has another concrete type
+    #[must_use]
+    pub fn get_downcast<E: Any>(&self, idx: usize) -> Option<&E> {
+        self.try_read(idx)?.downcast_ref()
+    }
+
+    /// Returns the index of the first initialized element whose concrete type is `E`
+    #[must_use]
+    pub fn position_of<E: Any>(&self) -> Option<usize> {
+        self.iter().position(|val| val.is::<E>())
+    }
+}
+
+///A view of the first elements of a [`Stele`] that are known to be initialized, created by [`ReadHandle::assume_len`]
+///
+///Reads never load the length of the [`Stele`], and indices are checked against the assumed length instead
+#[derive(Debug)]
+pub struct LenAssumed<'rh, T, S: Storage = DefaultStorage> {
+    handle: &'rh Stele<T, S>,
+    len: usize,
+}
+
+impl<'rh, T, S: Storage> LenAssumed<'rh, T, S> {
+    /// Returns the length that was assumed
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the assumed length is zero
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Reads the value at the given index
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given index is not below the assumed length
+    #[must_use]
+    pub fn read(&self, idx: usize) -> &'rh T {
+        assert!(
+            idx < self.len,
+            "Read index {} of a view assumed to hold {} elements",
+            idx,
+            self.len
+        );
+        //SAFETY: The index is below the assumed length, and by the safety contract of `assume_len`
+        //every element below it is initialized
+        unsafe { (*self.handle.raw.read_raw(idx)).read() }
+    }
+
+    /// Returns a [`RefIterator`] over every element below the assumed length
+    #[must_use]
+    pub fn iter(&self) -> RefIterator<'rh, T, S> {
+        //SAFETY: By the safety contract of `assume_len` every element below the assumed length is initialized
+        unsafe { RefIterator::assume_len(self.handle, self.len) }
+    }
+}
+
+impl<T: Copy, S: Storage> LenAssumed<'_, T, S> {
+    /// Returns a copy of the value at the given index
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given index is not below the assumed length
+    #[must_use]
+    pub fn get(&self, idx: usize) -> T {
+        *self.read(idx)
+    }
+}
+
+impl<'rh, T, S: Storage> IntoIterator for &LenAssumed<'rh, T, S> {
+    type Item = &'rh T;
+
+    type IntoIter = RefIterator<'rh, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Returns the index of the first element that differs between two slices of the same length
+fn mismatch<T: PartialEq>(a: &[T], b: &[T]) -> Option<usize> {
+    //Comparing the whole slices first lets types like `u8` compare them all at once
+    if a == b {
+        None
+    } else {
+        a.iter().zip(b).position(|(a, b)| a != b)
+    }
 }
 
-impl<T> Clone for ReadHandle<T> {
+impl<T, S: Storage> Clone for ReadHandle<T, S> {
     fn clone(&self) -> Self {
         Self {
             handle: Arc::clone(&self.handle),
@@ -81,27 +868,27 @@ impl<T> Clone for ReadHandle<T> {
     }
 }
 
-impl<'a, T> IntoIterator for &'a ReadHandle<T> {
+impl<'a, T, S: Storage> IntoIterator for &'a ReadHandle<T, S> {
     type Item = &'a T;
 
-    type IntoIter = super::iter::RefIterator<'a, T>;
+    type IntoIter = super::iter::RefIterator<'a, T, S>;
 
     fn into_iter(self) -> Self::IntoIter {
         RefIterator::new(self)
     }
 }
 
-impl<T: Copy> IntoIterator for ReadHandle<T> {
+impl<T: Copy, S: Storage> IntoIterator for ReadHandle<T, S> {
     type Item = T;
 
-    type IntoIter = super::iter::CopyIterator<T>;
+    type IntoIter = super::iter::CopyIterator<T, S>;
 
     fn into_iter(self) -> Self::IntoIter {
         CopyIterator::new(self)
     }
 }
 
-impl<T> Index<usize> for ReadHandle<T> {
+impl<T, S: Storage> Index<usize> for ReadHandle<T, S> {
     type Output = T;
 
     fn index(&self, index: usize) -> &Self::Output {
@@ -109,15 +896,15 @@ impl<T> Index<usize> for ReadHandle<T> {
     }
 }
 
-impl<T> From<&Arc<Stele<T>>> for ReadHandle<T> {
-    fn from(h: &Arc<Stele<T>>) -> Self {
+impl<T, S: Storage> From<&Arc<Stele<T, S>>> for ReadHandle<T, S> {
+    fn from(h: &Arc<Stele<T, S>>) -> Self {
         Self {
             handle: Arc::clone(h),
         }
     }
 }
 
-#[cfg(all(test, not(loom)))]
+#[cfg(all(test, not(any(loom, feature = "loom", shuttle))))]
 mod tests {
     use crate::Stele;
 
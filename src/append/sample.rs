@@ -0,0 +1,83 @@
+use alloc::vec::Vec;
+
+use rand::{
+    distributions::{Distribution, Uniform},
+    Rng,
+};
+
+use super::reader::ReadHandle;
+use crate::mem::Storage;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+impl<T, S: Storage> ReadHandle<T, S> {
+    /// Returns a uniformly random element, or [`None`] if the [`Stele`](super::Stele) is empty
+    ///
+    /// The element is chosen from the [initialized length](ReadHandle::initialized_len) when this is called,
+    /// so elements pushed concurrently are not considered
+    pub fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+        let len = self.initialized_len();
+        if len == 0 {
+            return None;
+        }
+        Some(self.read(rng.gen_range(0..len)))
+    }
+
+    /// Returns the indices of `amount` distinct elements chosen uniformly at random, in ascending order
+    ///
+    /// If the [`Stele`](super::Stele) holds fewer than `amount` elements, every index is returned.
+    /// This uses Floyd's algorithm, so it only allocates space for the indices it returns, no matter how long the [`Stele`](super::Stele) is
+    pub fn choose_multiple<R: Rng + ?Sized>(&self, rng: &mut R, amount: usize) -> Vec<usize> {
+        let len = self.initialized_len();
+        let amount = amount.min(len);
+        let mut chosen = Vec::with_capacity(amount);
+        for upper in len - amount..len {
+            let idx = rng.gen_range(0..=upper);
+            //`upper` has not been considered before, so it can always take the place of an index that was already chosen
+            let idx = match chosen.binary_search(&idx) {
+                Ok(_) => upper,
+                Err(_) => idx,
+            };
+            if let Err(at) = chosen.binary_search(&idx) {
+                chosen.insert(at, idx);
+            }
+        }
+        chosen
+    }
+
+    /// Returns a [`Uniform`] distribution over the indices of the elements initialized when this is called,
+    /// or [`None`] if the [`Stele`](super::Stele) is empty
+    #[must_use]
+    pub fn index_distribution(&self) -> Option<Uniform<usize>> {
+        match self.initialized_len() {
+            0 => None,
+            len => Some(Uniform::new(0, len)),
+        }
+    }
+
+    /// Returns the element at an index sampled from `distribution`, or [`None`] if that index is not initialized
+    pub fn choose_with<R, D>(&self, rng: &mut R, distribution: &D) -> Option<&T>
+    where
+        R: Rng + ?Sized,
+        D: Distribution<usize> + ?Sized,
+    {
+        let idx = distribution.sample(rng);
+        if idx < self.initialized_len() {
+            Some(self.read(idx))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+impl<T: Copy, S: Storage> ReadHandle<T, S> {
+    /// Returns copies of `amount` distinct elements chosen uniformly at random, in the order they appear in the [`Stele`](super::Stele)
+    ///
+    /// See [`choose_multiple`](ReadHandle::choose_multiple)
+    pub fn sample_copied<R: Rng + ?Sized>(&self, rng: &mut R, amount: usize) -> Vec<T> {
+        self.choose_multiple(rng, amount)
+            .into_iter()
+            .map(|idx| self.get(idx))
+            .collect()
+    }
+}
@@ -0,0 +1,189 @@
+use core::simd::{
+    num::{SimdFloat, SimdInt, SimdUint},
+    prelude::SimdOrd,
+    Simd, SimdElement,
+};
+
+use super::reader::ReadHandle;
+use crate::mem::Storage;
+
+/// The number of lanes the reductions on [`ReadHandle`] add and compare at once
+const WIDTH: usize = 8;
+
+mod sealed {
+    use super::{Simd, WIDTH};
+
+    pub trait Ops: super::SimdElement + PartialOrd {
+        const ZERO: Self;
+        fn scalar_add(self, other: Self) -> Self;
+        fn scalar_min(self, other: Self) -> Self;
+        fn scalar_max(self, other: Self) -> Self;
+        fn vector_add(a: Simd<Self, WIDTH>, b: Simd<Self, WIDTH>) -> Simd<Self, WIDTH>;
+        fn vector_min(a: Simd<Self, WIDTH>, b: Simd<Self, WIDTH>) -> Simd<Self, WIDTH>;
+        fn vector_max(a: Simd<Self, WIDTH>, b: Simd<Self, WIDTH>) -> Simd<Self, WIDTH>;
+        fn reduce_sum(v: Simd<Self, WIDTH>) -> Self;
+        fn reduce_min(v: Simd<Self, WIDTH>) -> Self;
+        fn reduce_max(v: Simd<Self, WIDTH>) -> Self;
+    }
+}
+
+/// The primitive numbers [`ReadHandle::sum_simd`] and [`ReadHandle::min_max_simd`] can reduce
+///
+/// This trait is sealed and implemented for every integer and float type
+pub trait SimdNum: sealed::Ops {}
+
+macro_rules! impl_int {
+    ($reduce:ident: $($ty:ty),*) => {$(
+        impl sealed::Ops for $ty {
+            const ZERO: Self = 0;
+            fn scalar_add(self, other: Self) -> Self {
+                self.wrapping_add(other)
+            }
+            fn scalar_min(self, other: Self) -> Self {
+                Ord::min(self, other)
+            }
+            fn scalar_max(self, other: Self) -> Self {
+                Ord::max(self, other)
+            }
+            fn vector_add(a: Simd<Self, WIDTH>, b: Simd<Self, WIDTH>) -> Simd<Self, WIDTH> {
+                a + b
+            }
+            fn vector_min(a: Simd<Self, WIDTH>, b: Simd<Self, WIDTH>) -> Simd<Self, WIDTH> {
+                a.simd_min(b)
+            }
+            fn vector_max(a: Simd<Self, WIDTH>, b: Simd<Self, WIDTH>) -> Simd<Self, WIDTH> {
+                a.simd_max(b)
+            }
+            fn reduce_sum(v: Simd<Self, WIDTH>) -> Self {
+                $reduce::reduce_sum(v)
+            }
+            fn reduce_min(v: Simd<Self, WIDTH>) -> Self {
+                $reduce::reduce_min(v)
+            }
+            fn reduce_max(v: Simd<Self, WIDTH>) -> Self {
+                $reduce::reduce_max(v)
+            }
+        }
+
+        impl SimdNum for $ty {}
+    )*};
+}
+
+impl_int!(SimdUint: u8, u16, u32, u64, usize);
+impl_int!(SimdInt: i8, i16, i32, i64, isize);
+
+macro_rules! impl_float {
+    ($($ty:ty),*) => {$(
+        impl sealed::Ops for $ty {
+            const ZERO: Self = 0.0;
+            fn scalar_add(self, other: Self) -> Self {
+                self + other
+            }
+            fn scalar_min(self, other: Self) -> Self {
+                self.min(other)
+            }
+            fn scalar_max(self, other: Self) -> Self {
+                self.max(other)
+            }
+            fn vector_add(a: Simd<Self, WIDTH>, b: Simd<Self, WIDTH>) -> Simd<Self, WIDTH> {
+                a + b
+            }
+            fn vector_min(a: Simd<Self, WIDTH>, b: Simd<Self, WIDTH>) -> Simd<Self, WIDTH> {
+                a.simd_min(b)
+            }
+            fn vector_max(a: Simd<Self, WIDTH>, b: Simd<Self, WIDTH>) -> Simd<Self, WIDTH> {
+                a.simd_max(b)
+            }
+            fn reduce_sum(v: Simd<Self, WIDTH>) -> Self {
+                v.reduce_sum()
+            }
+            fn reduce_min(v: Simd<Self, WIDTH>) -> Self {
+                v.reduce_min()
+            }
+            fn reduce_max(v: Simd<Self, WIDTH>) -> Self {
+                v.reduce_max()
+            }
+        }
+
+        impl SimdNum for $ty {}
+    )*};
+}
+
+impl_float!(f32, f64);
+
+impl<T: SimdElement, S: Storage> ReadHandle<T, S> {
+    /// Folds the initialized elements a block at a time, handing `f` each block split up like [`slice::as_simd`]:
+    /// the unaligned elements at the start, the aligned vectors of `LANES` elements in the middle,
+    /// and the elements left over at the end
+    ///
+    /// The length is read once up front, so the last block may only be partly covered
+    pub fn fold_blocks_simd<B, const LANES: usize>(
+        &self,
+        init: B,
+        mut f: impl FnMut(B, &[T], &[Simd<T, LANES>], &[T]) -> B,
+    ) -> B {
+        let len = self.initialized_len();
+        let mut acc = init;
+        let mut idx = 0;
+        while idx < len {
+            //SAFETY: `idx` is below `len`, and everything below the initialized length is initialized
+            let block = unsafe { self.handle.block_slice(idx, len) };
+            idx += block.len();
+            let (head, body, tail) = block.as_simd();
+            acc = f(acc, head, body, tail);
+        }
+        acc
+    }
+}
+
+impl<T: SimdNum, S: Storage> ReadHandle<T, S> {
+    /// Returns the sum of the initialized elements, adding each block with SIMD instructions
+    ///
+    /// Integers wrap on overflow, so the result is the same as folding with `wrapping_add`.
+    /// Floats are added in a different order than a sequential sum, so the result can differ from one by rounding:
+    /// by at most the length times the type's `EPSILON` times the sum of the absolute values of the elements
+    #[must_use]
+    pub fn sum_simd(&self) -> T {
+        let (vector, scalar) = self.fold_blocks_simd(
+            (Simd::splat(T::ZERO), T::ZERO),
+            |(mut vector, mut scalar), head, body, tail| {
+                for &v in body {
+                    vector = T::vector_add(vector, v);
+                }
+                for &val in head.iter().chain(tail) {
+                    scalar = scalar.scalar_add(val);
+                }
+                (vector, scalar)
+            },
+        );
+        T::reduce_sum(vector).scalar_add(scalar)
+    }
+
+    /// Returns the smallest and largest of the initialized elements, comparing each block with SIMD instructions,
+    /// or [`None`] if there are none
+    ///
+    /// The result is the same as a sequential search, except that `0.0` and `-0.0` may be told apart differently.
+    /// Like [`f32::min`] and [`f32::max`], NaNs are ignored unless every element is NaN
+    #[must_use]
+    pub fn min_max_simd(&self) -> Option<(T, T)> {
+        let first = *self.try_read(0)?;
+        let ((vmin, vmax), (min, max)) = self.fold_blocks_simd(
+            ((Simd::splat(first), Simd::splat(first)), (first, first)),
+            |((mut vmin, mut vmax), (mut min, mut max)), head, body, tail| {
+                for &v in body {
+                    vmin = T::vector_min(vmin, v);
+                    vmax = T::vector_max(vmax, v);
+                }
+                for &val in head.iter().chain(tail) {
+                    min = min.scalar_min(val);
+                    max = max.scalar_max(val);
+                }
+                ((vmin, vmax), (min, max))
+            },
+        );
+        Some((
+            T::reduce_min(vmin).scalar_min(min),
+            T::reduce_max(vmax).scalar_max(max),
+        ))
+    }
+}
@@ -0,0 +1,57 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_sink::Sink;
+
+use super::writer::WriteHandle;
+use crate::{
+    mem::{DefaultStorage, Storage},
+    Full,
+};
+
+///A [`Sink`] that pushes every item it is sent on to a [`Stele`](super::Stele)
+///
+///Pushing never has to wait, so the sink is always ready. Closing it drops the [`WriteHandle`],
+///which ends every [`SteleStream`](super::stream::SteleStream) reading from the same [`Stele`](super::Stele)
+#[derive(Debug)]
+pub struct SteleSink<T, S: Storage = DefaultStorage> {
+    writer: Option<WriteHandle<T, S>>,
+}
+
+impl<T, S: Storage> SteleSink<T, S> {
+    ///Creates a new [`SteleSink`], consuming the [`WriteHandle`]
+    #[must_use]
+    pub fn new(writer: WriteHandle<T, S>) -> Self {
+        Self {
+            writer: Some(writer),
+        }
+    }
+}
+
+impl<T, S: Storage> Sink<T> for SteleSink<T, S> {
+    /// Only returned when the [`Stele`](super::Stele) is [bounded](super::Stele::bounded) and full
+    type Error = Full<T>;
+
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        self.get_mut()
+            .writer
+            .as_ref()
+            .expect("Sent an item to a closed SteleSink")
+            .try_push(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().writer = None;
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,36 @@
+use super::Stele;
+use crate::{
+    mem::{DefaultStorage, Storage},
+    sync::Arc,
+};
+
+/// An element reserved with [`WriteHandle::push_uninit`](crate::WriteHandle::push_uninit) that has not been initialized yet
+///
+/// Until it is [filled](Slot::fill), reading its index returns [`None`] even though it is below the length of the [`Stele`],
+/// and iterators stop before it. A [`Slot`] can be sent to another thread to be filled there.
+///
+/// Dropping a [`Slot`] without filling it leaves its index permanently empty
+#[derive(Debug)]
+pub struct Slot<T, S: Storage = DefaultStorage> {
+    pub(crate) handle: Arc<Stele<T, S>>,
+    pub(crate) idx: usize,
+}
+
+//SAFETY: A Slot only ever writes to the one element it reserved, through the same atomic operations as the writer,
+//so as long as the Stele, and therefore its items and storage, is both Send and Sync it is safe to implement Send and Sync
+unsafe impl<T, S: Storage> Send for Slot<T, S> where Stele<T, S>: Send + Sync {}
+unsafe impl<T, S: Storage> Sync for Slot<T, S> where Stele<T, S>: Send + Sync {}
+
+impl<T, S: Storage> Slot<T, S> {
+    /// Returns the index this [`Slot`] reserved
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+
+    /// Initializes the reserved element with `val`, making it visible to every reader
+    pub fn fill(self, val: T) {
+        //SAFETY: The index was reserved for this Slot, which is consumed so it can only be filled once
+        unsafe { self.handle.fill(self.idx, val) };
+    }
+}
@@ -0,0 +1,161 @@
+use core::{mem::MaybeUninit, ops::Range};
+
+use super::Stele;
+use crate::{
+    mem::{DefaultStorage, Storage},
+    sync::ord,
+};
+
+/// The uninitialized slots past the end of a [`Stele`], handed out as one mutable slice per block,
+/// created by [`WriteHandle::spare_blocks_mut`](crate::WriteHandle::spare_blocks_mut)
+///
+/// This is the equivalent of [`Vec::spare_capacity_mut`](alloc::vec::Vec::spare_capacity_mut) for a [`Stele`]: the slices can be
+/// written to directly, such as by a foreign function filling a buffer, and the elements written are then made visible to readers
+/// with [`WriteHandle::publish_initialized`](crate::WriteHandle::publish_initialized). Readers never look past the end of the
+/// [`Stele`], so nothing written here can be seen until it is published
+#[derive(Debug)]
+pub struct SpareCapacity<'w, T, S: Storage = DefaultStorage> {
+    stele: &'w Stele<T, S>,
+    //The slots that have not been handed out yet, which all lie in allocated blocks
+    range: Range<usize>,
+}
+
+impl<T, S: Storage> Stele<T, S> {
+    /// Allocates every block needed to hold `additional` more elements and returns the slots past the end that they provide
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Stele is bounded and does not have room for `additional` more elements
+    ///
+    /// SAFETY: You must be the only writer, and nothing may be pushed while any of the returned slices is alive
+    pub(crate) unsafe fn spare_capacity(&self, additional: usize) -> SpareCapacity<'_, T, S> {
+        assert!(
+            !matches!(self.remaining(), Some(remaining) if remaining < additional),
+            "Pushed to a full Stele"
+        );
+        //SAFETY: By the safety contract we are the only writer
+        unsafe { self.reserve_blocks(additional) };
+        let start = self.raw.len.load(ord::ACQ);
+        SpareCapacity {
+            stele: self,
+            range: start..start + additional,
+        }
+    }
+
+    /// Publishes the `n` slots past the end as elements of the Stele
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `n` slots past the end have been allocated, or if the Stele is bounded and does not have room for them
+    ///
+    /// SAFETY: You must be the only writer, and the first `n` slots past the end must have been initialized
+    pub(crate) unsafe fn publish_spare(&self, n: usize) {
+        let start = self.raw.len.load(ord::ACQ);
+        let end = start + n;
+        assert!(
+            end <= self.capacity() && !matches!(self.remaining(), Some(remaining) if remaining < n),
+            "Published {} elements past the end, but only {} slots are spare",
+            n,
+            self.capacity().min(self.bound.unwrap_or(usize::MAX)) - start
+        );
+        //Only blocks that have held a reservation from `push_uninit` track which elements are initialized
+        (start..end).for_each(|idx| self.mark_initialized(idx, ord::RLX));
+        self.raw.len.store(end, ord::REL);
+        self.notify_readers();
+    }
+}
+
+impl<T, S: Storage> Stele<T, S> {
+    /// Pushes the values `next` returns until it returns [`None`] or the Stele is full, returning how many were pushed
+    ///
+    /// The values are written into the slots past the end, and the length is published once per block rather than once per value.
+    /// `next` is not called once the Stele is full
+    ///
+    /// SAFETY: You must be the only writer
+    #[cfg(feature = "std")]
+    pub(crate) unsafe fn push_batched(&self, mut next: impl FnMut() -> Option<T>) -> usize {
+        let mut pushed = 0;
+        loop {
+            let len = self.raw.len.load(ord::ACQ);
+            let mut batch = match self.remaining() {
+                Some(0) => return pushed,
+                Some(remaining) => remaining,
+                None => usize::MAX,
+            };
+            let (outer_idx, inner_idx) = self.raw.split_idx(len);
+            batch = batch.min(self.raw.block_len(outer_idx) - inner_idx);
+            //SAFETY: By the safety contract we are the only writer, and nothing else is pushed until the slots are published.
+            //The batch ends with the block, so its slots come as a single slice
+            let slots = unsafe { self.spare_capacity(batch) }
+                .next()
+                .expect("A batch has at least one slot");
+            let mut filled = 0;
+            //If `next` panics, the values written so far are leaked rather than published
+            while let Some(slot) = slots.get_mut(filled) {
+                match next() {
+                    Some(val) => {
+                        slot.write(val);
+                        filled += 1;
+                    }
+                    None => break,
+                }
+            }
+            //SAFETY: The first `filled` slots past the end were just written
+            unsafe { self.publish_spare(filled) };
+            pushed += filled;
+            if filled < batch {
+                return pushed;
+            }
+        }
+    }
+}
+
+impl<T, S: Storage> SpareCapacity<'_, T, S> {
+    /// Returns the index the first slot that has not been handed out yet will have once it is published
+    #[must_use]
+    pub fn start(&self) -> usize {
+        self.range.start
+    }
+
+    /// Returns the number of slots that have not been handed out yet
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    /// Returns `true` if every slot has been handed out
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+}
+
+impl<'w, T, S: Storage> Iterator for SpareCapacity<'w, T, S> {
+    type Item = &'w mut [MaybeUninit<T>];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let raw = &self.stele.raw;
+        let (outer_idx, inner_idx) = raw.split_idx(self.range.start);
+        let len = (raw.block_len(outer_idx) - inner_idx).min(self.range.len());
+        //SAFETY: The block holding the start of the range was allocated when the slots were handed out,
+        //and `Inner<T>` has the same layout as `MaybeUninit<T>`
+        let slots = unsafe { raw.read_raw(self.range.start) }.cast::<MaybeUninit<T>>();
+        self.range.start += len;
+        //SAFETY: The slots lie within one allocated block and past the end of the Stele, so no reader can reach them,
+        //and they are only handed out once while the writer is borrowed mutably, so nothing else writes to them either
+        Some(unsafe { core::slice::from_raw_parts_mut(slots, len) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let blocks = if self.range.is_empty() {
+            0
+        } else {
+            let raw = &self.stele.raw;
+            raw.split_idx(self.range.end - 1).0 - raw.split_idx(self.range.start).0 + 1
+        };
+        (blocks, Some(blocks))
+    }
+}
@@ -0,0 +1,212 @@
+use core::{marker::PhantomData, ops::Index};
+
+use super::{iter::RefIterator, Stele};
+use crate::{
+    mem::{DefaultStorage, Storage},
+    Full, PushError,
+};
+
+/// The writer for a [`Stele`] stored in a `static`, returned by [`Stele::claim_writer`]
+///
+/// Like [`WriteHandle`](crate::WriteHandle) this is `Send` but `!Sync`, and only one can ever be claimed for each [`Stele`]
+#[derive(Debug)]
+pub struct StaticWriteHandle<'a, T, S: Storage = DefaultStorage> {
+    pub(crate) handle: &'a Stele<T, S>,
+    pub(crate) _unsync: PhantomData<*mut T>,
+}
+
+//SAFETY: StaticWriteHandle only provides immutable references to its contents and uses atomic operations internally
+//so as long as the Stele, and therefore its items and storage, is both Send and Sync it is safe to implement Send
+unsafe impl<T, S: Storage> Send for StaticWriteHandle<'_, T, S> where Stele<T, S>: Send + Sync {}
+
+impl<'a, T, S: Storage> StaticWriteHandle<'a, T, S> {
+    /// Pushes a new item on to the end of the [`Stele`], allocating a new block of memory if necessary
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`Stele`] is [bounded](Stele::bounded) and full, see [`try_push`](StaticWriteHandle::try_push)
+    pub fn push(&self, val: T) {
+        assert!(self.try_push(val).is_ok(), "Pushed to a full Stele");
+    }
+
+    /// Pushes a new item on to the end of the [`Stele`] unless it is [bounded](Stele::bounded) and already full
+    ///
+    /// # Errors
+    ///
+    /// Returns `val` wrapped in [`Full`] if the [`Stele`] already holds as many elements as its bound allows
+    pub fn try_push(&self, val: T) -> Result<(), Full<T>> {
+        //SAFETY: StaticWriteHandle is neither Sync nor Clone and can only be claimed once,
+        //so it is the only writer and can only be used by one thread at a time
+        unsafe { self.handle.try_push(val) }
+    }
+
+    /// Pushes a new item on to the end of the [`Stele`] only if that does not require allocating a new block,
+    /// returning the item otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns `val` in a [`PushError`] with [`SteleError::CapacityExceeded`](crate::SteleError::CapacityExceeded) if the block it belongs in has not been allocated
+    /// or the [`Stele`] is [bounded](Stele::bounded) and full
+    pub fn push_within_capacity(&self, val: T) -> Result<(), PushError<T>> {
+        //SAFETY: StaticWriteHandle is neither Sync nor Clone and can only be claimed once,
+        //so it is the only writer and can only be used by one thread at a time
+        unsafe { self.handle.push_within_capacity(val) }
+    }
+
+    /// Creates a new [`StaticReadHandle`]
+    #[must_use]
+    pub fn reader(&self) -> StaticReadHandle<'a, T, S> {
+        StaticReadHandle {
+            handle: self.handle,
+        }
+    }
+
+    /// Reads the value at the given index
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the given index is out of bounds or the element is a reservation that has not been filled,
+    /// in every build
+    #[must_use]
+    pub fn read(&self, idx: usize) -> &'a T {
+        self.handle
+            .read(idx)
+            .expect("Read past the initialized elements")
+    }
+
+    /// Attempts to read the value at the index and returns [`Some`] if the value exists, and [`None`] otherwise
+    #[must_use]
+    pub fn try_read(&self, idx: usize) -> Option<&'a T> {
+        self.handle.read(idx)
+    }
+
+    /// Returns the current length of the underlying [`Stele`]
+    ///
+    /// Note:
+    /// By calling this through the [`StaticWriteHandle`], you hold the only handle that can change the
+    /// length and therefore this information is accurate until the next call to [`push`](StaticWriteHandle::push)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handle.len()
+    }
+
+    /// Returns whether the underlying [`Stele`] is empty or not
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.handle.is_empty()
+    }
+}
+
+/// The reader for a [`Stele`] stored in a `static`, returned by [`Stele::reader`]
+///
+/// It only borrows the [`Stele`], so it can be copied freely. The same reader borrowed from a [`WriteHandle`](crate::WriteHandle)
+/// goes by [`ReaderRef`]
+#[derive(Debug)]
+pub struct StaticReadHandle<'a, T, S: Storage = DefaultStorage> {
+    pub(crate) handle: &'a Stele<T, S>,
+}
+
+/// A reader borrowed from a [`WriteHandle`](crate::WriteHandle) with [`as_reader`](crate::WriteHandle::as_reader)
+///
+/// This is a [`StaticReadHandle`] under another name: it reads through a plain reference to the [`Stele`],
+/// so creating and copying it never touches the reference count, and the borrow keeps it from outliving the writer
+pub type ReaderRef<'a, T, S = DefaultStorage> = StaticReadHandle<'a, T, S>;
+
+impl<'a, T, S: Storage> StaticReadHandle<'a, T, S> {
+    /// Reads the value at the given index
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the given index is out of bounds or the element is a reservation that has not been filled,
+    /// in every build. Since [`Index`] operates through this function, indexing panics the same way
+    #[must_use]
+    pub fn read(&self, idx: usize) -> &'a T {
+        self.handle
+            .read(idx)
+            .expect("Read past the initialized elements")
+    }
+
+    /// Attempts to read the value at the index and returns [`Some`] if the value exists, and [`None`] otherwise
+    #[must_use]
+    pub fn try_read(&self, idx: usize) -> Option<&'a T> {
+        self.handle.read(idx)
+    }
+
+    /// Returns the current length of the underlying [`Stele`]
+    ///
+    /// Note: this is an optimistic operation and the length may be changing under you
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handle.len()
+    }
+
+    /// Returns whether the underlying [`Stele`] is empty or not
+    ///
+    /// Note: if this returns `false` it *cannot* return `true` in the future
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.handle.is_empty()
+    }
+
+    /// Returns a snapshot of the initialized length and the blocks holding those elements, laid out for foreign readers,
+    /// see [`ReadHandle::as_raw_parts`](crate::ReadHandle::as_raw_parts)
+    #[must_use]
+    pub fn as_raw_parts(&self) -> crate::ffi::RawParts<T> {
+        self.handle.raw_parts()
+    }
+
+    /// Creates a [`RefIterator`] over the elements pushed so far
+    #[must_use]
+    pub fn iter(&self) -> RefIterator<'a, T, S> {
+        RefIterator::from_stele(self.handle)
+    }
+}
+
+impl<T: Copy, S: Storage> StaticReadHandle<'_, T, S> {
+    /// Get provides a way to get an owned copy of a value inside a [`Stele`]
+    /// provided the `T` implements [`Copy`]
+    ///
+    /// # Panics
+    ///
+    /// This function panics like [`read`](StaticReadHandle::read)
+    #[must_use]
+    pub fn get(&self, idx: usize) -> T {
+        *self.read(idx)
+    }
+}
+
+impl<T, S: Storage> Clone for StaticReadHandle<'_, T, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, S: Storage> Copy for StaticReadHandle<'_, T, S> {}
+
+impl<'a, T, S: Storage> IntoIterator for StaticReadHandle<'a, T, S> {
+    type Item = &'a T;
+
+    type IntoIter = RefIterator<'a, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, S: Storage> IntoIterator for &StaticReadHandle<'a, T, S> {
+    type Item = &'a T;
+
+    type IntoIter = RefIterator<'a, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, S: Storage> Index<usize> for StaticReadHandle<'_, T, S> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.read(index)
+    }
+}
@@ -0,0 +1,133 @@
+use alloc::vec::Vec;
+use core::{
+    pin::Pin,
+    sync::atomic::Ordering,
+    task::{Context, Poll, Waker},
+};
+
+use futures_core::Stream;
+
+use super::{reader::ReadHandle, Stele};
+use crate::{
+    mem::{DefaultStorage, Storage},
+    sync::{fence, ord},
+};
+
+/// The wakers of every stream waiting for the next push, and whether the writer is gone
+#[derive(Debug, Default)]
+pub(crate) struct WakerSlot {
+    wakers: Vec<Waker>,
+    closed: bool,
+}
+
+impl WakerSlot {
+    //Only used by `Stele::const_new`, which loom and shuttle cannot support
+    #[cfg(not(any(loom, feature = "loom", shuttle)))]
+    pub(crate) const fn new() -> Self {
+        Self {
+            wakers: Vec::new(),
+            closed: false,
+        }
+    }
+}
+
+//A stream registers its waker and then checks the length again, while a push stores the length and then checks for wakers.
+//With a SeqCst fence between the store and the load on both sides at least one of them sees the other,
+//so a push can never slip in between a stream's last check and its registration without either being seen or waking it
+impl<T, S: Storage> Stele<T, S> {
+    /// Registers `waker` to be woken by the next push, returning `true` instead if the writer is gone
+    pub(crate) fn register_waker(&self, waker: &Waker) -> bool {
+        let mut slot = self.wakers.lock().unwrap();
+        if slot.closed {
+            return true;
+        }
+        if !slot.wakers.iter().any(|w| w.will_wake(waker)) {
+            slot.wakers.push(waker.clone());
+            self.waiting.store(slot.wakers.len(), ord::RLX);
+        }
+        drop(slot);
+        fence(Ordering::SeqCst);
+        false
+    }
+
+    /// Wakes every registered stream, called after every push
+    pub(crate) fn wake_waiting(&self) {
+        fence(Ordering::SeqCst);
+        if self.waiting.load(ord::RLX) == 0 {
+            return;
+        }
+        let wakers = {
+            let mut slot = self.wakers.lock().unwrap();
+            self.waiting.store(0, ord::RLX);
+            core::mem::take(&mut slot.wakers)
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Wakes every registered stream for the last time, called when the writer is dropped
+    pub(crate) fn close_streams(&self) {
+        let wakers = {
+            let mut slot = self.wakers.lock().unwrap();
+            slot.closed = true;
+            self.waiting.store(0, ord::RLX);
+            core::mem::take(&mut slot.wakers)
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Returns whether streams have been told that nothing more is coming
+    #[cfg(feature = "tokio-io")]
+    pub(crate) fn streams_closed(&self) -> bool {
+        self.wakers.lock().unwrap().closed
+    }
+
+    /// Lets streams wait for pushes again, called when a reader is promoted to the writer
+    pub(crate) fn reopen_streams(&self) {
+        self.wakers.lock().unwrap().closed = false;
+    }
+}
+
+///A [`Stream`] that yields clones of the elements of a [`Stele`] as they are pushed, ending once the writer is dropped
+#[derive(Debug)]
+pub struct SteleStream<T, S: Storage = DefaultStorage> {
+    handle: ReadHandle<T, S>,
+    pos: usize,
+}
+
+impl<T, S: Storage> SteleStream<T, S> {
+    ///Creates a new [`SteleStream`] starting at the first element
+    #[must_use]
+    pub fn new(handle: ReadHandle<T, S>) -> Self {
+        Self { handle, pos: 0 }
+    }
+}
+
+impl<T: Clone, S: Storage> SteleStream<T, S> {
+    fn next_ready(&mut self) -> Option<T> {
+        let val = self.handle.try_read(self.pos)?.clone();
+        self.pos += 1;
+        Some(val)
+    }
+}
+
+impl<T: Clone, S: Storage> Stream for SteleStream<T, S> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(val) = this.next_ready() {
+            return Poll::Ready(Some(val));
+        }
+        let closed = this.handle.handle.register_waker(cx.waker());
+        //Check again now that the waker is registered, in case a push landed in between
+        match this.next_ready() {
+            Some(val) => Poll::Ready(Some(val)),
+            None if closed => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.handle.len() - self.pos, None)
+    }
+}
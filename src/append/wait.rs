@@ -0,0 +1,139 @@
+#[cfg(feature = "futures")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::Stele;
+#[cfg(feature = "futures")]
+use crate::mem::DefaultStorage;
+use crate::mem::Storage;
+#[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+use crate::sync::ord;
+
+impl<T, S: Storage> Stele<T, S> {
+    /// Checks every initialized element from `*pos` on against `pred` and returns the first that matches,
+    /// leaving `*pos` just past it, or past the last element checked if none did
+    ///
+    /// Only the initialized prefix is checked, so elements are always checked in order even if a reservation is still unfilled
+    #[cfg(any(
+        feature = "futures",
+        not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+    ))]
+    pub(crate) fn scan_for_match<F>(&self, pos: &mut usize, pred: &mut F) -> Option<(usize, &T)>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let end = self.initialized_len();
+        while *pos < end {
+            let idx = *pos;
+            *pos += 1;
+            let val = self.read_at(idx);
+            if pred(val) {
+                return Some((idx, val));
+            }
+        }
+        None
+    }
+
+    /// Blocks until an element from `start` on matches `pred`, giving up once the writer is gone or, if there is one,
+    /// the deadline has passed, in which case the index to resume from is returned
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`Notify`](crate::Notify) was set with [`set_notifier`](Stele::set_notifier)
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+    ))]
+    pub(crate) fn wait_for_match<F>(
+        &self,
+        start: usize,
+        pred: &mut F,
+        deadline: Option<std::time::Instant>,
+    ) -> Result<Option<(usize, &T)>, usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let notifier = self
+            .notifier
+            .as_ref()
+            .expect("Waiting requires a notifier to be set");
+        let mut pos = start;
+        loop {
+            if let Some(found) = self.scan_for_match(&mut pos, pred) {
+                return Ok(Some(found));
+            }
+            //Every push made before the writer was dropped is visible once it is seen to be gone
+            if self.closed.load(ord::ACQ) {
+                return Ok(self.scan_for_match(&mut pos, pred));
+            }
+            let mut ready = || self.initialized_len() > pos || self.closed.load(ord::ACQ);
+            match deadline {
+                Some(deadline) => {
+                    let timeout = deadline.saturating_duration_since(std::time::Instant::now());
+                    if !notifier.wait_until_timeout(&mut ready, timeout) {
+                        return Err(pos);
+                    }
+                }
+                None => notifier.wait_until(&mut ready),
+            }
+        }
+    }
+}
+
+/// A [`Future`] that resolves to the first element from some index on that matches a predicate,
+/// created by [`ReadHandle::wait_for_match_async`](crate::ReadHandle::wait_for_match_async)
+///
+/// It resolves to [`None`] if the [`WriteHandle`](crate::WriteHandle) is dropped before a matching element is pushed
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitForMatch<'a, T, F, S: Storage = DefaultStorage> {
+    handle: &'a Stele<T, S>,
+    pos: usize,
+    pred: F,
+}
+
+#[cfg(feature = "futures")]
+impl<'a, T, F, S: Storage> WaitForMatch<'a, T, F, S> {
+    pub(crate) fn new(handle: &'a Stele<T, S>, start: usize, pred: F) -> Self {
+        WaitForMatch {
+            handle,
+            pos: start,
+            pred,
+        }
+    }
+
+    /// Returns the index the search continues from, as every element before it has been checked
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<'a, T, F, S> Future for WaitForMatch<'a, T, F, S>
+where
+    F: FnMut(&T) -> bool + Unpin,
+    S: Storage,
+{
+    type Output = Option<(usize, &'a T)>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let handle = this.handle;
+        if let Some(found) = handle.scan_for_match(&mut this.pos, &mut this.pred) {
+            return Poll::Ready(Some(found));
+        }
+        let closed = handle.register_waker(cx.waker());
+        //Check again now that the waker is registered, in case a push landed in between
+        match handle.scan_for_match(&mut this.pos, &mut this.pred) {
+            Some(found) => Poll::Ready(Some(found)),
+            None if closed => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
@@ -1,6 +1,12 @@
-use core::marker::PhantomData;
+use core::{marker::PhantomData, pin::Pin, ptr::NonNull};
 
-use crate::{sync::Arc, ReadHandle, Stele};
+use super::{key::Key, ReadHandle, Stele};
+use crate::{
+    mem::{DefaultStorage, Storage},
+    sync::Arc,
+    Full, MonotonicError, Placement, PushError, RetryOrFail, SteleError,
+};
+use alloc::{alloc::Layout, boxed::Box, vec::Vec};
 
 /// The writer for a [`Stele`]
 ///
@@ -20,31 +26,432 @@ use crate::{sync::Arc, ReadHandle, Stele};
 /// and all data is reclaimed if and only if there are no more handles left,
 /// at which point there cannot be any way to access the data inside and therefore we leave no dangling references.
 #[derive(Debug)]
-pub struct WriteHandle<T> {
-    pub(crate) handle: Arc<Stele<T>>,
+#[repr(transparent)]
+pub struct WriteHandle<T, S: Storage = DefaultStorage> {
+    pub(crate) handle: Arc<Stele<T, S>>,
     pub(crate) _unsync: PhantomData<*mut T>,
 }
 
 //SAFETY: WriteHandle only provides immutable references to its contents and uses atomic operations internally
-//so as long as the type of its items are both Send and Sync it is safe to implement Send
-unsafe impl<T> Send for WriteHandle<T> where T: Send + Sync {}
+//so as long as the Stele, and therefore its items and storage, is both Send and Sync it is safe to implement Send
+unsafe impl<T, S: Storage> Send for WriteHandle<T, S> where Stele<T, S>: Send + Sync {}
 
-impl<T> WriteHandle<T> {
+impl<T, S: Storage> WriteHandle<T, S> {
     /// Pushes a new item on to the end of the [`Stele`], allocating a new block of memory if necessary
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`Stele`] is [bounded](Stele::bounded) and full, see [`try_push`](WriteHandle::try_push)
     pub fn push(&self, val: T) {
+        assert!(self.try_push(val).is_ok(), "Pushed to a full Stele");
+    }
+
+    /// Pushes a new item on to the end of the [`Stele`] unless it is [bounded](Stele::bounded) and already full
+    ///
+    /// There is no blocking variant, as elements are never removed and this is the only handle that could
+    /// [`recycle`](WriteHandle::try_recycle) the [`Stele`] to make room again
+    ///
+    /// # Errors
+    ///
+    /// Returns `val` wrapped in [`Full`] if the [`Stele`] already holds as many elements as its bound allows
+    pub fn try_push(&self, val: T) -> Result<(), Full<T>> {
         //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
         //and can only be used by one thread at a time
-        unsafe {
-            self.handle.push(val);
-        };
+        unsafe { self.handle.try_push(val) }
+    }
+
+    /// Moves every item of `items` on to the end of the [`Stele`] and publishes them at once, returning the index of the first one
+    ///
+    /// Readers see either all of the items or none of them, even when they straddle a block boundary
+    ///
+    /// # Panics
+    ///
+    /// Panics without pushing anything if the [`Stele`] is [bounded](Stele::bounded) and does not have room for all of them
+    pub fn push_array<const N: usize>(&self, items: [T; N]) -> usize {
+        //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+        //and can only be used by one thread at a time
+        unsafe { self.handle.push_array(items) }
+    }
+
+    /// Reserves the next index without initializing it, allocating its block if necessary, and returns a [`Slot`](super::slot::Slot)
+    /// that can fill it later, possibly on another thread
+    ///
+    /// The reserved index counts towards [`len`](WriteHandle::len) straight away, so later pushes go after it
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`Stele`] is [bounded](Stele::bounded) and full
+    #[must_use]
+    pub fn push_uninit(&self) -> super::slot::Slot<T, S> {
+        //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+        //and can only be used by one thread at a time
+        let idx = unsafe { self.handle.reserve() };
+        super::slot::Slot {
+            handle: Arc::clone(&self.handle),
+            idx,
+        }
+    }
+
+    /// Pushes a new item on to the end of the [`Stele`] and returns it pinned in place, see [`ReadHandle::read_pinned`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`Stele`] is [bounded](Stele::bounded) and full
+    pub fn push_pinned(&self, val: T) -> Pin<&T> {
+        let idx = self.len();
+        self.push(val);
+        //SAFETY: Elements never move once pushed, see `ReadHandle::read_pinned`
+        unsafe { Pin::new_unchecked(self.read(idx)) }
+    }
+
+    /// Pushes every item from `iter` if they all fit within the bound, and pushes none of them otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns the untouched iterator wrapped in [`Full`] if the [`Stele`] is [bounded](Stele::bounded)
+    /// and does not have room for all of its items
+    pub fn try_extend<I>(&self, iter: I) -> Result<(), Full<I::IntoIter>>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        if matches!(self.remaining(), Some(remaining) if remaining < iter.len()) {
+            return Err(Full(iter));
+        }
+        for val in iter {
+            self.push(val);
+        }
+        Ok(())
+    }
+
+    /// Pushes `val` unless it is equal to the last element, returning the index it was pushed at or [`None`] if it was dropped
+    ///
+    /// This is the only handle that can push, so the last element cannot change between comparing and pushing.
+    /// A last element that is a [reservation](WriteHandle::push_uninit) that has not been filled never compares equal
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is pushed and the [`Stele`] is [bounded](Stele::bounded) and full
+    pub fn push_dedup(&self, val: T) -> Option<usize>
+    where
+        T: PartialEq,
+    {
+        self.push_dedup_by(val, |val, last| val == last)
+    }
+
+    /// Pushes `val` unless `same` returns `true` for it and the last element, see [`push_dedup`](WriteHandle::push_dedup)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is pushed and the [`Stele`] is [bounded](Stele::bounded) and full
+    pub fn push_dedup_by<F>(&self, val: T, same: F) -> Option<usize>
+    where
+        F: FnOnce(&T, &T) -> bool,
+    {
+        let len = self.len();
+        let last = len.checked_sub(1).and_then(|last| self.try_read(last));
+        if last.is_some_and(|last| same(&val, last)) {
+            return None;
+        }
+        self.push(val);
+        Some(len)
+    }
+
+    /// Pushes `val` unless it has the same key as the last element, see [`push_dedup`](WriteHandle::push_dedup)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is pushed and the [`Stele`] is [bounded](Stele::bounded) and full
+    pub fn push_dedup_by_key<K, F>(&self, val: T, mut key: F) -> Option<usize>
+    where
+        K: PartialEq,
+        F: FnMut(&T) -> K,
+    {
+        self.push_dedup_by(val, |val, last| key(val) == key(last))
+    }
+
+    /// Pushes every item from `iter` that is not equal to the element before it, whether that was pushed earlier or
+    /// comes from `iter` as well, and returns how many were pushed
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`Stele`] is [bounded](Stele::bounded) and fills up, after pushing as many items as fit
+    pub fn extend_dedup<I>(&self, iter: I) -> usize
+    where
+        I: IntoIterator<Item = T>,
+        T: PartialEq,
+    {
+        iter.into_iter()
+            .filter_map(|val| self.push_dedup(val))
+            .count()
+    }
+
+    /// Pushes `val` if it is greater than or equal to the last element, returning the index it was pushed at
+    ///
+    /// Pushing only through this keeps the [`Stele`] [sorted](ReadHandle::is_sorted), so it can be binary searched.
+    /// Like [`push_dedup`](WriteHandle::push_dedup) there is no race between comparing and pushing,
+    /// and a last element that is an unfilled [reservation](WriteHandle::push_uninit) is not compared against
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`MonotonicError`] holding `val` if it is less than the last element or cannot be compared with it
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is pushed and the [`Stele`] is [bounded](Stele::bounded) and full
+    pub fn push_monotonic(&self, val: T) -> Result<usize, MonotonicError<T>>
+    where
+        T: PartialOrd,
+    {
+        self.push_ordered(val, |val, last| val >= last)
+    }
+
+    /// Pushes `val` if it is greater than the last element, returning the index it was pushed at,
+    /// see [`push_monotonic`](WriteHandle::push_monotonic)
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`MonotonicError`] holding `val` if it is less than or equal to the last element or cannot be compared with it
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is pushed and the [`Stele`] is [bounded](Stele::bounded) and full
+    pub fn push_strictly_monotonic(&self, val: T) -> Result<usize, MonotonicError<T>>
+    where
+        T: PartialOrd,
+    {
+        self.push_ordered(val, |val, last| val > last)
+    }
+
+    fn push_ordered(
+        &self,
+        val: T,
+        ordered: impl FnOnce(&T, &T) -> bool,
+    ) -> Result<usize, MonotonicError<T>> {
+        let len = self.len();
+        if let Some(last) = len.checked_sub(1) {
+            if self.try_read(last).is_some_and(|last| !ordered(&val, last)) {
+                return Err(MonotonicError { value: val, last });
+            }
+        }
+        self.push(val);
+        Ok(len)
+    }
+
+    /// Drops every element and resets the length to zero while keeping all allocated blocks,
+    /// returning `true` if it succeeded
+    ///
+    /// This only succeeds while there are no [`ReadHandle`]s left, otherwise it returns `false` and
+    /// leaves the [`Stele`] untouched
+    pub fn try_recycle(&mut self) -> bool {
+        match Arc::get_mut(&mut self.handle) {
+            Some(stele) => {
+                stele.recycle();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Recovers the owned [`Stele`] from the [`WriteHandle`] and a [`ReadHandle`] to it, so that it can be recycled or consumed
+    ///
+    /// # Errors
+    ///
+    /// Returns both handles unchanged if `reader` belongs to a different [`Stele`] or if any other [`ReadHandle`] is still alive
+    pub fn try_unwrap(
+        self,
+        reader: ReadHandle<T, S>,
+    ) -> Result<Stele<T, S>, (Self, ReadHandle<T, S>)> {
+        if !Arc::ptr_eq(&self.handle, &reader.handle) {
+            return Err((self, reader));
+        }
+        drop(reader);
+        let this = core::mem::ManuallyDrop::new(self);
+        //SAFETY: `this` is never used or dropped again, so the handle is moved out exactly once
+        let handle = unsafe { core::ptr::read(core::ptr::addr_of!(this.handle)) };
+        Arc::try_unwrap(handle).map_err(|handle| {
+            (
+                WriteHandle {
+                    handle: Arc::clone(&handle),
+                    _unsync: PhantomData,
+                },
+                ReadHandle { handle },
+            )
+        })
+    }
+
+    /// Moves every element into a [`Vec`] if `reader` is the only other handle, see [`try_unwrap`](WriteHandle::try_unwrap)
+    /// and [`Stele::into_vec`]
+    ///
+    /// # Errors
+    ///
+    /// Returns both handles unchanged if `reader` belongs to a different [`Stele`] or if any other [`ReadHandle`] is still alive
+    pub fn try_into_vec(self, reader: ReadHandle<T, S>) -> Result<Vec<T>, (Self, ReadHandle<T, S>)>
+    where
+        T: Unpin,
+    {
+        self.try_unwrap(reader).map(Stele::into_vec)
+    }
+
+    /// Consumes the [`WriteHandle`] and frees every allocated block that does not hold any elements,
+    /// returning a [`ReadHandle`] to the now sealed [`Stele`]
+    ///
+    /// No more elements can be pushed once the writer is gone, so any capacity beyond the current length
+    /// would otherwise stay allocated until the last handle is dropped
+    #[must_use]
+    pub fn shrink_unused(self) -> ReadHandle<T, S> {
+        //SAFETY: We consume the only WriteHandle so there can be no further pushes
+        unsafe { self.handle.shrink_unused() };
+        ReadHandle::from(&self.handle)
+    }
+
+    /// Pushes a new item on to the end of the [`Stele`] only if that does not require allocating a new block,
+    /// returning the item otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns `val` in a [`PushError`] with [`SteleError::CapacityExceeded`](crate::SteleError::CapacityExceeded) if the block it belongs in has not been allocated
+    /// or the [`Stele`] is [bounded](Stele::bounded) and full
+    pub fn push_within_capacity(&self, val: T) -> Result<(), PushError<T>> {
+        //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+        //and can only be used by one thread at a time
+        unsafe { self.handle.push_within_capacity(val) }
+    }
+
+    /// Allocates every block needed to push `additional` more elements without allocating,
+    /// so that [`push_within_capacity`](WriteHandle::push_within_capacity) succeeds for them
+    ///
+    /// A [bounded](Stele::bounded) [`Stele`] only allocates up to its bound
+    pub fn reserve(&self, additional: usize) {
+        //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+        //and can only be used by one thread at a time
+        unsafe { self.handle.reserve_blocks(additional) };
+    }
+
+    /// Reserves `total` more elements and returns one [`BlockWriter`](super::bulk::BlockWriter) for each block they fall in,
+    /// which can fill their part of the range in parallel on other threads
+    ///
+    /// Readers see none of the elements until every writer is handed to [`BlockWriter::publish`](super::bulk::BlockWriter::publish),
+    /// which publishes the new length once. The writers borrow this handle mutably, so nothing else can be pushed in the meantime
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`Stele`] is [bounded](Stele::bounded) and does not have room for `total` more elements
+    pub fn reserve_blocks(&mut self, total: usize) -> Vec<super::bulk::BlockWriter<'_, T, S>> {
+        //SAFETY: This is the only writer, and the returned writers borrow it mutably so nothing is pushed until they are gone
+        unsafe { self.handle.reserve_block_writers(total) }
+    }
+
+    /// Allocates every block needed to hold `additional` more elements and returns the uninitialized slots for them,
+    /// as one [`MaybeUninit`](core::mem::MaybeUninit) slice per block
+    ///
+    /// This mirrors [`Vec::spare_capacity_mut`]: write to the slots, such as by handing them to a foreign function,
+    /// and then call [`publish_initialized`](WriteHandle::publish_initialized) with the number of leading slots that were initialized.
+    /// The slots borrow this handle mutably, so nothing else can be pushed while they are being written
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`Stele`] is [bounded](Stele::bounded) and does not have room for `additional` more elements
+    pub fn spare_blocks_mut(&mut self, additional: usize) -> super::spare::SpareCapacity<'_, T, S> {
+        //SAFETY: This is the only writer, and the slots borrow it mutably so nothing is pushed until they are gone
+        unsafe { self.handle.spare_capacity(additional) }
+    }
+
+    /// Makes the first `n` slots past the end visible to readers as elements, publishing the new length once
+    ///
+    /// This is the counterpart of [`spare_blocks_mut`](WriteHandle::spare_blocks_mut), like [`Vec::set_len`] is for
+    /// [`Vec::spare_capacity_mut`]. The slots are counted across blocks, so `n` may cover several of the slices handed out
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `n` slots past the end have been allocated, or if the [`Stele`] is [bounded](Stele::bounded)
+    /// and does not have room for `n` more elements
+    ///
+    /// # Safety
+    ///
+    /// The first `n` slots past the end must all have been fully initialized, such as through the slices returned by
+    /// [`spare_blocks_mut`](WriteHandle::spare_blocks_mut), since readers can access them as soon as this returns
+    pub unsafe fn publish_initialized(&self, n: usize) {
+        //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time, and the slots are initialized by the safety contract
+        unsafe { self.handle.publish_spare(n) };
+    }
+
+    /// Turns the [`WriteHandle`] into an [`IsrWriteHandle`](super::isr::IsrWriteHandle) that can be shared with interrupt handlers
+    ///
+    /// Call [`reserve`](WriteHandle::reserve) first, as the shared handle never allocates
+    #[cfg(feature = "critical-section")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "critical-section")))]
+    #[must_use]
+    pub fn into_isr_shared(self) -> super::isr::IsrWriteHandle<T, S> {
+        super::isr::IsrWriteHandle { writer: self }
+    }
+
+    /// Turns the [`WriteHandle`] into a [`SteleSink`](super::sink::SteleSink) that pushes every item it is sent
+    #[cfg(feature = "futures")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+    #[must_use]
+    pub fn sink(self) -> super::sink::SteleSink<T, S> {
+        super::sink::SteleSink::new(self)
+    }
+
+    /// Sets a hook that is consulted whenever allocating a block fails
+    ///
+    /// If the hook returns [`RetryOrFail::Retry`], usually after freeing some memory, the allocation is attempted again,
+    /// up to a bounded number of times, before falling back to [`handle_alloc_error`](alloc::alloc::handle_alloc_error).
+    /// Without a hook an allocation failure goes straight to [`handle_alloc_error`](alloc::alloc::handle_alloc_error)
+    pub fn set_alloc_error_hook(
+        &self,
+        hook: impl Fn(Layout) -> RetryOrFail + Send + Sync + 'static,
+    ) {
+        //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+        //and can only be used by one thread at a time
+        unsafe { self.handle.set_alloc_error_hook_unchecked(Box::new(hook)) };
+    }
+
+    /// Sets a hook that picks where every block allocated from now on is placed
+    ///
+    /// The hook is called with the index of the block and its layout after the block is allocated but before any reader can see it,
+    /// so that a [`Placement::Node`] moves it to the NUMA node of the threads reading it before they fault in its pages.
+    /// Binding blocks to a node needs the `numa` feature on Linux, elsewhere the hook is still called but its answer is ignored
+    pub fn set_block_placement(
+        &self,
+        hook: impl Fn(usize, Layout) -> Placement + Send + Sync + 'static,
+    ) {
+        //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+        //and can only be used by one thread at a time
+        unsafe { self.handle.set_block_placement_unchecked(Box::new(hook)) };
+    }
+
+    /// Sets a hook that is called with every element just before it is dropped
+    ///
+    /// This lets elements that are handles into some external pool, such as plain ids or file descriptors, be returned to it
+    /// without wrapping each of them in a type with a [`Drop`] impl. The hook is called exactly once for every initialized element,
+    /// in index order, whenever the elements are dropped: when the last handle to the [`Stele`] is dropped, and when it is
+    /// [recycled](WriteHandle::try_recycle). Elements moved out by [`Stele::into_vec`] are not dropped and so are not passed to it
+    pub fn set_drop_hook(&self, hook: impl Fn(&T) + Send + Sync + 'static) {
+        //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+        //and can only be used by one thread at a time
+        unsafe { self.handle.set_drop_hook_unchecked(hook) };
     }
 
     /// Creates a new [`ReadHandle`]
     #[must_use]
-    pub fn new_read_handle(&self) -> ReadHandle<T> {
+    pub fn new_read_handle(&self) -> ReadHandle<T, S> {
         ReadHandle::from(&self.handle)
     }
 
+    /// Borrows a reader from this handle, without touching the reference count like [`new_read_handle`](WriteHandle::new_read_handle)
+    ///
+    /// The [`ReaderRef`](super::static_handle::ReaderRef) reads like a [`ReadHandle`] but cannot outlive this borrow,
+    /// which makes it a cheap way to pass the elements to a function for the duration of a call
+    #[must_use]
+    pub fn as_reader(&self) -> super::static_handle::ReaderRef<'_, T, S> {
+        super::static_handle::StaticReadHandle {
+            handle: &self.handle,
+        }
+    }
+
     /// Reads the value at the given index
     ///
     /// # Panic
@@ -52,16 +459,63 @@ impl<T> WriteHandle<T> {
     /// This function panics in debug if the given index is out of bounds.
     #[must_use]
     pub fn read(&self, idx: usize) -> &T {
-        self.handle.read(idx)
+        self.handle.read_at(idx)
     }
 
     /// Attempts to read the value at the index and returns [`Some`] if the value exists, and [`None`] otherwise
     #[must_use]
     pub fn try_read(&self, idx: usize) -> Option<&T> {
-        self.handle.try_read(idx)
+        self.handle.read(idx)
     }
 
-    /// Returns the current length of the underlying [`Stele`]
+    /// Reads the element at `key`, see [`ReadHandle::read_key`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is out of bounds, in every build
+    #[must_use]
+    pub fn read_key(&self, key: Key<T>) -> &T {
+        self.try_read(key.index())
+            .expect("Read a key past the initialized elements")
+    }
+
+    /// Returns the [`Key`] the next element pushed will have
+    #[must_use]
+    pub fn next_key(&self) -> Key<T> {
+        Key::new(self.len())
+    }
+
+    /// Returns the address of the element at the index, or [`None`] if it does not exist, see [`ReadHandle::address_of`]
+    #[must_use]
+    pub fn address_of(&self, idx: usize) -> Option<NonNull<T>> {
+        self.try_read(idx).map(NonNull::from)
+    }
+
+    /// Returns the element at an address returned by [`address_of`](WriteHandle::address_of)
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `address_of` on a handle to the same [`Stele`] as this one, and since then the
+    /// [`Stele`] must not have been recycled or accessed mutably, see [`ReadHandle::element_at`]
+    #[must_use]
+    pub unsafe fn element_at(&self, ptr: NonNull<T>) -> &T {
+        //SAFETY: By the safety contract the element is in this Stele and has been neither dropped nor borrowed mutably since
+        //its address was taken, so it lives at least as long as this handle
+        unsafe { ptr.as_ref() }
+    }
+
+    /// Returns a reference to the allocator backing the underlying [`Stele`]
+    #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "allocator_api", feature = "allocator-api2")))
+    )]
+    #[must_use]
+    pub fn allocator(&self) -> &S {
+        self.handle.allocator()
+    }
+
+    /// Returns the current length of the underlying [`Stele`], including elements [reserved](WriteHandle::push_uninit) but not filled yet
     ///
     /// Note:
     /// By calling this through the [`WriteHandle`], you hold the only handle that can change the
@@ -71,6 +525,14 @@ impl<T> WriteHandle<T> {
         self.handle.len()
     }
 
+    /// Returns the number of leading elements that are all initialized, which is what iterators cover
+    ///
+    /// This is the same as [`len`](WriteHandle::len) unless some [reserved](WriteHandle::push_uninit) elements have not been filled yet
+    #[must_use]
+    pub fn initialized_len(&self) -> usize {
+        self.handle.initialized_len()
+    }
+
     /// Returns whether the underlying [`Stele`] is empty or not
     ///
     /// Note:
@@ -81,9 +543,57 @@ impl<T> WriteHandle<T> {
     pub fn is_empty(&self) -> bool {
         self.handle.is_empty()
     }
+
+    /// Returns how many more elements can be pushed before a [bounded](Stele::bounded) [`Stele`] is full,
+    /// or [`None`] if it is unbounded
+    #[must_use]
+    pub fn remaining(&self) -> Option<usize> {
+        self.handle.remaining()
+    }
+
+    /// Returns whether the [`Stele`] is [bounded](Stele::bounded) and full
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.handle.is_full()
+    }
+
+    /// Returns the number of elements the currently allocated blocks can hold without allocating
+    ///
+    /// This includes blocks that were preallocated but have not been written to yet
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.handle.capacity()
+    }
+
+    /// Returns the number of bytes held by the currently allocated blocks
+    ///
+    /// Zero sized types always report `0`
+    #[must_use]
+    pub fn allocated_bytes(&self) -> usize {
+        self.handle.allocated_bytes()
+    }
+
+    /// Returns the number of blocks that are currently allocated
+    #[must_use]
+    pub fn block_count(&self) -> usize {
+        self.handle.block_count()
+    }
+
+    /// Returns the size in bytes of the [`Stele`] itself, which holds the block pointers and the length
+    /// regardless of how many blocks are allocated
+    #[must_use]
+    pub fn overhead_bytes(&self) -> usize {
+        self.handle.overhead_bytes()
+    }
 }
 
-impl<T: Copy> WriteHandle<T> {
+impl<T, S: Storage> Drop for WriteHandle<T, S> {
+    fn drop(&mut self) {
+        self.handle.close();
+    }
+}
+
+impl<T: Copy, S: Storage> WriteHandle<T, S> {
     /// Get provides a way to get an owned copy of a value inside a [`Stele`]
     /// provided the type `T` implements [`Copy`]
     ///
@@ -95,3 +605,48 @@ impl<T: Copy> WriteHandle<T> {
         self.handle.get(idx)
     }
 }
+
+impl<T: Copy, S: Storage, const N: usize> WriteHandle<[T; N], S> {
+    /// Pushes `values` as consecutive records of `N` scalars each, so that they can be read back with
+    /// [`flat_read`](ReadHandle::flat_read) and [`iter_flat`](ReadHandle::iter_flat)
+    ///
+    /// Either every record is pushed or none of them are, and the length is published once for all of them
+    /// so that readers see either the whole batch or none of it
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SteleError::RaggedLength`](crate::SteleError::RaggedLength) if the length of `values` is not a multiple of `N`,
+    /// and [`SteleError::CapacityExceeded`](crate::SteleError::CapacityExceeded) if the [`Stele`] is [bounded](Stele::bounded)
+    /// and does not have room for all of the records
+    pub fn push_flat(&self, values: &[T]) -> Result<(), SteleError> {
+        let ragged = match values.len().checked_rem(N) {
+            Some(rest) => rest >= 1,
+            //Records without any scalars can only be made from no scalars at all
+            None => !values.is_empty(),
+        };
+        if ragged {
+            return Err(SteleError::RaggedLength {
+                len: values.len(),
+                record_len: N,
+            });
+        }
+        let records = values.len().checked_div(N).unwrap_or(0);
+        if let Some(bound) = self.handle.bound {
+            let requested = self.len() + records;
+            if requested > bound {
+                return Err(SteleError::CapacityExceeded {
+                    requested,
+                    max: bound,
+                });
+            }
+        }
+        //SAFETY: `values` holds exactly `records` runs of `N` scalars, and `[T; N]` is laid out as `N` consecutive `T`s
+        //with the same alignment as `T`
+        let records =
+            unsafe { core::slice::from_raw_parts(values.as_ptr().cast::<[T; N]>(), records) };
+        //SAFETY: WriteHandle is neither Sync nor Clone so only one exists at a time
+        //and can only be used by one thread at a time
+        unsafe { self.handle.extend_from_slices(&[records]) };
+        Ok(())
+    }
+}
@@ -0,0 +1,305 @@
+use core::marker::PhantomData;
+
+use crate::{
+    append::builder::SteleBuilder,
+    mem::{DefaultStorage, Storage},
+    sync::{ord, Arc, AtomicUsize},
+    Stele,
+};
+
+//The number of bits in every word
+const BITS: usize = usize::BITS as usize;
+
+/// An append-only bitvector that packs its bits into the words of a [`Stele`], created with [`BitStele::new`]
+///
+/// Bits are never moved or cleared, so like a [`Stele`] it has one [`BitWriteHandle`] and any number of [`BitReadHandle`]s.
+/// The last word is usually only partly filled while readers look at it, so the number of bits is published separately
+/// from the words and every bit past it is masked off before anything is read.
+///
+/// ```
+/// use stele::bits::BitStele;
+///
+/// let (writer, reader) = BitStele::new();
+/// (0..100).for_each(|n| writer.push(n % 3 == 0));
+/// assert_eq!(reader.len(), 100);
+/// assert!(reader.get(99));
+/// assert_eq!(reader.count_ones(), 34);
+/// //Translate between bit indices and the positions of the set bits
+/// assert_eq!(reader.rank(10), 4);
+/// assert_eq!(reader.select(4), Some(12));
+/// ```
+#[derive(Debug)]
+pub struct BitStele<S: Storage = DefaultStorage> {
+    words: Stele<AtomicUsize, S>,
+    //The number of bits, which is published after the word holding the last one
+    len: AtomicUsize,
+}
+
+impl BitStele {
+    #[allow(clippy::new_ret_no_self)]
+    #[must_use]
+    /// Creates an empty [`BitStele`] and returns a [`BitWriteHandle`] and [`BitReadHandle`] to it
+    pub fn new() -> (BitWriteHandle, BitReadHandle) {
+        Self::new_in(DefaultStorage::default())
+    }
+}
+
+impl<S: Storage> BitStele<S> {
+    /// Creates an empty [`BitStele`] whose words are allocated from `storage`, and returns a [`BitWriteHandle`] and [`BitReadHandle`]
+    pub fn new_in(storage: S) -> (BitWriteHandle<S>, BitReadHandle<S>) {
+        let s = Arc::new(BitStele {
+            words: SteleBuilder::new_in(storage).finish(),
+            len: AtomicUsize::new(0),
+        });
+        let reader = BitReadHandle {
+            handle: Arc::clone(&s),
+        };
+        (
+            BitWriteHandle {
+                handle: s,
+                _unsync: PhantomData,
+            },
+            reader,
+        )
+    }
+
+    fn len(&self) -> usize {
+        self.len.load(ord::ACQ)
+    }
+
+    /// Returns the word at `word` with every bit at or past `len` cleared
+    ///
+    /// `len` must have been loaded from `self.len` and `word` must hold at least one bit below it
+    fn word(&self, word: usize, len: usize) -> usize {
+        //Loading `len` acquired every bit set before it was published, and bits set since are masked off
+        let bits = self.words.read_at(word).load(ord::RLX);
+        match len - word * BITS {
+            n if n >= BITS => bits,
+            n => bits & ((1 << n) - 1),
+        }
+    }
+
+    fn get(&self, idx: usize) -> Option<bool> {
+        let len = self.len();
+        (idx < len).then(|| self.word(idx / BITS, len) & (1 << (idx % BITS)) != 0)
+    }
+
+    /// Counts the set bits below `end`, which must be at most `len`
+    fn ones_below(&self, end: usize, len: usize) -> usize {
+        let full = (0..end / BITS)
+            .map(|word| self.word(word, len).count_ones() as usize)
+            .sum::<usize>();
+        match end % BITS {
+            0 => full,
+            rest => full + (self.word(end / BITS, len) & ((1 << rest) - 1)).count_ones() as usize,
+        }
+    }
+}
+
+/// The writer of a [`BitStele`]
+///
+/// Like a [`WriteHandle`](crate::WriteHandle) this is `Send` but not `Sync` or `Clone`, so there is only ever one
+#[derive(Debug)]
+pub struct BitWriteHandle<S: Storage = DefaultStorage> {
+    handle: Arc<BitStele<S>>,
+    _unsync: PhantomData<*mut ()>,
+}
+
+//SAFETY: BitWriteHandle only hands out copies of bits and uses atomic operations internally
+//so as long as the BitStele is both Send and Sync it is safe to implement Send
+unsafe impl<S: Storage> Send for BitWriteHandle<S> where BitStele<S>: Send + Sync {}
+
+impl<S: Storage> BitWriteHandle<S> {
+    /// Appends `bit`, allocating a new block of words if necessary
+    pub fn push(&self, bit: bool) {
+        let s = &*self.handle;
+        //Only the writer changes the length, so this is the current one
+        let len = s.len.load(ord::RLX);
+        match len % BITS {
+            //SAFETY: BitWriteHandle is neither Sync nor Clone, so this is the only writer
+            0 => unsafe { s.words.push(AtomicUsize::new(usize::from(bit))) },
+            //Readers may be loading this word, but they mask off the bit until the new length is published
+            offset if bit => {
+                s.words.read_at(len / BITS).fetch_or(1 << offset, ord::RLX);
+            }
+            _ => {}
+        }
+        s.len.store(len + 1, ord::REL);
+    }
+
+    /// Returns the bit at `idx`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds
+    #[must_use]
+    pub fn get(&self, idx: usize) -> bool {
+        self.handle.get(idx).expect("Index out of bounds")
+    }
+
+    /// Returns the number of bits
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handle.len()
+    }
+
+    /// Returns whether no bits have been pushed
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates a new [`BitReadHandle`]
+    #[must_use]
+    pub fn new_read_handle(&self) -> BitReadHandle<S> {
+        BitReadHandle {
+            handle: Arc::clone(&self.handle),
+        }
+    }
+}
+
+/// A reader of a [`BitStele`]
+#[derive(Debug)]
+pub struct BitReadHandle<S: Storage = DefaultStorage> {
+    handle: Arc<BitStele<S>>,
+}
+
+impl<S: Storage> BitReadHandle<S> {
+    /// Returns the bit at `idx`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds, see [`try_get`](BitReadHandle::try_get)
+    #[must_use]
+    pub fn get(&self, idx: usize) -> bool {
+        self.try_get(idx).expect("Index out of bounds")
+    }
+
+    /// Returns the bit at `idx`, or [`None`] if it is out of bounds
+    #[must_use]
+    pub fn try_get(&self, idx: usize) -> Option<bool> {
+        self.handle.get(idx)
+    }
+
+    /// Returns the number of bits
+    ///
+    /// Note: this is an optimistic operation and the length may be changing under you
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handle.len()
+    }
+
+    /// Returns whether no bits have been pushed
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of set bits
+    #[must_use]
+    pub fn count_ones(&self) -> usize {
+        let len = self.handle.len();
+        self.handle.ones_below(len, len)
+    }
+
+    /// Returns the number of set bits below `idx`, which is the position of the bit at `idx` among the set bits if it is set
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is greater than the length
+    #[must_use]
+    pub fn rank(&self, idx: usize) -> usize {
+        let len = self.handle.len();
+        assert!(
+            idx <= len,
+            "Ranked index {} of a BitStele with {} bits",
+            idx,
+            len
+        );
+        self.handle.ones_below(idx, len)
+    }
+
+    /// Returns the index of the set bit with `n` set bits before it, or [`None`] if there are not that many
+    ///
+    /// This is the inverse of [`rank`](BitReadHandle::rank): `rank(select(n)) == n` for every set bit
+    #[must_use]
+    pub fn select(&self, mut n: usize) -> Option<usize> {
+        let len = self.handle.len();
+        for word in 0..len.div_ceil(BITS) {
+            let mut bits = self.handle.word(word, len);
+            let ones = bits.count_ones() as usize;
+            if n < ones {
+                //Clears the lowest set bit `n` times, leaving the one asked for as the lowest
+                for _ in 0..n {
+                    bits &= bits - 1;
+                }
+                return Some(word * BITS + bits.trailing_zeros() as usize);
+            }
+            n -= ones;
+        }
+        None
+    }
+
+    /// Returns an iterator over the bits pushed before it was created
+    #[must_use]
+    pub fn iter(&self) -> Bits<'_, S> {
+        Bits {
+            handle: &self.handle,
+            pos: 0,
+            len: self.handle.len(),
+            word: 0,
+        }
+    }
+}
+
+impl<S: Storage> Clone for BitReadHandle<S> {
+    fn clone(&self) -> Self {
+        Self {
+            handle: Arc::clone(&self.handle),
+        }
+    }
+}
+
+impl<'a, S: Storage> IntoIterator for &'a BitReadHandle<S> {
+    type Item = bool;
+
+    type IntoIter = Bits<'a, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the bits of a [`BitStele`], created by [`BitReadHandle::iter`]
+#[derive(Debug)]
+pub struct Bits<'a, S: Storage = DefaultStorage> {
+    handle: &'a BitStele<S>,
+    pos: usize,
+    len: usize,
+    //The word holding `pos`, loaded whenever `pos` enters a new one
+    word: usize,
+}
+
+impl<S: Storage> Iterator for Bits<'_, S> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.len {
+            return None;
+        }
+        let offset = self.pos % BITS;
+        if offset == 0 {
+            self.word = self.handle.word(self.pos / BITS, self.len);
+        }
+        let bit = self.word & (1 << offset) != 0;
+        self.pos += 1;
+        Some(bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<S: Storage> ExactSizeIterator for Bits<'_, S> {}
@@ -0,0 +1,295 @@
+#[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+use core::time::Duration;
+use core::{cell::Cell, fmt};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+#[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+use std::time::Instant;
+
+use crate::{ReadHandle, Stele, WriteHandle};
+
+/// Creates a broadcast channel backed by a [`Stele`], returning the only [`Sender`] and a first [`Receiver`]
+///
+/// Every element sent is kept and seen by every [`Receiver`], each of which reads at its own pace through its own cursor
+#[must_use]
+pub fn broadcast<T>() -> (Sender<T>, Receiver<T>) {
+    let (writer, reader) = Stele::new();
+    let shared = Arc::new(Shared {
+        disconnected: Mutex::new(false),
+        notify: Condvar::new(),
+    });
+    (
+        Sender {
+            writer,
+            shared: Arc::clone(&shared),
+        },
+        Receiver {
+            reader,
+            shared,
+            cursor: Cell::new(0),
+        },
+    )
+}
+
+//Receivers only go to sleep while holding the lock and after checking the length, and the sender takes the lock after
+//pushing and before notifying, so a push can never land between a receiver's check and its wait
+#[derive(Debug)]
+struct Shared {
+    disconnected: Mutex<bool>,
+    notify: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, bool> {
+        self.disconnected
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// The sending half of a [`broadcast`] channel, which wraps the [`WriteHandle`]
+///
+/// Dropping it disconnects the channel, after which receivers can still read everything that was sent
+#[derive(Debug)]
+pub struct Sender<T> {
+    writer: WriteHandle<T>,
+    shared: Arc<Shared>,
+}
+
+impl<T> Sender<T> {
+    /// Appends `val` to the channel and wakes every waiting [`Receiver`]
+    pub fn send(&self, val: T) {
+        self.writer.push(val);
+        let _guard = self.shared.lock();
+        self.shared.notify.notify_all();
+    }
+
+    /// Returns the number of elements sent so far
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.writer.len()
+    }
+
+    /// Returns whether nothing has been sent yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.writer.is_empty()
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        *self.shared.lock() = true;
+        self.shared.notify.notify_all();
+    }
+}
+
+/// The receiving half of a [`broadcast`] channel, which wraps a [`ReadHandle`] along with its own cursor
+///
+/// Cloning a receiver creates a new cursor that starts again from the first element,
+/// while [`resubscribe`](Receiver::resubscribe) creates one that starts at the current end
+#[derive(Debug)]
+pub struct Receiver<T> {
+    reader: ReadHandle<T>,
+    shared: Arc<Shared>,
+    cursor: Cell<usize>,
+}
+
+impl<T> Receiver<T> {
+    /// Returns the next element, blocking until one is sent
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecvError`] once the [`Sender`] has been dropped and every element has been received
+    #[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics")))))
+    )]
+    pub fn recv(&self) -> Result<&T, RecvError> {
+        let mut disconnected = self.shared.lock();
+        loop {
+            if let Some(val) = self.advance() {
+                return Ok(val);
+            }
+            if *disconnected {
+                return Err(RecvError);
+            }
+            disconnected = self
+                .shared
+                .notify
+                .wait(disconnected)
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+        }
+    }
+
+    /// Returns the next element if one has already been sent
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TryRecvError::Empty`] if every element sent so far has been received,
+    /// or [`TryRecvError::Disconnected`] if the [`Sender`] is also gone
+    pub fn try_recv(&self) -> Result<&T, TryRecvError> {
+        if let Some(val) = self.advance() {
+            return Ok(val);
+        }
+        if *self.shared.lock() {
+            //Anything sent before the sender dropped is visible once the lock has been taken
+            self.advance().ok_or(TryRecvError::Disconnected)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+
+    /// Returns the next element, blocking for at most `timeout` until one is sent
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecvTimeoutError::Timeout`] if nothing was sent in time, or [`RecvTimeoutError::Disconnected`]
+    /// once the [`Sender`] has been dropped and every element has been received
+    #[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics")))))
+    )]
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<&T, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut disconnected = self.shared.lock();
+        loop {
+            if let Some(val) = self.advance() {
+                return Ok(val);
+            }
+            if *disconnected {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            disconnected = self
+                .shared
+                .notify
+                .wait_timeout(disconnected, deadline - now)
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    /// Creates a new [`Receiver`] whose cursor starts at the current end of the channel,
+    /// so it only sees elements sent from now on
+    #[must_use]
+    pub fn resubscribe(&self) -> Self {
+        Self {
+            reader: self.reader.clone(),
+            shared: Arc::clone(&self.shared),
+            cursor: Cell::new(self.reader.len()),
+        }
+    }
+
+    /// Creates a blocking iterator over the elements this receiver has not seen yet, which ends once the channel is disconnected
+    #[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics")))))
+    )]
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { receiver: self }
+    }
+
+    fn advance(&self) -> Option<&T> {
+        let val = self.reader.try_read(self.cursor.get())?;
+        self.cursor.set(self.cursor.get() + 1);
+        Some(val)
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            reader: self.reader.clone(),
+            shared: Arc::clone(&self.shared),
+            cursor: Cell::new(0),
+        }
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = &'a T;
+
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+///A blocking iterator over the elements of a [`Receiver`]
+#[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics")))))
+)]
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    receiver: &'a Receiver<T>,
+}
+
+#[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// The error returned by [`Receiver::recv`] once the channel is disconnected and empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError;
+
+/// The error returned by [`Receiver::try_recv`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// Every element sent so far has been received
+    Empty,
+    /// The [`Sender`] has been dropped and every element has been received
+    Disconnected,
+}
+
+/// The error returned by [`Receiver::recv_timeout`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    /// Nothing was sent before the timeout elapsed
+    Timeout,
+    /// The [`Sender`] has been dropped and every element has been received
+    Disconnected,
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("receiving on an empty and disconnected channel")
+    }
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("receiving on an empty channel"),
+            TryRecvError::Disconnected => RecvError.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvTimeoutError::Timeout => f.write_str("timed out waiting on a channel"),
+            RecvTimeoutError::Disconnected => RecvError.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+impl std::error::Error for TryRecvError {}
+impl std::error::Error for RecvTimeoutError {}
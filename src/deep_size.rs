@@ -0,0 +1,34 @@
+use deepsize::{Context, DeepSizeOf};
+
+use crate::{mem::Storage, Stele};
+#[cfg(not(any(loom, feature = "loom", shuttle, feature = "portable-atomic")))]
+use crate::{ReadHandle, WriteHandle};
+
+impl<T: DeepSizeOf, S: Storage> DeepSizeOf for Stele<T, S> {
+    /// Counts every allocated block in full, whether or not it holds any elements yet, the initialized flags of the blocks
+    /// that have held a reservation, and whatever the initialized elements own in turn
+    fn deep_size_of_children(&self, context: &mut Context) -> usize {
+        let elements = (0..self.len())
+            .filter_map(|idx| self.read(idx))
+            .map(|val| val.deep_size_of_children(context))
+            .sum::<usize>();
+        self.allocated_bytes() + self.flag_bytes() + elements
+    }
+}
+
+//The handles share the Stele through an `Arc`, which the context only counts the first time it is seen,
+//so any number of handles to the same Stele count it once. That needs the `Arc` from `alloc` or the `Rc` that
+//replaces it on single-threaded wasm, as the context cannot record any other kind of shared pointer
+#[cfg(not(any(loom, feature = "loom", shuttle, feature = "portable-atomic")))]
+impl<T: DeepSizeOf, S: Storage> DeepSizeOf for ReadHandle<T, S> {
+    fn deep_size_of_children(&self, context: &mut Context) -> usize {
+        self.handle.deep_size_of_children(context)
+    }
+}
+
+#[cfg(not(any(loom, feature = "loom", shuttle, feature = "portable-atomic")))]
+impl<T: DeepSizeOf, S: Storage> DeepSizeOf for WriteHandle<T, S> {
+    fn deep_size_of_children(&self, context: &mut Context) -> usize {
+        self.handle.deep_size_of_children(context)
+    }
+}
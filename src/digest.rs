@@ -0,0 +1,203 @@
+#[cfg(feature = "bytemuck")]
+use bytemuck::Pod;
+
+#[cfg(feature = "serde")]
+use crate::SteleError;
+use crate::{
+    mem::{DefaultStorage, Storage},
+    ReadHandle,
+};
+
+/// A hash function that can be fed bytes a piece at a time and report its digest at any point
+///
+/// Feeding the same bytes always gives the same digest, however they are split into calls to [`update`](ContentHasher::update)
+pub trait ContentHasher {
+    /// The digest this hash function produces
+    type Output;
+
+    /// Hashes `bytes` after everything hashed so far
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Returns the digest of everything hashed so far, leaving the state untouched so that more can be hashed
+    fn digest(&self) -> Self::Output;
+}
+
+/// CRC-32 with the IEEE polynomial, as used by zlib, gzip and PNG
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    //The reflected IEEE polynomial
+    const POLY: u32 = 0xedb8_8320;
+    //The remainder of every byte value, so that each byte costs one lookup
+    const TABLE: [u32; 256] = Self::table();
+
+    /// Creates a [`Crc32`] that has not hashed anything yet
+    #[must_use]
+    pub const fn new() -> Self {
+        Crc32 { state: !0 }
+    }
+
+    const fn table() -> [u32; 256] {
+        let mut table = [0; 256];
+        let mut byte = 0_u32;
+        while byte < 256 {
+            let mut crc = byte;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ Self::POLY
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[byte as usize] = crc;
+            byte += 1;
+        }
+        table
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContentHasher for Crc32 {
+    type Output = u32;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            let low = self.state.to_le_bytes()[0];
+            self.state = Self::TABLE[usize::from(low ^ byte)] ^ (self.state >> 8);
+        }
+    }
+
+    fn digest(&self) -> u32 {
+        !self.state
+    }
+}
+
+/// SHA-256 from the `sha2` crate, for when the digest has to hold up against tampering rather than just accidents
+#[cfg(feature = "sha2")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sha2")))]
+#[derive(Debug, Clone, Default)]
+pub struct Sha256(sha2::Sha256);
+
+#[cfg(feature = "sha2")]
+impl Sha256 {
+    /// Creates a [`Sha256`] that has not hashed anything yet
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "sha2")]
+impl ContentHasher for Sha256 {
+    type Output = [u8; 32];
+
+    fn update(&mut self, bytes: &[u8]) {
+        sha2::Digest::update(&mut self.0, bytes);
+    }
+
+    fn digest(&self) -> [u8; 32] {
+        sha2::Digest::finalize(self.0.clone()).into()
+    }
+}
+
+/// The digest of the first `len` elements of a [`Stele`](crate::Stele), returned by [`Digester::update`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Checkpoint<D> {
+    /// The number of elements the digest covers
+    pub len: usize,
+    /// The digest of those elements
+    pub digest: D,
+}
+
+/// Keeps a running digest of the contents of a [`Stele`](crate::Stele), hashing only what was appended since the last update
+///
+/// Elements are hashed as their raw bytes with [`update`](Digester::update) when they are `bytemuck::Pod`,
+/// or as their postcard encoding with [`update_serde`](Digester::update_serde) otherwise, the same encodings
+/// snapshots use. Stick to one of them for each [`Digester`], as the two give different digests.
+/// Either way the digest only depends on the elements, not on how they are spread over blocks or how often it is updated
+#[derive(Debug)]
+pub struct Digester<T, H, S: Storage = DefaultStorage> {
+    handle: ReadHandle<T, S>,
+    hasher: H,
+    pos: usize,
+}
+
+impl<T, H: ContentHasher, S: Storage> Digester<T, H, S> {
+    /// Creates a [`Digester`] that has not hashed any elements of `handle` yet
+    pub fn new(handle: ReadHandle<T, S>, hasher: H) -> Self {
+        Digester {
+            handle,
+            hasher,
+            pos: 0,
+        }
+    }
+
+    /// Returns the digest of the elements hashed so far without hashing any new ones
+    #[must_use]
+    pub fn checkpoint(&self) -> Checkpoint<H::Output> {
+        Checkpoint {
+            len: self.pos,
+            digest: self.hasher.digest(),
+        }
+    }
+
+    /// Returns the [`ReadHandle`] the elements are read through
+    #[must_use]
+    pub fn into_inner(self) -> ReadHandle<T, S> {
+        self.handle
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T: Pod, H: ContentHasher, S: Storage> Digester<T, H, S> {
+    /// Hashes the raw bytes of every element initialized since the last update a block at a time,
+    /// and returns the digest of everything hashed so far
+    pub fn update(&mut self) -> Checkpoint<H::Output> {
+        let stele = &*self.handle.handle;
+        let end = stele.initialized_len();
+        //Zero sized elements have no bytes to hash
+        if core::mem::size_of::<T>() == 0 {
+            self.pos = end;
+        }
+        while self.pos < end {
+            //SAFETY: Everything below the initialized length is initialized
+            let block = unsafe { stele.block_slice(self.pos, end) };
+            self.hasher.update(bytemuck::cast_slice(block));
+            self.pos += block.len();
+        }
+        self.checkpoint()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, H: ContentHasher, S: Storage> Digester<T, H, S> {
+    /// Hashes the postcard encoding of every element initialized since the last update, and returns the digest of everything hashed so far
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SteleError::SerializeFailed`] if an element fails to serialize. The elements before it stay hashed,
+    /// so the next update starts over at the one that failed
+    pub fn update_serde(&mut self) -> Result<Checkpoint<H::Output>, SteleError> {
+        let stele = &*self.handle.handle;
+        let end = stele.initialized_len();
+        let mut encoded = alloc::vec::Vec::new();
+        while self.pos < end {
+            encoded.clear();
+            encoded = postcard::to_extend(stele.read_at(self.pos), encoded)
+                .map_err(|_| SteleError::SerializeFailed { index: self.pos })?;
+            self.hasher.update(&encoded);
+            self.pos += 1;
+        }
+        Ok(self.checkpoint())
+    }
+}
@@ -0,0 +1,177 @@
+use alloc::alloc::Layout;
+use core::fmt;
+
+/// Everything that can go wrong in a fallible operation on a [`Stele`](crate::Stele)
+///
+/// Operations that have to hand a value back, such as [`push_within_capacity`](crate::WriteHandle::push_within_capacity),
+/// wrap this in [`PushError`] instead, which returns it as its [`source`](core::error::Error::source)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SteleError {
+    /// Allocating a block with the given layout failed
+    AllocationFailed {
+        /// The layout of the block that could not be allocated
+        layout: Layout,
+    },
+    /// The operation needed room for more elements than the [`Stele`](crate::Stele) can hold without allocating or exceeding its bound
+    CapacityExceeded {
+        /// The number of elements the [`Stele`](crate::Stele) would have had to hold
+        requested: usize,
+        /// The number of elements it can hold
+        max: usize,
+    },
+    /// The index is not below the length of the [`Stele`](crate::Stele)
+    OutOfBounds {
+        /// The index that was accessed
+        index: usize,
+        /// The length of the [`Stele`](crate::Stele) at the time
+        len: usize,
+    },
+    /// The bytes starting at `offset` do not hold a complete [record](crate::records), either because they end too early
+    /// or because the length prefix is invalid
+    MalformedRecord {
+        /// The offset of the first byte of the record
+        offset: usize,
+    },
+    /// The values do not split evenly into records of `record_len`, so pushing them would leave a partial record
+    RaggedLength {
+        /// The number of values given
+        len: usize,
+        /// The number of values in every record
+        record_len: usize,
+    },
+    /// The element at `index` could not be encoded with serde
+    SerializeFailed {
+        /// The index of the element
+        index: usize,
+    },
+    /// Elements reserved with [`reserve_blocks`](crate::WriteHandle::reserve_blocks) were published before every one of them was written
+    Unfilled {
+        /// The number of reserved elements that had been written
+        filled: usize,
+        /// The number of elements that were reserved
+        reserved: usize,
+    },
+    /// The options given to a [`SteleBuilder`](crate::SteleBuilder) cannot be combined or are out of range
+    InvalidOptions {
+        /// What is wrong with the options
+        reason: &'static str,
+    },
+    /// The [`Stele`](crate::Stele) already has a writer, and only one can exist at a time
+    WriterExists,
+    /// The operation could only succeed by waiting for another thread
+    WouldBlock,
+    /// Waiting for a matching element timed out, such as in `ReadHandle::wait_for_match_timeout`
+    TimedOut {
+        /// The index to resume from, as every element before it has been checked
+        next: usize,
+    },
+}
+
+impl fmt::Display for SteleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SteleError::AllocationFailed { layout } => write!(
+                f,
+                "failed to allocate {} bytes aligned to {}",
+                layout.size(),
+                layout.align()
+            ),
+            SteleError::CapacityExceeded { requested, max } => write!(
+                f,
+                "room for {requested} elements was needed but the Stele can hold {max}"
+            ),
+            SteleError::OutOfBounds { index, len } => {
+                write!(
+                    f,
+                    "index {index} is out of bounds for a Stele of length {len}"
+                )
+            }
+            SteleError::MalformedRecord { offset } => {
+                write!(
+                    f,
+                    "the bytes at offset {offset} do not hold a complete record"
+                )
+            }
+            SteleError::RaggedLength { len, record_len } => write!(
+                f,
+                "{len} values do not split into whole records of {record_len}"
+            ),
+            SteleError::SerializeFailed { index } => {
+                write!(f, "the element at index {index} could not be serialized")
+            }
+            SteleError::Unfilled { filled, reserved } => write!(
+                f,
+                "only {filled} of the {reserved} reserved elements were written"
+            ),
+            SteleError::InvalidOptions { reason } => {
+                write!(f, "invalid Stele options: {reason}")
+            }
+            SteleError::WriterExists => f.write_str("the Stele already has a writer"),
+            SteleError::WouldBlock => f.write_str("the operation would block"),
+            SteleError::TimedOut { next } => write!(
+                f,
+                "timed out waiting for a match after checking every element before index {next}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for SteleError {}
+
+/// The error returned when a value could not be pushed, which hands the value back along with the reason
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PushError<T> {
+    /// The value that was not pushed
+    pub value: T,
+    /// Why it was not pushed
+    pub error: SteleError,
+}
+
+impl<T> PushError<T> {
+    /// Returns the value that was not pushed
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Display for PushError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("failed to push to the Stele")
+    }
+}
+
+/// The error returned by [`push_monotonic`](crate::WriteHandle::push_monotonic) when a value is out of order,
+/// which hands back the value that was not pushed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonotonicError<T> {
+    /// The value that was not pushed
+    pub value: T,
+    /// The index of the last element, which the value was out of order with
+    pub last: usize,
+}
+
+impl<T> MonotonicError<T> {
+    /// Returns the value that was not pushed
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Display for MonotonicError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value is out of order with the element at index {}",
+            self.last
+        )
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for MonotonicError<T> {}
+
+impl<T: fmt::Debug> core::error::Error for PushError<T> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
@@ -0,0 +1,100 @@
+use core::{fmt, ptr::null};
+
+use crate::{mem::Storage, Stele};
+
+/// The number of entries in [`RawParts::blocks`], which is the most blocks a [`Stele`] can have
+pub const MAX_BLOCKS: usize = 32;
+
+/// One block of a [`Stele`] as seen by a foreign reader
+///
+/// The block holds `len` slots starting at `ptr`, only some of which may be initialized, see [`RawParts::len`]
+#[repr(C)]
+pub struct RawBlock<T> {
+    /// The first slot of the block, or null if the block is not part of the export
+    pub ptr: *const T,
+    /// The number of slots in the block, or 0 if the block is not part of the export
+    pub len: usize,
+}
+
+/// A snapshot of where the elements of a [`Stele`] live, laid out so that code in other languages can read them,
+/// created by [`ReadHandle::as_raw_parts`](crate::ReadHandle::as_raw_parts)
+///
+/// Element `idx` lives in the first block whose slots, counted from the start of block 0, reach past `idx`.
+/// The blocks keep the layout of the [`GrowthPolicy`](crate::GrowthPolicy) of the [`Stele`], so with the default one block 0
+/// holds 1 element and every block `n > 0` holds 2<sup>n-1</sup> elements starting at index 2<sup>n-1</sup>.
+/// `include/stele.h` declares the same structs for C and C++ along with a function that finds any element.
+///
+/// The pointers stay valid for as long as a handle to the [`Stele`] is alive, which
+/// [`ReadHandle::leak_raw`](crate::ReadHandle::leak_raw) extends to the rest of the process.
+/// Elements below `len` are initialized and never change, so they can be read from any thread without synchronization
+#[repr(C)]
+pub struct RawParts<T> {
+    /// The number of leading elements that were initialized when the snapshot was taken
+    pub len: usize,
+    /// The number of leading entries of `blocks` needed to hold `len` elements
+    pub block_count: usize,
+    /// The blocks in order, of which only the first `block_count` are filled in
+    pub blocks: [RawBlock<T>; MAX_BLOCKS],
+}
+
+impl<T, S: Storage> Stele<T, S> {
+    pub(crate) fn raw_parts(&self) -> RawParts<T> {
+        let len = self.initialized_len();
+        let block_count = self.raw.blocks_for_len(len);
+        let blocks = core::array::from_fn(|block| {
+            if block < block_count {
+                RawBlock {
+                    //`Inner<T>` has the same layout as `T`
+                    ptr: self.raw.block(block).cast_const().cast::<T>(),
+                    len: self.raw.block_len(block),
+                }
+            } else {
+                RawBlock {
+                    ptr: null(),
+                    len: 0,
+                }
+            }
+        });
+        RawParts {
+            len,
+            block_count,
+            blocks,
+        }
+    }
+}
+
+//Written out so that copying the pointers does not need `T: Copy`
+impl<T> Clone for RawBlock<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RawBlock<T> {}
+
+impl<T> fmt::Debug for RawBlock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawBlock")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<T> Clone for RawParts<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RawParts<T> {}
+
+impl<T> fmt::Debug for RawParts<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawParts")
+            .field("len", &self.len)
+            .field("block_count", &self.block_count)
+            .field("blocks", &&self.blocks[..self.block_count])
+            .finish()
+    }
+}
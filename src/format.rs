@@ -0,0 +1,146 @@
+use defmt::{write, Format, Formatter};
+
+use crate::{
+    append::iter::RefIterator, mem::Storage, CachedReadHandle, Full, MonotonicError, PushError,
+    ReadHandle, StaticReadHandle, StaticWriteHandle, Stele, SteleError, WriteHandle,
+};
+
+//Log buffers on embedded targets are small, so at most this many elements are formatted and the rest are elided
+const MAX_FORMATTED: usize = 8;
+
+impl<T: Format, S: Storage> Stele<T, S> {
+    /// Formats the length and the first [`MAX_FORMATTED`] elements, without allocating
+    fn format_handle(&self, f: Formatter<'_>, name: &str) {
+        write!(f, "{=str} {{ len: {=usize}, elements: [", name, self.len());
+        for (idx, val) in RefIterator::from_stele(self)
+            .take(MAX_FORMATTED)
+            .enumerate()
+        {
+            if idx != 0 {
+                write!(f, ", ");
+            }
+            write!(f, "{}", val);
+        }
+        if self.initialized_len() > MAX_FORMATTED {
+            write!(f, ", ..");
+        }
+        write!(f, "] }}");
+    }
+}
+
+impl<T: Format, S: Storage> Format for ReadHandle<T, S> {
+    fn format(&self, f: Formatter<'_>) {
+        self.handle.format_handle(f, "ReadHandle");
+    }
+}
+
+impl<T: Format, S: Storage> Format for CachedReadHandle<T, S> {
+    fn format(&self, f: Formatter<'_>) {
+        self.handle.handle.format_handle(f, "CachedReadHandle");
+    }
+}
+
+impl<T: Format, S: Storage> Format for WriteHandle<T, S> {
+    fn format(&self, f: Formatter<'_>) {
+        self.handle.format_handle(f, "WriteHandle");
+    }
+}
+
+impl<T: Format, S: Storage> Format for StaticReadHandle<'_, T, S> {
+    fn format(&self, f: Formatter<'_>) {
+        self.handle.format_handle(f, "StaticReadHandle");
+    }
+}
+
+impl<T: Format, S: Storage> Format for StaticWriteHandle<'_, T, S> {
+    fn format(&self, f: Formatter<'_>) {
+        self.handle.format_handle(f, "StaticWriteHandle");
+    }
+}
+
+#[cfg(feature = "critical-section")]
+impl<T: Format, S: Storage> Format for crate::IsrWriteHandle<T, S> {
+    fn format(&self, f: Formatter<'_>) {
+        self.writer.handle.format_handle(f, "IsrWriteHandle");
+    }
+}
+
+//Like its `Display` implementation this does not need `T` to be formattable
+impl<T> Format for Full<T> {
+    fn format(&self, f: Formatter<'_>) {
+        write!(f, "the Stele is full");
+    }
+}
+
+impl Format for SteleError {
+    //defmt's mock encoder, used by the tests, interns every string with the same call, which makes the last two arms identical
+    #[allow(clippy::match_same_arms)]
+    fn format(&self, f: Formatter<'_>) {
+        match self {
+            SteleError::AllocationFailed { layout } => write!(
+                f,
+                "failed to allocate {=usize} bytes aligned to {=usize}",
+                layout.size(),
+                layout.align()
+            ),
+            SteleError::CapacityExceeded { requested, max } => write!(
+                f,
+                "room for {=usize} elements was needed but the Stele can hold {=usize}",
+                requested, max
+            ),
+            SteleError::OutOfBounds { index, len } => write!(
+                f,
+                "index {=usize} is out of bounds for a Stele of length {=usize}",
+                index, len
+            ),
+            SteleError::MalformedRecord { offset } => write!(
+                f,
+                "the bytes at offset {=usize} do not hold a complete record",
+                offset
+            ),
+            SteleError::RaggedLength { len, record_len } => write!(
+                f,
+                "{=usize} values do not split into whole records of {=usize}",
+                len, record_len
+            ),
+            SteleError::SerializeFailed { index } => write!(
+                f,
+                "the element at index {=usize} could not be serialized",
+                index
+            ),
+            SteleError::Unfilled { filled, reserved } => write!(
+                f,
+                "only {=usize} of the {=usize} reserved elements were written",
+                filled, reserved
+            ),
+            SteleError::InvalidOptions { reason } => {
+                write!(f, "invalid Stele options: {=str}", reason);
+            }
+            SteleError::WriterExists => write!(f, "the Stele already has a writer"),
+            SteleError::WouldBlock => write!(f, "the operation would block"),
+            SteleError::TimedOut { next } => write!(
+                f,
+                "timed out waiting for a match after checking every element before index {=usize}",
+                next
+            ),
+        }
+    }
+}
+
+//The value is left out for the same reason as for `Full`
+impl<T> Format for MonotonicError<T> {
+    fn format(&self, f: Formatter<'_>) {
+        write!(
+            f,
+            "the value is out of order with the element at index {=usize}",
+            self.last
+        );
+    }
+}
+
+//The value is left out for the same reason as for `Full`, only the reason is formatted
+impl<T> Format for PushError<T> {
+    fn format(&self, f: Formatter<'_>) {
+        write!(f, "failed to push to the Stele: {}", self.error);
+    }
+}
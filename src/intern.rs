@@ -0,0 +1,222 @@
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt, hash::BuildHasher};
+use std::collections::{hash_map::RandomState, HashMap};
+
+use crate::{layout, ReadHandle, Stele, WriteHandle};
+
+//Padding is pushed from here a chunk at a time, so skipping to the next block never allocates
+const PADDING: [u8; 256] = [0; 256];
+
+/// An interned string, which is the order it was first interned in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Returns the number of distinct strings interned before this one
+    #[must_use]
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+
+    /// Creates a [`Symbol`] from the value returned by [`as_u32`](Symbol::as_u32)
+    ///
+    /// Resolving a symbol that was not returned by the same [`Interner`] may give any string or none at all, see [`Resolver::try_resolve`]
+    #[must_use]
+    pub fn from_u32(symbol: u32) -> Self {
+        Self(symbol)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Symbol {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Symbol {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(Self)
+    }
+}
+
+/// Deduplicates strings, handing out the same [`Symbol`] every time an equal string is interned
+///
+/// The bytes of every string are stored in a [`Stele<u8>`](Stele) and their spans in a second [`Stele`], so a [`Resolver`]
+/// can turn symbols back into strings on any thread without locking or allocating while strings are still being interned.
+/// Every string is kept within a single block, skipping to the next block that can hold it if necessary,
+/// so it can always be borrowed as one `&str`.
+///
+/// This is the writer, so like a [`WriteHandle`] it is `Send` but `!Sync`
+pub struct Interner {
+    bytes: WriteHandle<u8>,
+    spans: WriteHandle<(usize, usize)>,
+    //Symbols by the hash of their string, as the strings themselves live in the Stele
+    index: HashMap<u64, Vec<Symbol>>,
+    hasher: RandomState,
+    resolver: Resolver,
+}
+
+impl fmt::Debug for Interner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Interner")
+            .field("len", &self.len())
+            .field("bytes", &self.bytes.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Interner {
+    /// Creates an empty [`Interner`]
+    #[must_use]
+    pub fn new() -> Self {
+        let (bytes, byte_reader) = Stele::new();
+        let (spans, span_reader) = Stele::new();
+        Self {
+            bytes,
+            spans,
+            index: HashMap::new(),
+            hasher: RandomState::new(),
+            resolver: Resolver {
+                bytes: byte_reader,
+                spans: span_reader,
+            },
+        }
+    }
+
+    /// Returns the [`Symbol`] for `s`, storing it first if no equal string has been interned before
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`u32::MAX`] distinct strings have already been interned
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        let hash = self.hasher.hash_one(s);
+        let resolver = &self.resolver;
+        if let Some(&symbol) = self.index.get(&hash).and_then(|symbols| {
+            symbols
+                .iter()
+                .find(|&&symbol| resolver.resolve(symbol) == s)
+        }) {
+            return symbol;
+        }
+        let symbol =
+            Symbol(u32::try_from(self.spans.len()).expect("Interned more than u32::MAX strings"));
+        let start = self.skip_to_fit(s.len());
+        //SAFETY: The Interner owns the WriteHandle, which is neither Sync nor Clone, so this is the only writer
+        unsafe { self.bytes.handle.extend_from_slices(&[s.as_bytes()]) };
+        self.spans.push((start, s.len()));
+        self.index.entry(hash).or_default().push(symbol);
+        symbol
+    }
+
+    /// Pushes padding until `len` bytes fit in the rest of the block holding the end of the bytes, returning where they start
+    fn skip_to_fit(&self, len: usize) -> usize {
+        let mut start = self.bytes.len();
+        if len == 0 {
+            return start;
+        }
+        loop {
+            let block = layout::block_of(start);
+            let block_end = layout::first_index_of_block(block) + layout::block_capacity(block);
+            if block_end - start >= len {
+                return start;
+            }
+            //Blocks double in size, so this skips less than twice `len` in total
+            while start < block_end {
+                let padding = &PADDING[..PADDING.len().min(block_end - start)];
+                //SAFETY: As in `intern`, this is the only writer
+                unsafe { self.bytes.handle.extend_from_slices(&[padding]) };
+                start += padding.len();
+            }
+        }
+    }
+
+    /// Creates a new [`Resolver`] for the same strings
+    #[must_use]
+    pub fn resolver(&self) -> Resolver {
+        self.resolver.clone()
+    }
+
+    /// Returns the string `symbol` was interned from
+    ///
+    /// # Panics
+    ///
+    /// Panics if `symbol` was not returned by this [`Interner`]
+    #[must_use]
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.resolver.resolve(symbol)
+    }
+
+    /// Returns the number of distinct strings interned so far
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns whether nothing has been interned yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns the number of bytes stored, including the padding that keeps every string within one block
+    #[must_use]
+    pub fn byte_len(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Turns the [`Symbol`]s of an [`Interner`] back into strings, and can be cloned and shared between threads
+///
+/// Resolving never locks or allocates
+#[derive(Debug, Clone)]
+pub struct Resolver {
+    bytes: ReadHandle<u8>,
+    spans: ReadHandle<(usize, usize)>,
+}
+
+impl Resolver {
+    /// Returns the string `symbol` was interned from
+    ///
+    /// # Panics
+    ///
+    /// Panics if `symbol` was not returned by the [`Interner`] this resolver belongs to
+    #[must_use]
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.try_resolve(symbol)
+            .expect("Resolved a symbol that was not interned")
+    }
+
+    /// Returns the string `symbol` was interned from, or [`None`] if nothing has been interned as `symbol` yet
+    #[must_use]
+    pub fn try_resolve(&self, symbol: Symbol) -> Option<&str> {
+        let &(start, len) = self.spans.try_read(usize::try_from(symbol.0).ok()?)?;
+        if len == 0 {
+            return Some("");
+        }
+        //SAFETY: The span is pushed after its bytes, so they are initialized, and `start` is below the end of the span
+        let bytes = unsafe { self.bytes.handle.block_slice(start, start + len) };
+        debug_assert_eq!(bytes.len(), len, "Interned strings lie within one block");
+        //SAFETY: Every span covers exactly the bytes of a `str`
+        Some(unsafe { core::str::from_utf8_unchecked(bytes) })
+    }
+
+    /// Returns the number of distinct strings interned so far
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns whether nothing has been interned yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
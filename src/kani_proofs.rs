@@ -0,0 +1,96 @@
+use alloc::{alloc::Layout, vec::Vec};
+use core::cell::RefCell;
+
+use crate::{layout, max_len, mem::Storage, split_idx, Stele};
+
+//The most elements a Stele with the default first block size holds, as documented on `layout`
+const MAX_IDX: usize = (1 << 31) - 1;
+
+#[kani::proof]
+fn split_idx_round_trips() {
+    let idx: usize = kani::any();
+    kani::assume(idx <= MAX_IDX);
+    let (outer, inner) = split_idx(idx);
+    assert!(outer < 32);
+    assert!(inner < max_len(outer));
+    assert_eq!(layout::first_index_of_block(outer) + inner, idx);
+}
+
+#[kani::proof]
+fn split_idx_scaled_round_trips() {
+    let idx: usize = kani::any();
+    let first_block_exp: u32 = kani::any();
+    kani::assume(first_block_exp <= 16);
+    kani::assume(idx <= MAX_IDX);
+    let policy = layout::GrowthPolicy::doubling(first_block_exp);
+    let (outer, inner) = policy.split_idx(idx);
+    assert!(outer < 32);
+    assert!(inner < policy.block_capacity(outer));
+    assert_eq!(policy.first_index_of_block(outer) + inner, idx);
+}
+
+#[kani::proof]
+fn split_idx_uniform_round_trips() {
+    let block_len: usize = kani::any();
+    kani::assume(block_len > 0 && block_len <= usize::MAX / 32);
+    let policy = layout::GrowthPolicy::uniform(block_len);
+    let idx: usize = kani::any();
+    kani::assume(idx < block_len * 32);
+    let (outer, inner) = policy.split_idx(idx);
+    assert!(outer < 32);
+    assert!(inner < policy.block_capacity(outer));
+    assert_eq!(policy.first_index_of_block(outer) + inner, idx);
+}
+
+#[kani::proof]
+#[kani::unwind(6)]
+fn push_then_read() {
+    let len: usize = kani::any();
+    kani::assume(len <= 4);
+    let (writer, reader) = Stele::new();
+    let vals: [u32; 4] = kani::any();
+    for &val in &vals[..len] {
+        writer.push(val);
+    }
+    assert_eq!(reader.len(), len);
+    for (idx, val) in vals[..len].iter().enumerate() {
+        assert_eq!(reader.read(idx), val);
+    }
+}
+
+/// Checks every block freed against the blocks handed out, so a null pointer or a wrong length fails the proof
+#[derive(Debug, Default)]
+struct Tracking {
+    live: RefCell<Vec<(*mut u8, Layout)>>,
+}
+
+impl Storage for &Tracking {
+    fn allocate_block(&self, layout: Layout) -> *mut u8 {
+        //SAFETY: Blocks are never zero sized, as zero sized types do not allocate
+        let ptr = unsafe { alloc::alloc::alloc(layout) };
+        self.live.borrow_mut().push((ptr, layout));
+        ptr
+    }
+
+    unsafe fn deallocate_block(&self, ptr: *mut u8, layout: Layout) {
+        assert!(!ptr.is_null());
+        let mut live = self.live.borrow_mut();
+        let pos = live
+            .iter()
+            .position(|&block| block == (ptr, layout))
+            .expect("Freed a block that was not allocated with this layout");
+        live.swap_remove(pos);
+        //SAFETY: The block was allocated above with this layout
+        unsafe { alloc::alloc::dealloc(ptr, layout) };
+    }
+}
+
+#[kani::proof]
+#[kani::unwind(10)]
+fn drop_frees_every_block_once() {
+    let len: u16 = kani::any();
+    kani::assume(len <= 8);
+    let storage = Tracking::default();
+    drop(Stele::from_iter_in(0..len, &storage));
+    assert!(storage.live.borrow().is_empty());
+}
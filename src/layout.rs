@@ -0,0 +1,277 @@
+/// Returns the block that holds the element at `idx`
+#[inline]
+#[must_use]
+pub const fn block_of(idx: usize) -> usize {
+    crate::split_idx(idx).0
+}
+
+/// Returns the offset of `idx` from the start of the block that holds it
+#[inline]
+#[must_use]
+pub const fn offset_in_block(idx: usize) -> usize {
+    crate::split_idx(idx).1
+}
+
+/// Returns the number of elements the given block can hold
+#[inline]
+#[must_use]
+pub const fn block_capacity(block: usize) -> usize {
+    crate::max_len(block)
+}
+
+/// Returns the index of the first element stored in the given block
+#[inline]
+#[must_use]
+pub const fn first_index_of_block(block: usize) -> usize {
+    match block {
+        0 => 0,
+        _ => 1 << (block - 1),
+    }
+}
+
+/// Returns the number of blocks needed to hold `len` elements
+#[must_use]
+pub const fn blocks_for_len(len: usize) -> usize {
+    match len {
+        0 => 0,
+        _ => block_of(len - 1) + 1,
+    }
+}
+
+/// How a [`Stele`](crate::Stele) spreads its elements over its 32 blocks
+///
+/// The default doubles the size of every block, so a Stele can grow to nearly any length while only ever wasting
+/// up to half of its capacity, but its last block alone holds about half of the elements. A uniform policy gives
+/// every block the same size instead, which bounds both the largest single allocation and the unused capacity,
+/// at the cost of the Stele holding at most 32 blocks' worth of elements
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GrowthPolicy(Growth);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Growth {
+    //Every block holds 2^first_block_exp times as many elements as it would by default
+    Doubling { first_block_exp: u32 },
+    //Every block holds the same number of elements
+    Uniform { block_len: usize },
+}
+
+impl Default for Growth {
+    fn default() -> Self {
+        Growth::Doubling { first_block_exp: 0 }
+    }
+}
+
+impl GrowthPolicy {
+    //The largest first block is 2^16 elements, which keeps the last block addressable on 64 bit targets
+    const MAX_FIRST_BLOCK_EXP: u32 = 16;
+
+    /// Blocks that double in size, starting with a first block of 2<sup>`first_block_exp`</sup> elements
+    ///
+    /// `first_block_exp` is clamped to at most 16, and 0 gives the default layout described in this module
+    #[must_use]
+    pub const fn doubling(first_block_exp: u32) -> Self {
+        let first_block_exp = if first_block_exp > Self::MAX_FIRST_BLOCK_EXP {
+            Self::MAX_FIRST_BLOCK_EXP
+        } else {
+            first_block_exp
+        };
+        GrowthPolicy(Growth::Doubling { first_block_exp })
+    }
+
+    /// Blocks that all hold `block_len` elements, so that the Stele holds at most 32 times that many
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_len` is 0 or the 32 blocks together would hold more than [`usize::MAX`] elements
+    #[must_use]
+    pub const fn uniform(block_len: usize) -> Self {
+        assert!(
+            block_len > 0 && block_len <= usize::MAX / 32,
+            "Uniform blocks must hold at least one element and at most usize::MAX / 32"
+        );
+        GrowthPolicy(Growth::Uniform { block_len })
+    }
+
+    /// Returns the block that holds `idx` and its offset within that block
+    #[inline]
+    #[must_use]
+    pub const fn split_idx(self, idx: usize) -> (usize, usize) {
+        match self.0 {
+            Growth::Doubling { first_block_exp } => {
+                let block = block_of(idx >> first_block_exp);
+                (
+                    block,
+                    idx - (first_index_of_block(block) << first_block_exp),
+                )
+            }
+            Growth::Uniform { block_len } => (idx / block_len, idx % block_len),
+        }
+    }
+
+    /// Returns the number of elements the given block holds
+    #[inline]
+    #[must_use]
+    pub const fn block_capacity(self, block: usize) -> usize {
+        match self.0 {
+            Growth::Doubling { first_block_exp } => block_capacity(block) << first_block_exp,
+            Growth::Uniform { block_len } => block_len,
+        }
+    }
+
+    /// Returns the index of the first element stored in the given block
+    #[must_use]
+    pub const fn first_index_of_block(self, block: usize) -> usize {
+        match self.0 {
+            Growth::Doubling { first_block_exp } => first_index_of_block(block) << first_block_exp,
+            Growth::Uniform { block_len } => block * block_len,
+        }
+    }
+
+    /// Returns the number of blocks needed to hold `len` elements
+    #[must_use]
+    pub const fn blocks_for_len(self, len: usize) -> usize {
+        match len {
+            0 => 0,
+            _ => self.split_idx(len - 1).0 + 1,
+        }
+    }
+
+    /// Returns the most elements a Stele with this policy can hold, or [`None`] if only the address space limits it
+    #[must_use]
+    pub const fn max_len(self) -> Option<usize> {
+        match self.0 {
+            Growth::Doubling { .. } => None,
+            Growth::Uniform { block_len } => Some(block_len * 32),
+        }
+    }
+
+    /// Returns the size of the first block as a power of two, if every block doubles in size
+    #[must_use]
+    pub const fn first_block_exp(self) -> Option<u32> {
+        match self.0 {
+            Growth::Doubling { first_block_exp } => Some(first_block_exp),
+            Growth::Uniform { .. } => None,
+        }
+    }
+
+    /// Whether this is the default layout, the only one whose smallest blocks can be preallocated together
+    pub(crate) fn is_default(self) -> bool {
+        self == GrowthPolicy::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        block_capacity, block_of, blocks_for_len, first_index_of_block, offset_in_block,
+        GrowthPolicy,
+    };
+
+    //The highest index a Stele can hold with 32 blocks
+    const MAX_IDX: usize = (1 << 31) - 1;
+
+    fn check(idx: usize) {
+        let block = block_of(idx);
+        assert!(block < 32);
+        assert_eq!(first_index_of_block(block) + offset_in_block(idx), idx);
+        assert!(offset_in_block(idx) < block_capacity(block));
+        assert_eq!(blocks_for_len(idx + 1), block + 1);
+    }
+
+    #[test]
+    fn round_trip() {
+        (0..1 << 16).for_each(check);
+        for shift in 1..31 {
+            let pow = 1_usize << shift;
+            check(pow - 1);
+            check(pow);
+            check(pow + 1);
+        }
+        check(MAX_IDX);
+    }
+
+    #[test]
+    fn blocks_are_contiguous() {
+        assert_eq!(blocks_for_len(0), 0);
+        for block in 0..31 {
+            let next = first_index_of_block(block) + block_capacity(block);
+            assert_eq!(first_index_of_block(block + 1), next);
+            assert_eq!(block_of(next - 1), block);
+        }
+    }
+
+    #[test]
+    fn growth_policies_round_trip() {
+        let policies = [
+            GrowthPolicy::default(),
+            GrowthPolicy::doubling(3),
+            GrowthPolicy::doubling(u32::MAX),
+            GrowthPolicy::uniform(1),
+            GrowthPolicy::uniform(7),
+            GrowthPolicy::uniform(4096),
+        ];
+        for policy in policies {
+            assert_eq!(policy.first_index_of_block(0), 0);
+            for block in 0..31 {
+                let next = policy.first_index_of_block(block) + policy.block_capacity(block);
+                assert_eq!(policy.first_index_of_block(block + 1), next);
+                assert_eq!(
+                    policy.split_idx(next - 1),
+                    (block, policy.block_capacity(block) - 1)
+                );
+                assert_eq!(policy.split_idx(next), (block + 1, 0));
+                assert_eq!(policy.blocks_for_len(next), block + 1);
+            }
+            if let Some(max_len) = policy.max_len() {
+                assert_eq!(policy.blocks_for_len(max_len), 32);
+            }
+        }
+        assert_eq!(GrowthPolicy::doubling(u32::MAX), GrowthPolicy::doubling(16));
+        for idx in 0..1 << 12 {
+            assert_eq!(
+                GrowthPolicy::default().split_idx(idx),
+                (block_of(idx), offset_in_block(idx))
+            );
+        }
+    }
+
+    //The saturating formulation `split_idx` and `max_len` used before the lookup table, kept to check against
+    fn split_idx_reference(idx: usize) -> (usize, usize) {
+        let outer_idx = 32_usize.saturating_sub(
+            (idx.leading_zeros() as usize).saturating_sub(usize::BITS.saturating_sub(32) as usize),
+        );
+        let inner_idx = idx.saturating_sub(1 << (outer_idx.saturating_sub(1)));
+        (outer_idx, inner_idx)
+    }
+
+    fn max_len_reference(n: usize) -> usize {
+        match n {
+            0 | 1 => 1,
+            _ => 1 << (n - 1),
+        }
+    }
+
+    #[test]
+    fn matches_reference() {
+        let exhaustive = if cfg!(miri) { 1 << 10 } else { 1 << 22 };
+        for idx in 0..exhaustive {
+            assert_eq!(crate::split_idx(idx), split_idx_reference(idx), "{idx}");
+        }
+        let pows = (1..usize::BITS).map(|shift| 1_usize << shift);
+        let boundaries =
+            pows.flat_map(|pow| [pow - 1, pow, pow + 1])
+                .chain([0, 1, usize::MAX - 1, usize::MAX]);
+        for idx in boundaries {
+            assert_eq!(crate::split_idx(idx), split_idx_reference(idx), "{idx}");
+        }
+        for n in 0..=32 {
+            assert_eq!(crate::max_len(n), max_len_reference(n), "{n}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Uniform blocks")]
+    fn uniform_rejects_empty_blocks() {
+        let _ = GrowthPolicy::uniform(0);
+    }
+}
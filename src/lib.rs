@@ -7,48 +7,181 @@
 )]
 #![warn(missing_docs)]
 #![cfg_attr(feature = "allocator_api", feature(allocator_api))]
-#![cfg_attr(doc, feature(doc_cfg))]
+#![cfg_attr(feature = "nightly-simd", feature(portable_simd))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 #![cfg_attr(not(feature = "std"), no_std)]
 #![doc = include_str!("../README.md")]
 extern crate alloc;
 
-///The Standard Stele implementation
-#[cfg(any(not(feature = "allocator_api"), doc))]
-#[cfg_attr(doc, doc(cfg(not(feature = "allocator_api"))))]
+///The Stele implementation, generic over the storage its blocks are allocated from
+///
+///With the `allocator_api` feature blocks can be allocated from any nightly [`Allocator`](alloc::alloc::Allocator), and with the
+///`allocator-api2` feature from any stable `allocator_api2::alloc::Allocator`. If both are enabled, `allocator_api` takes precedence
 pub mod append;
-
-#[cfg(any(feature = "allocator_api", doc))]
-#[cfg_attr(doc, doc(cfg(feature = "allocator_api")))]
-///The Allocator API compatible Stele implementation
-pub mod append_alloc;
-//This is a hacky way to make the rename not error when compiling documentation
-#[cfg(all(feature = "allocator_api", not(doc)))]
-pub use append_alloc as append;
+///The Allocator API compatible Stele implementation, which is the same as [`append`]
+#[cfg(any(any(feature = "allocator_api", feature = "allocator-api2"), docsrs))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "allocator_api", feature = "allocator-api2")))
+)]
+pub use append as append_alloc;
+///An append-only bitvector that packs its bits into the words of a [`Stele`]
+pub mod bits;
+///A broadcast channel where every receiver reads every element through its own cursor
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod channel;
+#[cfg(feature = "deepsize")]
+mod deep_size;
+///Running digests of the contents of a Stele that only hash what was appended since the last update
+#[cfg(any(feature = "bytemuck", feature = "serde"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bytemuck", feature = "serde"))))]
+pub mod digest;
+///The errors returned by fallible operations, which implement [`Error`](core::error::Error) without needing `std`
+pub mod error;
+///`#[repr(C)]` snapshots of where the elements of a Stele live, so that code in other languages can read them
+pub mod ffi;
+#[cfg(feature = "defmt")]
+mod format;
+///A string interner that hands out the same [`Symbol`](intern::Symbol) for equal strings and resolves them without locking
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod intern;
+///The exact block geometry used by every [`Stele`] with the default [`GrowthPolicy`]
+///
+///Index `0` lives in block `0`, and every block `n > 0` holds 2<sup>n-1</sup> elements starting at index 2<sup>n-1</sup>.
+///These are the same functions the crate uses internally, so they can be relied on to batch work along block boundaries.
+///Steles with another policy have the same functions as methods on it.
+pub mod layout;
+///A single-threaded Stele that keeps the stable addresses and copy-free growth without any atomics or [`Arc`](alloc::sync::Arc)
+pub mod local;
+///An insertion-ordered map that only ever grows, storing its entries in a [`Stele`]
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod map;
 mod mem;
-mod sync;
+///A k-way merge over several sorted Steles, for reading sorted runs as one ordered sequence without collecting them first
+pub mod merge;
+///Stele blocks kept in a memory-mapped file, so that the elements survive the process and can be reopened without copying
+#[cfg(all(feature = "mmap", unix))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "mmap", unix))))]
+pub mod mmap;
+#[cfg(all(feature = "numa", target_os = "linux"))]
+mod numa;
+///A storage that reuses the blocks of dropped Steles instead of freeing them
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod pool;
+mod prefetch;
+///A work queue that hands every element of a [`Stele`] to exactly one consumer without removing anything
+pub mod queue;
+///The block table a [`Stele`] is built from, for data structures that need stable addresses and lock-free growth
+///under rules of their own
+pub mod raw;
+///Traits that read any kind of Stele handle the same way, so that functions can accept whichever one they are given
+pub mod reader;
+///Length-prefixed byte records over a [`Stele<u8>`](Stele), for using it as an event log
+pub mod records;
+///A log split over a series of [`Stele`]s, so that old elements can be freed while it keeps growing
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod rotate;
+///A multi-producer wrapper that gives every producer its own [`Stele`] and reads them all as one
+pub mod sharded;
+///A Stele inside a shared memory mapping, written by one process and read by others
+#[cfg(feature = "shmem")]
+#[cfg_attr(docsrs, doc(cfg(feature = "shmem")))]
+pub mod shm;
+///Write a [`Stele`] to disk and rebuild it from there, either as raw bytes for `bytemuck::Pod` elements or encoded with serde
+#[cfg(all(feature = "std", any(feature = "bytemuck", feature = "serde")))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "std", any(feature = "bytemuck", feature = "serde"))))
+)]
+pub mod snapshot;
+///The `Arc` and atomics every [`Stele`] is built from, which are loom's when the `loom` feature or `--cfg loom` is enabled
+///
+///Code that builds on Stele can use these too, so that a loom model of it and the Stele inside agree on which primitives are instrumented
+pub mod sync;
+///Utilities for testing code built on [`Stele`], such as an allocator that tracks what it hands out
+#[cfg(any(test, feature = "testing"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
 
+pub use append::builder::{Prealloc, SteleBuilder};
+pub use append::cached::CachedReadHandle;
+#[cfg(feature = "critical-section")]
+pub use append::isr::IsrWriteHandle;
+pub use append::key::Key;
 pub use append::reader::ReadHandle;
+pub use append::slot::Slot;
+pub use append::static_handle::{ReaderRef, StaticReadHandle, StaticWriteHandle};
 pub use append::writer::WriteHandle;
-pub use append::Stele;
+pub use append::{Full, Stele};
+pub use error::{MonotonicError, PushError, SteleError};
+pub use layout::GrowthPolicy;
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+pub use mem::GlobalStorage;
 pub(crate) use mem::Inner;
+pub use mem::{BufferStorage, Placement, RetryOrFail};
+pub use reader::{SteleReader, SteleReaderCopied};
+#[cfg(feature = "atomic-wait")]
+pub use sync::AtomicWaitNotify;
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+))]
+pub use sync::CondvarNotify;
+pub use sync::Notify;
+
+//The block and the first index of that block for every possible number of leading zeros of an index,
+//so that splitting an index is a single lookup. Block `n > 0` starts at the highest set bit of its indices,
+//except that every index past the 32nd block is put in a 33rd one starting where it would
+const SPLIT_TABLE: [(usize, usize); usize::BITS as usize + 1] = {
+    let mut table = [(0, 0); usize::BITS as usize + 1];
+    let mut leading_zeros = 0;
+    while leading_zeros < table.len() {
+        let bits = usize::BITS as usize - leading_zeros;
+        let block = if bits > 32 { 32 } else { bits };
+        let first = if block == 0 { 0 } else { max_len(block) };
+        table[leading_zeros] = (block, first);
+        leading_zeros += 1;
+    }
+    table
+};
 
+#[inline]
 const fn split_idx(idx: usize) -> (usize, usize) {
-    let outer_idx = 32_usize.saturating_sub(
-        (idx.leading_zeros() as usize).saturating_sub(usize::BITS.saturating_sub(32) as usize),
-    );
-    let inner_idx = idx.saturating_sub(1 << (outer_idx.saturating_sub(1)));
-    (outer_idx, inner_idx)
+    let (outer_idx, first) = SPLIT_TABLE[idx.leading_zeros() as usize];
+    (outer_idx, idx - first)
 }
 
+#[inline]
 const fn max_len(n: usize) -> usize {
-    match n {
-        0 | 1 => 1,
-        _ => 1 << (n - 1),
-    }
+    1 << n.saturating_sub(1)
 }
 
-#[cfg(all(not(loom), test))]
+#[cfg(all(
+    not(any(loom, feature = "loom", shuttle, target_arch = "wasm32")),
+    test
+))]
 mod test;
 
-#[cfg(all(loom, test))]
+#[cfg(kani)]
+mod kani_proofs;
+
+#[cfg(all(any(loom, feature = "loom"), test))]
 mod loom_test;
+
+#[cfg(all(shuttle, test))]
+mod shuttle_test;
+
+#[cfg(all(
+    feature = "portable-atomic",
+    not(any(loom, feature = "loom", shuttle, target_arch = "wasm32")),
+    test
+))]
+mod portable_atomic_test;
+
+#[cfg(all(target_arch = "wasm32", test))]
+mod wasm_test;
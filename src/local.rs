@@ -0,0 +1,557 @@
+use alloc::rc::Rc;
+use core::{cell::Cell, marker::PhantomData, ops::Index, ptr::null_mut};
+
+use crate::{
+    error::{PushError, SteleError},
+    max_len,
+    mem::{DefaultStorage, Storage},
+    split_idx, Inner,
+};
+
+/// A single-threaded [`Stele`](crate::Stele), which keeps the stable addresses and copy-free growth without any atomics
+///
+/// The length is a [`Cell`], the blocks are plain pointers and the handles share it through an [`Rc`],
+/// so neither the [`LocalStele`] nor its handles are `Send` or `Sync`. Blocks have the same sizes as those of a [`Stele`](crate::Stele)
+/// created with [`Stele::new`](crate::Stele::new) and are allocated through the same storage.
+///
+/// Without other threads there are no concurrent readers, but a [`LocalReadHandle`] can still be reading, or iterating,
+/// while the [`LocalWriteHandle`] pushes, including from inside the storage while it allocates a block. This is sound because:
+/// - A push only ever writes to the slot at the current length, which no reference handed out so far points to,
+///   and the length is only bumped once that write is done, so every reference is to an element that is never written again
+/// - Blocks are never moved or freed while a handle is alive, so references stay valid across every later push
+/// - A push looks at the length and the block pointers again after allocating, and frees its block if a push from inside
+///   the storage already allocated it, so a re-entrant push is never overwritten
+#[derive(Debug)]
+pub struct LocalStele<T, S: Storage = DefaultStorage> {
+    inners: [Cell<*mut Inner<T>>; 32],
+    len: Cell<usize>,
+    storage: S,
+}
+
+impl<T> LocalStele<T> {
+    #[allow(clippy::new_ret_no_self)]
+    #[must_use]
+    /// Creates a new [`LocalStele`] and returns a [`LocalWriteHandle`] and [`LocalReadHandle`]
+    pub fn new() -> (LocalWriteHandle<T>, LocalReadHandle<T>) {
+        Self::new_in(DefaultStorage::default())
+    }
+}
+
+impl<T, S: Storage> LocalStele<T, S> {
+    /// Creates a new [`LocalStele`] with the given allocator and returns a [`LocalWriteHandle`] and [`LocalReadHandle`]
+    pub fn new_in(storage: S) -> (LocalWriteHandle<T, S>, LocalReadHandle<T, S>) {
+        Self::from_iter_in(core::iter::empty(), storage).to_handles()
+    }
+
+    /// Creates a [`LocalStele`] with the given allocator from the contents of an iterator,
+    /// mirroring [`FromIterator`](core::iter::FromIterator) for custom allocators
+    #[must_use]
+    pub fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, storage: S) -> Self {
+        let s = LocalStele {
+            inners: [(); 32].map(|()| Cell::new(null_mut())),
+            len: Cell::new(0),
+            storage,
+        };
+        iter.into_iter().for_each(|item| s.push(item));
+        s
+    }
+
+    /// Returns a reference to the allocator backing this [`LocalStele`]
+    #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "allocator_api", feature = "allocator-api2")))
+    )]
+    #[must_use]
+    pub fn allocator(&self) -> &S {
+        &self.storage
+    }
+
+    /// Creates a pair of handles from an owned [`LocalStele`] after using [`FromIterator`](core::iter::FromIterator)
+    pub fn to_handles(self) -> (LocalWriteHandle<T, S>, LocalReadHandle<T, S>) {
+        let s = Rc::new(self);
+        let h = LocalWriteHandle {
+            handle: Rc::clone(&s),
+        };
+        let r = LocalReadHandle { handle: s };
+        (h, r)
+    }
+
+    /// Pushes a new item on to the end of a [`LocalStele`] that has not been shared yet, allocating a new block if necessary
+    pub fn push_mut(&mut self, val: T) {
+        self.push(val);
+    }
+
+    /// Returns a reference to the element at `idx`, or `None` if it is out of bounds
+    #[must_use]
+    pub fn read(&self, idx: usize) -> Option<&T> {
+        if idx >= self.len() {
+            return None;
+        }
+        //SAFETY: Every element below the length has been written and is never written again
+        Some(unsafe { (*self.read_raw(idx)).read() })
+    }
+
+    /// Returns a mutable reference to the element at `idx`, or `None` if it is out of bounds
+    #[must_use]
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        if idx >= self.len() {
+            return None;
+        }
+        //SAFETY: The element has been written, and holding `&mut self` means no handle can read it while it is borrowed
+        Some(unsafe { (*self.read_raw(idx)).read_mut() })
+    }
+
+    /// Returns the number of elements in the [`LocalStele`]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Returns `true` if the [`LocalStele`] holds no elements
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the elements by reference
+    #[must_use]
+    pub fn iter(&self) -> RefIterator<'_, T, S> {
+        RefIterator::from_stele(self)
+    }
+
+    /// Returns an iterator that allows modifying every element
+    #[must_use]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, S> {
+        IterMut {
+            len: self.len(),
+            stele: self,
+            pos: 0,
+            _mut: PhantomData,
+        }
+    }
+
+    fn push(&self, val: T) {
+        //Allocating calls into the storage, which may push to this LocalStele itself,
+        //so the length and the block are only relied on once no allocation is needed
+        loop {
+            let idx = self.len.get();
+            let (outer_idx, inner_idx) = split_idx(idx);
+            let block = self.inners[outer_idx].get();
+            if block.is_null() {
+                self.allocate(outer_idx);
+                continue;
+            }
+            //SAFETY: The block holding `idx` is allocated, and the slot at the length has never been handed out
+            //so nothing else refers to it. Writing runs no user code, so nothing can push in between
+            unsafe { block.add(inner_idx).write(Inner::new(val)) };
+            self.len.set(idx + 1);
+            return;
+        }
+    }
+
+    fn push_within_capacity(&self, val: T) -> Result<(), PushError<T>> {
+        let idx = self.len.get();
+        let (outer_idx, inner_idx) = split_idx(idx);
+        let block = self.inners[outer_idx].get();
+        if block.is_null() {
+            return Err(PushError {
+                value: val,
+                error: SteleError::CapacityExceeded {
+                    requested: idx + 1,
+                    max: self.capacity(),
+                },
+            });
+        }
+        //SAFETY: See `push`
+        unsafe { block.add(inner_idx).write(Inner::new(val)) };
+        self.len.set(idx + 1);
+        Ok(())
+    }
+
+    /// Allocates the block at `idx`, unless the storage already did so while allocating it
+    fn allocate(&self, idx: usize) {
+        if !self.inners[idx].get().is_null() {
+            return;
+        }
+        //SAFETY: Block lengths are the same as those of a Stele, which fit in memory
+        let ptr = unsafe { crate::mem::alloc_inner(&self.storage, max_len(idx), 1, None) };
+        if self.inners[idx].get().is_null() {
+            self.inners[idx].set(ptr);
+        } else {
+            //The storage pushed to this LocalStele while allocating and allocated the block itself
+            //SAFETY: `ptr` was just allocated with the same storage and length and holds no elements
+            unsafe { crate::mem::dealloc_inner(&self.storage, ptr, max_len(idx), 1) };
+        }
+    }
+
+    pub(crate) fn read_at(&self, idx: usize) -> &T {
+        self.read(idx).expect("Index out of bounds")
+    }
+
+    fn capacity(&self) -> usize {
+        (0..self.inners.len())
+            .filter(|&i| !self.inners[i].get().is_null())
+            .map(max_len)
+            .sum()
+    }
+
+    /// SAFETY: `idx` must be below the length, or at least in an allocated block
+    unsafe fn read_raw(&self, idx: usize) -> *mut Inner<T> {
+        let (outer_idx, inner_idx) = split_idx(idx);
+        unsafe { self.inners[outer_idx].get().add(inner_idx) }
+    }
+}
+
+impl<T: Copy, S: Storage> LocalStele<T, S> {
+    fn get(&self, idx: usize) -> T {
+        assert!(idx < self.len(), "Index out of bounds");
+        //SAFETY: Every element below the length has been written and is never written again
+        unsafe { (*self.read_raw(idx)).get() }
+    }
+}
+
+impl<T> core::iter::FromIterator<T> for LocalStele<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_iter_in(iter, DefaultStorage::default())
+    }
+}
+
+impl<'a, T, S: Storage> IntoIterator for &'a LocalStele<T, S> {
+    type Item = &'a T;
+
+    type IntoIter = RefIterator<'a, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, S: Storage> IntoIterator for &'a mut LocalStele<T, S> {
+    type Item = &'a mut T;
+
+    type IntoIter = IterMut<'a, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, S: Storage> Drop for LocalStele<T, S> {
+    fn drop(&mut self) {
+        let len = self.len.replace(0);
+        if core::mem::needs_drop::<T>() {
+            for idx in 0..len {
+                //SAFETY: The element is initialized, and holding `&mut self` means nothing else can read it
+                unsafe { (*self.read_raw(idx)).drop_in_place() };
+            }
+        }
+        for (idx, block) in self.inners.iter().enumerate() {
+            let ptr = block.get();
+            if !ptr.is_null() {
+                //SAFETY: Every block was allocated by `allocate` with this storage and length, and its elements are dropped
+                unsafe { crate::mem::dealloc_inner(&self.storage, ptr, max_len(idx), 1) };
+            }
+        }
+    }
+}
+
+/// The writing handle for a [`LocalStele`]
+///
+/// There is only ever one, but unlike [`WriteHandle`](crate::WriteHandle) it can push while references into the
+/// [`LocalStele`] are alive, even ones handed out by iterators that are still running
+#[derive(Debug)]
+pub struct LocalWriteHandle<T, S: Storage = DefaultStorage> {
+    handle: Rc<LocalStele<T, S>>,
+}
+
+impl<T, S: Storage> LocalWriteHandle<T, S> {
+    /// Pushes a new item on to the end of the [`LocalStele`], allocating a new block of memory if necessary
+    pub fn push(&self, val: T) {
+        self.handle.push(val);
+    }
+
+    /// Pushes a new item on to the end of the [`LocalStele`] only if that does not require allocating a new block,
+    /// returning the item otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns `val` in a [`PushError`] with [`SteleError::CapacityExceeded`] if the block it belongs in has not been allocated
+    pub fn push_within_capacity(&self, val: T) -> Result<(), PushError<T>> {
+        self.handle.push_within_capacity(val)
+    }
+
+    /// Creates a new [`LocalReadHandle`]
+    #[must_use]
+    pub fn new_read_handle(&self) -> LocalReadHandle<T, S> {
+        LocalReadHandle {
+            handle: Rc::clone(&self.handle),
+        }
+    }
+
+    /// Reads the value at the given index
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given index is out of bounds
+    #[must_use]
+    pub fn read(&self, idx: usize) -> &T {
+        self.handle.read_at(idx)
+    }
+
+    /// Attempts to read the value at the index and returns [`Some`] if the value exists, and [`None`] otherwise
+    #[must_use]
+    pub fn try_read(&self, idx: usize) -> Option<&T> {
+        self.handle.read(idx)
+    }
+
+    /// Returns a reference to the allocator backing the [`LocalStele`]
+    #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "allocator_api", feature = "allocator-api2")))
+    )]
+    #[must_use]
+    pub fn allocator(&self) -> &S {
+        self.handle.allocator()
+    }
+
+    /// Returns the current length of the underlying [`LocalStele`]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handle.len()
+    }
+
+    /// Returns whether the underlying [`LocalStele`] is empty or not
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.handle.len() == 0
+    }
+
+    /// Returns the number of elements the currently allocated blocks can hold
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.handle.capacity()
+    }
+}
+
+impl<T: Copy, S: Storage> LocalWriteHandle<T, S> {
+    /// Get provides a way to get an owned copy of a value inside a [`LocalStele`]
+    /// provided the `T` implements [`Copy`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given index is out of bounds
+    #[must_use]
+    pub fn get(&self, idx: usize) -> T {
+        self.handle.get(idx)
+    }
+}
+
+/// The reading handle for a [`LocalStele`]
+#[derive(Debug)]
+pub struct LocalReadHandle<T, S: Storage = DefaultStorage> {
+    handle: Rc<LocalStele<T, S>>,
+}
+
+impl<T, S: Storage> LocalReadHandle<T, S> {
+    /// Reads the value at the given index
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given index is out of bounds.
+    /// Since [`Index`] operates through this function, this same caveat also applies when indexing
+    #[must_use]
+    pub fn read(&self, idx: usize) -> &T {
+        self.handle.read_at(idx)
+    }
+
+    /// Attempts to read the value at the index and returns [`Some`] if the value exists, and [`None`] otherwise
+    #[must_use]
+    pub fn try_read(&self, idx: usize) -> Option<&T> {
+        self.handle.read(idx)
+    }
+
+    /// Returns a reference to the allocator backing the [`LocalStele`]
+    #[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "allocator_api", feature = "allocator-api2")))
+    )]
+    #[must_use]
+    pub fn allocator(&self) -> &S {
+        self.handle.allocator()
+    }
+
+    /// Returns the current length of the underlying [`LocalStele`]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handle.len()
+    }
+
+    /// Returns whether the underlying [`LocalStele`] is empty or not
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.handle.len() == 0
+    }
+
+    /// Returns the number of elements the currently allocated blocks can hold
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.handle.capacity()
+    }
+
+    /// Creates a [`RefIterator`] over the elements pushed so far
+    #[must_use]
+    pub fn iter(&self) -> RefIterator<'_, T, S> {
+        RefIterator::new(self)
+    }
+}
+
+impl<T: Copy, S: Storage> LocalReadHandle<T, S> {
+    /// Get provides a way to get an owned copy of a value inside a [`LocalStele`]
+    /// provided the `T` implements [`Copy`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given index is out of bounds
+    #[must_use]
+    pub fn get(&self, idx: usize) -> T {
+        self.handle.get(idx)
+    }
+}
+
+impl<T, S: Storage> Clone for LocalReadHandle<T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            handle: Rc::clone(&self.handle),
+        }
+    }
+}
+
+impl<'a, T, S: Storage> IntoIterator for &'a LocalReadHandle<T, S> {
+    type Item = &'a T;
+
+    type IntoIter = RefIterator<'a, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Copy, S: Storage> IntoIterator for LocalReadHandle<T, S> {
+    type Item = T;
+
+    type IntoIter = CopyIterator<T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CopyIterator::new(self)
+    }
+}
+
+impl<T, S: Storage> Index<usize> for LocalReadHandle<T, S> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.read(index)
+    }
+}
+
+///An iterator that yields items of a [`LocalStele`] by reference
+///
+///Like the iterators of a [`Stele`](crate::Stele) it only yields the elements pushed before it was created,
+///and pushing while it is running is allowed
+#[derive(Debug)]
+pub struct RefIterator<'rh, T, S: Storage = DefaultStorage> {
+    handle: &'rh LocalStele<T, S>,
+    pos: usize,
+    len: usize,
+}
+
+impl<'rh, T, S: Storage> RefIterator<'rh, T, S> {
+    ///Creates a new [`RefIterator`], borrowing the handle until dropped
+    #[must_use]
+    pub fn new(handle: &'rh LocalReadHandle<T, S>) -> Self {
+        Self::from_stele(&handle.handle)
+    }
+
+    fn from_stele(handle: &'rh LocalStele<T, S>) -> Self {
+        RefIterator {
+            handle,
+            pos: 0,
+            len: handle.len(),
+        }
+    }
+}
+
+impl<'rh, T, S: Storage> Iterator for RefIterator<'rh, T, S> {
+    type Item = &'rh T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.len > self.pos).then(|| {
+            self.pos += 1;
+            self.handle.read_at(self.pos - 1)
+        })
+    }
+}
+
+///An iterator that yields items of a [`LocalStele`] by mutable reference, created by [`LocalStele::iter_mut`]
+#[derive(Debug)]
+pub struct IterMut<'s, T, S: Storage = DefaultStorage> {
+    stele: &'s LocalStele<T, S>,
+    pos: usize,
+    len: usize,
+    _mut: PhantomData<&'s mut T>,
+}
+
+impl<'s, T, S: Storage> Iterator for IterMut<'s, T, S> {
+    type Item = &'s mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.len > self.pos).then(|| {
+            self.pos += 1;
+            //SAFETY: The element is below the length, the LocalStele is mutably borrowed for `'s`,
+            //and every element is yielded at most once
+            unsafe { (*self.stele.read_raw(self.pos - 1)).read_mut() }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, S: Storage> ExactSizeIterator for IterMut<'_, T, S> {}
+
+///An iterator that yields items of a [`LocalStele`] by value if the type implements copy
+#[derive(Debug)]
+pub struct CopyIterator<T: Copy, S: Storage = DefaultStorage> {
+    handle: LocalReadHandle<T, S>,
+    pos: usize,
+    len: usize,
+}
+
+impl<T: Copy, S: Storage> CopyIterator<T, S> {
+    ///Creates a new [`CopyIterator`], consuming the [`LocalReadHandle`]
+    #[must_use]
+    pub fn new(handle: LocalReadHandle<T, S>) -> Self {
+        let len = handle.len();
+        Self {
+            handle,
+            pos: 0,
+            len,
+        }
+    }
+}
+
+impl<T: Copy, S: Storage> Iterator for CopyIterator<T, S> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.len > self.pos).then(|| {
+            self.pos += 1;
+            self.handle.get(self.pos - 1)
+        })
+    }
+}
@@ -28,5 +28,244 @@ fn loom() {
         t2.join().unwrap();
         t3.join().unwrap();
         assert_eq!(rh.len(), size);
-    })
+    });
+}
+
+#[test]
+fn shrink_unused() {
+    use loom::thread;
+
+    loom::model(|| {
+        let (wh, rh1) = Stele::new();
+        let rh = rh1.clone();
+        (0..3).for_each(|n| wh.push(n));
+        let t1 = thread::spawn(move || {
+            let rh = wh.shrink_unused();
+            assert_eq!(rh.capacity(), 4);
+        });
+        let t2 = thread::spawn(move || {
+            for i in &rh1 {
+                let _ = i;
+            }
+            assert!(rh1.try_read(3).is_none());
+        });
+        t1.join().unwrap();
+        t2.join().unwrap();
+        assert_eq!(rh.len(), 3);
+    });
+}
+
+#[test]
+fn try_promote() {
+    use loom::thread;
+
+    loom::model(|| {
+        let (wh, rh) = Stele::new();
+        wh.push(0);
+        let t1 = thread::spawn(move || drop(wh));
+        let promote = |rh: crate::ReadHandle<usize>| {
+            move || {
+                let wh = rh.try_promote().ok()?;
+                wh.push(wh.len());
+                //The promoted writer is kept alive so the other reader cannot take over from it
+                Some(wh)
+            }
+        };
+        let t2 = thread::spawn(promote(rh.clone()));
+        let t3 = thread::spawn(promote(rh.clone()));
+        if let Some(val) = rh.try_read(1) {
+            assert_eq!(*val, 1);
+        }
+        t1.join().unwrap();
+        let w2 = t2.join().unwrap();
+        let w3 = t3.join().unwrap();
+        //Both readers may have tried before the writer was dropped, but they can never both succeed
+        assert!(w2.is_none() || w3.is_none());
+        assert_eq!(rh.len(), 1 + usize::from(w2.is_some() || w3.is_some()));
+    });
+}
+
+#[test]
+fn slot_fill() {
+    use loom::thread;
+
+    loom::model(|| {
+        let (wh, rh) = Stele::new();
+        let slot = wh.push_uninit();
+        wh.push(1);
+        let t1 = thread::spawn(move || slot.fill(0));
+        let t2 = thread::spawn(move || {
+            //The reservation is either still empty or fully written
+            if let Some(val) = rh.try_read(0) {
+                assert_eq!(*val, 0);
+            }
+            assert_eq!(rh.try_read(1), Some(&1));
+            rh.iter().count()
+        });
+        t1.join().unwrap();
+        assert!(t2.join().unwrap() <= 2);
+        assert_eq!(wh.initialized_len(), 2);
+    });
+}
+
+#[test]
+fn cached_reads() {
+    use loom::thread;
+
+    loom::model(|| {
+        let (wh, rh) = Stele::new();
+        wh.push(0);
+        let cached = rh.cached();
+        //Remembers block 0 before the writer moves on to the next blocks
+        assert_eq!(cached.get(0), 0);
+        let t1 = thread::spawn(move || {
+            (1..3).for_each(|n| wh.push(n));
+        });
+        let t2 = thread::spawn(move || {
+            for idx in [2, 1, 0, 2] {
+                if let Some(&val) = cached.try_read(idx) {
+                    assert_eq!(val, idx);
+                }
+            }
+        });
+        t1.join().unwrap();
+        t2.join().unwrap();
+        assert_eq!(rh.cached().get(2), 2);
+    });
+}
+
+#[test]
+fn work_queue() {
+    use crate::queue::WorkQueue;
+    use loom::thread;
+
+    loom::model(|| {
+        let (wh, rh) = Stele::new();
+        let queue = WorkQueue::new(rh);
+        let claim = |queue: WorkQueue<usize>| move || queue.claim().map(|(idx, &val)| (idx, val));
+        let t1 = thread::spawn(claim(queue.clone()));
+        let t2 = thread::spawn(claim(queue.clone()));
+        wh.push(0);
+        wh.push(1);
+        let c1 = t1.join().unwrap();
+        let c2 = t2.join().unwrap();
+        //Every claimed element is read correctly and no index is handed out twice
+        for &(idx, val) in c1.iter().chain(c2.iter()) {
+            assert_eq!(idx, val);
+        }
+        assert!(c1.is_none() || c1 != c2);
+        let claimed = usize::from(c1.is_some()) + usize::from(c2.is_some());
+        assert_eq!(queue.claimed(), claimed);
+    });
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn stream_wakeup() {
+    use core::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+    use futures_core::Stream;
+    use loom::{
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+    };
+    use std::sync::Arc;
+
+    struct Flag(AtomicBool);
+
+    impl std::task::Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    loom::model(|| {
+        let (wh, rh) = Stele::new();
+        let mut stream = rh.stream();
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        let waker = Arc::clone(&flag).into();
+        //The writer is handed back instead of dropped so that only the push itself can wake the stream
+        let t1 = thread::spawn(move || {
+            wh.push(1);
+            wh
+        });
+        let polled = Pin::new(&mut stream).poll_next(&mut Context::from_waker(&waker));
+        let _wh = t1.join().unwrap();
+        match polled {
+            Poll::Ready(val) => assert_eq!(val, Some(1)),
+            //If the stream missed the push it must have been woken by it
+            Poll::Pending => assert!(flag.0.load(Ordering::SeqCst)),
+        }
+    });
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+#[test]
+fn allocator_block_allocation() {
+    use crate::mem::Global;
+    use loom::thread;
+
+    loom::model(|| {
+        //The third push allocates block 2 while the reader is reading
+        let (wh, rh) = Stele::new_in(Global);
+        wh.push([0_u64; 128]);
+        wh.push([1_u64; 128]);
+        let t1 = thread::spawn(move || {
+            wh.push([2_u64; 128]);
+        });
+        let t2 = thread::spawn(move || {
+            if let Some(val) = rh.try_read(2) {
+                assert_eq!(val[127], 2);
+            }
+            assert_eq!(rh.read(1)[127], 1);
+        });
+        t1.join().unwrap();
+        t2.join().unwrap();
+    });
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+#[test]
+fn allocator_iteration() {
+    use crate::mem::Global;
+    use loom::thread;
+
+    loom::model(|| {
+        let (wh, rh1) = Stele::new_in(Global);
+        let rh = rh1.clone();
+        let t1 = thread::spawn(move || {
+            (0..3).for_each(|n| wh.push(n));
+        });
+        let t2 = thread::spawn(move || {
+            //Whatever prefix the iterator sees must be in push order
+            for (idx, val) in rh1.iter().enumerate() {
+                assert_eq!(*val, idx);
+            }
+        });
+        t1.join().unwrap();
+        t2.join().unwrap();
+        assert_eq!(rh.len(), 3);
+    });
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+#[test]
+fn allocator_drop() {
+    use crate::mem::Global;
+    use loom::thread;
+
+    loom::model(|| {
+        //Either thread may drop the last handle, which frees every block through the unsynchronized loads in `Drop`
+        let (wh, rh) = Stele::new_in(Global);
+        let t1 = thread::spawn(move || {
+            (0..3).for_each(|n| wh.push(n));
+        });
+        let t2 = thread::spawn(move || {
+            let _ = rh.try_read(0);
+        });
+        t1.join().unwrap();
+        t2.join().unwrap();
+    });
 }
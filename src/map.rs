@@ -0,0 +1,222 @@
+use core::{borrow::Borrow, fmt, hash::Hash};
+use std::{
+    collections::HashMap,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use crate::{ReadHandle, Stele, WriteHandle};
+
+/// The position of an entry in an [`AppendMap`], which is also its index in the underlying [`Stele`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key(usize);
+
+impl Key {
+    /// Returns the index of the entry, which counts the entries inserted before it
+    #[must_use]
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// The error returned by [`AppendMap::insert`] when the key is already in the map, which hands back the entry that was not inserted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occupied<K, V> {
+    /// The key that was already in the map
+    pub key: K,
+    /// The value that was not inserted
+    pub value: V,
+    /// The entry that already holds the key
+    pub existing: Key,
+}
+
+impl<K, V> fmt::Display for Occupied<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the key is already in the map at index {}",
+            self.existing.0
+        )
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> std::error::Error for Occupied<K, V> {}
+
+/// An insertion-ordered map that never removes or replaces an entry, backed by a [`Stele<(K, V)>`](Stele)
+///
+/// This is the writer, so like a [`WriteHandle`] it is `Send` but `!Sync`. Every [`MapReader`] can look entries up concurrently.
+///
+/// The entries live in the [`Stele`] and a lock-protected index maps every key to its position. A key is only added to the index
+/// after its entry has been pushed, so a lookup that finds it can always read the entry, and the lock is only held
+/// to look a key up rather than while reading the entry
+#[derive(Debug)]
+pub struct AppendMap<K, V> {
+    writer: WriteHandle<(K, V)>,
+    reader: MapReader<K, V>,
+}
+
+impl<K: Hash + Eq + Clone, V> AppendMap<K, V> {
+    /// Creates an empty [`AppendMap`]
+    #[must_use]
+    pub fn new() -> Self {
+        let (writer, entries) = Stele::new();
+        Self {
+            writer,
+            reader: MapReader {
+                entries,
+                index: Arc::new(RwLock::new(HashMap::new())),
+            },
+        }
+    }
+
+    /// Appends the entry and indexes its key, unless the key is already in the map
+    ///
+    /// # Errors
+    ///
+    /// Returns the key and value wrapped in [`Occupied`] along with the existing entry if the key is already in the map
+    pub fn insert(&self, key: K, value: V) -> Result<Key, Occupied<K, V>> {
+        let mut index = self
+            .reader
+            .index
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(&existing) = index.get(&key) {
+            return Err(Occupied {
+                key,
+                value,
+                existing: Key(existing),
+            });
+        }
+        let idx = self.writer.len();
+        self.writer.push((key.clone(), value));
+        index.insert(key, idx);
+        Ok(Key(idx))
+    }
+
+    /// Creates a new [`MapReader`] for the same map
+    #[must_use]
+    pub fn reader(&self) -> MapReader<K, V> {
+        self.reader.clone()
+    }
+
+    /// Returns the value for `key`, if it is in the map
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.reader.get(key)
+    }
+
+    /// Returns the [`Key`] of the entry for `key`, if it is in the map
+    pub fn find<Q>(&self, key: &Q) -> Option<Key>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.reader.find(key)
+    }
+
+    /// Returns the entry at `key`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is past the entries in the map, see [`MapReader::entry`]
+    #[must_use]
+    pub fn entry(&self, key: Key) -> (&K, &V) {
+        self.reader.entry(key)
+    }
+
+    /// Returns an iterator over the entries in the order they were inserted
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.reader.iter()
+    }
+
+    /// Returns the number of entries
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.writer.len()
+    }
+
+    /// Returns whether the map is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.writer.is_empty()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Default for AppendMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A reader for an [`AppendMap`], which can be cloned and shared between threads to look entries up while the map grows
+#[derive(Debug)]
+pub struct MapReader<K, V> {
+    entries: ReadHandle<(K, V)>,
+    index: Arc<RwLock<HashMap<K, usize>>>,
+}
+
+impl<K: Hash + Eq, V> MapReader<K, V> {
+    /// Returns the value for `key`, if it is in the map
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).map(|key| self.entry(key).1)
+    }
+
+    /// Returns the [`Key`] of the entry for `key`, if it is in the map
+    pub fn find<Q>(&self, key: &Q) -> Option<Key>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.index.read().unwrap_or_else(PoisonError::into_inner);
+        index.get(key).copied().map(Key)
+    }
+
+    /// Returns the entry at `key`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is past the entries in the map, which it can only be if it came from another map.
+    /// A key from another map that is not past them reads whichever entry is at its index
+    #[must_use]
+    pub fn entry(&self, key: Key) -> (&K, &V) {
+        let (k, v) = self
+            .entries
+            .try_read(key.0)
+            .expect("Read a key past the entries in the map");
+        (k, v)
+    }
+
+    /// Returns an iterator over the entries in the order they were inserted
+    ///
+    /// Only the entries inserted when this is called are included
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Returns the number of entries
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the map is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<K, V> Clone for MapReader<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            index: Arc::clone(&self.index),
+        }
+    }
+}
@@ -1,10 +1,123 @@
-#[cfg(feature = "allocator_api")]
-pub(crate) use allocator::{alloc_inner, dealloc_inner};
+use alloc::alloc::{handle_alloc_error, Layout};
 use core::{cell::UnsafeCell, mem::MaybeUninit};
-#[cfg(not(feature = "allocator_api"))]
-pub(crate) use without_allocator::{alloc_inner, dealloc_inner};
 
+//The nightly allocator api takes precedence over the stable polyfill when both are enabled
+#[cfg(all(any(test, feature = "std"), feature = "allocator_api"))]
+pub(crate) use alloc::alloc::AllocError;
+#[cfg(feature = "allocator_api")]
+pub(crate) use alloc::alloc::{Allocator, Global};
+#[cfg(all(
+    any(test, feature = "std"),
+    feature = "allocator-api2",
+    not(feature = "allocator_api")
+))]
+pub(crate) use allocator_api2::alloc::AllocError;
+#[cfg(all(feature = "allocator-api2", not(feature = "allocator_api")))]
+pub(crate) use allocator_api2::alloc::{Allocator, Global};
+
+/// The [`Storage`] used when none is given
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+pub(crate) type DefaultStorage = Global;
+/// The [`Storage`] used when none is given
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+pub(crate) type DefaultStorage = GlobalStorage;
+
+/// The number of times an allocation is retried at the request of an allocation error hook before giving up
+pub(crate) const MAX_ALLOC_RETRIES: usize = 8;
+
+/// The byte freshly allocated blocks are filled with when the `debug-poison` feature is enabled
+#[cfg(feature = "debug-poison")]
+pub(crate) const POISON_FRESH: u8 = 0xA5;
+
+/// The byte blocks are overwritten with before being freed when the `debug-poison` feature is enabled
+#[cfg(feature = "debug-poison")]
+pub(crate) const POISON_FREED: u8 = 0xDE;
+
+/// The last block allocated along with block 0 by the first push with [`Prealloc::SmallBlocks`](crate::Prealloc::SmallBlocks),
+/// so that a few small pushes do not each allocate
+//Taken from the standard libraries small vector optimization
+pub(crate) const fn initial_blocks<T>() -> usize {
+    match core::mem::size_of::<T>() {
+        1 => 3,
+        2..=1023 => 2,
+        _ => 1,
+    }
+}
+
+/// A hook consulted when allocating a block fails
+pub(crate) type AllocErrorHook = dyn Fn(Layout) -> RetryOrFail + Send + Sync;
+
+/// What an allocation error hook wants to happen after it has run
+///
+/// See [`WriteHandle::set_alloc_error_hook`](crate::WriteHandle::set_alloc_error_hook)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryOrFail {
+    /// Try the allocation again, usually because the hook has freed some memory
+    Retry,
+    /// Give up and fall back to [`handle_alloc_error`]
+    Fail,
+}
+
+/// A hook that picks where each newly allocated block is placed
+pub(crate) type PlacementHook = dyn Fn(usize, Layout) -> Placement + Send + Sync;
+
+/// Where a block placement hook wants a newly allocated block to live
+///
+/// See [`WriteHandle::set_block_placement`](crate::WriteHandle::set_block_placement)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Placement {
+    /// Leave the block wherever the storage put it
+    #[default]
+    Default,
+    /// Bind the block to the given NUMA node
+    ///
+    /// This only has an effect on Linux with the `numa` feature enabled, and only on blocks of at least 64 KiB,
+    /// as smaller ones mostly share their pages with other allocations. Only the pages that lie entirely within the block are bound,
+    /// and they keep that binding after the block is freed
+    Node(u32),
+}
+
+/// Applies `placement` to the freshly allocated block at `ptr`, which must not have been published yet
+pub(crate) fn place_block(ptr: *mut u8, layout: Layout, placement: Placement) {
+    match placement {
+        Placement::Default => {}
+        #[cfg(all(feature = "numa", target_os = "linux"))]
+        Placement::Node(node) => {
+            if layout.size() >= crate::numa::MIN_PLACED_BYTES {
+                crate::numa::bind(ptr, layout.size(), node);
+            }
+        }
+        #[cfg(not(all(feature = "numa", target_os = "linux")))]
+        Placement::Node(_) => {
+            let _ = (ptr, layout);
+        }
+    }
+}
+
+/// Calls `allocate` until it succeeds, consulting `hook` after each failure for up to [`MAX_ALLOC_RETRIES`] retries
+fn alloc_with_hook(
+    layout: Layout,
+    hook: Option<&AllocErrorHook>,
+    mut allocate: impl FnMut() -> *mut u8,
+) -> *mut u8 {
+    let mut retries = 0;
+    loop {
+        let ptr = allocate();
+        if !ptr.is_null() {
+            return ptr;
+        }
+        match hook {
+            Some(hook) if retries < MAX_ALLOC_RETRIES && hook(layout) == RetryOrFail::Retry => {
+                retries += 1;
+            }
+            _ => handle_alloc_error(layout),
+        }
+    }
+}
+
+//Transparent so that a run of elements within a block can be viewed as a slice of `T`
 #[derive(Debug)]
+#[repr(transparent)]
 pub(crate) struct Inner<T> {
     raw: MaybeUninit<UnsafeCell<T>>,
 }
@@ -25,6 +138,21 @@ impl<T> Inner<T> {
                 .expect("Pointer is non null")
         }
     }
+
+    /// SAFETY: The Inner must have been written to before reading
+    pub(crate) unsafe fn read_mut(&mut self) -> &mut T {
+        unsafe { (*self.raw.as_mut_ptr()).get_mut() }
+    }
+
+    /// SAFETY: The Inner must have been written to and must not be read or dropped again afterwards
+    pub(crate) unsafe fn take(&self) -> T {
+        unsafe { self.raw.as_ptr().read().into_inner() }
+    }
+
+    /// SAFETY: The Inner must have been written to and must not be read or dropped again afterwards
+    pub(crate) unsafe fn drop_in_place(&mut self) {
+        unsafe { core::ptr::drop_in_place(self.raw.as_mut_ptr()) }
+    }
 }
 
 impl<T> Inner<T>
@@ -36,112 +164,145 @@ where
     }
 }
 
-#[cfg(not(feature = "allocator_api"))]
-mod without_allocator {
-    use alloc::alloc::{alloc, dealloc};
-    use core::alloc::Layout;
-    /// # Safety
-    /// `alloc_inner` must be called with `len` such that `len` * [`size_of::<T>()`](core::mem::size_of()),
-    /// when aligned to [`align_of::<T>()`](core::mem::align_of()), is no more than [`usize::max`]
-    pub(crate) unsafe fn alloc_inner<T>(len: usize) -> *mut crate::Inner<T> {
-        debug_assert!(core::mem::size_of::<T>().checked_mul(len).is_some());
-        if core::mem::size_of::<T>() == 0 {
-            core::ptr::NonNull::dangling().as_ptr()
-        } else {
-            let layout = Layout::array::<T>(len)
-                .expect("Len is constrained by the safety contract of alloc_inner()!");
-            unsafe { alloc(layout).cast() }
-        }
-    }
+/// The interface a [`Stele`](crate::Stele) uses to allocate and free its blocks
+///
+/// This is implemented by [`GlobalStorage`], [`BufferStorage`] and, with the allocator features, by every allocator
+///
+/// The trait is sealed on purpose: it is `pub` so that it can bound the storage parameter of public types, but `mem`
+/// is private and the trait is not re-exported, so code outside this crate can neither name nor implement it
+pub trait Storage {
+    /// Allocates memory that fits `layout`, returning null if the allocation failed
+    fn allocate_block(&self, layout: Layout) -> *mut u8;
 
-    /// # Safety
-    /// The following two points must hold:
+    /// Frees memory previously returned by [`allocate_block`](Storage::allocate_block)
     ///
-    /// - `dealloc_inner` must be called with the correct `len` for `ptr`
+    /// # Safety
+    /// `ptr` must have been returned by [`allocate_block`](Storage::allocate_block) on this storage with the same `layout`
+    unsafe fn deallocate_block(&self, ptr: *mut u8, layout: Layout);
+
+    /// Returns whether blocks outlive the [`Stele`](crate::Stele) they were allocated for, such as blocks kept in a file
     ///
-    /// - `ptr` must have been allocated by `alloc_inner` and therefore must not be null
-    pub(crate) unsafe fn dealloc_inner<T>(ptr: *mut crate::Inner<T>, len: usize) {
-        debug_assert!(core::mem::size_of::<T>().checked_mul(len).is_some());
-        debug_assert!(!ptr.is_null());
-        if core::mem::size_of::<T>() != 0 {
-            let layout = Layout::array::<T>(len)
-                .expect("Len is constrained by the safety contract of dealloc_inner()!");
-            // SAFETY: By the safety contract of `dealloc_inner` and (in debug) the asserts above, we know
-            // that ptr can not be null as `alloc_inner` does not hand out null pointers
-            unsafe {
-                dealloc(ptr.cast(), layout);
-            }
-        }
+    /// Blocks that do are left as they are when freed instead of being overwritten by the `debug-poison` feature
+    fn keeps_blocks(&self) -> bool {
+        false
     }
+}
 
-    #[cfg(test)]
-    #[test]
-    fn allocation() {
-        unsafe {
-            let ptr = alloc_inner::<u8>(1);
-            assert!(!core::ptr::eq(ptr, core::ptr::null()));
-            dealloc_inner(ptr, 1);
-        }
+/// The default storage for a [`Stele`](crate::Stele), which allocates blocks using the global allocator
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GlobalStorage;
+
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+impl Storage for GlobalStorage {
+    fn allocate_block(&self, layout: Layout) -> *mut u8 {
+        unsafe { alloc::alloc::alloc(layout) }
+    }
+
+    unsafe fn deallocate_block(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { alloc::alloc::dealloc(ptr, layout) }
     }
 }
 
-#[cfg(feature = "allocator_api")]
-mod allocator {
-    use alloc::alloc::{handle_alloc_error, Allocator, Layout};
-    use core::ptr::NonNull;
-    /// # Safety
-    /// `alloc_inner` must be called with `len` such that `len` * [`size_of::<T>()`](core::mem::size_of()),
-    /// when aligned to [`align_of::<T>()`](core::mem::align_of()), is no more than [`usize::max`]
-    pub(crate) unsafe fn alloc_inner<T, A: Allocator>(
-        allocator: &A,
-        len: usize,
-    ) -> *mut crate::Inner<T> {
-        debug_assert!(core::mem::size_of::<T>().checked_mul(len).is_some());
-        if core::mem::size_of::<T>() == 0 {
-            NonNull::dangling().as_ptr()
-        } else {
-            let layout = Layout::array::<T>(len)
-                .expect("Len is constrained by the safety contract of alloc_inner()!");
-            let ptr = match allocator.allocate(layout) {
-                Ok(p) => p,
-                Err(_) => handle_alloc_error(layout),
-            };
-            ptr.as_ptr().cast()
-        }
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+impl<A: Allocator> Storage for A {
+    fn allocate_block(&self, layout: Layout) -> *mut u8 {
+        self.allocate(layout)
+            .map_or(core::ptr::null_mut(), |ptr| ptr.as_ptr().cast())
     }
 
-    /// # Safety
-    /// The following two points must hold:
-    ///
-    /// - `dealloc_inner` must be called with the correct `len` for `ptr`
-    ///
-    /// - `ptr` must have been allocated by `alloc_inner` and therefore must not be null
-    pub(crate) unsafe fn dealloc_inner<T, A: Allocator>(
-        allocator: &A,
-        ptr: *mut crate::Inner<T>,
-        len: usize,
-    ) {
-        debug_assert!(core::mem::size_of::<T>().checked_mul(len).is_some());
-        debug_assert!(!ptr.is_null());
-        if core::mem::size_of::<T>() != 0 {
-            let layout = Layout::array::<T>(len)
-                .expect("Len is constrained by the safety contract of dealloc_inner()!");
-            // SAFETY: By the safety contract of `dealloc_inner` and (in debug) the asserts above, we know
-            // that ptr can not be null as `alloc_inner` does not hand out null pointers
-            unsafe { allocator.deallocate(NonNull::new_unchecked(ptr.cast()), layout) }
-        }
+    unsafe fn deallocate_block(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: By the safety contract of `deallocate_block`, `ptr` came from `allocate_block` which never hands out null
+        unsafe { self.deallocate(core::ptr::NonNull::new_unchecked(ptr), layout) }
     }
+}
+
+/// A storage that never allocates, used by [`Stele::new_in_buffer`](crate::Stele::new_in_buffer) to store elements
+/// in a caller provided buffer
+///
+/// Every block is carved out of the buffer up front, so any further allocation fails and nothing is ever freed
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BufferStorage;
+
+impl Storage for BufferStorage {
+    fn allocate_block(&self, _: Layout) -> *mut u8 {
+        core::ptr::null_mut()
+    }
+
+    unsafe fn deallocate_block(&self, _: *mut u8, _: Layout) {}
+}
+
+/// The layout of a block of `len` elements, aligned to at least `align` bytes
+///
+/// `align` must be a power of two, and 1 keeps the alignment of `T`
+pub(crate) fn block_layout<T>(len: usize, align: usize) -> Option<Layout> {
+    Layout::array::<T>(len).ok()?.align_to(align).ok()
+}
 
-    #[cfg(test)]
-    #[test]
-    fn allocation() {
-        use alloc::alloc::Global;
+/// # Safety
+/// `alloc_inner` must be called with `len` such that `len` * [`size_of::<T>()`](core::mem::size_of()),
+/// when aligned to [`align_of::<T>()`](core::mem::align_of()) and `align`, is no more than [`usize::max`],
+/// and `align` must be a power of two
+pub(crate) unsafe fn alloc_inner<T, S: Storage>(
+    storage: &S,
+    len: usize,
+    align: usize,
+    hook: Option<&AllocErrorHook>,
+) -> *mut Inner<T> {
+    debug_assert!(core::mem::size_of::<T>().checked_mul(len).is_some());
+    if core::mem::size_of::<T>() == 0 {
+        core::ptr::NonNull::dangling().as_ptr()
+    } else {
+        let layout = block_layout::<T>(len, align)
+            .expect("Len is constrained by the safety contract of alloc_inner()!");
+        let ptr = alloc_with_hook(layout, hook, || storage.allocate_block(layout));
+        #[cfg(feature = "debug-poison")]
+        //SAFETY: `alloc_with_hook` only returns non-null pointers to at least `layout.size()` bytes
+        unsafe {
+            ptr.write_bytes(POISON_FRESH, layout.size());
+        }
+        ptr.cast()
+    }
+}
 
-        let allocator = &Global;
+/// # Safety
+/// The following two points must hold:
+///
+/// - `dealloc_inner` must be called with the same `len` and `align` that `ptr` was allocated with
+///
+/// - `ptr` must have been allocated by `alloc_inner` with the same `storage` and therefore must not be null
+///
+/// Any elements in the block must already have been dropped, as the block may be poisoned before it is freed
+pub(crate) unsafe fn dealloc_inner<T, S: Storage>(
+    storage: &S,
+    ptr: *mut Inner<T>,
+    len: usize,
+    align: usize,
+) {
+    debug_assert!(core::mem::size_of::<T>().checked_mul(len).is_some());
+    debug_assert!(!ptr.is_null());
+    if core::mem::size_of::<T>() != 0 {
+        let layout = block_layout::<T>(len, align)
+            .expect("Len is constrained by the safety contract of dealloc_inner()!");
+        // SAFETY: By the safety contract of `dealloc_inner` and (in debug) the asserts above, we know
+        // that ptr can not be null as `alloc_inner` does not hand out null pointers
         unsafe {
-            let ptr = alloc_inner::<u8, _>(allocator, 1);
-            assert!(!core::ptr::eq(ptr, core::ptr::null()));
-            dealloc_inner(allocator, ptr, 1);
+            #[cfg(feature = "debug-poison")]
+            if !storage.keeps_blocks() {
+                ptr.cast::<u8>().write_bytes(POISON_FREED, layout.size());
+            }
+            storage.deallocate_block(ptr.cast(), layout);
         }
     }
 }
+
+#[cfg(test)]
+#[test]
+fn allocation() {
+    let storage = &DefaultStorage::default();
+    unsafe {
+        let ptr = alloc_inner::<u8, _>(storage, 1, 1, None);
+        assert!(!core::ptr::eq(ptr, core::ptr::null()));
+        dealloc_inner(storage, ptr, 1, 1);
+    }
+}
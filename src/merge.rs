@@ -0,0 +1,192 @@
+use alloc::vec::Vec;
+use core::{cmp::Ordering, fmt, iter::FusedIterator};
+
+use crate::{
+    append::iter::RefIterator,
+    mem::{DefaultStorage, Storage},
+    ReadHandle,
+};
+
+/// Merges Steles that are each sorted into a single iterator over all of their elements in sorted order
+///
+/// Elements that compare equal are yielded in the order their Steles were given in, and in the order they were pushed
+/// within a Stele. Each Stele's length is captured when the iterator is created, see [`MergeIter`]
+pub fn merge<'rh, T, S>(
+    handles: impl IntoIterator<Item = &'rh ReadHandle<T, S>>,
+) -> MergeIter<'rh, T, S>
+where
+    T: Ord + 'rh,
+    S: Storage + 'rh,
+{
+    merge_by(handles, T::cmp)
+}
+
+/// Merges Steles that are each sorted by `cmp` into a single iterator over all of their elements in that order, see [`merge`]
+pub fn merge_by<'rh, T, S, F>(
+    handles: impl IntoIterator<Item = &'rh ReadHandle<T, S>>,
+    cmp: F,
+) -> MergeIter<'rh, T, S, F>
+where
+    T: 'rh,
+    S: Storage + 'rh,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut iter = MergeIter {
+        heap: Vec::new(),
+        cmp,
+    };
+    for (source, handle) in handles.into_iter().enumerate() {
+        let mut rest = handle.iter();
+        if let Some(head) = rest.next() {
+            iter.heap.push(Cursor { source, head, rest });
+            iter.sift_up(iter.heap.len() - 1);
+        }
+    }
+    iter
+}
+
+/// The next element of one of the merged Steles along with the ones after it
+#[derive(Debug)]
+struct Cursor<'rh, T, S: Storage> {
+    source: usize,
+    head: &'rh T,
+    rest: RefIterator<'rh, T, S>,
+}
+
+/// An iterator over the elements of several sorted Steles in sorted order, created by [`merge`] and [`merge_by`]
+///
+/// The next element of every Stele that has any left is kept in a binary heap, so each element takes `O(log k)`
+/// comparisons for `k` Steles. Only the elements initialized when the iterator was created are merged, and whether they
+/// are actually sorted is not checked: unsorted input is merged as if each Stele were sorted, in an unspecified order
+pub struct MergeIter<'rh, T, S: Storage = DefaultStorage, F = fn(&T, &T) -> Ordering> {
+    heap: Vec<Cursor<'rh, T, S>>,
+    cmp: F,
+}
+
+impl<'rh, T, S: Storage, F> MergeIter<'rh, T, S, F>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    /// Returns an iterator that yields the index of the Stele each element came from along with it,
+    /// in the order the Steles were given in
+    #[must_use]
+    pub fn with_source(self) -> WithSource<'rh, T, S, F> {
+        WithSource { merge: self }
+    }
+
+    fn next_with_source(&mut self) -> Option<(usize, &'rh T)> {
+        let top = self.heap.first_mut()?;
+        let next = (top.source, top.head);
+        match top.rest.next() {
+            Some(head) => top.head = head,
+            None => {
+                self.heap.swap_remove(0);
+            }
+        }
+        if !self.heap.is_empty() {
+            self.sift_down(0);
+        }
+        Some(next)
+    }
+
+    /// Whether the cursor at `a` comes before the one at `b`, which for equal elements is the one from the earlier Stele
+    fn precedes(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (&self.heap[a], &self.heap[b]);
+        (self.cmp)(a.head, b.head).then(a.source.cmp(&b.source)) == Ordering::Less
+    }
+
+    fn sift_up(&mut self, mut idx: usize) {
+        while idx > 0 {
+            let parent = (idx - 1) / 2;
+            if !self.precedes(idx, parent) {
+                break;
+            }
+            self.heap.swap(idx, parent);
+            idx = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut idx: usize) {
+        loop {
+            let mut first = idx;
+            for child in [2 * idx + 1, 2 * idx + 2] {
+                if child < self.heap.len() && self.precedes(child, first) {
+                    first = child;
+                }
+            }
+            if first == idx {
+                break;
+            }
+            self.heap.swap(idx, first);
+            idx = first;
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.heap
+            .iter()
+            .map(|cursor| cursor.rest.remaining_range().len() + 1)
+            .sum()
+    }
+}
+
+impl<T: fmt::Debug, S: Storage + fmt::Debug, F> fmt::Debug for MergeIter<'_, T, S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MergeIter")
+            .field("heap", &self.heap)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'rh, T, S: Storage, F> Iterator for MergeIter<'rh, T, S, F>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    type Item = &'rh T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_source().map(|(_, val)| val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, S: Storage, F: FnMut(&T, &T) -> Ordering> ExactSizeIterator for MergeIter<'_, T, S, F> {}
+
+impl<T, S: Storage, F: FnMut(&T, &T) -> Ordering> FusedIterator for MergeIter<'_, T, S, F> {}
+
+/// An iterator over the elements of several sorted Steles in sorted order along with the index of the Stele each came from,
+/// created by [`MergeIter::with_source`]
+pub struct WithSource<'rh, T, S: Storage = DefaultStorage, F = fn(&T, &T) -> Ordering> {
+    merge: MergeIter<'rh, T, S, F>,
+}
+
+impl<T: fmt::Debug, S: Storage + fmt::Debug, F> fmt::Debug for WithSource<'_, T, S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithSource")
+            .field("merge", &self.merge)
+            .finish()
+    }
+}
+
+impl<'rh, T, S: Storage, F> Iterator for WithSource<'rh, T, S, F>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    type Item = (usize, &'rh T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.merge.next_with_source()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.merge.size_hint()
+    }
+}
+
+impl<T, S: Storage, F: FnMut(&T, &T) -> Ordering> ExactSizeIterator for WithSource<'_, T, S, F> {}
+
+impl<T, S: Storage, F: FnMut(&T, &T) -> Ordering> FusedIterator for WithSource<'_, T, S, F> {}
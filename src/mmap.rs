@@ -0,0 +1,392 @@
+use alloc::{alloc::Layout, vec::Vec};
+use core::{
+    convert::TryFrom,
+    fmt,
+    mem::{align_of, size_of},
+    ptr::NonNull,
+};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read},
+    path::Path,
+    sync::{Mutex, PoisonError},
+};
+
+use bytemuck::Pod;
+use memmap2::{MmapMut, MmapOptions};
+
+use crate::{layout, mem::Storage, ReadHandle, Stele, WriteHandle};
+
+const MAGIC: [u8; 8] = *b"STELEMAP";
+const VERSION: u64 = 1;
+//Magic, version, element size, committed length, block count and the offset and size of every block
+const INDEX_LEN: usize = 8 + 8 + 8 + 8 + 8 + 32 * 16;
+//The index is given a page of its own, so the first block starts page aligned
+const INDEX_RESERVED: u64 = 4096;
+
+//Byte offsets of the fields of the index
+const VERSION_AT: usize = 8;
+const ELEMENT_SIZE_AT: usize = 16;
+const LEN_AT: usize = 24;
+const BLOCK_COUNT_AT: usize = 32;
+const BLOCKS_AT: usize = 40;
+
+fn to_u64(n: usize) -> u64 {
+    u64::try_from(n).expect("usize fits in a u64")
+}
+
+fn read_u64(index: &[u8], at: usize) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&index[at..at + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn write_u64(index: &mut [u8], at: usize, val: u64) {
+    index[at..at + 8].copy_from_slice(&val.to_le_bytes());
+}
+
+/// The error returned when reopening a file fails
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MmapError {
+    /// Opening, reading or mapping the file failed
+    Io(io::Error),
+    /// The file does not start with the magic bytes written by [`MmapStorage::create`]
+    BadMagic,
+    /// The file was written by a different version of the format
+    UnsupportedVersion {
+        /// The version found in the index
+        found: u64,
+    },
+    /// The elements in the file have a different size than the type it is reopened as
+    ElementSizeMismatch {
+        /// The size of the type the file is reopened as
+        expected: usize,
+        /// The element size found in the index
+        found: u64,
+    },
+    /// The index describes blocks that do not fit the committed length or lie outside the file
+    Corrupted,
+}
+
+impl fmt::Display for MmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MmapError::Io(_) => f.write_str("failed to map the file"),
+            MmapError::BadMagic => f.write_str("the file does not hold Stele blocks"),
+            MmapError::UnsupportedVersion { found } => {
+                write!(f, "index version {found} is not supported")
+            }
+            MmapError::ElementSizeMismatch { expected, found } => write!(
+                f,
+                "the file holds elements of {found} bytes but {expected} were expected"
+            ),
+            MmapError::Corrupted => f.write_str("the index does not match the blocks in the file"),
+        }
+    }
+}
+
+impl std::error::Error for MmapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MmapError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MmapError {
+    fn from(err: io::Error) -> Self {
+        MmapError::Io(err)
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    //The backing file and the mapping of its index, or `None` once reopened,
+    //after which new blocks are allocated in anonymous memory and the file is never written to again
+    file: Option<(File, MmapMut)>,
+    //The offset just past the last block in the file
+    end: u64,
+    //Every block mapped so far by its index in the Stele
+    blocks: Vec<Block>,
+}
+
+#[derive(Debug)]
+struct Block {
+    map: MmapMut,
+    //Cleared when the Stele frees the block, which stays mapped and in the file so that allocating it again reuses its region
+    live: bool,
+}
+
+/// A storage that allocates every block in a memory-mapped file, so that the elements outlive the process
+///
+/// The file starts with a small index holding the element size, the committed length and the offset and size of every block.
+/// Each allocation grows the file by one block and maps just that region. Freeing a block leaves it mapped and in the file,
+/// and allocating the same block again reuses its region.
+///
+/// Pushing only writes to the mapped blocks. Nothing is known to be on disk until [`WriteHandle::flush`] has synced every block
+/// and only then commits the length to the index, so after a crash [`reopen`](MmapStorage::reopen) never sees a length
+/// that covers unsynced elements. [Recycling](WriteHandle::try_recycle) rewrites the blocks in place below the committed length,
+/// so the file should not be relied on again until the next flush
+#[derive(Debug)]
+pub struct MmapStorage {
+    state: Mutex<State>,
+}
+
+impl MmapStorage {
+    /// Creates the file at `path`, truncating it if it exists, and writes an empty index to it
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from creating, growing or mapping the file
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(INDEX_RESERVED)?;
+        //SAFETY: The file was just created by us, and like every file mapping this relies on nothing else truncating it
+        let mut index = unsafe { MmapOptions::new().len(INDEX_LEN).map_mut(&file)? };
+        write_u64(&mut index, VERSION_AT, VERSION);
+        index[..MAGIC.len()].copy_from_slice(&MAGIC);
+        index.flush()?;
+        Ok(Self {
+            state: Mutex::new(State {
+                file: Some((file, index)),
+                end: INDEX_RESERVED,
+                blocks: Vec::new(),
+            }),
+        })
+    }
+
+    /// Reopens a file written through an [`MmapStorage`] and returns a [`ReadHandle`] to the elements up to the committed length
+    ///
+    /// The blocks are mapped copy-on-write, so the file is never modified. The handle has no writer, and if it is
+    /// [promoted](ReadHandle::try_promote) anything pushed afterwards only lives in memory
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`MmapError`] if the file cannot be read or mapped, was not written by an [`MmapStorage`],
+    /// holds elements of a different size than `T`, or has an index that does not match its blocks.
+    /// Only Steles whose blocks [double](crate::GrowthPolicy::doubling) in size can be reopened
+    pub fn reopen<T: Pod>(path: impl AsRef<Path>) -> Result<ReadHandle<T, Self>, MmapError> {
+        let mut file = File::open(path)?;
+        let mut index = [0; INDEX_LEN];
+        file.read_exact(&mut index)
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::UnexpectedEof => MmapError::BadMagic,
+                _ => MmapError::Io(err),
+            })?;
+        if index[..MAGIC.len()] != MAGIC {
+            return Err(MmapError::BadMagic);
+        }
+        let version = read_u64(&index, VERSION_AT);
+        if version != VERSION {
+            return Err(MmapError::UnsupportedVersion { found: version });
+        }
+        let len = usize::try_from(read_u64(&index, LEN_AT)).map_err(|_| MmapError::Corrupted)?;
+        let element_size = read_u64(&index, ELEMENT_SIZE_AT);
+        //Nothing is committed until the first flush, which is also when the element size is written
+        if len > 0 && element_size != to_u64(size_of::<T>()) {
+            return Err(MmapError::ElementSizeMismatch {
+                expected: size_of::<T>(),
+                found: element_size,
+            });
+        }
+        let block_count = usize::try_from(read_u64(&index, BLOCK_COUNT_AT))
+            .ok()
+            .filter(|&count| count <= 32)
+            .ok_or(MmapError::Corrupted)?;
+        let file_len = file.metadata()?.len();
+        let regions = (0..block_count)
+            .map(|block| {
+                let at = BLOCKS_AT + block * 16;
+                (read_u64(&index, at), read_u64(&index, at + 8))
+            })
+            .collect::<Vec<_>>();
+        let first_block_exp = first_block_exp::<T>(&regions)?;
+        let mut blocks = Vec::with_capacity(block_count);
+        let mut capacity = 0_usize;
+        for (block, &(offset, size)) in regions.iter().enumerate() {
+            let block_len = layout::block_capacity(block) << first_block_exp;
+            let fits = offset.checked_add(size).is_some_and(|end| end <= file_len);
+            if size != to_u64(block_len * size_of::<T>())
+                || !fits
+                || offset % to_u64(align_of::<T>()) != 0
+            {
+                return Err(MmapError::Corrupted);
+            }
+            capacity += block_len;
+            let size = usize::try_from(size).map_err(|_| MmapError::Corrupted)?;
+            //SAFETY: The region lies within the file, and the private mapping is never written back to it
+            let map = unsafe {
+                MmapOptions::new()
+                    .offset(offset)
+                    .len(size)
+                    .map_copy(&file)?
+            };
+            blocks.push(Block { map, live: true });
+        }
+        if size_of::<T>() != 0 && capacity < len {
+            return Err(MmapError::Corrupted);
+        }
+        let mut inners = blocks
+            .iter_mut()
+            .map(|block| block.map.as_mut_ptr().cast())
+            .collect::<Vec<_>>();
+        if size_of::<T>() == 0 {
+            //Zero sized elements never allocate, so like the Stele itself every block is dangling
+            inners = (0..32).map(|_| NonNull::dangling().as_ptr()).collect();
+        }
+        if inners
+            .iter()
+            .any(|&block: &*mut crate::Inner<T>| block.align_offset(align_of::<T>()) != 0)
+        {
+            return Err(MmapError::Corrupted);
+        }
+        let storage = Self {
+            state: Mutex::new(State {
+                file: None,
+                end: 0,
+                blocks,
+            }),
+        };
+        //SAFETY: The index was checked to hold a block of the right length for every element up to `len`, each of which
+        //is kept mapped by the storage and never freed by it. Every element below the committed length was synced before
+        //the length was committed, and any bit pattern is a valid `T`
+        Ok(unsafe { Stele::from_blocks(first_block_exp, storage, &inners, len) })
+    }
+
+    /// Syncs every block to the file and then commits `len` elements of `element_size` bytes to the index
+    fn commit(&self, element_size: usize, len: usize) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = &mut *state;
+        let (_, index) = state
+            .file
+            .as_mut()
+            .ok_or_else(|| io::Error::other("cannot flush a Stele reopened from a file"))?;
+        for block in &state.blocks {
+            block.map.flush()?;
+        }
+        write_u64(index, ELEMENT_SIZE_AT, to_u64(element_size));
+        write_u64(index, LEN_AT, to_u64(len));
+        index.flush()
+    }
+}
+
+/// Works out the size of the first block from the size of block 0, as every later block doubles from there
+fn first_block_exp<T>(regions: &[(u64, u64)]) -> Result<u32, MmapError> {
+    let first = match regions.first() {
+        Some(&(_, size)) if size_of::<T>() != 0 => size / to_u64(size_of::<T>()),
+        _ => return Ok(0),
+    };
+    if !first.is_power_of_two() {
+        return Err(MmapError::Corrupted);
+    }
+    Ok(first.trailing_zeros())
+}
+
+impl Storage for MmapStorage {
+    fn allocate_block(&self, layout: Layout) -> *mut u8 {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = &mut *state;
+        //Blocks are only ever freed from the end, so the block being allocated is the first one the Stele is not using
+        let idx = state
+            .blocks
+            .iter()
+            .position(|block| !block.live)
+            .unwrap_or(state.blocks.len());
+        if idx == 32 {
+            return core::ptr::null_mut();
+        }
+        if let Some(block) = state.blocks.get_mut(idx) {
+            if block.map.len() == layout.size()
+                && block.map.as_ptr().align_offset(layout.align()) == 0
+            {
+                block.live = true;
+                return block.map.as_mut_ptr();
+            }
+        }
+        let mut map = match &mut state.file {
+            Some((file, index)) => {
+                let offset = state.end.next_multiple_of(to_u64(layout.align()));
+                let end = offset + to_u64(layout.size());
+                if file.set_len(end).is_err() {
+                    return core::ptr::null_mut();
+                }
+                //SAFETY: The region was just added to the file, which only this storage writes to
+                let mapped = unsafe {
+                    MmapOptions::new()
+                        .offset(offset)
+                        .len(layout.size())
+                        .map_mut(&*file)
+                };
+                let Some(map) = mapped
+                    .ok()
+                    .filter(|map| map.as_ptr().align_offset(layout.align()) == 0)
+                else {
+                    //Give the region back, so that the file holds no block the index does not describe
+                    let _ = file.set_len(state.end);
+                    return core::ptr::null_mut();
+                };
+                //The entry is only written once the block is mapped, at the position of the block in the Stele
+                let at = BLOCKS_AT + idx * 16;
+                write_u64(index, at, offset);
+                write_u64(index, at + 8, to_u64(layout.size()));
+                write_u64(
+                    index,
+                    BLOCK_COUNT_AT,
+                    to_u64(state.blocks.len().max(idx + 1)),
+                );
+                state.end = end;
+                map
+            }
+            None => match MmapMut::map_anon(layout.size()) {
+                Ok(map) if map.as_ptr().align_offset(layout.align()) == 0 => map,
+                _ => return core::ptr::null_mut(),
+            },
+        };
+        let ptr = map.as_mut_ptr();
+        let block = Block { map, live: true };
+        match state.blocks.get_mut(idx) {
+            Some(slot) => *slot = block,
+            None => state.blocks.push(block),
+        }
+        ptr
+    }
+
+    //Blocks stay mapped until the storage is dropped, and stay in the file after that
+    unsafe fn deallocate_block(&self, ptr: *mut u8, _: Layout) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(block) = state
+            .blocks
+            .iter_mut()
+            .find(|block| block.map.as_ptr() == ptr.cast_const())
+        {
+            block.live = false;
+        }
+    }
+
+    fn keeps_blocks(&self) -> bool {
+        true
+    }
+}
+
+impl<T: Pod> WriteHandle<T, MmapStorage> {
+    /// Syncs every block to the file and then commits the current length, so that [`MmapStorage::reopen`] sees every element pushed so far
+    ///
+    /// The length is only written to the index once the blocks are synced, so a crash part way through leaves the previous
+    /// length in place. Reserved elements that have not been filled yet, and everything after them, are not committed
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from syncing the file, or an error if the [`Stele`] was reopened from a file rather than created
+    pub fn flush(&self) -> io::Result<()> {
+        self.handle
+            .storage()
+            .commit(size_of::<T>(), self.handle.initialized_len())
+    }
+}
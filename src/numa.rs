@@ -0,0 +1,43 @@
+use core::{convert::TryFrom, ffi::c_ulong};
+
+/// The smallest block that is bound to a node, as smaller ones mostly share their pages with other allocations
+pub(crate) const MIN_PLACED_BYTES: usize = 64 * 1024;
+
+//From linux/mempolicy.h
+const MPOL_BIND: libc::c_int = 2;
+const MPOL_MF_MOVE: libc::c_uint = 1 << 1;
+
+/// Binds every whole page of the `size` bytes at `ptr` to `node`, moving any that were already touched
+///
+/// Placement is only a hint, so failures such as a node that does not exist are ignored and leave the memory where it was
+pub(crate) fn bind(ptr: *mut u8, size: usize, node: u32) {
+    //SAFETY: sysconf has no preconditions
+    let page = match usize::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }) {
+        Ok(page) if page.is_power_of_two() => page,
+        _ => return,
+    };
+    let start = (ptr as usize + page - 1) & !(page - 1);
+    let end = (ptr as usize + size) & !(page - 1);
+    if start >= end {
+        return;
+    }
+    let bits = c_ulong::BITS as usize;
+    let node = node as usize;
+    let mut mask = alloc::vec![0 as c_ulong; node / bits + 1];
+    mask[node / bits] |= 1 << (node % bits);
+    //The kernel ignores the last bit of the mask length it is given
+    let max_node = mask.len() * bits + 1;
+    //SAFETY: The range lies within the block, which nothing else can reach yet,
+    //and changing the memory policy of a mapping does not change its contents
+    unsafe {
+        libc::syscall(
+            libc::SYS_mbind,
+            start,
+            end - start,
+            MPOL_BIND,
+            mask.as_ptr(),
+            max_node,
+            MPOL_MF_MOVE,
+        );
+    }
+}
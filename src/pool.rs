@@ -0,0 +1,151 @@
+use alloc::{alloc::Layout, vec::Vec};
+use core::ptr::NonNull;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+use crate::mem::{AllocError, Allocator};
+use crate::mem::{DefaultStorage, Storage};
+
+/// A storage that keeps the blocks of dropped Steles and hands them out again to later ones
+///
+/// Every block of a [`Stele`](crate::Stele) has one of a handful of sizes, so Steles that are created and dropped
+/// over and over keep asking for the same layouts. Freed blocks are kept in a free list per layout and reused before
+/// anything new is allocated from the backing storage, until they are released with [`trim`](BlockPool::trim) or the pool is dropped.
+///
+/// Pass it to [`Stele::new_in`](crate::Stele::new_in) by reference to share it between Steles, even across threads.
+///
+/// ```
+/// use stele::{pool::BlockPool, Stele};
+///
+/// let pool = BlockPool::new();
+/// for _ in 0..3 {
+///     let (wh, _rh) = Stele::new_in(&pool);
+///     (0..4_u32).for_each(|n| wh.push(n));
+/// }
+/// //The second and third Stele reused the blocks of the first
+/// assert_eq!(pool.pooled_blocks(), 3);
+/// pool.trim();
+/// assert_eq!(pool.pooled_blocks(), 0);
+/// ```
+#[derive(Debug, Default)]
+pub struct BlockPool<S: Storage = DefaultStorage> {
+    free: Mutex<HashMap<Layout, Vec<FreeBlock>>>,
+    backing: S,
+}
+
+//A block that is not used by any Stele, and so is only ever reached through the pool's lock
+#[derive(Debug)]
+struct FreeBlock(NonNull<u8>);
+
+//SAFETY: Nothing else holds a pointer to a free block, so it can be handed to whichever thread takes it out of the pool
+unsafe impl Send for FreeBlock {}
+
+impl BlockPool {
+    /// Creates a new pool that does not hold any blocks and allocates new ones from the global allocator
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S: Storage> BlockPool<S> {
+    /// Creates a new pool that does not hold any blocks and allocates new ones from `backing`
+    pub fn new_in(backing: S) -> Self {
+        Self {
+            free: Mutex::default(),
+            backing,
+        }
+    }
+
+    /// Returns the number of free blocks the pool is holding on to
+    #[must_use]
+    pub fn pooled_blocks(&self) -> usize {
+        self.lock().values().map(Vec::len).sum()
+    }
+
+    /// Returns the number of bytes taken up by the free blocks the pool is holding on to
+    #[must_use]
+    pub fn pooled_bytes(&self) -> usize {
+        self.lock()
+            .iter()
+            .map(|(layout, blocks)| layout.size() * blocks.len())
+            .sum()
+    }
+
+    /// Returns every free block to the backing storage
+    ///
+    /// Blocks still used by a Stele are not affected, and return to the pool as usual once it is dropped
+    pub fn trim(&self) {
+        let free = core::mem::take(&mut *self.lock());
+        for (layout, blocks) in free {
+            for block in blocks {
+                //SAFETY: Every pooled block was allocated from the backing storage with the layout it is filed under
+                unsafe { self.backing.deallocate_block(block.0.as_ptr(), layout) };
+            }
+        }
+    }
+
+    /// Takes a free block of exactly `layout` out of the pool, or allocates a new one
+    fn take(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let reused = self.lock().get_mut(&layout).and_then(Vec::pop);
+        match reused {
+            Some(block) => Some(block.0),
+            None => NonNull::new(self.backing.allocate_block(layout)),
+        }
+    }
+
+    /// Puts a block that is no longer used back into the pool
+    fn put(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.lock().entry(layout).or_default().push(FreeBlock(ptr));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Layout, Vec<FreeBlock>>> {
+        //The free lists are valid after every step, so a panic while holding the lock leaves nothing to clean up
+        self.free.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<S: Storage> Drop for BlockPool<S> {
+    fn drop(&mut self) {
+        self.trim();
+    }
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+unsafe impl<S: Storage> Allocator for BlockPool<S> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = self.take(layout).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.put(ptr, layout);
+    }
+}
+
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+impl<S: Storage> Storage for BlockPool<S> {
+    fn allocate_block(&self, layout: Layout) -> *mut u8 {
+        self.take(layout)
+            .map_or(core::ptr::null_mut(), NonNull::as_ptr)
+    }
+
+    unsafe fn deallocate_block(&self, ptr: *mut u8, layout: Layout) {
+        //SAFETY: By the safety contract of `deallocate_block`, `ptr` came from `allocate_block` which never hands out null
+        self.put(unsafe { NonNull::new_unchecked(ptr) }, layout);
+    }
+}
+
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+impl<S: Storage> Storage for &BlockPool<S> {
+    fn allocate_block(&self, layout: Layout) -> *mut u8 {
+        (**self).allocate_block(layout)
+    }
+
+    unsafe fn deallocate_block(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { (**self).deallocate_block(ptr, layout) }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{queue::WorkQueue, Stele};
+
+#[test]
+fn backend() {
+    //The handles and the Stele itself must be built from portable-atomic's types
+    let (wh, rh) = Stele::<u32>::new();
+    let _: &portable_atomic_util::Arc<Stele<u32>> = &rh.handle;
+    let _: &portable_atomic_util::Arc<Stele<u32>> = &wh.handle;
+    let _: portable_atomic::AtomicPtr<u32> = crate::sync::AtomicPtr::new(core::ptr::null_mut());
+    let _: portable_atomic::AtomicUsize = crate::sync::AtomicUsize::new(0);
+}
+
+#[test]
+fn push_and_read() {
+    let (wh, rh) = Stele::new();
+    (0..100).for_each(|n| wh.push(n));
+    assert!(rh.iter().copied().eq(0..100));
+    let slot = wh.push_uninit();
+    slot.fill(100);
+    assert_eq!(rh.initialized_len(), 101);
+    drop(wh);
+    let wh = rh.clone().try_promote().unwrap();
+    wh.push(101);
+    let queue = WorkQueue::new(rh);
+    assert_eq!(queue.steal_batch(200).count(), 102);
+}
+
+#[test]
+fn static_stele() {
+    static STELE: Stele<u32> = Stele::const_new();
+    let wh = STELE.claim_writer().unwrap();
+    wh.push(1);
+    assert_eq!(STELE.reader().get(0), 1);
+}
@@ -0,0 +1,67 @@
+/// How far ahead of the element being read the scan prefetches, in bytes
+pub(crate) const LOOKAHEAD_BYTES: usize = 4096;
+
+/// The cache line size of every supported target, which is the distance between prefetches
+pub(crate) const LINE_BYTES: usize = 64;
+
+/// How many bytes are folded between two rounds of prefetches, large enough not to get in the way of unrolling the fold
+pub(crate) const STRIDE_BYTES: usize = 1024;
+
+/// Hints that the cache line holding `ptr` will be read soon
+///
+/// Prefetching never faults, so `ptr` does not need to point into an allocation
+#[inline]
+pub(crate) fn prefetch<T>(ptr: *const T) {
+    #[cfg(all(feature = "prefetch", target_arch = "x86_64"))]
+    //SAFETY: SSE is part of the x86_64 baseline, and prefetching has no observable effect
+    unsafe {
+        core::arch::x86_64::_mm_prefetch(ptr.cast::<i8>(), core::arch::x86_64::_MM_HINT_T0);
+    }
+    #[cfg(all(feature = "prefetch", target_arch = "aarch64"))]
+    //SAFETY: Prefetching has no observable effect
+    unsafe {
+        core::arch::asm!(
+            "prfm pldl1keep, [{ptr}]",
+            ptr = in(reg) ptr,
+            options(nostack, readonly, preserves_flags)
+        );
+    }
+    #[cfg(not(all(
+        feature = "prefetch",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )))]
+    let _ = ptr;
+}
+
+/// Folds every element of `block` in order, prefetching [`LOOKAHEAD_BYTES`] ahead of it and,
+/// once that runs past the end of the block, the start of `next`
+#[inline]
+pub(crate) fn fold_prefetched<'a, T, B>(
+    block: &'a [T],
+    next: Option<*const T>,
+    init: B,
+    f: &mut impl FnMut(B, &'a T) -> B,
+) -> B {
+    let size = core::mem::size_of::<T>();
+    if !cfg!(feature = "prefetch") || size == 0 {
+        return block.iter().fold(init, f);
+    }
+    let stride = (STRIDE_BYTES / size).max(1);
+    let mut acc = init;
+    for (start, chunk) in (0..).step_by(stride).zip(block.chunks(stride)) {
+        //Every line of the stride that lies `LOOKAHEAD_BYTES` ahead, continuing into the next block past the end of this one
+        for offset in (0..stride * size).step_by(LINE_BYTES) {
+            let ahead = start + (LOOKAHEAD_BYTES + offset) / size;
+            match ahead.checked_sub(block.len()) {
+                None => prefetch(block.as_ptr().wrapping_add(ahead)),
+                Some(past) => {
+                    if let Some(next) = next {
+                        prefetch(next.wrapping_add(past));
+                    }
+                }
+            }
+        }
+        acc = chunk.iter().fold(acc, &mut *f);
+    }
+    acc
+}
@@ -0,0 +1,128 @@
+use core::ops::Range;
+
+use crate::{
+    mem::{DefaultStorage, Storage},
+    sync::{ord, Arc, AtomicUsize},
+    ReadHandle,
+};
+
+/// A single producer, multiple consumer work queue over a [`Stele`](crate::Stele) that hands every element to exactly one consumer
+///
+/// Nothing is ever removed: consumers share a claim cursor that only moves forward, so the elements stay readable
+/// through the [`ReadHandle`] after they have been processed. Cloning a [`WorkQueue`] shares the cursor with the clone.
+///
+/// A consumer that finds the queue empty can wait for more work with [`ReadHandle::wait_for_len`] through [`handle`](WorkQueue::handle)
+#[derive(Debug)]
+pub struct WorkQueue<T, S: Storage = DefaultStorage> {
+    handle: ReadHandle<T, S>,
+    cursor: Arc<AtomicUsize>,
+}
+
+impl<T, S: Storage> WorkQueue<T, S> {
+    /// Creates a [`WorkQueue`] that starts claiming from the first element
+    #[must_use]
+    pub fn new(handle: ReadHandle<T, S>) -> Self {
+        Self {
+            handle,
+            cursor: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Claims the next unclaimed element, returning its index along with it, or [`None`] if every element pushed so far is claimed
+    #[must_use]
+    pub fn claim(&self) -> Option<(usize, &T)> {
+        self.steal_batch(1).next()
+    }
+
+    /// Claims up to `n` consecutive unclaimed elements at once, which means less contention on the cursor than claiming them one by one
+    ///
+    /// The returned [`Batch`] is empty if every element pushed so far is claimed
+    #[must_use]
+    pub fn steal_batch(&self, n: usize) -> Batch<'_, T, S> {
+        let mut start = self.cursor.load(ord::RLX);
+        loop {
+            //Only initialized elements are handed out, so a claimed element can always be read
+            let end = self.handle.initialized_len().min(start.saturating_add(n));
+            if end <= start {
+                return self.batch(start..start);
+            }
+            match self
+                .cursor
+                .compare_exchange_weak(start, end, ord::RLX, ord::RLX)
+            {
+                Ok(_) => return self.batch(start..end),
+                Err(current) => start = current,
+            }
+        }
+    }
+
+    /// Returns the number of elements claimed so far
+    ///
+    /// Note: this is an optimistic operation and other consumers may be claiming elements under you
+    #[must_use]
+    pub fn claimed(&self) -> usize {
+        self.cursor.load(ord::RLX)
+    }
+
+    /// Returns the number of elements pushed but not claimed yet
+    ///
+    /// Note: this is an optimistic operation and the writer and other consumers may be changing it under you
+    #[must_use]
+    pub fn unclaimed(&self) -> usize {
+        self.handle.initialized_len().saturating_sub(self.claimed())
+    }
+
+    /// Returns the [`ReadHandle`] the queue reads through
+    #[must_use]
+    pub fn handle(&self) -> &ReadHandle<T, S> {
+        &self.handle
+    }
+
+    fn batch(&self, range: Range<usize>) -> Batch<'_, T, S> {
+        Batch {
+            handle: &self.handle,
+            range,
+        }
+    }
+}
+
+impl<T, S: Storage> Clone for WorkQueue<T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.handle.clone(),
+            cursor: Arc::clone(&self.cursor),
+        }
+    }
+}
+
+/// A run of consecutive elements claimed with [`WorkQueue::steal_batch`], which yields each index along with its element
+///
+/// Dropping it early does not give the remaining elements back, they stay claimed
+#[derive(Debug)]
+pub struct Batch<'q, T, S: Storage = DefaultStorage> {
+    handle: &'q ReadHandle<T, S>,
+    range: Range<usize>,
+}
+
+impl<T, S: Storage> Batch<'_, T, S> {
+    /// Returns the indices of the claimed elements that have not been yielded yet
+    #[must_use]
+    pub fn indices(&self) -> Range<usize> {
+        self.range.clone()
+    }
+}
+
+impl<'q, T, S: Storage> Iterator for Batch<'q, T, S> {
+    type Item = (usize, &'q T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.range.next()?;
+        Some((idx, self.handle.read(idx)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<T, S: Storage> ExactSizeIterator for Batch<'_, T, S> {}
@@ -0,0 +1,347 @@
+use core::{mem::MaybeUninit, ptr::null_mut};
+
+use alloc::alloc::Layout;
+
+use crate::{
+    layout::GrowthPolicy,
+    mem::{AllocErrorHook, DefaultStorage, Storage},
+    sync::{ord, AtomicPtr, AtomicUsize},
+    Inner,
+};
+
+/// The block table and length a [`Stele`](crate::Stele) is built from, without any of its rules about who may write
+///
+/// A `RawStele` owns up to 32 blocks sized by its [`GrowthPolicy`] and a published length, and nothing else.
+/// Elements never move once their block is allocated, and blocks can be allocated from any number of threads at once.
+/// It never reads, writes or drops an element itself: which slots are initialized, who writes them and when they
+/// are dropped is entirely up to the code built on it, and so is keeping the published length truthful.
+///
+/// # Safety obligations
+///
+/// The pointers handed out by [`slot_ptr`](RawStele::slot_ptr) and [`block_table`](RawStele::block_table) stay valid
+/// until the `RawStele` is dropped, even if it is moved, and every slot may be accessed through them under the usual
+/// aliasing rules:
+///
+/// - A slot must be written before it is read, and must not be written while anything else reads or writes it
+/// - Writes to a slot must happen before another thread reads it, such as by [publishing](RawStele::publish_len)
+///   a length that includes it after writing, and [loading](RawStele::load_len) that length before reading
+/// - Elements that need dropping must be dropped by the code that wrote them before the `RawStele` is dropped,
+///   as dropping it only frees the blocks
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use stele::raw::RawStele;
+///
+/// let raw = RawStele::<u64>::new();
+/// for idx in 0..100 {
+///     let slot = raw.slot_ptr(idx, true);
+///     //SAFETY: Only this thread writes, and each slot exactly once
+///     unsafe { slot.write(MaybeUninit::new(idx as u64 * 2)) };
+///     //SAFETY: Every slot below the new length was just written
+///     unsafe { raw.publish_len(idx + 1) };
+/// }
+/// assert_eq!(raw.load_len(), 100);
+/// //SAFETY: Slot 42 is below the published length, so it was written
+/// assert_eq!(unsafe { (*raw.slot_ptr(42, false)).assume_init() }, 84);
+/// ```
+#[derive(Debug)]
+//`repr(C)` so that the layout only depends on the layouts of the fields, like a `Stele`
+#[repr(C)]
+pub struct RawStele<T, S: Storage = DefaultStorage> {
+    pub(crate) inners: [AtomicPtr<Inner<T>>; 32],
+    //The published length, which the code built on top decides the meaning of
+    pub(crate) len: AtomicUsize,
+    //How the elements are spread over the blocks
+    pub(crate) growth: GrowthPolicy,
+    //The alignment of every block in bytes, or 1 to keep the alignment of `T`
+    pub(crate) block_align: usize,
+    pub(crate) storage: S,
+    //A buffer the leading blocks point into instead of each being allocated on their own
+    pub(crate) adopted: Option<Adopted<T>>,
+}
+
+/// A buffer adopted from a [`Vec`](alloc::vec::Vec) by [`RawStele::adopt`], which is freed as a whole instead of block by block
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Adopted<T> {
+    //The start of the buffer, which is also the start of block 0
+    ptr: *mut Inner<T>,
+    //The number of elements the buffer was allocated for, which it is freed with
+    cap: usize,
+    //The number of leading blocks that point into the buffer
+    blocks: usize,
+}
+
+//SAFETY: Moving a RawStele moves its blocks along with it, and sharing one lets every thread reach the elements
+//and allocate from the storage, so this needs the same bounds as a Stele
+unsafe impl<T, S: Storage + Send> Send for RawStele<T, S> where T: Send + Sync {}
+unsafe impl<T, S: Storage + Sync> Sync for RawStele<T, S> where T: Send + Sync {}
+
+impl<T> RawStele<T> {
+    /// Creates an empty `RawStele` with the default [`GrowthPolicy`] that allocates from the global allocator
+    #[must_use]
+    pub fn new() -> Self {
+        Self::new_in(DefaultStorage::default())
+    }
+
+    /// Creates an empty `RawStele` in a `const` context, so that it can be stored directly in a `static`
+    #[cfg(not(any(loom, feature = "loom", shuttle)))]
+    #[must_use]
+    pub const fn const_new() -> Self {
+        RawStele {
+            inners: [Self::NULL_BLOCK; 32],
+            len: AtomicUsize::new(0),
+            growth: GrowthPolicy::doubling(0),
+            block_align: 1,
+            storage: DefaultStorage {},
+            adopted: None,
+        }
+    }
+
+    //Only used to repeat in an array, where each use is a fresh value
+    #[cfg(not(any(loom, feature = "loom", shuttle)))]
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NULL_BLOCK: AtomicPtr<Inner<T>> = AtomicPtr::new(null_mut());
+}
+
+impl<T> Default for RawStele<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S: Storage> RawStele<T, S> {
+    /// Creates an empty `RawStele` with the default [`GrowthPolicy`] that allocates from `storage`
+    pub fn new_in(storage: S) -> Self {
+        Self::with_growth_in(GrowthPolicy::default(), storage)
+    }
+
+    /// Creates an empty `RawStele` whose blocks are sized by `growth` and allocated from `storage`
+    pub fn with_growth_in(growth: GrowthPolicy, storage: S) -> Self {
+        RawStele {
+            inners: [(); 32].map(|()| AtomicPtr::new(null_mut())),
+            len: AtomicUsize::new(0),
+            growth,
+            block_align: 1,
+            storage,
+            adopted: None,
+        }
+    }
+
+    /// Returns the [`GrowthPolicy`] that decides which block holds each index
+    #[must_use]
+    pub fn growth(&self) -> GrowthPolicy {
+        self.growth
+    }
+
+    /// Returns a pointer to the slot for `idx`, allocating its block first if `allocate` is `true`
+    ///
+    /// Returns null if the block has not been allocated and `allocate` is `false`. Several threads may allocate the
+    /// same block at once, in which case all of them get the block that was published first and the others are freed.
+    /// The pointer is valid for reads and writes of one `T` for as long as the `RawStele` lives, see the
+    /// [safety obligations](RawStele#safety-obligations) for accessing it
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` lies past the last of the 32 blocks
+    #[must_use]
+    pub fn slot_ptr(&self, idx: usize, allocate: bool) -> *mut MaybeUninit<T> {
+        let (outer_idx, inner_idx) = self.split_idx(idx);
+        assert!(
+            outer_idx < self.inners.len() && inner_idx < self.block_len(outer_idx),
+            "Index {} lies past the last block",
+            idx
+        );
+        let mut block = self.block(outer_idx);
+        if block.is_null() {
+            if !allocate {
+                return null_mut();
+            }
+            block = self.allocate_block(outer_idx, None, |_| {});
+        }
+        //SAFETY: The offset lies within the block, as was just checked
+        unsafe { block.add(inner_idx).cast() }
+    }
+
+    /// Returns the length last published with [`publish_len`](RawStele::publish_len)
+    ///
+    /// The load acquires the publishing store, so every slot written before the length was published can be read
+    #[must_use]
+    pub fn load_len(&self) -> usize {
+        self.len.load(ord::ACQ)
+    }
+
+    /// Publishes `len` as the new length, releasing every write made before it to whoever [loads](RawStele::load_len) it
+    ///
+    /// # Safety
+    ///
+    /// Every slot below `len` must have been written, as readers rely on that to read any of them.
+    /// A published length must also never be lowered while a reader may still use the slots beyond the new one,
+    /// and when several threads publish, they must agree on an order among themselves so that it only ever grows
+    pub unsafe fn publish_len(&self, len: usize) {
+        self.len.store(len, ord::REL);
+    }
+
+    /// Returns the start of every block, which is null for blocks that have not been allocated
+    ///
+    /// Block `n` holds [`GrowthPolicy::block_capacity`] elements starting at index [`GrowthPolicy::first_index_of_block`].
+    /// The blocks can be accessed like the pointers from [`slot_ptr`](RawStele::slot_ptr), and one that is not null
+    /// here stays allocated for as long as the `RawStele` lives
+    #[must_use]
+    pub fn block_table(&self) -> [*mut MaybeUninit<T>; 32] {
+        core::array::from_fn(|block| self.block(block).cast())
+    }
+
+    /// Returns the number of elements the currently allocated blocks can hold
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.allocated_blocks().map(|i| self.block_len(i)).sum()
+    }
+
+    /// Splits `idx` into the block holding it and its offset within that block, according to the growth policy
+    pub(crate) fn split_idx(&self, idx: usize) -> (usize, usize) {
+        self.growth.split_idx(idx)
+    }
+
+    /// The number of elements the given block holds
+    pub(crate) fn block_len(&self, block: usize) -> usize {
+        self.growth.block_capacity(block)
+    }
+
+    /// The number of blocks needed to hold `len` elements
+    pub(crate) fn blocks_for_len(&self, len: usize) -> usize {
+        self.growth.blocks_for_len(len)
+    }
+
+    /// The layout of the given block
+    pub(crate) fn block_layout(&self, block: usize) -> Option<Layout> {
+        crate::mem::block_layout::<T>(self.block_len(block), self.block_align)
+    }
+
+    /// Loads the start of the given block, which is null if it has not been allocated
+    pub(crate) fn block(&self, block: usize) -> *mut Inner<T> {
+        self.inners[block].load(ord::ACQ)
+    }
+
+    pub(crate) fn allocated_blocks(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.inners.len()).filter(move |&i| !self.block(i).is_null())
+    }
+
+    /// Allocates the given block, lets `place` see it before it is published and returns the block that ends up published
+    ///
+    /// If another thread published the block first, the one allocated here is freed and that one is returned instead
+    pub(crate) fn allocate_block(
+        &self,
+        block: usize,
+        hook: Option<&AllocErrorHook>,
+        place: impl FnOnce(*mut Inner<T>),
+    ) -> *mut Inner<T> {
+        //SAFETY: The block length comes from the growth policy, which keeps every block addressable
+        let ptr = unsafe {
+            crate::mem::alloc_inner(&self.storage, self.block_len(block), self.block_align, hook)
+        };
+        place(ptr);
+        match self.inners[block].compare_exchange(null_mut(), ptr, ord::ACQREL, ord::ACQ) {
+            Ok(_) => ptr,
+            Err(published) => {
+                //SAFETY: The block was just allocated with this length and alignment and was never published
+                unsafe {
+                    crate::mem::dealloc_inner(
+                        &self.storage,
+                        ptr,
+                        self.block_len(block),
+                        self.block_align,
+                    );
+                }
+                published
+            }
+        }
+    }
+
+    /// Points as many leading blocks into the buffer at `ptr` as fit in `cap` elements, and returns how many elements they hold
+    ///
+    /// The buffer is only freed once the `RawStele` is dropped, even if the blocks pointing into it are freed before that
+    ///
+    /// SAFETY: No block may be allocated yet, and the storage must be able to free `ptr` as a block of `cap` elements
+    /// aligned to `block_align`, such as a buffer of a [`Vec`](alloc::vec::Vec) when the storage is the global allocator
+    pub(crate) unsafe fn adopt(&mut self, ptr: *mut Inner<T>, cap: usize) -> usize {
+        debug_assert!(self.allocated_blocks().next().is_none());
+        let blocks = self.split_idx(cap).0.min(self.inners.len());
+        for block in 0..blocks {
+            //SAFETY: Every block before the one holding index `cap` lies entirely within the buffer
+            let start = unsafe { ptr.add(self.growth.first_index_of_block(block)) };
+            self.inners[block].store(start, ord::RLX);
+        }
+        self.adopted = Some(Adopted { ptr, cap, blocks });
+        self.growth.first_index_of_block(blocks)
+    }
+
+    /// Returns whether `ptr`, published as the given block, points into an [adopted](RawStele::adopt) buffer rather than
+    /// being allocated on its own
+    ///
+    /// A block of the buffer that was freed can be allocated again later, so the pointer is compared instead of only the block
+    fn is_adopted(&self, block: usize, ptr: *mut Inner<T>) -> bool {
+        self.adopted.as_ref().is_some_and(|adopted| {
+            block < adopted.blocks
+                && core::ptr::eq(
+                    ptr,
+                    adopted
+                        .ptr
+                        .wrapping_add(self.growth.first_index_of_block(block)),
+                )
+        })
+    }
+
+    /// Unpublishes and frees the given block if it is allocated
+    ///
+    /// SAFETY: Nothing may access the block anymore, and every element in it that needs dropping must have been dropped
+    pub(crate) unsafe fn free_block(&self, block: usize) {
+        //Swapping in null before freeing means anything that loads this pointer afterwards only ever observes null
+        let ptr = self.inners[block].swap(null_mut(), ord::ACQREL);
+        //An adopted buffer is only freed once every block pointing into it is gone, which is when the `RawStele` is dropped
+        if !ptr.is_null() && !self.is_adopted(block, ptr) {
+            //SAFETY: Every published block was allocated from this storage with this length and alignment
+            unsafe {
+                crate::mem::dealloc_inner(
+                    &self.storage,
+                    ptr,
+                    self.block_len(block),
+                    self.block_align,
+                );
+            }
+        }
+    }
+
+    /// Returns a pointer to the slot for `idx`
+    ///
+    /// SAFETY: The block holding `idx` must be allocated
+    pub(crate) unsafe fn read_raw(&self, idx: usize) -> *mut Inner<T> {
+        let (outer_idx, inner_idx) = self.split_idx(idx);
+        unsafe { self.block(outer_idx).add(inner_idx) }
+    }
+}
+
+impl<T, S: Storage> Drop for RawStele<T, S> {
+    fn drop(&mut self) {
+        for idx in 0..self.inners.len() {
+            let ptr = crate::sync::load_mut(&mut self.inners[idx]);
+            //Blocks that were never allocated or were already freed are null, and adopted ones are freed along with their buffer
+            if !ptr.is_null() && !self.is_adopted(idx, ptr) {
+                //SAFETY: Every published block was allocated from this storage with this length and alignment,
+                //and by the safety obligations of the code built on top its elements have been dropped
+                unsafe {
+                    crate::mem::dealloc_inner(
+                        &self.storage,
+                        ptr,
+                        self.block_len(idx),
+                        self.block_align,
+                    );
+                };
+            }
+        }
+        if let Some(Adopted { ptr, cap, .. }) = self.adopted.take() {
+            //SAFETY: By the safety contract of `adopt` the storage can free the buffer as a block of `cap` elements
+            unsafe { crate::mem::dealloc_inner(&self.storage, ptr, cap, self.block_align) };
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use alloc::boxed::Box;
+
+use crate::{
+    local::{LocalReadHandle, LocalStele, LocalWriteHandle},
+    mem::Storage,
+    CachedReadHandle, ReadHandle, StaticReadHandle, StaticWriteHandle, Stele, WriteHandle,
+};
+
+/// Reads the elements of a [`Stele`] through whichever handle it is given
+///
+/// Every handle keeps its inherent methods, this only lets code accept any of them, including as a `&dyn SteleReader<T>`.
+///
+/// ```
+/// use stele::{Stele, SteleReader};
+///
+/// fn total(reader: &dyn SteleReader<u32>) -> u32 {
+///     reader.iter().sum()
+/// }
+///
+/// let (wh, rh) = Stele::new();
+/// (1..=4).for_each(|n| wh.push(n));
+/// assert_eq!(total(&wh), 10);
+/// assert_eq!(total(&rh), 10);
+/// ```
+pub trait SteleReader<T> {
+    /// Returns the number of elements, which may include reservations that have not been filled yet
+    ///
+    /// Note: unless this is the writer itself, this is an optimistic operation and the length may be changing under you
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no elements
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element at `idx`, or [`None`] if it is out of bounds or has not been filled yet
+    fn try_read(&self, idx: usize) -> Option<&T>;
+
+    /// Returns the element at `idx`
+    ///
+    /// # Panic
+    ///
+    /// Panics if the index is out of bounds, in debug for the handles that only check it there
+    fn read(&self, idx: usize) -> &T;
+
+    /// Returns an iterator over the elements by reference, up to the first one that has not been filled yet
+    fn iter(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new((0..self.len()).map_while(move |idx| self.try_read(idx)))
+    }
+}
+
+/// Copies the elements out of any [`SteleReader`] when they are [`Copy`]
+///
+/// This is implemented for every [`SteleReader`], so it only needs to be imported
+pub trait SteleReaderCopied<T: Copy>: SteleReader<T> {
+    /// Returns a copy of the element at `idx`
+    ///
+    /// # Panic
+    ///
+    /// Panics like [`SteleReader::read`]
+    fn get(&self, idx: usize) -> T {
+        *self.read(idx)
+    }
+}
+
+impl<T: Copy, R: SteleReader<T> + ?Sized> SteleReaderCopied<T> for R {}
+
+impl<T, S: Storage> SteleReader<T> for Stele<T, S> {
+    fn len(&self) -> usize {
+        Stele::len(self)
+    }
+
+    fn try_read(&self, idx: usize) -> Option<&T> {
+        Stele::read(self, idx)
+    }
+
+    fn read(&self, idx: usize) -> &T {
+        self.read_at(idx)
+    }
+}
+
+impl<T, S: Storage> SteleReader<T> for ReadHandle<T, S> {
+    fn len(&self) -> usize {
+        ReadHandle::len(self)
+    }
+
+    fn try_read(&self, idx: usize) -> Option<&T> {
+        ReadHandle::try_read(self, idx)
+    }
+
+    fn read(&self, idx: usize) -> &T {
+        ReadHandle::read(self, idx)
+    }
+}
+
+impl<T, S: Storage> SteleReader<T> for CachedReadHandle<T, S> {
+    fn len(&self) -> usize {
+        CachedReadHandle::len(self)
+    }
+
+    fn try_read(&self, idx: usize) -> Option<&T> {
+        CachedReadHandle::try_read(self, idx)
+    }
+
+    fn read(&self, idx: usize) -> &T {
+        CachedReadHandle::read(self, idx)
+    }
+}
+
+impl<T, S: Storage> SteleReader<T> for WriteHandle<T, S> {
+    fn len(&self) -> usize {
+        WriteHandle::len(self)
+    }
+
+    fn try_read(&self, idx: usize) -> Option<&T> {
+        WriteHandle::try_read(self, idx)
+    }
+
+    fn read(&self, idx: usize) -> &T {
+        WriteHandle::read(self, idx)
+    }
+}
+
+impl<T, S: Storage> SteleReader<T> for StaticReadHandle<'_, T, S> {
+    fn len(&self) -> usize {
+        StaticReadHandle::len(self)
+    }
+
+    fn try_read(&self, idx: usize) -> Option<&T> {
+        StaticReadHandle::try_read(self, idx)
+    }
+
+    fn read(&self, idx: usize) -> &T {
+        StaticReadHandle::read(self, idx)
+    }
+}
+
+impl<T, S: Storage> SteleReader<T> for StaticWriteHandle<'_, T, S> {
+    fn len(&self) -> usize {
+        StaticWriteHandle::len(self)
+    }
+
+    fn try_read(&self, idx: usize) -> Option<&T> {
+        StaticWriteHandle::try_read(self, idx)
+    }
+
+    fn read(&self, idx: usize) -> &T {
+        StaticWriteHandle::read(self, idx)
+    }
+}
+
+#[cfg(feature = "critical-section")]
+impl<T, S: Storage> SteleReader<T> for crate::IsrWriteHandle<T, S> {
+    fn len(&self) -> usize {
+        crate::IsrWriteHandle::len(self)
+    }
+
+    fn try_read(&self, idx: usize) -> Option<&T> {
+        crate::IsrWriteHandle::try_read(self, idx)
+    }
+
+    fn read(&self, idx: usize) -> &T {
+        crate::IsrWriteHandle::read(self, idx)
+    }
+}
+
+impl<T, S: Storage> SteleReader<T> for LocalStele<T, S> {
+    fn len(&self) -> usize {
+        LocalStele::len(self)
+    }
+
+    fn try_read(&self, idx: usize) -> Option<&T> {
+        LocalStele::read(self, idx)
+    }
+
+    fn read(&self, idx: usize) -> &T {
+        self.read_at(idx)
+    }
+}
+
+impl<T, S: Storage> SteleReader<T> for LocalReadHandle<T, S> {
+    fn len(&self) -> usize {
+        LocalReadHandle::len(self)
+    }
+
+    fn try_read(&self, idx: usize) -> Option<&T> {
+        LocalReadHandle::try_read(self, idx)
+    }
+
+    fn read(&self, idx: usize) -> &T {
+        LocalReadHandle::read(self, idx)
+    }
+}
+
+impl<T, S: Storage> SteleReader<T> for LocalWriteHandle<T, S> {
+    fn len(&self) -> usize {
+        LocalWriteHandle::len(self)
+    }
+
+    fn try_read(&self, idx: usize) -> Option<&T> {
+        LocalWriteHandle::try_read(self, idx)
+    }
+
+    fn read(&self, idx: usize) -> &T {
+        LocalWriteHandle::read(self, idx)
+    }
+}
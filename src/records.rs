@@ -0,0 +1,249 @@
+use alloc::{borrow::Cow, vec::Vec};
+use core::convert::TryFrom;
+
+use crate::{
+    mem::{DefaultStorage, Storage},
+    ReadHandle, SteleError, WriteHandle,
+};
+
+//A usize needs at most this many bytes as an LEB128 varint, 7 bits per byte
+const MAX_PREFIX_LEN: usize = (usize::BITS as usize).div_ceil(7);
+
+/// The position of a record in the log, which is the offset of its first byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RecordId(usize);
+
+impl RecordId {
+    /// Returns the offset of the first byte of the record, including its length prefix
+    #[must_use]
+    pub fn offset(self) -> usize {
+        self.0
+    }
+}
+
+/// Appends length-prefixed records to a [`Stele<u8>`](crate::Stele)
+///
+/// Every record is an LEB128 varint holding the payload length followed by the payload, and the whole record is published
+/// with a single length store, so readers never see part of a record
+#[derive(Debug)]
+pub struct RecordWriter<S: Storage = DefaultStorage> {
+    writer: WriteHandle<u8, S>,
+}
+
+impl<S: Storage> RecordWriter<S> {
+    /// Creates a [`RecordWriter`] that appends after any bytes already in the [`Stele`](crate::Stele)
+    #[must_use]
+    pub fn new(writer: WriteHandle<u8, S>) -> Self {
+        Self { writer }
+    }
+
+    /// Appends `record` and returns its [`RecordId`]
+    ///
+    /// # Panics
+    ///
+    /// Panics without appending anything if the [`Stele`](crate::Stele) is [bounded](crate::Stele::bounded) and the record does not fit
+    #[must_use]
+    pub fn append(&self, record: &[u8]) -> RecordId {
+        let mut prefix = [0; MAX_PREFIX_LEN];
+        let mut prefix_len = 0;
+        let mut len = record.len();
+        loop {
+            let byte = u8::try_from(len & 0x7F).expect("Masked to 7 bits");
+            len >>= 7;
+            if len == 0 {
+                prefix[prefix_len] = byte;
+                prefix_len += 1;
+                break;
+            }
+            prefix[prefix_len] = byte | 0x80;
+            prefix_len += 1;
+        }
+        let id = RecordId(self.writer.len());
+        //SAFETY: RecordWriter owns the WriteHandle, which is neither Sync nor Clone, so this is the only writer
+        unsafe {
+            self.writer
+                .handle
+                .extend_from_slices(&[&prefix[..prefix_len], record]);
+        }
+        id
+    }
+
+    /// Creates a [`RecordReader`] over the same [`Stele`](crate::Stele)
+    #[must_use]
+    pub fn new_reader(&self) -> RecordReader<S> {
+        RecordReader::new(self.writer.new_read_handle())
+    }
+
+    /// Returns the number of bytes in the log, including the length prefixes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.writer.len()
+    }
+
+    /// Returns whether nothing has been appended yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.writer.is_empty()
+    }
+
+    /// Returns the [`WriteHandle`] the records are appended through
+    #[must_use]
+    pub fn into_inner(self) -> WriteHandle<u8, S> {
+        self.writer
+    }
+}
+
+/// Reads the length-prefixed records appended by a [`RecordWriter`]
+///
+/// Bytes that do not form a complete record, such as those left behind by pushing to the [`Stele`](crate::Stele) directly,
+/// are reported as [`SteleError::MalformedRecord`] instead of being misparsed
+#[derive(Debug, Clone)]
+pub struct RecordReader<S: Storage = DefaultStorage> {
+    handle: ReadHandle<u8, S>,
+}
+
+impl<S: Storage> RecordReader<S> {
+    /// Creates a [`RecordReader`] whose first record starts at the first byte of the [`Stele`](crate::Stele)
+    #[must_use]
+    pub fn new(handle: ReadHandle<u8, S>) -> Self {
+        Self { handle }
+    }
+
+    /// Returns the payload of the record at `id`
+    ///
+    /// The payload is borrowed if it lies within a single block, and copied into a [`Vec`] if it straddles a block boundary.
+    /// `id` must have been returned by [`RecordWriter::append`] for this log, any other offset is read as if a record started there
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SteleError::OutOfBounds`] if `id` is not below the length of the log, and [`SteleError::MalformedRecord`]
+    /// if the bytes at `id` do not hold a complete record
+    pub fn get(&self, id: RecordId) -> Result<Cow<'_, [u8]>, SteleError> {
+        let end = self.handle.initialized_len();
+        if id.0 >= end {
+            return Err(SteleError::OutOfBounds {
+                index: id.0,
+                len: end,
+            });
+        }
+        self.parse(id.0, end).map(|(payload, _)| payload)
+    }
+
+    /// Returns an iterator over every record, starting with the first one
+    ///
+    /// The iterator ends once it has caught up with the writer, but yields records appended later if it is polled again
+    #[must_use]
+    pub fn iter(&self) -> Records<'_, S> {
+        Records {
+            reader: self,
+            pos: 0,
+        }
+    }
+
+    /// Returns an iterator that skips every record appended so far and only yields the ones appended after this call
+    #[must_use]
+    pub fn tail(&self) -> Records<'_, S> {
+        Records {
+            reader: self,
+            pos: self.handle.initialized_len(),
+        }
+    }
+
+    /// Returns the number of bytes in the log, including the length prefixes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handle.initialized_len()
+    }
+
+    /// Returns whether the log is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Parses the record starting at `offset`, which must be below `end`, returning its payload and the offset of the next record
+    fn parse(&self, offset: usize, end: usize) -> Result<(Cow<'_, [u8]>, usize), SteleError> {
+        let malformed = SteleError::MalformedRecord { offset };
+        let mut len = 0_usize;
+        let mut pos = offset;
+        loop {
+            if pos == end || pos - offset == MAX_PREFIX_LEN {
+                return Err(malformed);
+            }
+            let byte = self.handle.get(pos);
+            let shift = 7 * (pos - offset);
+            let bits = usize::from(byte & 0x7F);
+            //Bits shifted past the top of a usize would be lost, so the prefix cannot have come from `append`
+            if (bits.leading_zeros() as usize) < shift {
+                return Err(malformed);
+            }
+            len |= bits << shift;
+            pos += 1;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        let payload_end = pos
+            .checked_add(len)
+            .filter(|&e| e <= end)
+            .ok_or(malformed)?;
+        if pos == payload_end {
+            return Ok((Cow::Borrowed(&[]), payload_end));
+        }
+        let stele = &*self.handle.handle;
+        //SAFETY: Everything below the initialized length is initialized, and `pos` is below `payload_end`
+        let first = unsafe { stele.block_slice(pos, payload_end) };
+        if first.len() == len {
+            return Ok((Cow::Borrowed(first), payload_end));
+        }
+        let mut payload = Vec::with_capacity(len);
+        while pos < payload_end {
+            //SAFETY: As above
+            let part = unsafe { stele.block_slice(pos, payload_end) };
+            payload.extend_from_slice(part);
+            pos += part.len();
+        }
+        Ok((Cow::Owned(payload), payload_end))
+    }
+}
+
+impl<'a, S: Storage> IntoIterator for &'a RecordReader<S> {
+    type Item = Result<(RecordId, Cow<'a, [u8]>), SteleError>;
+    type IntoIter = Records<'a, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the records of a [`RecordReader`], returned by [`iter`](RecordReader::iter) and [`tail`](RecordReader::tail)
+///
+/// A malformed record is yielded as an error, after which the iterator ends for good,
+/// as there is no way to tell where the next record would start
+#[derive(Debug)]
+pub struct Records<'a, S: Storage = DefaultStorage> {
+    reader: &'a RecordReader<S>,
+    pos: usize,
+}
+
+impl<'a, S: Storage> Iterator for Records<'a, S> {
+    type Item = Result<(RecordId, Cow<'a, [u8]>), SteleError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let end = self.reader.handle.initialized_len();
+        if self.pos >= end {
+            return None;
+        }
+        let id = RecordId(self.pos);
+        match self.reader.parse(self.pos, end) {
+            Ok((payload, next)) => {
+                self.pos = next;
+                Some(Ok((id, payload)))
+            }
+            Err(err) => {
+                self.pos = usize::MAX;
+                Some(Err(err))
+            }
+        }
+    }
+}
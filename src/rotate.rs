@@ -0,0 +1,343 @@
+use alloc::collections::VecDeque;
+use std::sync::{PoisonError, RwLock};
+
+use crate::{
+    mem::{DefaultStorage, Storage},
+    sync::{ord, Arc, AtomicUsize},
+    ReadHandle, Stele, WriteHandle,
+};
+
+/// One [`Stele`] of a [`RotatingLog`] and the global index of its first element
+#[derive(Debug)]
+struct Segment<T, S: Storage> {
+    start: usize,
+    handle: ReadHandle<T, S>,
+}
+
+impl<T, S: Storage> Segment<T, S> {
+    /// The global index one past the last element pushed so far
+    fn end(&self) -> usize {
+        self.start + self.handle.len()
+    }
+}
+
+impl<T, S: Storage> Clone for Segment<T, S> {
+    fn clone(&self) -> Self {
+        Segment {
+            start: self.start,
+            handle: self.handle.clone(),
+        }
+    }
+}
+
+/// The segments every [`RotatingReader`] picks up when it refreshes
+#[derive(Debug)]
+struct Shared<T, S: Storage> {
+    segments: RwLock<VecDeque<Segment<T, S>>>,
+    //Bumped every time a segment is added or retired, so that readers only take the lock when something changed
+    generation: AtomicUsize,
+}
+
+/// Finds the segment holding the global index `idx` and reads it
+fn read_segments<T, S: Storage>(segments: &VecDeque<Segment<T, S>>, idx: usize) -> Option<&T> {
+    let segment = segments.partition_point(|segment| segment.start <= idx);
+    let segment = segments.get(segment.checked_sub(1)?)?;
+    segment.handle.try_read(idx - segment.start)
+}
+
+/// The number of elements from the start of the oldest segment to the end of the newest one
+fn segments_len<T, S: Storage>(segments: &VecDeque<Segment<T, S>>) -> usize {
+    match (segments.front(), segments.back()) {
+        (Some(first), Some(last)) => last.end() - first.start,
+        _ => 0,
+    }
+}
+
+/// An append-only log split over a series of [`Stele`]s, so that the oldest elements can be freed while it keeps growing
+///
+/// Elements are pushed on to the current segment until it holds the configured number of elements, at which point it is
+/// sealed and a new one is started. Every element keeps the global index it was pushed at for as long as it is retained,
+/// and [`retire`](RotatingLog::retire) drops the oldest sealed segments. A retired segment is freed once every
+/// [`RotatingReader`] that still holds it has [refreshed](RotatingReader::refresh) or been dropped.
+#[derive(Debug)]
+pub struct RotatingLog<T, S: Storage = DefaultStorage> {
+    writer: WriteHandle<T, S>,
+    //The retained segments, oldest first, ending with the current one
+    segments: VecDeque<Segment<T, S>>,
+    shared: Arc<Shared<T, S>>,
+    segment_len: usize,
+    storage: S,
+}
+
+impl<T> RotatingLog<T> {
+    /// Creates a log whose segments each hold `segment_len` elements
+    ///
+    /// # Panics
+    ///
+    /// Panics if `segment_len` is 0
+    #[must_use]
+    pub fn new(segment_len: usize) -> Self {
+        Self::new_in(segment_len, DefaultStorage::default())
+    }
+}
+
+impl<T, S: Storage + Clone> RotatingLog<T, S> {
+    /// Creates a log whose segments each hold `segment_len` elements and allocate from their own clone of `storage`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `segment_len` is 0
+    pub fn new_in(segment_len: usize, storage: S) -> Self {
+        assert!(segment_len > 0, "Segments must hold at least one element");
+        let (writer, handle) = Stele::bounded_in(segment_len, storage.clone());
+        let segments = VecDeque::from([Segment { start: 0, handle }]);
+        RotatingLog {
+            writer,
+            shared: Arc::new(Shared {
+                segments: RwLock::new(segments.clone()),
+                generation: AtomicUsize::new(0),
+            }),
+            segments,
+            segment_len,
+            storage,
+        }
+    }
+
+    /// Pushes `val` on to the current segment, first starting a new one if it is full, and returns its global index
+    pub fn push(&mut self, val: T) -> usize {
+        if self.writer.is_full() {
+            self.rotate();
+        }
+        let idx = self.current().end();
+        self.writer.push(val);
+        idx
+    }
+
+    /// Seals the current segment and starts a new one, even if the current one is not full yet
+    ///
+    /// Does nothing if the current segment is empty
+    pub fn rotate(&mut self) {
+        let start = self.current().end();
+        if start == self.current().start {
+            return;
+        }
+        let (writer, handle) = Stele::bounded_in(self.segment_len, self.storage.clone());
+        //The sealed segment can never grow again, so none of the blocks past its end are needed
+        drop(core::mem::replace(&mut self.writer, writer).shrink_unused());
+        let segment = Segment { start, handle };
+        self.segments.push_back(segment.clone());
+        self.publish(|segments| segments.push_back(segment));
+    }
+}
+
+impl<T, S: Storage> RotatingLog<T, S> {
+    /// Drops the oldest `n_segments` sealed segments, or every sealed segment if there are fewer, and returns how many were dropped
+    ///
+    /// The current segment is never retired. Retired elements can no longer be read through the log or through readers
+    /// created or refreshed afterwards, and are freed once the last reader holding them refreshes or is dropped
+    pub fn retire(&mut self, n_segments: usize) -> usize {
+        let n_segments = n_segments.min(self.segments.len() - 1);
+        if n_segments > 0 {
+            self.segments.drain(..n_segments);
+            self.publish(|segments| drop(segments.drain(..n_segments)));
+        }
+        n_segments
+    }
+
+    /// Returns a [`RotatingReader`] over the segments that are retained now
+    #[must_use]
+    pub fn reader(&self) -> RotatingReader<T, S> {
+        RotatingReader {
+            shared: Arc::clone(&self.shared),
+            segments: self.segments.clone(),
+            generation: self.shared.generation.load(ord::ACQ),
+        }
+    }
+
+    /// Attempts to read the element at the global index `idx`, returning [`None`] if it was retired or not pushed yet
+    #[must_use]
+    pub fn try_read(&self, idx: usize) -> Option<&T> {
+        read_segments(&self.segments, idx)
+    }
+
+    /// Returns the global index of the oldest retained element
+    #[must_use]
+    pub fn first_index(&self) -> usize {
+        self.segments.front().map_or(0, |segment| segment.start)
+    }
+
+    /// Returns the number of retained elements, which have the global indices from [`first_index`](RotatingLog::first_index) on
+    #[must_use]
+    pub fn len(&self) -> usize {
+        segments_len(&self.segments)
+    }
+
+    /// Returns `true` if no elements are retained
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of retained segments, including the current one
+    #[must_use]
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns an iterator over every retained element, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.segments
+            .iter()
+            .flat_map(|segment| segment.handle.iter())
+    }
+
+    fn current(&self) -> &Segment<T, S> {
+        self.segments
+            .back()
+            .expect("The current segment is never retired")
+    }
+
+    /// Applies `change` to the segments readers pick up and lets them know there is something to pick up
+    fn publish(&self, change: impl FnOnce(&mut VecDeque<Segment<T, S>>)) {
+        change(
+            &mut self
+                .shared
+                .segments
+                .write()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        self.shared.generation.fetch_add(1, ord::REL);
+    }
+}
+
+/// A reader of a [`RotatingLog`], which keeps the segments it has seen alive until it [refreshes](RotatingReader::refresh)
+///
+/// Reads only look at the segments picked up by the last refresh. The newest of those keeps growing until the log rotates,
+/// so a reader only has to refresh to see elements pushed after a rotation, or to let go of retired segments
+#[derive(Debug)]
+pub struct RotatingReader<T, S: Storage = DefaultStorage> {
+    shared: Arc<Shared<T, S>>,
+    segments: VecDeque<Segment<T, S>>,
+    generation: usize,
+}
+
+impl<T, S: Storage> RotatingReader<T, S> {
+    /// Picks up the segments the log has started and drops the ones it has retired since the last refresh,
+    /// returning whether anything changed
+    pub fn refresh(&mut self) -> bool {
+        let generation = self.shared.generation.load(ord::ACQ);
+        if generation == self.generation {
+            return false;
+        }
+        self.segments.clone_from(
+            &self
+                .shared
+                .segments
+                .read()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        self.generation = generation;
+        true
+    }
+
+    /// Attempts to read the element at the global index `idx`, returning [`None`] if it is not in a segment this reader holds
+    #[must_use]
+    pub fn try_read(&self, idx: usize) -> Option<&T> {
+        read_segments(&self.segments, idx)
+    }
+
+    /// Returns the global index of the oldest element this reader holds
+    #[must_use]
+    pub fn first_index(&self) -> usize {
+        self.segments.front().map_or(0, |segment| segment.start)
+    }
+
+    /// Returns the number of elements this reader holds, which have the global indices from
+    /// [`first_index`](RotatingReader::first_index) on
+    #[must_use]
+    pub fn len(&self) -> usize {
+        segments_len(&self.segments)
+    }
+
+    /// Returns `true` if this reader holds no elements
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over every element this reader holds, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.segments
+            .iter()
+            .flat_map(|segment| segment.handle.iter())
+    }
+
+    /// Returns a [`Tail`] that starts reading at the global index `idx`
+    #[must_use]
+    pub fn tail_from(self, idx: usize) -> Tail<T, S> {
+        Tail {
+            reader: self,
+            pos: idx,
+            skipped: 0,
+        }
+    }
+}
+
+impl<T, S: Storage> Clone for RotatingReader<T, S> {
+    fn clone(&self) -> Self {
+        RotatingReader {
+            shared: Arc::clone(&self.shared),
+            segments: self.segments.clone(),
+            generation: self.generation,
+        }
+    }
+}
+
+/// A cursor that reads a [`RotatingLog`] in order, following it from segment to segment as it rotates
+///
+/// The position is a global index, so it stays valid across rotations. If the log retires the element at the
+/// position before the cursor gets to it, the cursor moves on to the oldest element that is still retained
+#[derive(Debug)]
+pub struct Tail<T, S: Storage = DefaultStorage> {
+    reader: RotatingReader<T, S>,
+    pos: usize,
+    skipped: usize,
+}
+
+impl<T, S: Storage> Tail<T, S> {
+    /// Returns the next element, or [`None`] if it has not been pushed yet
+    ///
+    /// This only refreshes the underlying reader when the segments it holds have nothing to read at the position
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&T> {
+        if self.reader.try_read(self.pos).is_none() {
+            self.reader.refresh();
+            let first = self.reader.first_index();
+            if self.pos < first {
+                self.skipped += first - self.pos;
+                self.pos = first;
+            }
+        }
+        let val = self.reader.try_read(self.pos)?;
+        self.pos += 1;
+        Some(val)
+    }
+
+    /// Returns the global index of the next element
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns how many elements were retired before the cursor got to them
+    #[must_use]
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Returns the underlying [`RotatingReader`]
+    #[must_use]
+    pub fn into_reader(self) -> RotatingReader<T, S> {
+        self.reader
+    }
+}
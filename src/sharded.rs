@@ -0,0 +1,157 @@
+use alloc::vec::Vec;
+use core::iter::Peekable;
+
+use crate::{
+    append::iter::RefIterator,
+    mem::{DefaultStorage, Storage},
+    ReadHandle, Stele, WriteHandle,
+};
+
+/// A set of independent [`Stele`]s, one per producer, read through a single [`ShardedReadHandle`]
+///
+/// Each shard has its own [`WriteHandle`], so every producer thread can push without any locking,
+/// while readers see the shards as one collection laid out shard by shard.
+#[derive(Debug)]
+pub struct ShardedStele<T, S: Storage = DefaultStorage> {
+    shards: Vec<Stele<T, S>>,
+}
+
+impl<T> ShardedStele<T> {
+    #[allow(clippy::new_ret_no_self)]
+    #[must_use]
+    /// Creates `n_shards` new Steles and returns a [`WriteHandle`] for each of them along with a [`ShardedReadHandle`] over all of them
+    pub fn new(n_shards: usize) -> (Vec<WriteHandle<T>>, ShardedReadHandle<T>) {
+        Self::new_in(n_shards, &DefaultStorage::default())
+    }
+}
+
+impl<T, S: Storage + Clone> ShardedStele<T, S> {
+    /// Creates `n_shards` new Steles that each allocate from their own clone of `storage` and returns a [`WriteHandle`]
+    /// for each of them along with a [`ShardedReadHandle`] over all of them
+    pub fn new_in(
+        n_shards: usize,
+        storage: &S,
+    ) -> (Vec<WriteHandle<T, S>>, ShardedReadHandle<T, S>) {
+        Self {
+            shards: (0..n_shards)
+                .map(|_| Stele::from_iter_in(core::iter::empty(), storage.clone()))
+                .collect(),
+        }
+        .to_handles()
+    }
+}
+
+impl<T, S: Storage> ShardedStele<T, S> {
+    /// Creates a [`ShardedStele`] from existing Steles, each of which becomes one shard
+    #[must_use]
+    pub fn from_shards(shards: Vec<Stele<T, S>>) -> Self {
+        Self { shards }
+    }
+
+    /// Splits every shard into its handles, returning the [`WriteHandle`]s in shard order and a [`ShardedReadHandle`] over all of them
+    pub fn to_handles(self) -> (Vec<WriteHandle<T, S>>, ShardedReadHandle<T, S>) {
+        let (writers, readers) = self.shards.into_iter().map(Stele::to_handles).unzip();
+        (writers, ShardedReadHandle { shards: readers })
+    }
+}
+
+/// The reader for a [`ShardedStele`]
+///
+/// Global indices are interleaved across shards: index `i` is element `i / n` of shard `i % n`, where `n` is the number of shards.
+/// This mapping never changes as shards grow, but since shards grow independently an index below [`len`](ShardedReadHandle::len)
+/// may not have been written yet.
+#[derive(Debug)]
+pub struct ShardedReadHandle<T, S: Storage = DefaultStorage> {
+    shards: Vec<ReadHandle<T, S>>,
+}
+
+impl<T, S: Storage> ShardedReadHandle<T, S> {
+    /// Returns the number of shards
+    #[must_use]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the [`ReadHandle`] for the given shard
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shard` is not less than [`shard_count`](ShardedReadHandle::shard_count)
+    #[must_use]
+    pub fn shard(&self, shard: usize) -> &ReadHandle<T, S> {
+        &self.shards[shard]
+    }
+
+    /// Attempts to read the value at the given global index, see [`ShardedReadHandle`] for how global indices map to shards
+    #[must_use]
+    pub fn try_read(&self, idx: usize) -> Option<&T> {
+        let n = self.shards.len();
+        if n == 0 {
+            return None;
+        }
+        self.shards[idx % n].try_read(idx / n)
+    }
+
+    /// Returns the sum of the current lengths of every shard
+    ///
+    /// Note: this is an optimistic operation and the length may be changing under you
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(ReadHandle::len).sum()
+    }
+
+    /// Returns whether every shard is empty
+    ///
+    /// Note: if this returns `false` it *cannot* return `true` in the future
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(ReadHandle::is_empty)
+    }
+
+    /// Creates an iterator that yields the elements of every shard, shard by shard
+    ///
+    /// Each shard's length is captured when the iterator reaches it
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.shards.iter().flat_map(ReadHandle::iter)
+    }
+}
+
+impl<T, S: Storage> Clone for ShardedReadHandle<T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            shards: self.shards.clone(),
+        }
+    }
+}
+
+impl<T, S: Storage> ShardedReadHandle<(u64, T), S> {
+    /// Creates an iterator that merges the shards by the sequence number each element was pushed with
+    ///
+    /// Every shard must hold strictly increasing sequence numbers, such as those taken from a shared
+    /// [`AtomicU64`](core::sync::atomic::AtomicU64) just before pushing. Each shard's length is captured when the iterator is created
+    #[must_use]
+    pub fn iter_sequenced(&self) -> SequencedIterator<'_, T, S> {
+        SequencedIterator {
+            shards: self.shards.iter().map(|s| s.iter().peekable()).collect(),
+        }
+    }
+}
+
+///An iterator that merges the shards of a [`ShardedReadHandle`] by sequence number
+#[derive(Debug)]
+pub struct SequencedIterator<'rh, T, S: Storage = DefaultStorage> {
+    shards: Vec<Peekable<RefIterator<'rh, (u64, T), S>>>,
+}
+
+impl<'rh, T, S: Storage> Iterator for SequencedIterator<'rh, T, S> {
+    type Item = &'rh (u64, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, shard) = self
+            .shards
+            .iter_mut()
+            .filter_map(|shard| Some((shard.peek()?.0, shard)))
+            .min_by_key(|&(seq, _)| seq)?;
+        shard.next()
+    }
+}
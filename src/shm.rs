@@ -0,0 +1,333 @@
+use core::{
+    convert::TryFrom,
+    fmt,
+    marker::PhantomData,
+    mem::{align_of, size_of},
+    sync::atomic::AtomicU64,
+};
+
+use bytemuck::Pod;
+use memmap2::{Mmap, MmapMut};
+
+use crate::{max_len, split_idx, sync::ord, Full};
+
+const MAGIC: [u8; 8] = *b"STELESHM";
+
+/// The header at the start of the mapping, which is all the two processes share besides the blocks themselves
+///
+/// Every field is 8 bytes wide so that the layout is the same for 32 and 64 bit processes
+#[repr(C)]
+struct Header {
+    magic: [u8; 8],
+    element_size: u64,
+    element_align: u64,
+    //The size of the mapping in bytes, which every block lies within
+    capacity: u64,
+    //Only ever written by the writer, with the same release/acquire contract as a Stele
+    len: AtomicU64,
+    //The offset from the start of the mapping to the first byte no block uses yet, only used by the writer
+    next_free: AtomicU64,
+    //The offset of every block from the start of the mapping, or 0 if it has not been allocated.
+    //The header is at offset 0, so no block can start there
+    blocks: [AtomicU64; 32],
+}
+
+fn to_u64(n: usize) -> u64 {
+    u64::try_from(n).expect("usize fits in a u64")
+}
+
+//Lengths and offsets the header could not have been given by a writer in this process saturate, which every bounds check then rejects
+fn to_usize(n: u64) -> usize {
+    usize::try_from(n).unwrap_or(usize::MAX)
+}
+
+/// The error returned when a mapping cannot be used for a [`ShmStele`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShmError {
+    /// The mapping is too small to hold the header
+    TooSmall {
+        /// The size of the mapping
+        len: usize,
+        /// The size of the header
+        needed: usize,
+    },
+    /// The mapping does not start with the magic bytes written by [`ShmStele::create`]
+    BadMagic,
+    /// The elements in the mapping have a different size or alignment than the type it is opened as
+    ElementMismatch {
+        /// The size of the elements in the mapping
+        size: u64,
+        /// The alignment of the elements in the mapping
+        align: u64,
+    },
+    /// The capacity in the header is larger than the mapping, so the mapping was cut short
+    CapacityMismatch {
+        /// The capacity in the header
+        capacity: u64,
+        /// The size of the mapping
+        len: usize,
+    },
+}
+
+impl fmt::Display for ShmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShmError::TooSmall { len, needed } => write!(
+                f,
+                "the mapping holds {len} bytes but the header alone needs {needed}"
+            ),
+            ShmError::BadMagic => f.write_str("the mapping does not hold a shared Stele"),
+            ShmError::ElementMismatch { size, align } => write!(
+                f,
+                "the mapping holds elements of {size} bytes aligned to {align}, which does not match the requested type"
+            ),
+            ShmError::CapacityMismatch { capacity, len } => write!(
+                f,
+                "the header claims {capacity} bytes but the mapping only holds {len}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ShmError {}
+
+/// A [`Stele`](crate::Stele) that lives entirely inside one shared memory mapping, so that another process can read it
+/// through a [`ShmReadHandle`]
+///
+/// Instead of pointers, the block table in the mapping holds the offset of every block from the start of the mapping,
+/// as the mapping can be at a different address in every process. Blocks are bump allocated inside the mapping and never freed,
+/// so the mapping has to be large enough for every block up front.
+///
+/// Pushing writes the element and then publishes the new length with a release store, and readers load it with an acquire load,
+/// which is the only synchronization between the processes. This is the only writer for the mapping, so it is `Send` but `!Sync`.
+#[derive(Debug)]
+pub struct ShmStele<T: Pod> {
+    mapping: MmapMut,
+    _unsync: PhantomData<*mut T>,
+}
+
+//SAFETY: The mapping is owned and `T: Pod` is plain data, so only `Sync` needs to be ruled out
+unsafe impl<T: Pod> Send for ShmStele<T> {}
+
+impl<T: Pod> ShmStele<T> {
+    /// Sets up an empty [`ShmStele`] in `mapping`, overwriting whatever it held before
+    ///
+    /// The magic bytes are written last, so a reader that opens the mapping part way through sees [`ShmError::BadMagic`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ShmError::TooSmall`] if the mapping cannot even hold the header
+    pub fn create(mut mapping: MmapMut) -> Result<Self, ShmError> {
+        let needed = size_of::<Header>();
+        if mapping.len() < needed {
+            return Err(ShmError::TooSmall {
+                len: mapping.len(),
+                needed,
+            });
+        }
+        mapping[..needed].fill(0);
+        //SAFETY: The mapping is page aligned, holds at least a header and was just zeroed, which is a valid header
+        #[allow(clippy::cast_ptr_alignment)]
+        let header = unsafe { &mut *mapping.as_mut_ptr().cast::<Header>() };
+        header.element_size = to_u64(size_of::<T>());
+        header.element_align = to_u64(align_of::<T>());
+        header.capacity = to_u64(mapping.len());
+        header.next_free = AtomicU64::new(to_u64(needed));
+        header.magic = MAGIC;
+        Ok(Self {
+            mapping,
+            _unsync: PhantomData,
+        })
+    }
+
+    //Mappings are page aligned, which is more than the header needs
+    #[allow(clippy::cast_ptr_alignment)]
+    fn header(&self) -> &Header {
+        //SAFETY: `create` checked that the mapping holds a header and set it up
+        unsafe { &*self.mapping.as_ptr().cast::<Header>() }
+    }
+
+    /// Pushes a new item on to the end of the [`ShmStele`], allocating its block inside the mapping if necessary
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mapping has no room left for the block, see [`try_push`](ShmStele::try_push)
+    pub fn push(&mut self, val: T) {
+        assert!(self.try_push(val).is_ok(), "Pushed to a full ShmStele");
+    }
+
+    /// Pushes a new item on to the end of the [`ShmStele`] unless its block does not fit in the mapping
+    ///
+    /// # Errors
+    ///
+    /// Returns `val` wrapped in [`Full`] if the block it belongs in is not allocated and the mapping has no room left for it
+    pub fn try_push(&mut self, val: T) -> Result<(), Full<T>> {
+        let header = self.header();
+        let idx = to_usize(header.len.load(ord::RLX));
+        let (outer_idx, inner_idx) = split_idx(idx);
+        let mut offset = header.blocks[outer_idx].load(ord::RLX);
+        if offset == 0 {
+            let start = header.next_free.load(ord::RLX);
+            let align = to_u64(align_of::<T>());
+            let start = start.div_ceil(align) * align;
+            let end = to_u64(max_len(outer_idx) * size_of::<T>()) + start;
+            if end > header.capacity {
+                return Err(Full(val));
+            }
+            header.next_free.store(end, ord::RLX);
+            //Published along with the element by the release store of the length below
+            header.blocks[outer_idx].store(start, ord::RLX);
+            offset = start;
+        }
+        let offset = to_usize(offset) + inner_idx * size_of::<T>();
+        //SAFETY: The block lies within the mapping and is aligned for `T`, and the slot is past the end,
+        //so no reader looks at it until the length is published
+        unsafe { self.mapping.as_mut_ptr().add(offset).cast::<T>().write(val) };
+        self.header().len.store(to_u64(idx + 1), ord::REL);
+        Ok(())
+    }
+
+    /// Returns the current length of the [`ShmStele`]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        to_usize(self.header().len.load(ord::RLX))
+    }
+
+    /// Returns whether the [`ShmStele`] is empty or not
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value at the given index, or [`None`] if it is out of bounds
+    #[must_use]
+    pub fn get(&self, idx: usize) -> Option<T> {
+        if idx >= self.len() {
+            return None;
+        }
+        let (outer_idx, inner_idx) = split_idx(idx);
+        let offset = to_usize(self.header().blocks[outer_idx].load(ord::RLX));
+        //SAFETY: Every element below the length was written by `try_push` to a block within the mapping
+        Some(unsafe {
+            self.mapping
+                .as_ptr()
+                .add(offset + inner_idx * size_of::<T>())
+                .cast::<T>()
+                .read()
+        })
+    }
+}
+
+/// A reader for a [`ShmStele`] through a separate mapping, usually in another process
+///
+/// Nothing in the mapping is trusted: the header is checked when opening it, and every block offset is checked
+/// against the size of the mapping before it is read from
+#[derive(Debug)]
+pub struct ShmReadHandle<T: Pod> {
+    mapping: Mmap,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Pod> ShmReadHandle<T> {
+    /// Opens a mapping set up by [`ShmStele::create`], checking its header
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ShmError`] if the mapping is too small, does not hold a [`ShmStele`], holds elements of another
+    /// size or alignment than `T`, or is smaller than the mapping it was created in
+    pub fn open(mapping: Mmap) -> Result<Self, ShmError> {
+        let needed = size_of::<Header>();
+        if mapping.len() < needed {
+            return Err(ShmError::TooSmall {
+                len: mapping.len(),
+                needed,
+            });
+        }
+        let handle = Self {
+            mapping,
+            _marker: PhantomData,
+        };
+        let header = handle.header();
+        if header.magic != MAGIC {
+            return Err(ShmError::BadMagic);
+        }
+        if header.element_size != to_u64(size_of::<T>())
+            || header.element_align != to_u64(align_of::<T>())
+        {
+            return Err(ShmError::ElementMismatch {
+                size: header.element_size,
+                align: header.element_align,
+            });
+        }
+        if header.capacity > to_u64(handle.mapping.len()) {
+            return Err(ShmError::CapacityMismatch {
+                capacity: header.capacity,
+                len: handle.mapping.len(),
+            });
+        }
+        Ok(handle)
+    }
+
+    //Mappings are page aligned, which is more than the header needs
+    #[allow(clippy::cast_ptr_alignment)]
+    fn header(&self) -> &Header {
+        //SAFETY: The mapping is page aligned and holds at least a header, and any bytes are a valid header
+        unsafe { &*self.mapping.as_ptr().cast::<Header>() }
+    }
+
+    /// Returns the current length of the [`ShmStele`]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        to_usize(self.header().len.load(ord::ACQ))
+    }
+
+    /// Returns whether the [`ShmStele`] is empty or not
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reads the value at the given index
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds or the block holding it does not lie within the mapping
+    #[must_use]
+    pub fn read(&self, idx: usize) -> &T {
+        self.try_read(idx)
+            .expect("Read out of bounds or from a block outside the mapping")
+    }
+
+    /// Attempts to read the value at the index and returns [`Some`] if it exists and its block lies within the mapping, and [`None`] otherwise
+    #[must_use]
+    pub fn try_read(&self, idx: usize) -> Option<&T> {
+        if idx >= self.len() {
+            return None;
+        }
+        let (outer_idx, inner_idx) = split_idx(idx);
+        let offset = to_usize(self.header().blocks[outer_idx].load(ord::ACQ));
+        let block_end = max_len(outer_idx)
+            .checked_mul(size_of::<T>())
+            .and_then(|len| len.checked_add(offset))?;
+        if offset == 0 || block_end > self.mapping.len() || offset & (align_of::<T>() - 1) != 0 {
+            return None;
+        }
+        //SAFETY: The slot is within the mapping and aligned for `T`, every bit pattern is a valid `T`,
+        //and the writer never writes to a slot below the length again
+        Some(unsafe {
+            &*self
+                .mapping
+                .as_ptr()
+                .add(offset + inner_idx * size_of::<T>())
+                .cast::<T>()
+        })
+    }
+
+    /// Returns an iterator over the elements pushed so far
+    ///
+    /// Elements in a block that does not lie within the mapping end the iterator early
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.len()).map_while(move |idx| self.try_read(idx))
+    }
+}
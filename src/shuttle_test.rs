@@ -0,0 +1,103 @@
+use crate::Stele;
+use shuttle::thread;
+
+//Shuttle samples schedules instead of exploring all of them, so unlike the loom models these push
+//enough elements to cross into the larger blocks while readers are racing the allocation
+const PUSHES: usize = 300;
+const ITERATIONS: usize = 200;
+
+#[test]
+fn concurrent_reads() {
+    shuttle::check_random(
+        || {
+            let (wh, rh) = Stele::new();
+            let readers: Vec<_> = (0..3)
+                .map(|_| {
+                    let rh = rh.clone();
+                    thread::spawn(move || {
+                        let mut seen = 0;
+                        while seen < PUSHES {
+                            if let Some(val) = rh.try_read(seen) {
+                                assert_eq!(*val, seen);
+                                seen += 1;
+                            } else {
+                                thread::yield_now();
+                            }
+                        }
+                    })
+                })
+                .collect();
+            (0..PUSHES).for_each(|n| wh.push(n));
+            readers.into_iter().for_each(|t| t.join().unwrap());
+            assert_eq!(rh.len(), PUSHES);
+        },
+        ITERATIONS,
+    );
+}
+
+#[test]
+fn concurrent_iteration() {
+    shuttle::check_random(
+        || {
+            let (wh, rh) = Stele::new();
+            let readers: Vec<_> = (0..3)
+                .map(|_| {
+                    let rh = rh.clone();
+                    thread::spawn(move || {
+                        //Each iterator captures the length when it is created, and must see exactly that prefix
+                        for _ in 0..4 {
+                            let len = rh.len();
+                            let mut count = 0;
+                            for (idx, val) in rh.iter().enumerate() {
+                                assert_eq!(*val, idx);
+                                count += 1;
+                            }
+                            assert!(count >= len);
+                            thread::yield_now();
+                        }
+                    })
+                })
+                .collect();
+            let writer = thread::spawn(move || (0..PUSHES).for_each(|n| wh.push(n)));
+            writer.join().unwrap();
+            readers.into_iter().for_each(|t| t.join().unwrap());
+            assert!(rh.iter().copied().eq(0..PUSHES));
+        },
+        ITERATIONS,
+    );
+}
+
+#[test]
+fn handle_churn() {
+    shuttle::check_random(
+        || {
+            //Every handle is dropped on its own thread, so any of them may be the last one and free the blocks
+            let (wh, rh) = Stele::new();
+            let readers: Vec<_> = (0..3)
+                .map(|n| {
+                    let rh = rh.clone();
+                    thread::spawn(move || {
+                        let clones: Vec<_> = (0..n + 1).map(|_| rh.clone()).collect();
+                        drop(rh);
+                        for (i, rh) in clones.into_iter().enumerate() {
+                            if let Some(val) = rh.try_read(i * PUSHES / 4) {
+                                assert_eq!(*val, i * PUSHES / 4);
+                            }
+                            let _ = rh.iter().count();
+                        }
+                    })
+                })
+                .collect();
+            drop(rh);
+            let writer = thread::spawn(move || {
+                (0..PUSHES).for_each(|n| wh.push(n));
+                let rh = wh.new_read_handle();
+                drop(wh);
+                assert_eq!(rh.len(), PUSHES);
+            });
+            writer.join().unwrap();
+            readers.into_iter().for_each(|t| t.join().unwrap());
+        },
+        ITERATIONS,
+    );
+}
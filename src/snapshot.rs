@@ -0,0 +1,355 @@
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt};
+use std::io::{self, Read, Write};
+
+#[cfg(feature = "bytemuck")]
+use bytemuck::Pod;
+
+use crate::{
+    layout,
+    mem::{DefaultStorage, Storage},
+    ReadHandle, Stele, WriteHandle,
+};
+
+const MAGIC: [u8; 8] = *b"STELESNP";
+const VERSION: u16 = 1;
+//Magic, version, encoding, element size, length, content length and checksum
+const HEADER_LEN: usize = 8 + 2 + 1 + 8 + 8 + 8 + 8;
+//The most elements a Stele can hold, which no valid snapshot exceeds
+const MAX_LEN: usize = layout::first_index_of_block(31) + layout::block_capacity(31);
+
+//The handles to a rebuilt Stele
+type Handles<T, S> = (WriteHandle<T, S>, ReadHandle<T, S>);
+
+/// How the elements of a snapshot are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    //The raw bytes of every element, for `T: Pod`
+    #[cfg(feature = "bytemuck")]
+    Raw = 0,
+    //Every element encoded with postcard one after the other
+    #[cfg(feature = "serde")]
+    Serde = 1,
+}
+
+/// The error returned when reading a snapshot fails
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SnapshotError {
+    /// Reading from the underlying reader failed
+    Io(io::Error),
+    /// The snapshot ended before its header or contents were complete
+    Truncated,
+    /// The data does not start with the snapshot magic bytes, so it is not a snapshot
+    BadMagic,
+    /// The snapshot was written by a different version of the format
+    UnsupportedVersion {
+        /// The version found in the header
+        found: u16,
+    },
+    /// The snapshot was written with the raw encoding and read with serde or the other way around
+    EncodingMismatch,
+    /// The elements of the snapshot have a different size than the type it is read as
+    ElementSizeMismatch {
+        /// The size of the type the snapshot is read as
+        expected: usize,
+        /// The element size found in the header
+        found: u64,
+    },
+    /// The contents do not match the checksum in the header
+    ChecksumMismatch {
+        /// The checksum found in the header
+        expected: u64,
+        /// The checksum of the contents that were read
+        found: u64,
+    },
+    /// The contents do not hold the number of elements the header promises
+    Corrupted,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(_) => f.write_str("failed to read the snapshot"),
+            SnapshotError::Truncated => f.write_str("the snapshot is truncated"),
+            SnapshotError::BadMagic => f.write_str("the data is not a Stele snapshot"),
+            SnapshotError::UnsupportedVersion { found } => {
+                write!(f, "snapshot version {found} is not supported")
+            }
+            SnapshotError::EncodingMismatch => {
+                f.write_str("the snapshot was written with a different encoding")
+            }
+            SnapshotError::ElementSizeMismatch { expected, found } => write!(
+                f,
+                "the snapshot holds elements of {found} bytes but {expected} were expected"
+            ),
+            SnapshotError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "the snapshot checksum is {expected:#018x} but its contents hash to {found:#018x}"
+            ),
+            SnapshotError::Corrupted => {
+                f.write_str("the snapshot contents do not match its header")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof => SnapshotError::Truncated,
+            _ => SnapshotError::Io(err),
+        }
+    }
+}
+
+/// 64 bit FNV-1a, which is small and good enough to catch accidental corruption
+#[derive(Debug, Clone, Copy)]
+struct Checksum(u64);
+
+impl Checksum {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+fn to_u64(n: usize) -> u64 {
+    u64::try_from(n).expect("usize fits in a u64")
+}
+
+/// Writes the header and returns its length
+fn write_header(
+    w: &mut impl Write,
+    encoding: Encoding,
+    element_size: usize,
+    len: usize,
+    content_len: usize,
+    checksum: Checksum,
+) -> io::Result<u64> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(&MAGIC);
+    header.extend_from_slice(&VERSION.to_le_bytes());
+    header.push(encoding as u8);
+    header.extend_from_slice(&to_u64(element_size).to_le_bytes());
+    header.extend_from_slice(&to_u64(len).to_le_bytes());
+    header.extend_from_slice(&to_u64(content_len).to_le_bytes());
+    header.extend_from_slice(&checksum.0.to_le_bytes());
+    w.write_all(&header)?;
+    Ok(to_u64(HEADER_LEN))
+}
+
+/// Reads and validates the header and the contents, returning the number of elements and the contents
+fn read_contents(
+    r: &mut impl Read,
+    encoding: Encoding,
+    element_size: usize,
+) -> Result<(usize, Vec<u8>), SnapshotError> {
+    let mut header = [0; HEADER_LEN];
+    r.read_exact(&mut header)?;
+    let u64_at = |at: usize| {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&header[at..at + 8]);
+        u64::from_le_bytes(bytes)
+    };
+    if header[..8] != MAGIC {
+        return Err(SnapshotError::BadMagic);
+    }
+    let version = u16::from_le_bytes([header[8], header[9]]);
+    if version != VERSION {
+        return Err(SnapshotError::UnsupportedVersion { found: version });
+    }
+    if header[10] != encoding as u8 {
+        return Err(SnapshotError::EncodingMismatch);
+    }
+    let found = u64_at(11);
+    if found != to_u64(element_size) {
+        return Err(SnapshotError::ElementSizeMismatch {
+            expected: element_size,
+            found,
+        });
+    }
+    let len = usize::try_from(u64_at(19))
+        .ok()
+        .filter(|&len| len <= MAX_LEN)
+        .ok_or(SnapshotError::Corrupted)?;
+    let content_len = u64_at(27);
+    let expected = u64_at(35);
+    //The contents grow as they are read rather than being allocated up front, so a corrupted length cannot exhaust memory
+    let mut contents = Vec::new();
+    r.take(content_len).read_to_end(&mut contents)?;
+    if to_u64(contents.len()) != content_len {
+        return Err(SnapshotError::Truncated);
+    }
+    let mut checksum = Checksum::new();
+    checksum.update(&contents);
+    if checksum.0 != expected {
+        return Err(SnapshotError::ChecksumMismatch {
+            expected,
+            found: checksum.0,
+        });
+    }
+    Ok((len, contents))
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T: Pod, S: Storage> ReadHandle<T, S> {
+    /// Writes a snapshot of every initialized element to `w` as raw bytes, returning the number of bytes written
+    ///
+    /// The snapshot starts with a header holding the format version, the element size, the length and a checksum of the contents,
+    /// which [`Stele::read_snapshot`] checks before rebuilding the [`Stele`]. The contents are copied block by block without encoding,
+    /// so the snapshot can only be read back on a target with the same endianness
+    ///
+    /// # Errors
+    ///
+    /// Returns any error returned by `w`
+    pub fn write_snapshot(&self, mut w: impl Write) -> io::Result<u64> {
+        let stele = &*self.handle;
+        let len = stele.initialized_len();
+        let size = core::mem::size_of::<T>();
+        //Zero sized elements have no contents to write
+        let blocks = || {
+            let mut idx = 0;
+            core::iter::from_fn(move || {
+                (idx < len && size != 0).then(|| {
+                    //SAFETY: Everything below the initialized length is initialized
+                    let block = unsafe { stele.block_slice(idx, len) };
+                    idx += block.len();
+                    bytemuck::cast_slice::<T, u8>(block)
+                })
+            })
+        };
+        let mut checksum = Checksum::new();
+        blocks().for_each(|bytes| checksum.update(bytes));
+        let mut written = write_header(&mut w, Encoding::Raw, size, len, len * size, checksum)?;
+        for bytes in blocks() {
+            w.write_all(bytes)?;
+            written += to_u64(bytes.len());
+        }
+        Ok(written)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, S: Storage> ReadHandle<T, S> {
+    /// Writes a snapshot of every initialized element to `w`, encoding each of them with serde, returning the number of bytes written
+    ///
+    /// The header is the same as for [`write_snapshot`](ReadHandle::write_snapshot), and the snapshot is read back with
+    /// [`Stele::read_snapshot_serde`]. The contents are encoded in memory first, as the header holds their checksum
+    ///
+    /// # Errors
+    ///
+    /// Returns any error returned by `w`, or an error of kind [`InvalidData`](io::ErrorKind::InvalidData) if an element fails to serialize
+    pub fn write_snapshot_serde(&self, mut w: impl Write) -> io::Result<u64> {
+        let len = self.handle.initialized_len();
+        let mut contents = Vec::new();
+        for idx in 0..len {
+            contents = postcard::to_extend(self.handle.read_at(idx), contents)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        }
+        let mut checksum = Checksum::new();
+        checksum.update(&contents);
+        let written = write_header(&mut w, Encoding::Serde, 0, len, contents.len(), checksum)?;
+        w.write_all(&contents)?;
+        Ok(written + to_u64(contents.len()))
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T: Pod> Stele<T> {
+    /// Reads a snapshot written by [`ReadHandle::write_snapshot`] and rebuilds the [`Stele`], returning a [`WriteHandle`] and [`ReadHandle`]
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SnapshotError`] if reading fails or if the snapshot is truncated, was written by another version,
+    /// holds elements of another size, or does not match its checksum. Nothing is returned from a snapshot that fails any check
+    pub fn read_snapshot(r: impl Read) -> Result<(WriteHandle<T>, ReadHandle<T>), SnapshotError> {
+        Self::read_snapshot_in(r, DefaultStorage::default())
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T: Pod, S: Storage> Stele<T, S> {
+    /// Reads a snapshot written by [`ReadHandle::write_snapshot`] and rebuilds the [`Stele`] with the given allocator,
+    /// returning a [`WriteHandle`] and [`ReadHandle`]
+    ///
+    /// # Errors
+    ///
+    /// See [`read_snapshot`](Stele::read_snapshot)
+    pub fn read_snapshot_in(mut r: impl Read, storage: S) -> Result<Handles<T, S>, SnapshotError> {
+        let size = core::mem::size_of::<T>();
+        let (len, contents) = read_contents(&mut r, Encoding::Raw, size)?;
+        if contents.len() != len * size {
+            return Err(SnapshotError::Corrupted);
+        }
+        let stele = if size == 0 {
+            Self::from_iter_in((0..len).map(|_| T::zeroed()), storage)
+        } else {
+            Self::from_iter_in(
+                contents
+                    .chunks_exact(size)
+                    .map(bytemuck::pod_read_unaligned),
+                storage,
+            )
+        };
+        Ok(stele.to_handles())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::de::DeserializeOwned> Stele<T> {
+    /// Reads a snapshot written by [`ReadHandle::write_snapshot_serde`] and rebuilds the [`Stele`], returning a [`WriteHandle`] and [`ReadHandle`]
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SnapshotError`] if reading fails or if the snapshot is truncated, was written by another version,
+    /// does not match its checksum or holds elements that fail to deserialize. Nothing is returned from a snapshot that fails any check
+    pub fn read_snapshot_serde(
+        r: impl Read,
+    ) -> Result<(WriteHandle<T>, ReadHandle<T>), SnapshotError> {
+        Self::read_snapshot_serde_in(r, DefaultStorage::default())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::de::DeserializeOwned, S: Storage> Stele<T, S> {
+    /// Reads a snapshot written by [`ReadHandle::write_snapshot_serde`] and rebuilds the [`Stele`] with the given allocator,
+    /// returning a [`WriteHandle`] and [`ReadHandle`]
+    ///
+    /// # Errors
+    ///
+    /// See [`read_snapshot_serde`](Stele::read_snapshot_serde)
+    pub fn read_snapshot_serde_in(
+        mut r: impl Read,
+        storage: S,
+    ) -> Result<Handles<T, S>, SnapshotError> {
+        let (len, contents) = read_contents(&mut r, Encoding::Serde, 0)?;
+        //Decode everything before building the Stele, so that a failure part way through does not leave a partial one behind
+        let mut rest = contents.as_slice();
+        let mut elements = Vec::new();
+        for _ in 0..len {
+            let (val, remaining) =
+                postcard::take_from_bytes(rest).map_err(|_| SnapshotError::Corrupted)?;
+            elements.push(val);
+            rest = remaining;
+        }
+        if !rest.is_empty() {
+            return Err(SnapshotError::Corrupted);
+        }
+        Ok(Self::from_iter_in(elements, storage).to_handles())
+    }
+}
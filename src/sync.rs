@@ -1,9 +1,472 @@
-#[cfg(not(loom))]
-pub use alloc::sync::Arc;
-#[cfg(not(loom))]
-pub use core::sync::atomic::{AtomicPtr, AtomicUsize};
-#[cfg(loom)]
-pub use loom::sync::{
-    atomic::{fence, AtomicPtr, AtomicUsize},
-    Arc,
-};
+//Every atomic and lock the data structure uses comes from here, so that it can be checked by loom or shuttle
+//by only changing which backend is re-exported
+#[cfg(all(any(loom, feature = "loom"), shuttle))]
+compile_error!("loom and `--cfg shuttle` cannot be used together");
+
+#[cfg(not(any(
+    loom,
+    feature = "loom",
+    shuttle,
+    feature = "portable-atomic",
+    all(
+        feature = "wasm-singlethread",
+        target_arch = "wasm32",
+        not(target_feature = "atomics")
+    )
+)))]
+mod backend {
+    pub use alloc::sync::Arc;
+    #[cfg(feature = "futures")]
+    pub use core::sync::atomic::fence;
+    pub use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+    #[cfg(feature = "futures")]
+    pub use std::sync::Mutex;
+
+    /// Loads from an atomic pointer that can no longer be shared
+    pub(crate) fn load_mut<T>(ptr: &mut AtomicPtr<T>) -> *mut T {
+        *ptr.get_mut()
+    }
+}
+
+//Targets without native compare-and-swap, where portable-atomic provides it through a critical section or by
+//assuming a single core
+#[cfg(all(
+    feature = "portable-atomic",
+    not(any(
+        loom,
+        feature = "loom",
+        shuttle,
+        all(
+            feature = "wasm-singlethread",
+            target_arch = "wasm32",
+            not(target_feature = "atomics")
+        )
+    ))
+))]
+mod backend {
+    #[cfg(feature = "futures")]
+    pub use portable_atomic::fence;
+    pub use portable_atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+    pub use portable_atomic_util::Arc;
+    #[cfg(feature = "futures")]
+    pub use std::sync::Mutex;
+
+    /// Loads from an atomic pointer that can no longer be shared
+    pub(crate) fn load_mut<T>(ptr: &mut AtomicPtr<T>) -> *mut T {
+        *ptr.get_mut()
+    }
+}
+
+//wasm32 without the atomics proposal cannot share memory between threads at all, so every atomic can be a plain `Cell`
+//and the handles can be reference counted without atomics
+#[cfg(all(
+    feature = "wasm-singlethread",
+    target_arch = "wasm32",
+    not(target_feature = "atomics"),
+    not(any(loom, feature = "loom", shuttle))
+))]
+mod backend {
+    pub use alloc::rc::Rc as Arc;
+    use core::{cell::Cell, sync::atomic::Ordering};
+    #[cfg(feature = "futures")]
+    pub use std::sync::Mutex;
+
+    /// There is nothing to order against without other threads
+    #[cfg(feature = "futures")]
+    pub fn fence(_: Ordering) {}
+
+    /// Loads from an atomic pointer that can no longer be shared
+    pub(crate) fn load_mut<T>(ptr: &mut AtomicPtr<T>) -> *mut T {
+        *ptr.get_mut()
+    }
+
+    //SAFETY: Without the atomics target feature a wasm module has no shared memory,
+    //so none of these can ever be accessed from more than one thread
+    unsafe impl Sync for AtomicBool {}
+    unsafe impl Sync for AtomicUsize {}
+    unsafe impl<T> Sync for AtomicPtr<T> {}
+
+    /// A `Cell` with the interface of [`AtomicBool`](core::sync::atomic::AtomicBool)
+    #[derive(Debug, Default)]
+    pub struct AtomicBool(Cell<bool>);
+
+    impl AtomicBool {
+        /// Like [`AtomicBool::new`](core::sync::atomic::AtomicBool::new)
+        #[must_use]
+        pub const fn new(val: bool) -> Self {
+            Self(Cell::new(val))
+        }
+
+        /// Like [`AtomicBool::store`](core::sync::atomic::AtomicBool::store)
+        pub fn store(&self, val: bool, _: Ordering) {
+            self.0.set(val);
+        }
+
+        /// Like [`AtomicBool::compare_exchange`](core::sync::atomic::AtomicBool::compare_exchange)
+        ///
+        /// # Errors
+        ///
+        /// Returns the current value if it was not `current`
+        pub fn compare_exchange(
+            &self,
+            current: bool,
+            new: bool,
+            _: Ordering,
+            _: Ordering,
+        ) -> Result<bool, bool> {
+            let prev = self.0.get();
+            if prev == current {
+                self.0.set(new);
+                Ok(prev)
+            } else {
+                Err(prev)
+            }
+        }
+    }
+
+    /// A `Cell` with the interface of [`AtomicUsize`](core::sync::atomic::AtomicUsize)
+    #[derive(Debug, Default)]
+    pub struct AtomicUsize(Cell<usize>);
+
+    impl AtomicUsize {
+        /// Like [`AtomicUsize::new`](core::sync::atomic::AtomicUsize::new)
+        #[must_use]
+        pub const fn new(val: usize) -> Self {
+            Self(Cell::new(val))
+        }
+
+        /// Like [`AtomicUsize::load`](core::sync::atomic::AtomicUsize::load)
+        #[must_use]
+        pub fn load(&self, _: Ordering) -> usize {
+            self.0.get()
+        }
+
+        /// Like [`AtomicUsize::store`](core::sync::atomic::AtomicUsize::store)
+        pub fn store(&self, val: usize, _: Ordering) {
+            self.0.set(val);
+        }
+
+        /// Like [`AtomicUsize::swap`](core::sync::atomic::AtomicUsize::swap)
+        pub fn swap(&self, val: usize, _: Ordering) -> usize {
+            self.0.replace(val)
+        }
+
+        /// Like [`AtomicUsize::compare_exchange`](core::sync::atomic::AtomicUsize::compare_exchange)
+        ///
+        /// # Errors
+        ///
+        /// Returns the current value if it was not `current`
+        pub fn compare_exchange(
+            &self,
+            current: usize,
+            new: usize,
+            _: Ordering,
+            _: Ordering,
+        ) -> Result<usize, usize> {
+            let prev = self.0.get();
+            if prev == current {
+                self.0.set(new);
+                Ok(prev)
+            } else {
+                Err(prev)
+            }
+        }
+
+        /// Like [`AtomicUsize::compare_exchange_weak`](core::sync::atomic::AtomicUsize::compare_exchange_weak)
+        ///
+        /// # Errors
+        ///
+        /// Returns the current value if it was not `current`
+        pub fn compare_exchange_weak(
+            &self,
+            current: usize,
+            new: usize,
+            success: Ordering,
+            failure: Ordering,
+        ) -> Result<usize, usize> {
+            self.compare_exchange(current, new, success, failure)
+        }
+
+        /// Like [`AtomicUsize::fetch_add`](core::sync::atomic::AtomicUsize::fetch_add)
+        pub fn fetch_add(&self, val: usize, _: Ordering) -> usize {
+            self.0.replace(self.0.get().wrapping_add(val))
+        }
+
+        /// Like [`AtomicUsize::fetch_sub`](core::sync::atomic::AtomicUsize::fetch_sub)
+        pub fn fetch_sub(&self, val: usize, _: Ordering) -> usize {
+            self.0.replace(self.0.get().wrapping_sub(val))
+        }
+
+        /// Like [`AtomicUsize::fetch_or`](core::sync::atomic::AtomicUsize::fetch_or)
+        pub fn fetch_or(&self, val: usize, _: Ordering) -> usize {
+            self.0.replace(self.0.get() | val)
+        }
+
+        /// Like [`AtomicUsize::fetch_max`](core::sync::atomic::AtomicUsize::fetch_max)
+        pub fn fetch_max(&self, val: usize, _: Ordering) -> usize {
+            self.0.replace(self.0.get().max(val))
+        }
+    }
+
+    /// A `Cell` with the interface of [`AtomicPtr`](core::sync::atomic::AtomicPtr)
+    #[derive(Debug)]
+    pub struct AtomicPtr<T>(Cell<*mut T>);
+
+    impl<T> AtomicPtr<T> {
+        /// Like [`AtomicPtr::new`](core::sync::atomic::AtomicPtr::new)
+        #[must_use]
+        pub const fn new(ptr: *mut T) -> Self {
+            Self(Cell::new(ptr))
+        }
+
+        /// Like [`AtomicPtr::get_mut`](core::sync::atomic::AtomicPtr::get_mut)
+        pub fn get_mut(&mut self) -> &mut *mut T {
+            self.0.get_mut()
+        }
+
+        /// Like [`AtomicPtr::load`](core::sync::atomic::AtomicPtr::load)
+        #[must_use]
+        pub fn load(&self, _: Ordering) -> *mut T {
+            self.0.get()
+        }
+
+        /// Like [`AtomicPtr::store`](core::sync::atomic::AtomicPtr::store)
+        pub fn store(&self, ptr: *mut T, _: Ordering) {
+            self.0.set(ptr);
+        }
+
+        /// Like [`AtomicPtr::swap`](core::sync::atomic::AtomicPtr::swap)
+        pub fn swap(&self, ptr: *mut T, _: Ordering) -> *mut T {
+            self.0.replace(ptr)
+        }
+
+        /// Like [`AtomicPtr::compare_exchange`](core::sync::atomic::AtomicPtr::compare_exchange)
+        ///
+        /// # Errors
+        ///
+        /// Returns the current pointer if it was not `current`
+        pub fn compare_exchange(
+            &self,
+            current: *mut T,
+            new: *mut T,
+            _: Ordering,
+            _: Ordering,
+        ) -> Result<*mut T, *mut T> {
+            let prev = self.0.get();
+            if prev == current {
+                self.0.set(new);
+                Ok(prev)
+            } else {
+                Err(prev)
+            }
+        }
+    }
+}
+
+#[cfg(any(loom, feature = "loom"))]
+mod backend {
+    #[cfg(feature = "futures")]
+    pub use loom::sync::{atomic::fence, Mutex};
+    pub use loom::sync::{
+        atomic::{AtomicBool, AtomicPtr, AtomicUsize},
+        Arc,
+    };
+
+    /// Loads from an atomic pointer that can no longer be shared
+    pub(crate) fn load_mut<T>(ptr: &mut AtomicPtr<T>) -> *mut T {
+        ptr.with_mut(|ptr| *ptr)
+    }
+}
+
+#[cfg(shuttle)]
+mod backend {
+    #[cfg(feature = "futures")]
+    pub use shuttle::sync::{atomic::fence, Mutex};
+    pub use shuttle::sync::{
+        atomic::{AtomicBool, AtomicPtr, AtomicUsize},
+        Arc,
+    };
+
+    /// Loads from an atomic pointer that can no longer be shared
+    pub(crate) fn load_mut<T>(ptr: &mut AtomicPtr<T>) -> *mut T {
+        *ptr.get_mut()
+    }
+}
+
+pub use backend::*;
+
+/// The orderings every atomic operation in the crate uses, which all become [`SeqCst`](Ordering::SeqCst) with the `seqcst-debug`
+/// feature to rule out a weak ordering as the cause of a bug
+///
+/// Naming them also keeps every use greppable, for whenever one is relaxed
+pub(crate) mod ord {
+    pub(crate) use core::sync::atomic::Ordering;
+
+    #[cfg(not(feature = "seqcst-debug"))]
+    pub(crate) const ACQ: Ordering = Ordering::Acquire;
+    #[cfg(not(feature = "seqcst-debug"))]
+    pub(crate) const REL: Ordering = Ordering::Release;
+    #[cfg(not(feature = "seqcst-debug"))]
+    pub(crate) const ACQREL: Ordering = Ordering::AcqRel;
+    #[cfg(not(feature = "seqcst-debug"))]
+    pub(crate) const RLX: Ordering = Ordering::Relaxed;
+
+    #[cfg(feature = "seqcst-debug")]
+    pub(crate) const ACQ: Ordering = Ordering::SeqCst;
+    #[cfg(feature = "seqcst-debug")]
+    pub(crate) const REL: Ordering = Ordering::SeqCst;
+    #[cfg(feature = "seqcst-debug")]
+    pub(crate) const ACQREL: Ordering = Ordering::SeqCst;
+    #[cfg(feature = "seqcst-debug")]
+    pub(crate) const RLX: Ordering = Ordering::SeqCst;
+}
+
+/// A way for the writer of a [`Stele`](crate::Stele) to wake readers that are blocked waiting for it
+///
+/// [`CondvarNotify`] is provided for `std`, and other primitives such as an async runtime's notifier
+/// can be used by implementing this trait for them
+pub trait Notify: core::fmt::Debug {
+    /// Wakes every thread currently blocked in [`wait_until`](Notify::wait_until)
+    ///
+    /// This is called after every push and when the writer is dropped
+    fn notify_all(&self);
+
+    /// Blocks until `pred` returns `true`, checking it again after every notification
+    ///
+    /// Implementations must not miss a notification that happens after `pred` returned `false` but before blocking
+    fn wait_until(&self, pred: &mut dyn FnMut() -> bool);
+
+    /// Blocks until `pred` returns `true` or `timeout` has passed, returning the last result of `pred`
+    ///
+    /// Not every primitive can stop waiting after a timeout, so by default this checks `pred` again every millisecond.
+    /// Implementations that can wait with a timeout should override it
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn wait_until_timeout(
+        &self,
+        pred: &mut dyn FnMut() -> bool,
+        timeout: core::time::Duration,
+    ) -> bool {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            if pred() {
+                return true;
+            }
+            let now = std::time::Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            std::thread::sleep((deadline - now).min(core::time::Duration::from_millis(1)));
+        }
+    }
+}
+
+/// A [`Notify`] implementation built on [`Condvar`](std::sync::Condvar)
+///
+/// It is not available on wasm32 without the atomics proposal, where there is no other thread to wait for
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+    )))
+)]
+#[derive(Debug, Default)]
+pub struct CondvarNotify {
+    lock: std::sync::Mutex<()>,
+    condvar: std::sync::Condvar,
+}
+
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+))]
+impl Notify for CondvarNotify {
+    fn notify_all(&self) {
+        //Taking the lock means a waiter is either still before its check or already waiting
+        let _guard = self
+            .lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        self.condvar.notify_all();
+    }
+
+    fn wait_until(&self, pred: &mut dyn FnMut() -> bool) {
+        let mut guard = self
+            .lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        while !pred() {
+            guard = self
+                .condvar
+                .wait(guard)
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+        }
+    }
+
+    fn wait_until_timeout(
+        &self,
+        pred: &mut dyn FnMut() -> bool,
+        timeout: core::time::Duration,
+    ) -> bool {
+        let guard = self
+            .lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let (_guard, result) = self
+            .condvar
+            .wait_timeout_while(guard, timeout, |()| !pred())
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        !result.timed_out()
+    }
+}
+
+/// A [`Notify`] implementation that blocks on a futex-like atomic word with [`atomic-wait`](https://crates.io/crates/atomic-wait),
+/// which does not need `std`
+///
+/// Notifying only makes a system call while a reader is actually waiting
+#[cfg(feature = "atomic-wait")]
+#[cfg_attr(docsrs, doc(cfg(feature = "atomic-wait")))]
+#[derive(Debug, Default)]
+pub struct AtomicWaitNotify {
+    //Bumped by every notification that has a waiter to wake. `atomic-wait` only works on 32 bit words,
+    //which is fine as waiters only compare it for equality and wrapping around would need 2^32 notifications
+    //to land between a waiter reading it and going to sleep
+    epoch: core::sync::atomic::AtomicU32,
+    waiters: core::sync::atomic::AtomicUsize,
+}
+
+//A waiter reads the epoch, registers itself, and then checks its condition, while the writer stores the new length
+//and then checks for waiters. With a SeqCst fence between the store and the load on both sides, either the waiter
+//sees the new length or the writer sees the waiter and bumps the epoch, which makes the waiter's `wait` return
+//immediately if it has not gone to sleep yet and wakes it otherwise
+#[cfg(feature = "atomic-wait")]
+impl Notify for AtomicWaitNotify {
+    fn notify_all(&self) {
+        use core::sync::atomic::{fence, Ordering};
+        fence(Ordering::SeqCst);
+        if self.waiters.load(ord::RLX) != 0 {
+            self.epoch.fetch_add(1, ord::REL);
+            atomic_wait::wake_all(core::ptr::addr_of!(self.epoch));
+        }
+    }
+
+    fn wait_until(&self, pred: &mut dyn FnMut() -> bool) {
+        use core::sync::atomic::{fence, Ordering};
+        loop {
+            let epoch = self.epoch.load(ord::ACQ);
+            self.waiters.fetch_add(1, ord::RLX);
+            fence(Ordering::SeqCst);
+            if pred() {
+                self.waiters.fetch_sub(1, ord::RLX);
+                return;
+            }
+            atomic_wait::wait(&self.epoch, epoch);
+            self.waiters.fetch_sub(1, ord::RLX);
+        }
+    }
+}
@@ -1,13 +1,42 @@
 #[allow(unused_imports)]
 use super::Stele;
+use crate::{
+    mem::{DefaultStorage, Storage},
+    testing::CountingAllocator,
+};
+use alloc::alloc::Layout;
 
-#[test]
-fn write_test() {
-    let (wh, rh) = Stele::new();
+//The writer may move between threads but never be shared, whichever path it is named through
+static_assertions::assert_impl_all!(crate::append::writer::WriteHandle<u32>: Send);
+static_assertions::assert_not_impl_any!(crate::append::writer::WriteHandle<u32>: Sync);
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+static_assertions::assert_impl_all!(crate::append_alloc::writer::WriteHandle<u32, crate::mem::Global>: Send);
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+static_assertions::assert_not_impl_any!(crate::append_alloc::writer::WriteHandle<u32, crate::mem::Global>: Sync);
+
+//Handles can be captured by `catch_unwind` without `AssertUnwindSafe`, unless the elements themselves are not unwind safe
+static_assertions::assert_impl_all!(crate::ReadHandle<u32>: core::panic::UnwindSafe, core::panic::RefUnwindSafe);
+static_assertions::assert_impl_all!(crate::WriteHandle<u32>: core::panic::UnwindSafe, core::panic::RefUnwindSafe);
+static_assertions::assert_impl_all!(crate::CachedReadHandle<u32>: core::panic::UnwindSafe, core::panic::RefUnwindSafe);
+static_assertions::assert_not_impl_any!(crate::ReadHandle<core::cell::Cell<u32>>: core::panic::UnwindSafe, core::panic::RefUnwindSafe);
+static_assertions::assert_not_impl_any!(crate::WriteHandle<core::cell::Cell<u32>>: core::panic::RefUnwindSafe);
+
+//The push, read, iterator and drop tests run against both the default storage and an allocator
+fn write_test_in<S: Storage>(storage: S) {
+    let (wh, rh) = Stele::new_in(storage);
     for n in 0..1 << 8 {
         wh.push(n);
     }
     assert_eq!(rh.len(), 1 << 8);
+    assert!((0..1 << 8).all(|n| *rh.read(n) == n && rh.get(n) == n));
+}
+
+#[test]
+fn write_test() {
+    write_test_in(DefaultStorage::default());
+    let counter = CountingAllocator::new();
+    write_test_in(&counter);
+    counter.assert_empty();
 }
 
 #[test]
@@ -24,6 +53,8 @@ fn getcopy() {
     let (wh, rh) = Stele::new();
     wh.push(0);
     assert_eq!(rh.get(0), 0);
+    #[cfg(feature = "debug-poison")]
+    (1..rh.capacity()).for_each(|idx| rh.handle.poison_check(idx));
 }
 
 #[test]
@@ -33,26 +64,105 @@ fn never_writes() {
     assert!(rh.is_empty());
 }
 
-#[test]
-fn iterator() {
+fn iterator_in<S: Storage>(storage: S) {
     let sequence = &[92, 47, 68, 23, 15];
-    let (_, rh) = sequence.iter().copied().collect::<Stele<_>>().to_handles();
+    let (_, rh) = Stele::from_iter_in(sequence.iter().copied(), storage).to_handles();
     let ref_iter = rh.iter();
     for (stele, orig) in ref_iter.zip(sequence.iter()) {
         assert_eq!(stele, orig);
     }
+    assert!(rh.iter_range(1..4).eq(&sequence[1..4]));
 }
 
 #[test]
-fn copy_iterator() {
+fn iterator() {
+    iterator_in(DefaultStorage::default());
+    let counter = CountingAllocator::new();
+    iterator_in(&counter);
+    counter.assert_empty();
+}
+
+fn copy_iterator_in<S: Storage>(storage: S) {
     let sequence = [92, 47, 68, 23, 15];
-    let (_, rh) = sequence.iter().copied().collect::<Stele<_>>().to_handles();
+    let (_, rh) = Stele::from_iter_in(sequence.iter().copied(), storage).to_handles();
     let ref_iter = rh.into_iter();
     for (stele, orig) in ref_iter.zip(sequence.iter().copied()) {
         assert_eq!(stele, orig);
     }
 }
 
+#[test]
+fn copy_iterator() {
+    copy_iterator_in(DefaultStorage::default());
+    let counter = CountingAllocator::new();
+    copy_iterator_in(&counter);
+    counter.assert_empty();
+}
+
+#[test]
+fn iterator_fold() {
+    use alloc::vec::Vec;
+    //Enough elements to span a dozen blocks, so that the lookahead keeps crossing block boundaries
+    let s = (0..5000_u64).collect::<Stele<_>>();
+    //Order sensitive, so that it also catches elements that are folded out of order
+    let hash = |acc: u64, n: u64| acc.wrapping_mul(31).wrapping_add(n);
+    let expected = (0..5000).fold(0, hash);
+    assert_eq!(s.iter().sum::<u64>(), (0..5000).sum::<u64>());
+    assert_eq!(s.iter().fold(0, |acc, &n| hash(acc, n)), expected);
+    let (wh, rh) = s.to_handles();
+    for range in [0..0, 0..1, 3..4, 7..4096, 100..5000] {
+        let mut seen = Vec::new();
+        rh.iter_range(range.clone()).for_each(|&n| seen.push(n));
+        assert!(seen
+            .iter()
+            .copied()
+            .eq(range.start.min(range.end) as u64..range.end as u64));
+    }
+    let mut iter = rh.iter();
+    iter.nth(999);
+    assert_eq!(
+        iter.fold(0, |acc, &n| hash(acc, n)),
+        (1000..5000).fold(0, hash)
+    );
+    let big = (0..300).map(|n| [n; 20]).collect::<Stele<[u64; 20]>>();
+    assert_eq!(
+        big.iter().fold(0, |acc, item| hash(acc, item[19])),
+        (0..300).fold(0, hash)
+    );
+    let zst = (0..100).map(|_| ()).collect::<Stele<_>>();
+    assert_eq!(
+        zst.iter().fold(0, |acc, ()| hash(acc, 1)),
+        (0..100).fold(0, |acc, _| hash(acc, 1))
+    );
+
+    let copied = wh.new_read_handle().into_iter();
+    assert_eq!(copied.fold(0, hash), expected);
+    let mut copied = rh.into_iter();
+    copied.nth(4094);
+    let mut rest = Vec::new();
+    copied.for_each(|n| rest.push(n));
+    assert!(rest.into_iter().eq(4095..5000));
+}
+
+#[test]
+fn copy_to_slice() {
+    let (_, rh) = (0..5000_usize).collect::<Stele<_>>().to_handles();
+    for (start, len) in [(0, 0), (0, 1), (1, 2), (3, 100), (1000, 4000), (0, 5000)] {
+        let mut dst = alloc::vec![usize::MAX; len];
+        rh.copy_to_slice(start, &mut dst);
+        assert!(dst.into_iter().eq(start..start + len));
+    }
+}
+
+#[test]
+#[should_panic(
+    expected = "Copied 2 elements from index 4999 of a Stele with 5000 initialized elements"
+)]
+fn copy_to_slice_past_end() {
+    let (_, rh) = (0..5000_u32).collect::<Stele<_>>().to_handles();
+    rh.copy_to_slice(4999, &mut [0; 2]);
+}
+
 #[test]
 fn read_through_writer() {
     let (wh, _) = Stele::<u8>::new();
@@ -63,4 +173,5162 @@ fn read_through_writer() {
     assert_eq!(wh.read(0), rh.read(0));
     assert_eq!(wh.get(0), rh.get(0));
     assert!(wh.try_read(1).is_none());
+    #[cfg(feature = "debug-poison")]
+    (1..wh.capacity()).for_each(|idx| wh.handle.poison_check(idx));
+}
+
+#[cfg(feature = "debug-poison")]
+#[test]
+fn poison_out_of_bounds_read() {
+    let (wh, rh) = Stele::<u32>::new();
+    wh.push(7);
+    wh.reserve(1);
+    //Reading past the end sees the poison pattern instead of something that looks like a real element
+    let planted = unsafe { (*rh.handle.raw.read_raw(1)).get() };
+    assert_eq!(planted, u32::from_ne_bytes([0xA5; 4]));
+    rh.handle.poison_check(1);
+}
+
+#[cfg(feature = "debug-poison")]
+#[test]
+#[should_panic(expected = "past the end was written to")]
+fn poison_out_of_bounds_write() {
+    let (wh, rh) = Stele::<u32>::new();
+    wh.push(7);
+    wh.reserve(1);
+    unsafe { rh.handle.raw.read_raw(1).write(crate::Inner::new(8)) };
+    rh.handle.poison_check(1);
+}
+
+#[test]
+fn memory_statistics() {
+    let (wh, rh) = Stele::<u8>::new();
+    assert_eq!(rh.capacity(), 0);
+    assert_eq!(rh.block_count(), 0);
+    assert_eq!(rh.allocated_bytes(), 0);
+    //Every block is allocated by the first push that lands in it
+    for n in 0..9 {
+        wh.push(n);
+        let len = usize::from(n) + 1;
+        assert_eq!(rh.block_count(), crate::layout::blocks_for_len(len));
+        assert_eq!(rh.capacity(), len.next_power_of_two());
+        assert_eq!(rh.allocated_bytes(), len.next_power_of_two());
+    }
+    assert_eq!(wh.block_count(), 5);
+    assert_eq!(wh.capacity(), 16);
+    assert_eq!(wh.overhead_bytes(), rh.overhead_bytes());
+
+    let (wh, rh) = Stele::<u32>::new();
+    wh.push(0);
+    assert_eq!(rh.block_count(), 1);
+    assert_eq!(rh.capacity(), 1);
+    assert_eq!(rh.allocated_bytes(), 4);
+    for n in 1..4 {
+        wh.push(n);
+    }
+    assert_eq!(rh.block_count(), 3);
+    assert_eq!(rh.capacity(), 4);
+    assert_eq!(rh.allocated_bytes(), 16);
+    for n in 4..9 {
+        wh.push(n);
+    }
+    assert_eq!(rh.block_count(), 5);
+    assert_eq!(rh.capacity(), 16);
+    assert_eq!(rh.allocated_bytes(), 64);
+}
+
+#[test]
+fn lazy_allocation() {
+    use crate::{append::builder::SteleBuilder, local::LocalStele, mem::initial_blocks, Prealloc};
+
+    //Every length around the end of the small blocks that `Prealloc::SmallBlocks` allocates together
+    let small = crate::layout::first_index_of_block(initial_blocks::<u8>() + 1);
+    for len in [0, 1, 2, small - 1, small, small + 1] {
+        let counter = CountingAllocator::new();
+        let (wh, rh) = Stele::new_in(&counter);
+        for n in 0..len {
+            wh.push(n);
+            //A push only allocates when it is the first one in its block
+            assert_eq!(counter.allocations(), crate::layout::blocks_for_len(n + 1));
+        }
+        assert_eq!(
+            rh.capacity(),
+            len.next_power_of_two() * usize::from(len > 0)
+        );
+        drop((wh, rh));
+        counter.assert_empty();
+
+        let counter = CountingAllocator::new();
+        let s = LocalStele::from_iter_in(0..len, &counter);
+        assert_eq!(counter.allocations(), crate::layout::blocks_for_len(len));
+        drop(s);
+        counter.assert_empty();
+    }
+
+    //Preallocating the small blocks is opt-in
+    let counter = CountingAllocator::new();
+    let mut s = SteleBuilder::new_in(&counter)
+        .prealloc(Prealloc::SmallBlocks)
+        .build_owned()
+        .unwrap();
+    s.push_mut(0_u8);
+    assert_eq!(counter.allocations(), initial_blocks::<u8>() + 1);
+    (1..small).for_each(|_| s.push_mut(1));
+    assert_eq!(counter.allocations(), initial_blocks::<u8>() + 1);
+    s.push_mut(0);
+    assert_eq!(counter.allocations(), initial_blocks::<u8>() + 2);
+    drop(s);
+    counter.assert_empty();
+}
+
+#[test]
+fn memory_statistics_zst() {
+    let (wh, rh) = Stele::new();
+    for _ in 0..9 {
+        wh.push(());
+    }
+    assert_eq!(rh.capacity(), 16);
+    assert_eq!(rh.allocated_bytes(), 0);
+    assert!(rh.overhead_bytes() > 0);
+}
+
+#[test]
+fn shrink_unused() {
+    let counter = CountingAllocator::new();
+    let (wh, rh) = Stele::<u8, _>::new_in(&counter);
+    for n in 0..3 {
+        wh.push(n);
+    }
+    wh.reserve(5);
+    assert_eq!(rh.block_count(), 4);
+    let rh2 = wh.shrink_unused();
+    assert_eq!(rh.block_count(), 3);
+    assert_eq!(counter.deallocations(), 1);
+    assert_eq!(counter.live_bytes(), 4);
+    assert_eq!(rh.capacity(), 4);
+    assert_eq!(
+        rh2.iter().copied().collect::<alloc::vec::Vec<_>>(),
+        [0, 1, 2]
+    );
+    assert!(rh.try_read(3).is_none());
+}
+
+#[test]
+fn to_stele_in() {
+    use alloc::{string::ToString, vec::Vec};
+
+    //Cloned out of the default storage into a counted one, which allocates exactly the blocks the elements need
+    let (wh, rh) = Stele::new();
+    (0..100).for_each(|n: u32| wh.push(n.to_string()));
+    let counter = CountingAllocator::new();
+    let (cwh, crh) = rh.to_stele_in(&counter);
+    assert_eq!(counter.allocations(), crate::layout::blocks_for_len(100));
+    assert!(crh.iter().eq(rh.iter()));
+    //Both stay usable on their own
+    cwh.push("new".to_string());
+    wh.push("old".to_string());
+    assert_eq!((crh.len(), crh.read(100).as_str()), (101, "new"));
+    assert_eq!((rh.len(), rh.read(100).as_str()), (101, "old"));
+    drop((cwh, crh));
+    counter.assert_empty();
+
+    //Copied out of a counted storage, which can be freed while the copy lives on with the same block layout
+    let counter = CountingAllocator::new();
+    let (wh, rh) = Stele::with_first_block_exp_in(3, &counter);
+    (0..50_u64).for_each(|n| wh.push(n * n));
+    let (copy_writer, copy) = rh.copy_to_stele_in(DefaultStorage::default());
+    assert_eq!(
+        (copy.capacity(), copy.block_count()),
+        (rh.capacity(), rh.block_count())
+    );
+    drop((wh, rh));
+    counter.assert_empty();
+    assert_eq!(
+        copy.iter().copied().collect::<Vec<_>>(),
+        (0..50).map(|n| n * n).collect::<Vec<_>>()
+    );
+    copy_writer.push(1);
+    assert_eq!(copy.len(), 51);
+
+    //Nothing is allocated for an empty Stele, and a bounded one stays bounded
+    let (_wh, rh) = Stele::<u8>::bounded(10);
+    let counter = CountingAllocator::new();
+    let (empty_writer, empty) = rh.copy_to_stele_in(&counter);
+    assert_eq!((counter.allocations(), empty.remaining()), (0, Some(10)));
+    drop(empty_writer);
+}
+
+#[test]
+fn first_block_exp() {
+    let counter = CountingAllocator::new();
+    let (wh, rh) = Stele::with_first_block_exp_in(4, &counter);
+    wh.push(0_u32);
+    //Only the first block is allocated up front, and it holds 16 elements
+    assert_eq!(rh.block_count(), 1);
+    assert_eq!(rh.capacity(), 16);
+    for n in 1..1000 {
+        wh.push(n);
+    }
+    assert_eq!(rh.block_count(), 7);
+    assert_eq!(rh.capacity(), 1024);
+    assert_eq!(
+        counter.layouts(),
+        [16, 16, 32, 64, 128, 256, 512].map(|len| Layout::array::<u32>(len).unwrap())
+    );
+    assert!((0..1000).all(|n| rh[n as usize] == n && rh.get(n as usize) == n));
+    assert!(rh.iter().copied().eq(0..1000));
+    assert!(rh.try_read(1000).is_none());
+    let rh2 = wh.shrink_unused();
+    assert_eq!(rh2.capacity(), 1024);
+    drop((rh, rh2));
+    counter.assert_empty();
+
+    let (wh, rh) = Stele::<u32>::with_first_block_exp(4);
+    for n in 0..17 {
+        wh.push(n);
+    }
+    let rh2 = wh.shrink_unused();
+    assert_eq!(rh.block_count(), 2);
+    assert!(rh2.iter().copied().eq(0..17));
+}
+
+#[test]
+fn first_block_exp_clamped() {
+    let (wh, rh) = Stele::with_first_block_exp(u32::MAX);
+    wh.push(0_u8);
+    assert_eq!(rh.capacity(), 1 << 16);
+    let (wh, rh) = Stele::with_first_block_exp(0);
+    wh.push(0_u8);
+    assert_eq!(rh.capacity(), 1);
+}
+
+#[test]
+fn growth_policies() {
+    use crate::GrowthPolicy;
+    let policies = [
+        GrowthPolicy::default(),
+        GrowthPolicy::doubling(2),
+        GrowthPolicy::uniform(1),
+        GrowthPolicy::uniform(5),
+        GrowthPolicy::uniform(64),
+    ];
+    for policy in policies {
+        let counter = CountingAllocator::new();
+        let (wh, rh) = Stele::with_growth_in(policy, &counter);
+        assert_eq!(rh.growth(), policy);
+        let len = policy.max_len().unwrap_or(200).min(200);
+        let quarter = len / 4;
+        for n in 0..quarter {
+            wh.push(n);
+        }
+        assert!(wh.try_extend(quarter..2 * quarter).is_ok());
+        let slots = (2 * quarter..3 * quarter)
+            .map(|_| wh.push_uninit())
+            .collect::<alloc::vec::Vec<_>>();
+        for slot in slots.into_iter().rev() {
+            let idx = slot.index();
+            slot.fill(idx);
+        }
+        wh.reserve(len - 3 * quarter);
+        for n in 3 * quarter..len {
+            assert_eq!(wh.push_within_capacity(n), Ok(()));
+        }
+        //Every block is allocated with exactly the size the policy gives it
+        assert_eq!(
+            counter.layouts(),
+            (0..policy.blocks_for_len(len))
+                .map(|block| Layout::array::<usize>(policy.block_capacity(block)).unwrap())
+                .collect::<alloc::vec::Vec<_>>()
+        );
+        assert_eq!(rh.len(), len);
+        assert!(rh.iter().copied().eq(0..len));
+        assert!(rh
+            .iter_range(quarter..3 * quarter)
+            .copied()
+            .eq(quarter..3 * quarter));
+        assert!((0..len).all(|n| rh[n] == n && rh.get(n) == n));
+        assert!(rh.try_read(len).is_none());
+        assert_eq!(rh.is_full(), policy.max_len() == Some(len));
+        let rh2 = wh.shrink_unused();
+        assert_eq!(rh2.block_count(), policy.blocks_for_len(len));
+        drop((rh, rh2));
+        counter.assert_empty();
+    }
+}
+
+#[test]
+fn uniform_growth_fills_up() {
+    let (wh, rh) = Stele::with_growth(crate::GrowthPolicy::uniform(3));
+    assert_eq!(rh.remaining(), Some(96));
+    for n in 0..96 {
+        wh.push(n);
+    }
+    assert!(wh.is_full());
+    assert_eq!(rh.block_count(), 32);
+    assert_eq!(rh.capacity(), 96);
+    assert_eq!(wh.try_push(96), Err(crate::Full(96)));
+    assert_eq!(
+        wh.try_into_vec(rh).unwrap(),
+        (0..96).collect::<alloc::vec::Vec<_>>()
+    );
+}
+
+#[test]
+fn builder_options() {
+    use crate::{append::builder::SteleBuilder, GrowthPolicy, Prealloc};
+
+    let (wh, rh) = Stele::<u32>::builder().build().unwrap();
+    wh.push(0);
+    //Blocks are allocated lazily, just like with `Stele::new`
+    assert_eq!(rh.block_count(), 1);
+    assert_eq!(rh.remaining(), None);
+
+    let counter = CountingAllocator::new();
+    let (wh, rh) = SteleBuilder::new_in(&counter)
+        .capacity(100)
+        .build()
+        .unwrap();
+    assert_eq!(rh.block_count(), 8);
+    assert_eq!(rh.capacity(), 128);
+    (0..100).for_each(|n| assert_eq!(wh.push_within_capacity(n), Ok(())));
+    drop((wh, rh));
+    counter.assert_empty();
+
+    let (wh, rh) = Stele::builder().bound(3).build().unwrap();
+    (0..3).for_each(|n| wh.push(n));
+    assert_eq!(wh.try_push(3), Err(crate::Full(3)));
+    assert_eq!(rh.remaining(), Some(0));
+
+    let (wh, rh) = Stele::<u8>::builder().first_block_exp(4).build().unwrap();
+    wh.push(0);
+    assert_eq!(rh.growth(), GrowthPolicy::doubling(4));
+    assert_eq!(rh.capacity(), 16);
+
+    let (wh, rh) = Stele::builder()
+        .growth(GrowthPolicy::uniform(3))
+        .build()
+        .unwrap();
+    (0..4).for_each(|n| wh.push(n));
+    assert_eq!(rh.capacity(), 6);
+    assert_eq!(rh.remaining(), Some(92));
+
+    let counter = CountingAllocator::new();
+    let (wh, rh) = SteleBuilder::new_in(&counter)
+        .prealloc(Prealloc::Lazy)
+        .build()
+        .unwrap();
+    wh.push(0_u32);
+    assert_eq!(rh.block_count(), 1);
+    wh.push(1);
+    assert_eq!(
+        counter.layouts(),
+        [1, 1].map(|len| Layout::array::<u32>(len).unwrap())
+    );
+    drop((wh, rh));
+    counter.assert_empty();
+
+    let (wh, rh) = Stele::<u32>::builder()
+        .first_block_exp(0)
+        .prealloc(Prealloc::SmallBlocks)
+        .build()
+        .unwrap();
+    wh.push(0);
+    assert_eq!(rh.block_count(), 3);
+}
+
+#[test]
+fn builder_align() {
+    use crate::{append::builder::SteleBuilder, GrowthPolicy};
+    let counter = CountingAllocator::new();
+    let mut s = SteleBuilder::new_in(&counter)
+        .align(4096)
+        .build_owned()
+        .unwrap();
+    for n in 0..100_u16 {
+        s.push_mut(n);
+    }
+    let policy = GrowthPolicy::default();
+    for block in 0..policy.blocks_for_len(100) {
+        let first = s.read(policy.first_index_of_block(block)).unwrap();
+        assert_eq!(core::ptr::from_ref(first) as usize % 4096, 0);
+    }
+    assert!(counter
+        .layouts()
+        .iter()
+        .all(|layout| layout.align() == 4096));
+    let (wh, rh) = s.to_handles();
+    assert!(rh.iter().copied().eq(0..100));
+    let rh2 = wh.shrink_unused();
+    drop((rh, rh2));
+    counter.assert_empty();
+
+    //Alignments below that of the element change nothing
+    let (wh, rh) = Stele::builder().align(1).build().unwrap();
+    wh.push(1_u64);
+    assert_eq!(rh.read(0), &1);
+    let (wh, _rh) = Stele::builder().align(64).build().unwrap();
+    wh.push(());
+}
+
+#[test]
+fn builder_conflicts() {
+    use crate::{GrowthPolicy, Prealloc, SteleError};
+    let invalid = |result: Result<Stele<u32>, SteleError>| match result {
+        Err(SteleError::InvalidOptions { reason }) => reason,
+        other => panic!("Expected invalid options, got {:?}", other.map(|s| s.len())),
+    };
+    assert_eq!(
+        invalid(
+            Stele::builder()
+                .first_block_exp(2)
+                .growth(GrowthPolicy::uniform(4))
+                .build_owned()
+        ),
+        "`first_block_exp` and `growth` both choose the block sizes"
+    );
+    //Even when both would choose the same layout
+    assert!(Stele::<u32>::builder()
+        .first_block_exp(0)
+        .growth(GrowthPolicy::default())
+        .build()
+        .is_err());
+    assert_eq!(
+        invalid(
+            Stele::builder()
+                .growth(GrowthPolicy::uniform(4))
+                .prealloc(Prealloc::SmallBlocks)
+                .build_owned()
+        ),
+        "small blocks are only preallocated with the default growth policy"
+    );
+    for align in [0, 3, 48] {
+        assert_eq!(
+            invalid(Stele::builder().align(align).build_owned()),
+            "`align` must be a power of two"
+        );
+    }
+    assert_eq!(
+        Stele::<u32>::builder().capacity(11).bound(10).build().err(),
+        Some(SteleError::CapacityExceeded {
+            requested: 11,
+            max: 10
+        })
+    );
+    assert_eq!(
+        Stele::<u32>::builder()
+            .growth(GrowthPolicy::uniform(2))
+            .bound(65)
+            .build()
+            .err(),
+        Some(SteleError::CapacityExceeded {
+            requested: 65,
+            max: 64
+        })
+    );
+    assert_eq!(
+        Stele::<u32>::builder()
+            .growth(GrowthPolicy::uniform(2))
+            .capacity(65)
+            .build()
+            .err(),
+        Some(SteleError::CapacityExceeded {
+            requested: 65,
+            max: 64
+        })
+    );
+}
+
+#[test]
+fn builder_combined() {
+    use crate::{append::builder::SteleBuilder, Prealloc};
+    let counter = CountingAllocator::new();
+    let mut s = SteleBuilder::new_in(&counter)
+        .first_block_exp(3)
+        .capacity(20)
+        .bound(30)
+        .align(128)
+        .prealloc(Prealloc::Lazy)
+        .build_owned()
+        .unwrap();
+    //Blocks of 8 and 8 cover the first 16, and the block of 16 the rest of the capacity
+    assert_eq!(
+        counter.layouts(),
+        [8, 8, 16].map(|len| Layout::array::<u64>(len).unwrap().align_to(128).unwrap())
+    );
+    (0..20_u64).for_each(|n| s.push_mut(n));
+    assert_eq!(counter.allocations(), 3);
+    let (wh, rh) = s.to_handles();
+    assert!(wh.try_extend((20..30_u32).map(u64::from)).is_ok());
+    assert_eq!(wh.try_push(30), Err(crate::Full(30)));
+    assert!(rh.iter().copied().eq(0..30));
+    drop((wh, rh));
+    counter.assert_empty();
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+#[test]
+fn builder_allocator() {
+    let counter = CountingAllocator::new();
+    let (wh, rh) = Stele::builder()
+        .capacity(4)
+        .allocator(&counter)
+        .build()
+        .unwrap();
+    //Blocks of 1, 1 and 2 elements
+    assert_eq!(counter.allocations(), 3);
+    (0..4).for_each(|n| wh.push(n));
+    assert_eq!(counter.allocations(), 3);
+    assert!(rh.iter().copied().eq(0..4_u32));
+    drop((wh, rh));
+    counter.assert_empty();
+}
+
+#[test]
+fn shrink_unused_empty() {
+    let (wh, rh) = Stele::<u8>::new();
+    let rh2 = wh.shrink_unused();
+    assert_eq!(rh.block_count(), 0);
+    assert!(rh2.is_empty());
+}
+
+#[derive(Debug)]
+struct DropCounter<'a>(&'a core::sync::atomic::AtomicUsize);
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn cached_random_reads() {
+    extern crate std;
+    let (wh, rh) = Stele::new();
+    let cached = rh.cached();
+    assert!(cached.is_empty());
+    assert_eq!(cached.try_read(0), None);
+    std::thread::scope(|s| {
+        //Every reader has its own cache and reads whatever is published while the writer pushes
+        for seed in 1..=3_usize {
+            let cached = cached.clone();
+            s.spawn(move || {
+                let mut state = seed;
+                while cached.len() < 1000 {
+                    let len = cached.len();
+                    if len == 0 {
+                        continue;
+                    }
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    let idx = state % len;
+                    assert_eq!(cached.get(idx), idx * 3);
+                }
+            });
+        }
+        for n in 0..1000 {
+            wh.push(n * 3);
+        }
+    });
+    //Indices from every block in a scattered order, each read several times
+    let mut idx = 0;
+    for _ in 0..4000 {
+        idx = (idx + 2_654_435_761) % 1000;
+        assert_eq!(cached[idx], rh[idx]);
+        assert!(core::ptr::eq(cached.read(idx), rh.read(idx)));
+    }
+    assert_eq!(cached.try_read(1000), None);
+
+    //Blocks past the length are freed and allocated again, but none of them had been read from
+    let sealed = wh.shrink_unused();
+    let promoted = sealed.try_promote().unwrap();
+    for n in 1000..2000 {
+        promoted.push(n * 3);
+    }
+    assert!((0..2000).step_by(7).all(|idx| cached.get(idx) == idx * 3));
+    let cached = cached.into_inner().cached();
+    assert_eq!(cached.get(1999), 1999 * 3);
+}
+
+#[test]
+#[should_panic(expected = "Read past the initialized elements")]
+fn cached_read_out_of_bounds() {
+    let (wh, rh) = Stele::new();
+    wh.push(0_u32);
+    let cached = rh.cached();
+    let _ = cached.read(0);
+    let _ = cached[1];
+}
+
+#[test]
+fn concurrent_counting() {
+    extern crate std;
+    let counter = CountingAllocator::new();
+    let (wh, rh) = Stele::new_in(&counter);
+    std::thread::scope(|s| {
+        let reader = s.spawn(|| {
+            while rh.len() < 100 {
+                //Blocks are recorded before they are published, so the count can only be ahead
+                let blocks = rh.block_count();
+                assert!(counter.live_allocations() >= blocks);
+            }
+        });
+        for n in 0..100_u64 {
+            wh.push(n);
+        }
+        reader.join().unwrap();
+    });
+    assert_eq!(counter.live_allocations(), rh.block_count());
+    drop((wh, rh));
+    counter.assert_empty();
+}
+
+//Pushes 10 elements and drops the writer, returning the reader that still keeps them alive
+fn drops_elements_in<S: Storage>(
+    storage: S,
+    drops: &core::sync::atomic::AtomicUsize,
+) -> crate::ReadHandle<DropCounter<'_>, S> {
+    let (wh, rh) = Stele::new_in(storage);
+    for _ in 0..10 {
+        wh.push(DropCounter(drops));
+    }
+    drop(wh);
+    assert_eq!(drops.load(core::sync::atomic::Ordering::Relaxed), 0);
+    rh
+}
+
+#[test]
+fn drops_elements() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    let drops = AtomicUsize::new(0);
+    drop(drops_elements_in(DefaultStorage::default(), &drops));
+    assert_eq!(drops.load(Ordering::Relaxed), 10);
+    let drops = AtomicUsize::new(0);
+    let counter = CountingAllocator::new();
+    let rh = drops_elements_in(&counter, &drops);
+    assert_eq!(counter.live_allocations(), 5);
+    drop(rh);
+    assert_eq!(drops.load(Ordering::Relaxed), 10);
+    counter.assert_empty();
+}
+
+#[test]
+fn from_vec_adopts_buffer() {
+    use alloc::vec::Vec;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    let drops = AtomicUsize::new(0);
+    //A capacity of 100 fits blocks 0 to 6, so the first 64 elements stay in the buffer and the rest are moved
+    let mut v = Vec::with_capacity(100);
+    v.extend((0..100).map(|n| (n, DropCounter(&drops))));
+    let (buf, cap) = (v.as_ptr(), v.capacity());
+    let s = Stele::from(v);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    assert_eq!(s.len(), 100);
+    assert!(s.iter().map(|&(n, _)| n).eq(0..100));
+    if cap < 128 {
+        assert!(core::ptr::eq(s.read(0).unwrap(), buf));
+        assert!(core::ptr::eq(s.read(63).unwrap(), buf.wrapping_add(63)));
+        assert!(!core::ptr::eq(s.read(64).unwrap(), buf.wrapping_add(64)));
+    }
+    drop(s);
+    assert_eq!(drops.load(Ordering::Relaxed), 100);
+
+    //Spare capacity becomes room to push into without allocating
+    let mut v = Vec::with_capacity(128);
+    v.extend((0..70).map(|n| (n, DropCounter(&drops))));
+    let (wh, rh) = Stele::from(v).to_handles();
+    assert_eq!(rh.capacity(), 128);
+    assert_eq!(rh.block_count(), 8);
+    for n in 70..129 {
+        wh.push((n, DropCounter(&drops)));
+    }
+    assert_eq!(rh.block_count(), 9);
+    assert!(rh.iter().map(|&(n, _)| n).eq(0..129));
+    drop((wh, rh));
+    assert_eq!(drops.load(Ordering::Relaxed), 229);
+
+    //Blocks of the buffer past the length are unpublished but the buffer is only freed once
+    let mut v = Vec::with_capacity(128);
+    v.extend((0..20).map(|n| (n, DropCounter(&drops))));
+    let (wh, rh) = Stele::from(v).to_handles();
+    let sealed = wh.shrink_unused();
+    assert_eq!(rh.block_count(), 6);
+    assert!(sealed.iter().map(|&(n, _)| n).eq(0..20));
+    drop((rh, sealed));
+    assert_eq!(drops.load(Ordering::Relaxed), 249);
+
+    //Blocks of the buffer that were unpublished are allocated on their own when pushed into again, and freed as such
+    let counter = CountingAllocator::new();
+    let mut v = Vec::with_capacity(128);
+    v.extend((0..20).map(|n| (n, DropCounter(&drops))));
+    counter.record_allocation(
+        v.as_mut_ptr().cast(),
+        Layout::array::<(i32, DropCounter<'_>)>(v.capacity()).unwrap(),
+    );
+    //SAFETY: The counting allocator frees to the global allocator, which the Vec allocated its buffer from
+    let (wh, rh) = unsafe { Stele::from_vec_in(v, &counter) }.to_handles();
+    drop(rh);
+    let wh = wh.shrink_unused().try_promote().unwrap();
+    assert_eq!(wh.block_count(), 6);
+    for n in 20..100 {
+        wh.push((n, DropCounter(&drops)));
+    }
+    let rh = wh.new_read_handle();
+    assert!(rh.iter().map(|&(n, _)| n).eq(0..100));
+    //The buffer, and blocks 6 and 7 allocated again past the 20 elements that were kept
+    assert_eq!(counter.live_allocations(), 3);
+    drop((wh, rh));
+    assert_eq!(drops.load(Ordering::Relaxed), 349);
+    counter.assert_empty();
+
+    //Recycling keeps the adopted blocks, and moving the elements out leaves them to be freed empty
+    let mut s = Stele::from((0..50_u32).collect::<Vec<_>>());
+    s.recycle();
+    assert!(s.is_empty());
+    for _ in 0..40 {
+        s.push_mut(7);
+    }
+    assert_eq!(s.block_count(), 7);
+    assert_eq!(s.into_vec(), [7; 40]);
+
+    //Small Vecs and zero sized elements are pushed one at a time
+    assert!(Stele::from(alloc::vec![1_u8, 2]).iter().eq(&[1, 2]));
+    assert_eq!(Stele::from(alloc::vec![(); 1000]).len(), 1000);
+}
+
+#[test]
+fn push_array_drops() {
+    extern crate std;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    let drops = AtomicUsize::new(0);
+    let (wh, rh) = Stele::new();
+    for n in 0..3 {
+        wh.push((n, DropCounter(&drops)));
+    }
+    //Index 3 ends the third block and 4..7 fill the fourth
+    let first = wh.push_array([3, 4, 5, 6].map(|n| (n, DropCounter(&drops))));
+    assert_eq!(first, 3);
+    //Ends the fourth block, fills the fifth and starts the sixth
+    let first = wh.push_array(core::array::from_fn::<_, 10, _>(|n| {
+        (n + 7, DropCounter(&drops))
+    }));
+    assert_eq!(first, 7);
+    assert_eq!(wh.push_array::<0>([]), 17);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    assert_eq!(rh.len(), 17);
+    assert!(rh.iter().map(|&(n, _)| n).eq(0..17));
+
+    //An array that does not fit is dropped once without any of it being pushed
+    let (bounded, bounded_rh) = Stele::bounded(5);
+    bounded.push_array([0, 1].map(|n| (n, DropCounter(&drops))));
+    let overflow = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+        bounded.push_array([2, 3, 4, 5].map(|n| (n, DropCounter(&drops))));
+    }));
+    assert!(overflow.is_err());
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+    assert_eq!(bounded_rh.len(), 2);
+    drop((bounded, bounded_rh));
+    assert_eq!(drops.load(Ordering::Relaxed), 6);
+
+    drop((wh, rh));
+    assert_eq!(drops.load(Ordering::Relaxed), 23);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn unwind_safety() {
+    extern crate std;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::panic::catch_unwind;
+
+    #[derive(Debug, PartialEq)]
+    struct Fragile(u32);
+
+    impl Clone for Fragile {
+        fn clone(&self) -> Self {
+            assert!(self.0 != 12, "Cloned a fragile element");
+            Fragile(self.0)
+        }
+    }
+
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    let (wh, rh) = Stele::new();
+    wh.push(DropCounter(&DROPS));
+    //The iterator panics part way, after the elements before it have been pushed one at a time
+    let extended = catch_unwind(|| {
+        wh.try_extend((0..10).map(|n| {
+            assert!(n < 5, "Ran out of elements");
+            DropCounter(&DROPS)
+        }))
+    });
+    assert!(extended.is_err());
+    assert_eq!((rh.len(), rh.initialized_len()), (6, 6));
+    assert_eq!(rh.iter().count(), 6);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 0);
+    //A clone that panics while migrating leaves the source untouched and frees every block of the new Stele
+    let (fragile, fragile_rh) = Stele::new();
+    (0..20).for_each(|n| fragile.push(Fragile(n)));
+    let counter = CountingAllocator::new();
+    assert!(catch_unwind(|| fragile_rh.to_stele_in(&counter)).is_err());
+    counter.assert_empty();
+    assert!(fragile_rh.iter().map(|val| val.0).eq(0..20));
+    //The Stele keeps working after the panic
+    wh.push(DropCounter(&DROPS));
+    assert_eq!(rh.len(), 7);
+    drop((wh, rh));
+    assert_eq!(DROPS.load(Ordering::Relaxed), 7);
+}
+
+#[test]
+fn recycle() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    let drops = AtomicUsize::new(0);
+    let mut s = (0..10).map(|_| DropCounter(&drops)).collect::<Stele<_>>();
+    s.recycle();
+    assert_eq!(drops.load(Ordering::Relaxed), 10);
+    let (mut wh, rh) = s.to_handles();
+    assert!(rh.is_empty());
+    assert_eq!(rh.capacity(), 16);
+    for _ in 0..20 {
+        wh.push(DropCounter(&drops));
+    }
+    assert_eq!(rh.len(), 20);
+    assert!(!wh.try_recycle());
+    assert_eq!(rh.len(), 20);
+    drop(rh);
+    assert!(wh.try_recycle());
+    assert_eq!(drops.load(Ordering::Relaxed), 30);
+    assert!(wh.is_empty());
+    assert_eq!(wh.capacity(), 32);
+    for _ in 0..40 {
+        wh.push(DropCounter(&drops));
+    }
+    assert_eq!(wh.len(), 40);
+    assert_eq!(wh.capacity(), 64);
+    drop(wh);
+    assert_eq!(drops.load(Ordering::Relaxed), 70);
+}
+
+#[test]
+fn drop_hook() {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    //Counts the elements passed to the hook, which must arrive in index order starting from 0
+    fn counting_hook(seen: &Arc<AtomicUsize>) -> impl Fn(&usize) + Send + Sync + 'static {
+        let seen = Arc::clone(seen);
+        move |&val| assert_eq!(seen.fetch_add(1, Ordering::Relaxed), val)
+    }
+
+    let seen = Arc::new(AtomicUsize::new(0));
+    let (mut wh, rh) = Stele::new();
+    wh.set_drop_hook(counting_hook(&seen));
+    (0..10).for_each(|n| wh.push(n));
+    drop(rh);
+    assert!(wh.try_recycle());
+    assert_eq!(seen.swap(0, Ordering::Relaxed), 10);
+    //The hook is kept across a recycle, and a reservation that was never filled is not passed to it
+    (0..5).for_each(|n| wh.push(n));
+    let slot = wh.push_uninit();
+    drop(slot);
+    drop(wh);
+    assert_eq!(seen.swap(0, Ordering::Relaxed), 5);
+
+    //Elements moved out are not dropped, so the hook never sees them
+    let mut s = (0..20).collect::<Stele<_>>();
+    s.set_drop_hook(counting_hook(&seen));
+    let v = s.into_vec();
+    assert_eq!((v.len(), seen.load(Ordering::Relaxed)), (20, 0));
+}
+
+#[test]
+fn try_unwrap() {
+    let (wh, rh) = Stele::new();
+    for n in 0..10_u32 {
+        wh.push(n);
+    }
+    let mut s = wh.try_unwrap(rh).unwrap();
+    s.recycle();
+    let (wh, rh) = s.to_handles();
+    assert!(rh.is_empty());
+    assert_eq!(rh.capacity(), 16);
+    wh.push(42);
+
+    let extra = rh.clone();
+    let (wh, rh) = wh.try_unwrap(rh).unwrap_err();
+    assert_eq!(rh.len(), 1);
+    drop(extra);
+    let (_, other) = Stele::<u32>::new();
+    let (wh, other) = wh.try_unwrap(other).unwrap_err();
+    assert!(other.is_empty());
+    let s = wh.try_unwrap(rh).unwrap();
+    assert_eq!(s.len(), 1);
+}
+
+#[test]
+fn try_unwrap_in() {
+    let counter = CountingAllocator::new();
+    let (wh, rh) = Stele::new_in(&counter);
+    wh.push(42_u32);
+    let s = wh.try_unwrap(rh).unwrap();
+    assert_eq!(counter.live_allocations(), s.block_count());
+    drop(s);
+    counter.assert_empty();
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+#[test]
+fn try_unwrap_allocator() {
+    let (wh, rh) = Stele::new_in(crate::mem::Global);
+    wh.push(42_u32);
+    let extra = wh.new_read_handle();
+    let (wh, rh) = wh.try_unwrap(rh).unwrap_err();
+    drop(extra);
+    let s = wh.try_unwrap(rh).unwrap();
+    assert_eq!(s.read(0), Some(&42));
+    let _: &crate::mem::Global = s.allocator();
+}
+
+#[test]
+fn try_promote() {
+    let (wh, rh) = Stele::new();
+    wh.push(0_u32);
+    let other = rh.clone();
+    let rh = rh.try_promote().unwrap_err();
+    drop(wh);
+    let wh = rh.try_promote().unwrap();
+    //Only one reader can take over from the dropped writer
+    let other = other.try_promote().unwrap_err();
+    wh.push(1);
+    drop(wh);
+    let wh = other.try_promote().unwrap();
+    wh.push(2);
+    assert!(wh.new_read_handle().iter().copied().eq(0..3));
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn try_promote_stream() {
+    use futures::{executor::block_on, StreamExt};
+    let (wh, rh) = Stele::new();
+    wh.push(0_u32);
+    drop(wh);
+    assert_eq!(block_on(rh.stream().collect::<alloc::vec::Vec<_>>()), [0]);
+    //Streams created after a reader is promoted wait for the new writer again
+    let wh = rh.clone().try_promote().unwrap();
+    let stream = rh.stream();
+    wh.push(1);
+    drop(wh);
+    assert_eq!(block_on(stream.collect::<alloc::vec::Vec<_>>()), [0, 1]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn static_stele() {
+    extern crate std;
+    static STELE: Stele<usize> = Stele::const_new();
+    let reader = STELE.reader();
+    assert!(reader.is_empty());
+    assert!(reader.try_read(0).is_none());
+    let readers = (0..3)
+        .map(|_| {
+            std::thread::spawn(move || {
+                let mut seen = 0;
+                while seen < 1000 {
+                    if let Some(&val) = reader.try_read(seen) {
+                        assert_eq!(val, seen);
+                        seen += 1;
+                    }
+                }
+            })
+        })
+        .collect::<alloc::vec::Vec<_>>();
+    let writer = std::thread::spawn(|| {
+        let wh = STELE.claim_writer().unwrap();
+        (0..1000).for_each(|n| wh.push(n));
+    });
+    writer.join().unwrap();
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    //Dropping the claimed writer does not give write access back
+    assert!(matches!(
+        STELE.claim_writer(),
+        Err(crate::SteleError::WriterExists)
+    ));
+    assert!(reader.iter().copied().eq(0..1000));
+    assert_eq!(reader[999], 999);
+}
+
+#[test]
+fn static_read_out_of_bounds() {
+    extern crate std;
+    use std::panic::catch_unwind;
+    static STELE: Stele<u32> = Stele::const_new();
+    let wh = STELE.claim_writer().unwrap();
+    wh.push(0);
+    let reader = wh.reader();
+    assert_eq!(reader.get(0), 0);
+    //Checked in every build, not only in debug
+    assert!(catch_unwind(|| reader.read(1)).is_err());
+    assert!(catch_unwind(|| reader.get(1)).is_err());
+    assert!(catch_unwind(|| reader[1]).is_err());
+    assert!(catch_unwind(core::panic::AssertUnwindSafe(|| wh.read(1))).is_err());
+}
+
+#[test]
+fn claim_writer_once() {
+    static STELE: Stele<u32> = Stele::const_new();
+    let wh = STELE.claim_writer().unwrap();
+    assert!(matches!(
+        STELE.claim_writer(),
+        Err(crate::SteleError::WriterExists)
+    ));
+    wh.push(1);
+    let reader = wh.reader();
+    assert_eq!(reader.get(0), 1);
+    //A leaked Stele that never had handles can be claimed as well
+    let raw = alloc::boxed::Box::into_raw(alloc::boxed::Box::new((0..3).collect::<Stele<u32>>()));
+    //SAFETY: The Stele is only turned back into a box after every handle is gone
+    let leaked: &'static Stele<u32> = unsafe { &*raw };
+    let wh = leaked.claim_writer().unwrap();
+    wh.push(3);
+    assert!(leaked.reader().iter().copied().eq(0..4));
+    assert!(leaked.claim_writer().is_err());
+    drop(unsafe { alloc::boxed::Box::from_raw(raw) });
+}
+
+#[test]
+fn stele_reader() {
+    use crate::{
+        local::LocalStele,
+        reader::{SteleReader, SteleReaderCopied},
+    };
+
+    fn check<R: SteleReader<u32> + ?Sized>(reader: &R, len: u32) {
+        assert_eq!(reader.len(), len as usize);
+        assert_eq!(reader.is_empty(), len == 0);
+        assert!(reader.iter().copied().eq(0..len));
+        assert_eq!(reader.try_read(len as usize), None);
+        if len > 0 {
+            assert_eq!(reader.read(len as usize - 1), &(len - 1));
+            assert_eq!(reader.get(0), 0);
+        }
+    }
+    //The same checks through a trait object, which is how another crate would store a reader of any kind
+    fn check_dyn(reader: &dyn SteleReader<u32>, len: u32) {
+        check(reader, len);
+    }
+
+    static STELE: Stele<u32> = Stele::const_new();
+    let swh = STELE.claim_writer().unwrap();
+    check(&swh, 0);
+    (0..100).for_each(|n| swh.push(n));
+    check(&swh, 100);
+    check_dyn(&STELE.reader(), 100);
+    check(&STELE, 100);
+
+    let (wh, rh) = Stele::new();
+    (0..100).for_each(|n| wh.push(n));
+    check(&wh, 100);
+    check_dyn(&rh, 100);
+    //Unfilled reservations are counted in the length but end the iterator
+    let slot = wh.push_uninit();
+    assert_eq!(SteleReader::len(&rh), 101);
+    assert_eq!(SteleReader::iter(&rh).count(), 100);
+    slot.fill(100);
+    check(&rh, 101);
+    #[cfg(feature = "critical-section")]
+    {
+        let wh = wh.into_isr_shared();
+        check_dyn(&wh, 101);
+        let mut owned = wh.into_writer().try_unwrap(rh).unwrap();
+        owned.push_mut(101);
+        check(&owned, 102);
+    }
+    #[cfg(not(feature = "critical-section"))]
+    {
+        let mut owned = wh.try_unwrap(rh).unwrap();
+        owned.push_mut(101);
+        check(&owned, 102);
+    }
+
+    let (lwh, lrh) = LocalStele::new();
+    (0..100).for_each(|n| lwh.push(n));
+    check(&lwh, 100);
+    check_dyn(&lrh, 100);
+    check(&(0..5).collect::<LocalStele<u32>>(), 5);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn keyed_iteration() {
+    extern crate std;
+    use crate::Key;
+    use std::collections::HashMap;
+
+    let (wh, rh) = Stele::new();
+    (0..20_u32).for_each(|n| wh.push(n * 10));
+    let keyed = rh.iter_keyed();
+    assert_eq!(keyed.len(), 20);
+    //A secondary index from every key to the value it was built from
+    let index = keyed
+        .map(|(key, &val)| (key, val as usize))
+        .collect::<HashMap<Key<u32>, usize>>();
+    assert_eq!(
+        rh.keys().collect::<Vec<_>>(),
+        rh.iter_keyed().map(|(key, _)| key).collect::<Vec<_>>()
+    );
+    assert_eq!(rh.keys().next_back().map(Key::index), Some(19));
+
+    //The keys handed out before later blocks were allocated keep reading the same elements
+    let next = wh.next_key();
+    assert_eq!(next.index(), 20);
+    (20..100_u32).for_each(|n| wh.push(n * 10));
+    assert_eq!(rh.read_key(next), &200);
+    for (&key, &val) in &index {
+        assert_eq!(*rh.read_key(key) as usize, val);
+        assert_eq!(*wh.read_key(key) as usize, val);
+    }
+    assert_eq!(
+        rh.iter_keyed()
+            .skip(95)
+            .fold(0, |acc, (key, _)| acc + key.index()),
+        95 + 96 + 97 + 98 + 99
+    );
+}
+
+#[test]
+#[should_panic(expected = "Read a key past the initialized elements")]
+fn read_key_out_of_bounds() {
+    let (wh, rh) = Stele::<u32>::new();
+    //Checked in release builds too, where `read` only checks in debug
+    let _ = rh.read_key(wh.next_key());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn downcast() {
+    use core::any::Any;
+
+    #[derive(Debug, PartialEq)]
+    struct Login(u32);
+    #[derive(Debug, PartialEq)]
+    struct Logout(u32);
+    #[derive(Debug, PartialEq)]
+    struct Message(&'static str);
+
+    let (wh, rh) = Stele::<Box<dyn Any + Send + Sync>>::new();
+    assert_eq!(rh.position_of::<Login>(), None);
+    wh.push(Box::new(Message("hello")));
+    for user in 0..10_u32 {
+        wh.push(Box::new(Login(user)));
+        if user % 2 == 0 {
+            wh.push(Box::new(Message("ping")));
+        }
+        if user % 3 == 0 {
+            wh.push(Box::new(Logout(user)));
+        }
+    }
+    assert_eq!(rh.iter_downcast::<Login>().count(), 10);
+    assert_eq!(rh.iter_downcast::<Logout>().count(), 4);
+    assert_eq!(rh.iter_downcast::<Message>().count(), 6);
+    assert_eq!(rh.iter_downcast::<u32>().count(), 0);
+    assert_eq!(
+        rh.iter_downcast::<Logout>().collect::<Vec<_>>(),
+        [&Logout(0), &Logout(3), &Logout(6), &Logout(9)]
+    );
+    assert_eq!(
+        rh.iter_downcast::<Login>()
+            .fold(0, |acc, login| acc + login.0),
+        45
+    );
+    assert_eq!(rh.position_of::<Message>(), Some(0));
+    assert_eq!(rh.position_of::<Login>(), Some(1));
+    assert_eq!(rh.position_of::<Logout>(), Some(3));
+    assert_eq!(rh.position_of::<u32>(), None);
+    assert_eq!(rh.get_downcast::<Logout>(3), Some(&Logout(0)));
+    assert_eq!(rh.get_downcast::<Login>(3), None);
+    assert_eq!(rh.get_downcast::<Message>(rh.len()), None);
+    //The box is itself `Any`, but only what it holds is ever matched
+    assert_eq!(rh.iter_downcast::<Box<dyn Any + Send + Sync>>().count(), 0);
+}
+
+#[test]
+fn reader_ref() {
+    use crate::{reader::SteleReader, ReaderRef};
+    use core::ops::Index;
+
+    //Generic over everything a reader offers, so that a borrowed view and an owned handle can be passed alike
+    fn sum_and_last<'a, R>(reader: &'a R) -> (u32, u32)
+    where
+        R: SteleReader<u32> + Index<usize, Output = u32>,
+        &'a R: IntoIterator<Item = &'a u32>,
+    {
+        let sum = reader.into_iter().sum();
+        (sum, reader[SteleReader::len(reader) - 1])
+    }
+
+    let (wh, rh) = Stele::new();
+    (1..=10).for_each(|n| wh.push(n));
+    let view: ReaderRef<'_, u32> = wh.as_reader();
+    assert_eq!(sum_and_last(&view), (55, 10));
+    assert_eq!(sum_and_last(&rh), (55, 10));
+    assert_eq!(
+        (view.read(2), view.try_read(10), view.get(9)),
+        (&3, None, 10)
+    );
+    //Borrowing a view leaves the reference count alone
+    let count = crate::sync::Arc::strong_count(&wh.handle);
+    let copy = view;
+    assert_eq!(crate::sync::Arc::strong_count(&wh.handle), count);
+    //The view reads the length at the time of each call, so it sees pushes made through the writer it borrows
+    wh.push(11);
+    assert_eq!(copy.len(), 11);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn raw_multi_writer() {
+    extern crate std;
+    use crate::{
+        raw::RawStele,
+        sync::{ord, AtomicUsize},
+    };
+    use alloc::string::{String, ToString};
+    use core::mem::MaybeUninit;
+
+    //A tiny multi-writer log: writers claim indices with a ticket and publish the length in ticket order
+    struct Log {
+        raw: RawStele<String>,
+        next: AtomicUsize,
+    }
+
+    impl Log {
+        fn push(&self, val: String) -> usize {
+            let idx = self.next.fetch_add(1, ord::RLX);
+            //SAFETY: The ticket is unique, so nothing else writes this slot, and nothing reads it before it is published
+            unsafe { self.raw.slot_ptr(idx, true).write(MaybeUninit::new(val)) };
+            while self.raw.load_len() != idx {
+                std::thread::yield_now();
+            }
+            //SAFETY: Every earlier ticket published before this one, after writing its slot
+            unsafe { self.raw.publish_len(idx + 1) };
+            idx
+        }
+
+        fn read(&self, idx: usize) -> Option<&String> {
+            //SAFETY: Every slot below the published length was written and is never written again
+            (idx < self.raw.load_len())
+                .then(|| unsafe { (*self.raw.slot_ptr(idx, false)).assume_init_ref() })
+        }
+    }
+
+    impl Drop for Log {
+        fn drop(&mut self) {
+            for idx in 0..self.raw.load_len() {
+                //SAFETY: The slot was written, and `&mut self` means nothing reads it anymore
+                unsafe { (*self.raw.slot_ptr(idx, false)).assume_init_drop() };
+            }
+        }
+    }
+
+    let log = Log {
+        raw: RawStele::new(),
+        next: AtomicUsize::new(0),
+    };
+    std::thread::scope(|s| {
+        for writer in 0..4 {
+            let log = &log;
+            s.spawn(move || {
+                for n in 0..25 {
+                    let idx = log.push(alloc::format!("{writer}-{n}"));
+                    assert!(log.read(idx).unwrap().starts_with(&writer.to_string()));
+                }
+            });
+        }
+    });
+    assert_eq!(log.raw.load_len(), 100);
+    assert!(log.read(100).is_none());
+    for writer in 0..4 {
+        //Each writer's elements keep the order it pushed them in
+        let mine = (0..100)
+            .filter_map(|idx| log.read(idx))
+            .filter(|val| val.starts_with(&alloc::format!("{writer}-")));
+        assert!(mine
+            .cloned()
+            .eq((0..25).map(|n| alloc::format!("{writer}-{n}"))));
+    }
+    let table = log.raw.block_table();
+    assert_eq!(
+        log.raw.capacity(),
+        (0..32)
+            .filter(|&block| !table[block].is_null())
+            .map(|block| log.raw.growth().block_capacity(block))
+            .sum::<usize>()
+    );
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn raw_allocation_race() {
+    extern crate std;
+    use crate::{layout::GrowthPolicy, raw::RawStele};
+
+    let counter = CountingAllocator::new();
+    let raw = RawStele::<u64, _>::with_growth_in(GrowthPolicy::uniform(64), &counter);
+    assert!(raw.slot_ptr(0, false).is_null());
+    //Every thread allocates the same block, and all of them must end up with the one that was published
+    let ptrs = std::thread::scope(|s| {
+        (0..4)
+            .map(|_| s.spawn(|| raw.slot_ptr(70, true) as usize))
+            .collect::<alloc::vec::Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<alloc::vec::Vec<_>>()
+    });
+    assert!(ptrs.iter().all(|&ptr| ptr == ptrs[0]));
+    assert_eq!(raw.slot_ptr(70, false) as usize, ptrs[0]);
+    assert_eq!(raw.capacity(), 64);
+    assert_eq!(raw.block_table()[1] as usize + 6 * 8, ptrs[0]);
+    drop(raw);
+    //The losing threads freed the blocks they allocated
+    assert!(counter.allocations() >= 1);
+    counter.assert_empty();
+}
+
+#[test]
+fn bit_stele() {
+    use crate::bits::BitStele;
+    use alloc::vec::Vec;
+
+    let is_prime = |n: usize| n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d >= 1);
+    let (writer, reader) = BitStele::new();
+    assert!(reader.is_empty());
+    assert_eq!(reader.select(0), None);
+    assert_eq!(reader.rank(0), 0);
+    let mut model = Vec::new();
+    //Crosses many word boundaries and the boundaries of the blocks holding the words
+    for n in 0..3000 {
+        writer.push(is_prime(n));
+        model.push(is_prime(n));
+        if n % 97 == 0 {
+            assert!(reader.iter().eq(model.iter().copied()));
+            assert_eq!(
+                reader.count_ones(),
+                model.iter().filter(|&&bit| bit).count()
+            );
+        }
+    }
+    assert_eq!(reader.len(), model.len());
+    assert_eq!(writer.len(), model.len());
+    assert!(model
+        .iter()
+        .enumerate()
+        .all(|(idx, &bit)| reader.get(idx) == bit && writer.get(idx) == bit));
+    assert_eq!(reader.try_get(3000), None);
+    assert_eq!(reader.iter().len(), 3000);
+    let ones = model
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, &bit)| bit.then_some(idx))
+        .collect::<Vec<_>>();
+    assert_eq!(reader.count_ones(), ones.len());
+    for idx in 0..=model.len() {
+        assert_eq!(
+            reader.rank(idx),
+            ones.iter().take_while(|&&one| one < idx).count()
+        );
+    }
+    for (n, &idx) in ones.iter().enumerate() {
+        assert_eq!(reader.select(n), Some(idx));
+        assert_eq!(reader.rank(idx), n);
+    }
+    assert_eq!(reader.select(ones.len()), None);
+    //A partly filled last word does not show bits pushed after the length was loaded
+    let iter = reader.iter();
+    writer.push(true);
+    assert_eq!(iter.count(), 3000);
+    assert!(writer.new_read_handle().get(3000));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn bit_stele_concurrent() {
+    extern crate std;
+    use crate::bits::BitStele;
+
+    let (writer, reader) = BitStele::new();
+    std::thread::scope(|s| {
+        s.spawn(move || (0..300).for_each(|n| writer.push(n % 3 == 0)));
+        //Every bit below the length the reader sees was fully pushed, even in the word being filled
+        for _ in 0..10 {
+            let len = reader.len();
+            assert!(reader
+                .iter()
+                .take(len)
+                .enumerate()
+                .all(|(n, bit)| bit == (n % 3 == 0)));
+            assert_eq!(reader.rank(len), len.div_ceil(3));
+        }
+    });
+    assert_eq!(reader.count_ones(), 100);
+}
+
+#[test]
+fn into_vec() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    let drops = AtomicUsize::new(0);
+    let counter = CountingAllocator::new();
+    let empty = Stele::<DropCounter<'_>, _>::from_iter_in(core::iter::empty(), &counter);
+    assert!(empty.into_vec().is_empty());
+    //A single element only fills the first block, while 100 elements span 8 blocks
+    for len in [1, 100] {
+        let s = Stele::from_iter_in((0..len).map(|_| DropCounter(&drops)), &counter);
+        let v = s.into_vec();
+        assert_eq!(v.len(), len);
+        assert_eq!(v.capacity(), len);
+        assert_eq!(drops.load(Ordering::Relaxed), 0);
+        counter.assert_empty();
+        drop(v);
+        assert_eq!(drops.load(Ordering::Relaxed), len);
+        drops.store(0, Ordering::Relaxed);
+    }
+    let s = (0..100_u32).collect::<Stele<_>>();
+    assert!(s.into_boxed_slice().iter().copied().eq(0..100));
+}
+
+#[test]
+fn into_vec_zst() {
+    let s = (0..100).map(|_| ()).collect::<Stele<_>>();
+    let v = s.into_vec();
+    assert_eq!(v.len(), 100);
+}
+
+#[test]
+fn try_into_vec() {
+    let (wh, rh) = Stele::new();
+    for n in 0..10_u32 {
+        wh.push(n);
+    }
+    let extra = rh.clone();
+    let (wh, rh) = wh.try_into_vec(rh).unwrap_err();
+    drop(extra);
+    assert_eq!(
+        wh.try_into_vec(rh).unwrap(),
+        (0..10).collect::<alloc::vec::Vec<_>>()
+    );
+}
+
+#[test]
+fn owned_build_mutate_share() {
+    let mut s = (0..10_u32).collect::<Stele<_>>();
+    assert!(!s.is_empty());
+    for n in 10..100 {
+        s.push_mut(n);
+    }
+    assert_eq!(s.len(), 100);
+    assert_eq!(s.read(42), Some(&42));
+    assert_eq!(s.read(100), None);
+    *s.get_mut(3).unwrap() = 300;
+    assert!(s.get_mut(100).is_none());
+    //Spans several blocks
+    for n in &mut s {
+        *n *= 2;
+    }
+    assert_eq!(s.iter_mut().len(), 100);
+    assert_eq!(s.iter().copied().nth(3), Some(600));
+    assert_eq!(
+        s.iter().copied().skip(4).sum::<u32>(),
+        (4..100).sum::<u32>() * 2
+    );
+    let (wh, rh) = s.to_handles();
+    assert_eq!(rh.read(3), &600);
+    wh.push(7);
+    assert_eq!(rh.len(), 101);
+    let mut s = wh.try_unwrap(rh).unwrap();
+    *s.get_mut(100).unwrap() += 1;
+    assert_eq!(s.into_vec().last(), Some(&8));
+}
+
+#[test]
+fn owned_patch_blocks() {
+    use alloc::vec::Vec;
+
+    let (wh, rh) = Stele::new();
+    for n in 0..50_usize {
+        wh.push(n);
+    }
+    let mut s = wh.try_unwrap(rh).unwrap();
+    let blocks = s
+        .make_mut_slice_blocks()
+        .map(|block| block.len())
+        .collect::<Vec<_>>();
+    assert_eq!(blocks, [1, 1, 2, 4, 8, 16, 18]);
+    //Patch every element to point at the first element of the next block
+    let mut start = 0;
+    for block in s.make_mut_slice_blocks() {
+        start += block.len();
+        block.fill(start);
+    }
+    for n in s.iter_mut().skip(40) {
+        *n += 1000;
+    }
+    let (wh, rh) = s.to_handles();
+    assert_eq!(rh.get(0), 1);
+    assert_eq!(rh.get(5), 8);
+    assert_eq!(rh.get(39), 50);
+    assert_eq!(rh.get(40), 1050);
+    wh.push(7);
+    assert_eq!(rh.len(), 51);
+
+    //Blocks stop before the first reservation that was never filled
+    let slot = wh.push_uninit();
+    wh.push(9);
+    drop(slot);
+    let mut s = wh.try_unwrap(rh).unwrap();
+    assert_eq!(
+        s.make_mut_slice_blocks()
+            .map(|block| block.len())
+            .sum::<usize>(),
+        51
+    );
+    let mut empty = core::iter::empty::<u8>().collect::<Stele<_>>();
+    assert_eq!(empty.make_mut_slice_blocks().count(), 0);
+}
+
+#[test]
+fn push_dedup() {
+    let (wh, rh) = Stele::new();
+    //The first element is always pushed
+    assert_eq!(wh.push_dedup(3_u32), Some(0));
+    assert_eq!(wh.push_dedup(3), None);
+    //Alternating values are all kept
+    for (i, &n) in [4, 3, 4, 3].iter().enumerate() {
+        assert_eq!(wh.push_dedup(n), Some(i + 1));
+    }
+    //A long run only keeps its first element
+    assert_eq!((0..1000).filter_map(|_| wh.push_dedup(7)).count(), 1);
+    assert!(rh.iter().copied().eq([3, 4, 3, 4, 3, 7]));
+
+    //Runs are collapsed within the items and against the element pushed before them
+    assert_eq!(wh.extend_dedup([7, 7, 8, 8, 8, 7, 9, 9]), 3);
+    assert!(rh.iter().copied().skip(6).eq([8, 7, 9]));
+    assert_eq!(wh.extend_dedup(core::iter::empty()), 0);
+
+    assert_eq!(wh.push_dedup_by(10, |a, b| a / 10 == b / 10), Some(9));
+    assert_eq!(wh.push_dedup_by(19, |a, b| a / 10 == b / 10), None);
+    assert_eq!(wh.push_dedup_by_key(12, |n| n % 2), None);
+    assert_eq!(wh.push_dedup_by_key(13, |n| n % 2), Some(10));
+    assert_eq!(rh.len(), 11);
+
+    //An unfilled reservation is never equal to anything
+    let slot = wh.push_uninit();
+    assert_eq!(wh.push_dedup(13), Some(12));
+    slot.fill(13);
+    assert_eq!(wh.push_dedup(13), None);
+}
+
+#[test]
+fn push_monotonic() {
+    use crate::MonotonicError;
+    let (wh, rh) = Stele::new();
+    assert!(rh.is_sorted());
+    //Anything can be pushed first
+    assert_eq!(wh.push_strictly_monotonic(5.0_f64), Ok(0));
+    assert_eq!(wh.push_monotonic(5.0), Ok(1));
+    assert_eq!(
+        wh.push_strictly_monotonic(5.0),
+        Err(MonotonicError {
+            value: 5.0,
+            last: 1
+        })
+    );
+    assert_eq!(wh.push_strictly_monotonic(6.5), Ok(2));
+    let err = wh.push_monotonic(6.25).unwrap_err();
+    assert_eq!(err.last, 2);
+    assert_eq!(
+        err.to_string(),
+        "the value is out of order with the element at index 2"
+    );
+    assert_eq!(err.into_inner().to_bits(), 6.25_f64.to_bits());
+    //NaN cannot be ordered against anything
+    assert!(wh.push_monotonic(f64::NAN).is_err());
+    assert!(rh.iter().copied().eq([5.0, 5.0, 6.5]));
+    assert!(rh.is_sorted());
+
+    //Values pushed around the checks can still break the order, which is what `is_sorted` catches
+    wh.push(1.0);
+    assert!(!rh.is_sorted());
+    let (wh, rh) = Stele::new();
+    wh.push(f64::NAN);
+    assert!(rh.is_sorted());
+    wh.push(f64::NAN);
+    assert!(!rh.is_sorted());
+}
+
+#[test]
+fn get_disjoint() {
+    use crate::SteleError;
+    let (wh, rh) = Stele::new();
+    for i in 0..100_usize {
+        wh.push(i * 2);
+    }
+    //Indices from the first, a middle and the last allocated block, with a repeat
+    assert_eq!(
+        rh.get_disjoint([0, 70, 3, 70, 99]),
+        Some([&0, &140, &6, &140, &198])
+    );
+    assert_eq!(rh.get_disjoint([]), Some([]));
+    assert_eq!(rh.get_disjoint([1, 100, 2]), None);
+
+    let mut out = vec![&1];
+    rh.read_many(&[64, 0, 17], &mut out).unwrap();
+    assert_eq!(out, [&1, &128, &0, &34]);
+    assert_eq!(
+        rh.read_many(&[5, 150, 200], &mut out),
+        Err(SteleError::OutOfBounds {
+            index: 150,
+            len: 100
+        })
+    );
+    assert_eq!(out.len(), 4);
+
+    //Elements behind an unfilled reservation are out of bounds until it is filled
+    let slot = wh.push_uninit();
+    wh.push(202);
+    assert_eq!(rh.get_disjoint([4, 101]), None);
+    slot.fill(200);
+    assert_eq!(rh.get_disjoint([4, 101]), Some([&8, &202]));
+}
+
+#[test]
+fn owned_empty() {
+    let mut s = core::iter::empty::<alloc::string::String>().collect::<Stele<_>>();
+    assert!(s.is_empty());
+    assert_eq!(s.read(0), None);
+    assert!(s.get_mut(0).is_none());
+    assert_eq!(s.iter().count(), 0);
+    assert_eq!(s.iter_mut().count(), 0);
+    s.push_mut("stele".into());
+    s.get_mut(0).unwrap().push('s');
+    assert_eq!(s.read(0).map(alloc::string::String::as_str), Some("steles"));
+}
+
+#[test]
+fn owned_unfilled() {
+    let (wh, rh) = Stele::new();
+    wh.push(0_u32);
+    drop(wh.push_uninit());
+    wh.push(2);
+    let mut s = wh.try_unwrap(rh).unwrap();
+    assert_eq!(s.len(), 3);
+    assert_eq!(s.read(1), None);
+    assert!(s.get_mut(1).is_none());
+    *s.get_mut(2).unwrap() = 20;
+    assert_eq!(s.read(2), Some(&20));
+    //Iterators stop before the first reservation that was never filled
+    assert_eq!(s.iter_mut().count(), 1);
+    assert_eq!(s.iter().count(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Pushed to a full Stele")]
+fn push_mut_full() {
+    let (wh, rh) = Stele::bounded(1);
+    wh.push(0_u32);
+    let mut s = wh.try_unwrap(rh).unwrap();
+    s.push_mut(1);
+}
+
+#[test]
+fn bounded() {
+    use crate::Full;
+    let (wh, rh) = Stele::bounded(5);
+    assert_eq!(rh.remaining(), Some(5));
+    for n in 0..5_u32 {
+        assert!(!wh.is_full());
+        assert_eq!(wh.remaining(), Some(5 - n as usize));
+        assert_eq!(wh.try_push(n), Ok(()));
+    }
+    assert!(rh.is_full());
+    assert_eq!(rh.remaining(), Some(0));
+    assert_eq!(wh.try_push(5), Err(Full(5)));
+    assert_eq!(
+        wh.push_within_capacity(5),
+        Err(crate::PushError {
+            value: 5,
+            error: crate::SteleError::CapacityExceeded {
+                requested: 6,
+                max: 5
+            }
+        })
+    );
+    assert_eq!(rh.len(), 5);
+    assert!(rh.iter().copied().eq(0..5));
+
+    let (wh, rh) = Stele::<u32>::new();
+    assert_eq!(rh.remaining(), None);
+    assert!(!wh.is_full());
+
+    let (wh, rh) = Stele::bounded(0);
+    assert!(rh.is_full());
+    assert_eq!(wh.try_push(0_u8).unwrap_err().into_inner(), 0);
+}
+
+#[test]
+fn bounded_extend() {
+    let (wh, rh) = Stele::bounded(8);
+    assert!(wh.try_extend(0..5_u32).is_ok());
+    //A batch that does not fit as a whole is rejected without pushing anything
+    let rest = wh.try_extend(5..9).unwrap_err().into_inner();
+    assert!(rest.eq(5..9));
+    assert_eq!(rh.len(), 5);
+    assert!(wh.try_extend(5..8).is_ok());
+    assert!(rh.is_full());
+    assert!(wh.try_extend(core::iter::empty()).is_ok());
+    assert!(wh.try_extend(8..9).is_err());
+    assert!(rh.iter().copied().eq(0..8));
+
+    let (wh, rh) = Stele::new();
+    assert!(wh.try_extend(0..100_u32).is_ok());
+    assert_eq!(rh.len(), 100);
+}
+
+#[test]
+#[should_panic(expected = "Pushed to a full Stele")]
+fn bounded_push_panics() {
+    let (wh, _rh) = Stele::bounded(1);
+    wh.push(0_u32);
+    wh.push(1);
+}
+
+#[test]
+fn bounded_recycle() {
+    let (mut wh, rh) = Stele::bounded(3);
+    for n in 0..3_u32 {
+        wh.push(n);
+    }
+    assert!(rh.is_full());
+    drop(rh);
+    assert!(wh.try_recycle());
+    assert_eq!(wh.remaining(), Some(3));
+    for n in 0..3 {
+        wh.push(n);
+    }
+    assert!(wh.is_full());
+}
+
+#[test]
+fn sharded() {
+    extern crate std;
+    use crate::sharded::ShardedStele;
+    let (writers, rh) = ShardedStele::<(usize, usize)>::new(4);
+    assert_eq!(rh.shard_count(), 4);
+    assert!(rh.is_empty());
+    std::thread::scope(|s| {
+        for (shard, wh) in writers.into_iter().enumerate() {
+            s.spawn(move || {
+                for n in 0..1000 {
+                    wh.push((shard, n));
+                }
+            });
+        }
+        while rh.len() < 4000 {
+            //Every shard is laid out in push order even while the writers are pushing
+            let mut last: Option<(usize, usize)> = None;
+            for &(shard, n) in rh.iter() {
+                match last {
+                    Some((last_shard, last_n)) if last_shard == shard => assert_eq!(n, last_n + 1),
+                    _ => assert_eq!(n, 0),
+                }
+                last = Some((shard, n));
+            }
+        }
+    });
+    assert_eq!(rh.len(), 4000);
+    assert_eq!(rh.iter().count(), 4000);
+    for idx in 0..4000 {
+        assert_eq!(rh.try_read(idx), Some(&(idx % 4, idx / 4)));
+    }
+    assert!(rh.try_read(4000).is_none());
+    assert_eq!(rh.shard(2).read(7), &(2, 7));
+}
+
+#[test]
+fn sharded_sequenced() {
+    extern crate std;
+    use crate::sharded::ShardedStele;
+    use core::sync::atomic::{AtomicU64, Ordering};
+    let seq = AtomicU64::new(0);
+    let (writers, rh) = ShardedStele::new(3);
+    std::thread::scope(|s| {
+        for wh in writers {
+            let seq = &seq;
+            s.spawn(move || {
+                for n in 0..100_u32 {
+                    wh.push((seq.fetch_add(1, Ordering::Relaxed), n));
+                }
+            });
+        }
+    });
+    assert!(rh.iter_sequenced().map(|&(seq, _)| seq).eq(0..300));
+    assert!(ShardedStele::<(u64, u32)>::new(0)
+        .1
+        .iter_sequenced()
+        .next()
+        .is_none());
+}
+
+#[test]
+fn merge_sorted_runs() {
+    use crate::merge::{merge, merge_by};
+    use alloc::vec::Vec;
+
+    let (evens_wh, evens) = Stele::new();
+    let (odds_wh, odds) = Stele::new();
+    (0..20_u32).for_each(|n| evens_wh.push(n * 2));
+    (0..10_u32).for_each(|n| odds_wh.push(n * 2 + 1));
+    let merged = merge([&evens, &odds]);
+    assert_eq!(merged.len(), 30);
+    assert!(merged.copied().eq((0..20).chain((10..20).map(|n| n * 2))));
+
+    //Sizes on either side of the block boundaries at 8 and 16, plus one empty run
+    let runs = [7_usize, 8, 0, 9, 16, 17]
+        .iter()
+        .enumerate()
+        .map(|(source, &len)| {
+            let (wh, rh) = Stele::new();
+            //Every run repeats the keys of the others, so equal keys come from several sources
+            (0..len).for_each(|n| wh.push((n * (source + 1) / 2, source)));
+            (wh, rh)
+        })
+        .collect::<Vec<_>>();
+    let handles = runs.iter().map(|(_, rh)| rh.clone()).collect::<Vec<_>>();
+    let mut expected = handles
+        .iter()
+        .flat_map(|rh| rh.iter().copied())
+        .collect::<Vec<_>>();
+    //A stable sort keeps equal keys in source order, and in push order within a source
+    expected.sort_by_key(|&(key, _)| key);
+    let merged = merge_by(&handles, |a, b| a.0.cmp(&b.0)).with_source();
+    assert_eq!(merged.len(), expected.len());
+    let merged = merged.collect::<Vec<_>>();
+    assert!(merged
+        .iter()
+        .all(|&(source, &(_, pushed_by))| source == pushed_by));
+    assert!(merged
+        .iter()
+        .map(|&(_, &val)| val)
+        .eq(expected.iter().copied()));
+
+    //Each run is read up to the length it had when the merge started
+    let mut merged = merge(&handles);
+    runs[0].0.push((0, 0));
+    assert_eq!(merged.by_ref().count(), expected.len());
+    assert_eq!(merged.next(), None);
+    assert_eq!(merge(&handles).count(), expected.len() + 1);
+
+    //Runs sorted in descending order merge by a reversed comparison
+    let (wh, high) = Stele::new();
+    for n in [9_u32, 5, 5, 1] {
+        wh.push(n);
+    }
+    let (wh, low) = Stele::new();
+    for n in [6_u32, 5, 0] {
+        wh.push(n);
+    }
+    assert!(merge_by([&high, &low], |a: &u32, b: &u32| b.cmp(a))
+        .with_source()
+        .map(|(source, &n)| (source, n))
+        .eq([(0, 9), (1, 6), (0, 5), (0, 5), (1, 5), (0, 1), (1, 0)]));
+    assert_eq!(
+        merge(core::iter::empty::<&crate::ReadHandle<u32>>()).next(),
+        None
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn rotating_log() {
+    use crate::rotate::RotatingLog;
+    let mut log = RotatingLog::new(4);
+    assert!(log.is_empty());
+    //A full segment only rotates once the next element arrives
+    for n in 0..4 {
+        assert_eq!(log.push(n * 10), n);
+    }
+    assert_eq!(log.segment_count(), 1);
+    assert_eq!(log.push(40), 4);
+    assert_eq!(log.segment_count(), 2);
+    for n in 5..10 {
+        log.push(n * 10);
+    }
+    assert_eq!(log.segment_count(), 3);
+    assert_eq!(log.len(), 10);
+    assert!(log.iter().copied().eq((0..10).map(|n| n * 10)));
+    assert_eq!(log.try_read(3), Some(&30));
+    assert_eq!(log.try_read(4), Some(&40));
+    assert_eq!(log.try_read(10), None);
+
+    //Rotating early leaves a short segment, and rotating an empty one does nothing
+    log.rotate();
+    log.rotate();
+    assert_eq!(log.segment_count(), 4);
+    assert_eq!(log.push(100), 10);
+
+    let mut reader = log.reader();
+    assert_eq!(log.retire(2), 2);
+    assert_eq!(log.first_index(), 8);
+    assert_eq!(log.try_read(7), None);
+    assert_eq!(log.len(), 3);
+    //Only the current segment is left, which is never retired
+    assert_eq!(log.retire(5), 1);
+    assert_eq!(log.retire(1), 0);
+    assert_eq!(log.first_index(), 10);
+
+    //The reader holds on to what it has seen until it refreshes
+    assert_eq!(reader.try_read(0), Some(&0));
+    assert_eq!(reader.len(), 11);
+    assert!(reader.refresh());
+    assert!(!reader.refresh());
+    assert_eq!(reader.try_read(0), None);
+    assert!(reader.iter().eq(&[100]));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn rotating_log_releases_memory() {
+    use crate::rotate::RotatingLog;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    let drops = AtomicUsize::new(0);
+    let mut log = RotatingLog::new(3);
+    for n in 0..9 {
+        log.push((n, DropCounter(&drops)));
+    }
+    let mut reader = log.reader();
+    let held = reader.clone();
+    log.retire(1);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    reader.refresh();
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    //The last reader holding the retired segment lets go of it
+    drop(held);
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+    log.retire(1);
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+    reader.refresh();
+    assert_eq!(drops.load(Ordering::Relaxed), 6);
+    assert!(reader.iter().map(|&(n, _)| n).eq(6..9));
+    drop((log, reader));
+    assert_eq!(drops.load(Ordering::Relaxed), 9);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn rotating_log_tail() {
+    extern crate std;
+    use crate::rotate::RotatingLog;
+    let mut log = RotatingLog::new(5);
+    let mut tail = log.reader().tail_from(0);
+    assert_eq!(tail.next(), None);
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            //Follows the log across every rotation without missing or repeating an element
+            let mut expected = 0;
+            while expected < 100 {
+                if let Some(&n) = tail.next() {
+                    assert_eq!(n, expected);
+                    expected += 1;
+                }
+            }
+        });
+        for n in 0..100 {
+            log.push(n);
+        }
+    });
+    assert_eq!(tail.position(), 100);
+    assert_eq!(tail.skipped(), 0);
+
+    //A tail keeps reading the segments it holds after they are retired, and moves on to new ones when it runs out
+    let mut behind = log.reader().tail_from(3);
+    assert_eq!(behind.next(), Some(&3));
+    assert_eq!(log.retire(100), 19);
+    log.push(100);
+    for n in 4..=100 {
+        assert_eq!(behind.next(), Some(&n));
+    }
+    assert_eq!(behind.skipped(), 0);
+    assert_eq!(behind.into_reader().first_index(), 95);
+
+    //One that starts before the oldest element that is left carries on from there
+    let mut late = log.reader().tail_from(3);
+    assert_eq!(late.next(), Some(&95));
+    assert_eq!(late.skipped(), 92);
+    assert_eq!(late.position(), 96);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn broadcast() {
+    extern crate std;
+    use crate::channel::{broadcast, RecvError, RecvTimeoutError, TryRecvError};
+    use core::time::Duration;
+    let (tx, rx) = broadcast();
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(1)),
+        Err(RecvTimeoutError::Timeout)
+    );
+    let handles = (0..3)
+        .map(|_| {
+            let rx = rx.clone();
+            std::thread::spawn(move || rx.iter().copied().collect::<alloc::vec::Vec<_>>())
+        })
+        .collect::<alloc::vec::Vec<_>>();
+    for n in 0..1000_u32 {
+        tx.send(n);
+    }
+    let late = rx.resubscribe();
+    assert_eq!(late.try_recv(), Err(TryRecvError::Empty));
+    drop(tx);
+    assert_eq!(late.recv(), Err(RecvError));
+    //Every receiver sees every element exactly once and in order
+    for handle in handles {
+        assert!(handle.join().unwrap().into_iter().eq(0..1000));
+    }
+    assert_eq!(rx.recv(), Ok(&0));
+    assert!(rx.iter().copied().eq(1..1000));
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(1)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn broadcast_disconnect_wakes_receivers() {
+    extern crate std;
+    use crate::channel::{broadcast, RecvError};
+    let (tx, rx) = broadcast::<u32>();
+    let handles = (0..2)
+        .map(|_| {
+            let rx = rx.clone();
+            std::thread::spawn(move || rx.recv().copied())
+        })
+        .collect::<alloc::vec::Vec<_>>();
+    std::thread::sleep(core::time::Duration::from_millis(10));
+    drop(tx);
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), Err(RecvError));
+    }
+}
+
+/// Checks that every producer's messages were pushed in the order they were sent, and that none are missing
+#[cfg(feature = "std")]
+fn assert_producer_order(reader: &crate::ReadHandle<(usize, u32)>, producers: usize, sent: u32) {
+    let mut next = alloc::vec![0; producers];
+    for &(producer, seq) in reader {
+        assert_eq!(seq, next[producer]);
+        next[producer] += 1;
+    }
+    assert!(next.iter().all(|&n| n == sent));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn drain_channel() {
+    extern crate std;
+    use core::time::Duration;
+    use std::{sync::mpsc, time::Instant};
+
+    let (wh, rh) = Stele::new();
+    let (tx, rx) = mpsc::channel();
+    let producers = (0..4)
+        .map(|producer| {
+            let tx = tx.clone();
+            std::thread::spawn(move || (0..1000).for_each(|seq| tx.send((producer, seq)).unwrap()))
+        })
+        .collect::<alloc::vec::Vec<_>>();
+    drop(tx);
+    //Returns once every producer is done and has dropped its sender
+    let deadline = Instant::now() + Duration::from_secs(30);
+    assert_eq!(wh.drain_channel_blocking(&rx, deadline), 4000);
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    assert_eq!(rh.len(), 4000);
+    assert_producer_order(&rh, 4, 1000);
+
+    let (tx, rx) = mpsc::channel();
+    assert_eq!(wh.drain_channel(&rx), 0);
+    (0..10).for_each(|seq| tx.send((4, seq)).unwrap());
+    assert_eq!(wh.drain_channel(&rx), 10);
+    assert_eq!(rh.read(4009), &(4, 9));
+    //Gives up at the deadline while the sender is still around
+    let start = Instant::now();
+    let deadline = start + Duration::from_millis(20);
+    assert_eq!(wh.drain_channel_blocking(&rx, deadline), 0);
+    assert!(start.elapsed() >= Duration::from_millis(20));
+
+    //Messages that do not fit in a bounded Stele stay in the channel
+    let (wh, rh) = Stele::bounded(5);
+    let (tx, rx) = mpsc::channel();
+    (0..8).for_each(|n| tx.send(n).unwrap());
+    assert_eq!(wh.drain_channel(&rx), 5);
+    assert_eq!(wh.drain_channel_blocking(&rx, deadline), 0);
+    assert!(rh.iter().copied().eq(0..5));
+    assert!(rx.try_iter().eq(5..8));
+}
+
+#[cfg(feature = "crossbeam-channel")]
+#[test]
+fn drain_crossbeam() {
+    extern crate std;
+    use core::time::Duration;
+    use std::time::Instant;
+
+    let (wh, rh) = Stele::new();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    std::thread::scope(|s| {
+        for producer in 0..3 {
+            let tx = tx.clone();
+            s.spawn(move || (0..500).for_each(|seq| tx.send((producer, seq)).unwrap()));
+        }
+        drop(tx);
+        let deadline = Instant::now() + Duration::from_secs(30);
+        assert_eq!(wh.drain_crossbeam_blocking(&rx, deadline), 1500);
+    });
+    assert_producer_order(&rh, 3, 500);
+    assert_eq!(wh.drain_crossbeam(&rx), 0);
+}
+
+#[cfg(feature = "futures")]
+struct CountingWaker(core::sync::atomic::AtomicUsize);
+
+#[cfg(feature = "futures")]
+impl std::task::Wake for CountingWaker {
+    fn wake(self: alloc::sync::Arc<Self>) {
+        self.0.fetch_add(1, core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn stream() {
+    use alloc::sync::Arc;
+    use core::{
+        pin::Pin,
+        sync::atomic::{AtomicUsize, Ordering},
+        task::{Context, Poll},
+    };
+    use futures_core::Stream;
+    let wake_count = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker = Arc::clone(&wake_count).into();
+    let mut cx = Context::from_waker(&waker);
+    let (wh, rh) = Stele::new();
+    wh.push(0_u32);
+    let mut stream = rh.stream();
+    assert_eq!(
+        Pin::new(&mut stream).poll_next(&mut cx),
+        Poll::Ready(Some(0))
+    );
+    assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+    assert_eq!(wake_count.0.load(Ordering::SeqCst), 0);
+    wh.push(1);
+    assert_eq!(wake_count.0.load(Ordering::SeqCst), 1);
+    //The waker is only registered again once the stream runs out of elements
+    wh.push(2);
+    assert_eq!(wake_count.0.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        Pin::new(&mut stream).poll_next(&mut cx),
+        Poll::Ready(Some(1))
+    );
+    assert_eq!(
+        Pin::new(&mut stream).poll_next(&mut cx),
+        Poll::Ready(Some(2))
+    );
+    assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+    drop(wh);
+    assert_eq!(wake_count.0.load(Ordering::SeqCst), 2);
+    assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(None));
+    let mut replay = rh.stream();
+    assert_eq!(replay.size_hint(), (3, None));
+    assert_eq!(
+        Pin::new(&mut replay).poll_next(&mut cx),
+        Poll::Ready(Some(0))
+    );
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn stream_concurrent() {
+    extern crate std;
+    use alloc::sync::Arc;
+    use core::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+    use futures_core::Stream;
+
+    struct Unpark(std::thread::Thread);
+
+    impl std::task::Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let (wh, rh) = Stele::new();
+    let mut stream = rh.stream();
+    let writer = std::thread::spawn(move || {
+        for n in 0..1000_u32 {
+            wh.push(n);
+        }
+    });
+    let waker = Arc::new(Unpark(std::thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut expected = 0;
+    loop {
+        match Pin::new(&mut stream).poll_next(&mut cx) {
+            Poll::Ready(Some(n)) => {
+                assert_eq!(n, expected);
+                expected += 1;
+            }
+            Poll::Ready(None) => break,
+            //A lost wakeup would leave this parked forever
+            Poll::Pending => std::thread::park(),
+        }
+    }
+    assert_eq!(expected, 1000);
+    writer.join().unwrap();
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn sink() {
+    use futures::{executor::block_on, stream, SinkExt, StreamExt};
+    let (wh, rh) = Stele::new();
+    let mut stream = rh.stream();
+    block_on(stream::iter((0..1000_u32).map(Ok)).forward(wh.sink())).unwrap();
+    assert_eq!(rh.len(), 1000);
+    //Forwarding closes the sink, which ends the stream once it has caught up
+    assert!(block_on((&mut stream).collect::<alloc::vec::Vec<_>>())
+        .into_iter()
+        .eq(0..1000));
+    assert_eq!(block_on(stream.next()), None);
+
+    let (wh, rh) = Stele::bounded(2);
+    let mut sink = wh.sink();
+    block_on(sink.send(0_u32)).unwrap();
+    block_on(sink.send(1)).unwrap();
+    assert_eq!(block_on(sink.send(2)), Err(crate::Full(2)));
+    assert_eq!(rh.len(), 2);
+}
+
+#[cfg(feature = "tokio-io")]
+#[tokio::test]
+async fn tokio_copy() {
+    use crate::append::io::SteleReader;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let bytes = (0..=255_u8)
+        .cycle()
+        .take(5000)
+        .collect::<alloc::vec::Vec<_>>();
+    let (mut src, src_rh) = Stele::new();
+    src.write_all(&bytes).await.unwrap();
+    src.shutdown().await.unwrap();
+    let (mut dst, dst_rh) = Stele::new();
+    let copied = tokio::io::copy(&mut SteleReader::new(src_rh), &mut dst)
+        .await
+        .unwrap();
+    assert_eq!(copied, 5000);
+    assert!(dst_rh.iter().eq(bytes.iter()));
+    //Writing after a shutdown fails instead of pushing behind the readers' backs
+    assert!(src.write_all(&[0]).await.is_err());
+    dst.shutdown().await.unwrap();
+    let mut out = alloc::vec::Vec::new();
+    SteleReader::new(dst_rh)
+        .read_to_end(&mut out)
+        .await
+        .unwrap();
+    assert_eq!(out, bytes);
+}
+
+#[cfg(feature = "tokio-io")]
+#[tokio::test]
+async fn tokio_read_before_write() {
+    use crate::append::io::SteleReader;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+    let (mut wh, rh) = Stele::new();
+    let mut reader = SteleReader::new(rh);
+    let read = async {
+        let mut lines = alloc::vec::Vec::new();
+        let mut line = alloc::string::String::new();
+        while reader.read_line(&mut line).await.unwrap() != 0 {
+            lines.push(core::mem::take(&mut line));
+        }
+        lines
+    };
+    let write = async {
+        //Let the reader find the Stele empty and wait first
+        tokio::task::yield_now().await;
+        for n in 0..100 {
+            wh.write_all(alloc::format!("line {n}\n").as_bytes())
+                .await
+                .unwrap();
+            tokio::task::yield_now().await;
+        }
+        wh.shutdown().await.unwrap();
+    };
+    let (lines, ()) = tokio::join!(read, write);
+    assert_eq!(lines.len(), 100);
+    assert_eq!(lines[42], "line 42\n");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn condvar_notify() {
+    blocking_waits(crate::CondvarNotify::default());
+}
+
+#[cfg(feature = "atomic-wait")]
+#[test]
+fn atomic_wait_notify() {
+    blocking_waits(crate::AtomicWaitNotify::default());
+}
+
+#[cfg(any(feature = "std", feature = "atomic-wait"))]
+fn blocking_waits(notifier: impl crate::Notify + Send + Sync + 'static) {
+    extern crate std;
+    let mut s = (0..0_u32).collect::<Stele<_>>();
+    s.set_notifier(notifier);
+    let (wh, rh) = s.to_handles();
+    let waiters = (0..3)
+        .map(|_| {
+            let rh = rh.clone();
+            std::thread::spawn(move || (rh.wait_for_len(100), rh.wait_for_len(1000)))
+        })
+        .collect::<alloc::vec::Vec<_>>();
+    for n in 0..100 {
+        wh.push(n);
+    }
+    //Dropping the writer wakes everything still waiting for elements that will never come
+    drop(wh);
+    for waiter in waiters {
+        assert_eq!(waiter.join().unwrap(), (true, false));
+    }
+    assert!(rh.wait_for_len(50));
+}
+
+#[derive(Debug, Default)]
+struct MockNotify {
+    notifications: alloc::sync::Arc<core::sync::atomic::AtomicUsize>,
+}
+
+impl crate::Notify for MockNotify {
+    fn notify_all(&self) {
+        self.notifications
+            .fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn wait_until(&self, pred: &mut dyn FnMut() -> bool) {
+        assert!(pred(), "Only waits for conditions that already hold");
+    }
+}
+
+#[test]
+fn mock_notify() {
+    use core::sync::atomic::Ordering;
+    let notify = MockNotify::default();
+    let notifications = alloc::sync::Arc::clone(&notify.notifications);
+    let mut s = (0..3_u32).collect::<Stele<_>>();
+    //Elements pushed before the notifier is set do not notify
+    s.set_notifier(notify);
+    let (wh, rh) = s.to_handles();
+    for n in 3..10 {
+        wh.push(n);
+    }
+    assert_eq!(notifications.load(Ordering::Relaxed), 7);
+    assert!(rh.wait_for_len(10));
+    drop(wh);
+    assert_eq!(notifications.load(Ordering::Relaxed), 8);
+    assert!(!rh.wait_for_len(11));
+}
+
+#[test]
+#[should_panic(expected = "Waiting requires a notifier to be set")]
+fn wait_without_notifier() {
+    let (_wh, rh) = Stele::<u32>::new();
+    let _ = rh.wait_for_len(1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn wait_for_match() {
+    extern crate std;
+    use core::time::Duration;
+
+    let mut s = (0..10_u32).collect::<Stele<_>>();
+    s.set_notifier(crate::CondvarNotify::default());
+    let (wh, rh) = s.to_handles();
+    //A match that is already there is returned without waiting, and the search starts at `start`
+    assert_eq!(rh.wait_for_match(0, |&n| n % 4 == 3), Some((3, &3)));
+    assert_eq!(rh.wait_for_match(4, |&n| n % 4 == 3), Some((7, &7)));
+    assert_eq!(
+        rh.wait_for_match_timeout(8, |&n| n == 100, Duration::from_millis(10)),
+        Err(crate::SteleError::TimedOut { next: 10 })
+    );
+
+    //A match pushed later wakes the waiter, which checks every element once
+    let waiter = {
+        let rh = rh.clone();
+        std::thread::spawn(move || {
+            let mut checked = 0;
+            let found = rh
+                .wait_for_match(10, |&n| {
+                    checked += 1;
+                    n == 50
+                })
+                .map(|(idx, &n)| (idx, n));
+            (found, checked)
+        })
+    };
+    let never = {
+        let rh = rh.clone();
+        std::thread::spawn(move || rh.wait_for_match(0, |&n| n > 1000).is_none())
+    };
+    (10..100).for_each(|n| wh.push(n));
+    assert_eq!(waiter.join().unwrap(), (Some((50, 50)), 41));
+    //Dropping the writer without a match ends the wait, even with a timeout left
+    drop(wh);
+    assert!(never.join().unwrap());
+    assert_eq!(
+        rh.wait_for_match_timeout(0, |&n| n > 1000, Duration::from_secs(30)),
+        Ok(None)
+    );
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn wait_for_match_async() {
+    extern crate std;
+    use core::task::{Context, Poll};
+    use futures::{executor::block_on, task::noop_waker, FutureExt};
+
+    let (wh, rh) = Stele::new();
+    (0..5_u32).for_each(|n| wh.push(n));
+    assert_eq!(
+        block_on(rh.wait_for_match_async(0, |&n| n == 3)),
+        Some((3, &3))
+    );
+
+    let mut fut = rh.wait_for_match_async(0, |&n| n == 7);
+    let waker = noop_waker();
+    assert_eq!(
+        fut.poll_unpin(&mut Context::from_waker(&waker)),
+        Poll::Pending
+    );
+    assert_eq!(fut.position(), 5);
+    //Pushed from another thread while the executor is parked on the future
+    let pusher = std::thread::spawn(move || {
+        (5..10).for_each(|n| wh.push(n));
+        wh
+    });
+    assert_eq!(block_on(fut), Some((7, &7)));
+    drop(pusher.join().unwrap());
+    assert_eq!(block_on(rh.wait_for_match_async(0, |&n| n > 100)), None);
+}
+
+#[test]
+fn alloc_error_hook_unused() {
+    use crate::RetryOrFail;
+    let (wh, rh) = Stele::new();
+    wh.set_alloc_error_hook(|_| panic!("Allocation should not fail"));
+    for n in 0..64 {
+        wh.push(n);
+    }
+    let mut s = (0..64).collect::<Stele<_>>();
+    s.set_alloc_error_hook(|_| RetryOrFail::Fail);
+    assert_eq!(rh.len(), 64);
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+#[test]
+fn alloc_error_hook_retries() {
+    use crate::mem::{AllocError, Allocator, Global};
+    use crate::RetryOrFail;
+    use alloc::{alloc::Layout, sync::Arc};
+    use core::{
+        ptr::NonNull,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    //Fails as many allocations as its counter holds before deferring to `Global`
+    #[derive(Debug)]
+    struct FailingAllocator(AtomicUsize);
+
+    unsafe impl Allocator for FailingAllocator {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            match self
+                .0
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            {
+                Ok(_) => Err(AllocError),
+                Err(_) => Global.allocate(layout),
+            }
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let hook_calls = Arc::clone(&calls);
+    let (wh, rh) = Stele::new_in(FailingAllocator(AtomicUsize::new(1)));
+    wh.set_alloc_error_hook(move |layout| {
+        assert_eq!(layout, Layout::array::<u32>(1).unwrap());
+        hook_calls.fetch_add(1, Ordering::Relaxed);
+        RetryOrFail::Retry
+    });
+    wh.push(42_u32);
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+    assert_eq!(rh.read(0), &42);
+}
+
+#[test]
+fn block_placement() {
+    use crate::{GrowthPolicy, Placement};
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    //One bit per block the hook was called for
+    let placed = Arc::new(AtomicUsize::new(0));
+    let hook_placed = Arc::clone(&placed);
+    let (wh, rh) = Stele::with_growth(GrowthPolicy::uniform(8));
+    wh.set_block_placement(move |idx, layout| {
+        assert_eq!(layout, Layout::array::<u32>(8).unwrap());
+        assert_eq!(
+            hook_placed.fetch_or(1 << idx, Ordering::Relaxed) & (1 << idx),
+            0
+        );
+        Placement::Node(0)
+    });
+    for n in 0..20 {
+        wh.push(n);
+    }
+    assert_eq!(placed.load(Ordering::Relaxed), 0b111);
+    assert!(rh.iter().copied().eq(0..20));
+
+    //Preallocated small blocks are each placed on their own
+    let placed = Arc::new(AtomicUsize::new(0));
+    let hook_placed = Arc::clone(&placed);
+    let mut s = Stele::<u32>::builder()
+        .prealloc(crate::Prealloc::SmallBlocks)
+        .build_owned()
+        .unwrap();
+    s.set_block_placement(move |idx, layout| {
+        let len = if idx == 0 { 1 } else { 1 << (idx - 1) };
+        assert_eq!(layout, Layout::array::<u32>(len).unwrap());
+        hook_placed.fetch_or(1 << idx, Ordering::Relaxed);
+        Placement::Default
+    });
+    s.push_mut(0);
+    assert_eq!(placed.load(Ordering::Relaxed), 0b111);
+    (1..5).for_each(|n| s.push_mut(n));
+    assert_eq!(placed.load(Ordering::Relaxed), 0b1111);
+
+    let (wh, _rh) = Stele::new();
+    wh.set_block_placement(|_, _| panic!("Zero sized elements take up no memory to place"));
+    wh.push(());
+}
+
+//Binding fails without a NUMA node 1, which only leaves the block where it was
+#[cfg(all(feature = "numa", target_os = "linux"))]
+#[test]
+fn block_placement_numa() {
+    use crate::{GrowthPolicy, Placement};
+    for node in [0, 1, 4096] {
+        let (wh, rh) = Stele::with_growth(GrowthPolicy::uniform(1 << 16));
+        wh.set_block_placement(move |_, _| Placement::Node(node));
+        for n in 0..(1_u64 << 17) {
+            wh.push(n);
+        }
+        assert!(rh.iter().copied().eq(0..1 << 17));
+    }
+}
+
+#[test]
+fn from_iter_in() {
+    let counter = CountingAllocator::new();
+    let s = Stele::from_iter_in(0..5_u32, &counter);
+    //Blocks 0 through 2 are preallocated and block 3 holds index 4
+    assert_eq!(counter.allocations(), 4);
+    assert_eq!(
+        counter.layouts(),
+        [1, 1, 2, 4].map(|len| Layout::array::<u32>(len).unwrap())
+    );
+    let (wh, rh) = s.to_handles();
+    for n in 5..9 {
+        wh.push(n);
+    }
+    assert_eq!(counter.allocations(), 5);
+    assert_eq!(counter.live_allocations(), rh.block_count());
+    assert_eq!(counter.live_bytes(), rh.allocated_bytes());
+    assert!(rh.iter().copied().eq(0..9));
+    drop((wh, rh));
+    assert_eq!(counter.deallocations(), 5);
+    counter.assert_empty();
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+#[test]
+fn handle_push_matches_from_iter() {
+    //Pushing through a handle must allocate exactly the blocks that collecting does, however the Stele started
+    for n in 0..20_u32 {
+        let collected = CountingAllocator::new();
+        let (cwh, crh) = Stele::from_iter_in(0..n, &collected).to_handles();
+        let pushed = CountingAllocator::new();
+        let (pwh, prh) = Stele::new_in(&pushed);
+        for i in 0..n {
+            pwh.push(i);
+        }
+        assert_eq!(collected.layouts(), pushed.layouts());
+        for i in n..n + 20 {
+            cwh.push(i);
+            pwh.push(i);
+        }
+        assert_eq!(collected.layouts(), pushed.layouts());
+        assert!(crh.iter().eq(prh.iter()));
+        assert!(crh.iter().copied().eq(0..n + 20));
+        drop((cwh, crh, pwh, prh));
+        collected.assert_empty();
+        pushed.assert_empty();
+    }
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+#[test]
+fn first_block_exp_then_push() {
+    let counter = CountingAllocator::new();
+    let (wh, rh) = Stele::with_first_block_exp_in(3, &counter);
+    for n in 0..8_u32 {
+        wh.push(n);
+    }
+    //The whole first block is allocated at once
+    assert_eq!(counter.layouts(), [Layout::array::<u32>(8).unwrap()]);
+    for n in 8..24 {
+        wh.push(n);
+    }
+    assert_eq!(
+        counter.layouts(),
+        [8, 8, 16].map(|len| Layout::array::<u32>(len).unwrap())
+    );
+    assert!(rh.iter().copied().eq(0..24));
+    drop((wh, rh));
+    counter.assert_empty();
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+#[test]
+fn bump_allocator() {
+    use crate::mem::{AllocError, Allocator};
+    use alloc::alloc::Layout;
+    use core::{
+        cell::UnsafeCell,
+        ptr::NonNull,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    #[repr(C, align(16))]
+    struct Bump {
+        buf: UnsafeCell<[u8; 1024]>,
+        used: AtomicUsize,
+    }
+
+    unsafe impl Allocator for Bump {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            let start =
+                (self.used.load(Ordering::Relaxed) + layout.align() - 1) & !(layout.align() - 1);
+            let end = start + layout.size();
+            if end > 1024 {
+                return Err(AllocError);
+            }
+            self.used.store(end, Ordering::Relaxed);
+            let ptr = unsafe { self.buf.get().cast::<u8>().add(start) };
+            Ok(NonNull::slice_from_raw_parts(
+                NonNull::new(ptr).ok_or(AllocError)?,
+                layout.size(),
+            ))
+        }
+
+        unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+    }
+
+    let bump = Bump {
+        buf: UnsafeCell::new([0; 1024]),
+        used: AtomicUsize::new(0),
+    };
+    let (wh, rh) = Stele::new_in(&bump);
+    for n in 0..100_u64 {
+        wh.push(n);
+    }
+    assert_eq!(bump.used.load(Ordering::Relaxed), 128 * 8);
+    assert!(rh.iter().copied().eq(0..100));
+}
+
+#[test]
+fn buffer_backed() {
+    use alloc::{boxed::Box, vec::Vec};
+    use core::{
+        mem::MaybeUninit,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    let buf = Box::into_raw(
+        (0..11)
+            .map(|_| MaybeUninit::uninit())
+            .collect::<Vec<_>>()
+            .into_boxed_slice(),
+    );
+    //SAFETY: The buffer is only turned back into a box after every handle is gone
+    let (wh, rh) = Stele::new_in_buffer(unsafe { &mut *buf });
+    //Only blocks 0 through 3 fit in 11 slots
+    assert_eq!(rh.capacity(), 8);
+    assert_eq!(rh.block_count(), 4);
+    for _ in 0..8 {
+        assert!(wh.push_within_capacity(DropCounter(&DROPS)).is_ok());
+    }
+    assert!(wh.push_within_capacity(DropCounter(&DROPS)).is_err());
+    assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+    assert!(matches!(
+        wh.try_push(DropCounter(&DROPS)),
+        Err(crate::Full(_))
+    ));
+    assert_eq!(DROPS.load(Ordering::Relaxed), 2);
+    assert_eq!(rh.remaining(), Some(0));
+    assert_eq!(rh.len(), 8);
+    assert!(core::ptr::eq(rh.read(0), unsafe { (*buf).as_ptr().cast() }));
+    drop((wh, rh));
+    assert_eq!(DROPS.load(Ordering::Relaxed), 10);
+    drop(unsafe { Box::from_raw(buf) });
+}
+
+#[test]
+fn push_within_capacity() {
+    use crate::{PushError, SteleError};
+    let (wh, rh) = Stele::new();
+    assert_eq!(
+        wh.push_within_capacity(0),
+        Err(PushError {
+            value: 0,
+            error: SteleError::CapacityExceeded {
+                requested: 1,
+                max: 0
+            }
+        })
+    );
+    wh.push(0);
+    wh.reserve(3);
+    for n in 1..4 {
+        assert_eq!(wh.push_within_capacity(n), Ok(()));
+    }
+    let err = wh.push_within_capacity(4).unwrap_err();
+    assert_eq!(
+        err.error,
+        SteleError::CapacityExceeded {
+            requested: 5,
+            max: 4
+        }
+    );
+    assert_eq!(err.into_inner(), 4);
+    assert!(rh.iter().copied().eq(0..4));
+}
+
+#[test]
+fn reserve() {
+    let (wh, rh) = Stele::new();
+    wh.reserve(100);
+    assert!(wh.capacity() >= 100);
+    for n in 0..100 {
+        assert_eq!(wh.push_within_capacity(n), Ok(()));
+    }
+    assert!(rh.iter().copied().eq(0..100));
+    //Nothing is allocated beyond the bound
+    let (wh, _rh) = Stele::<u8>::bounded(10);
+    wh.reserve(1000);
+    assert_eq!(wh.capacity(), 16);
+}
+
+#[cfg(feature = "critical-section")]
+#[test]
+fn isr_shared() {
+    extern crate std;
+    let (wh, rh) = Stele::new();
+    wh.reserve(200);
+    let capacity = wh.capacity();
+    let wh = wh.into_isr_shared();
+    std::thread::scope(|s| {
+        //Stands in for an interrupt handler pushing while the main loop does
+        s.spawn(|| (0..100).for_each(|n| wh.push_within_capacity(n).unwrap()));
+        (100..200).for_each(|n| wh.push_within_capacity(n).unwrap());
+    });
+    let mut seen = rh.iter().copied().collect::<alloc::vec::Vec<_>>();
+    seen.sort_unstable();
+    assert!(seen.into_iter().eq(0..200));
+    //The shared handle fails instead of allocating
+    (200..capacity).for_each(|n| wh.push_within_capacity(n).unwrap());
+    assert!(wh.push_within_capacity(capacity).is_err());
+    assert_eq!(wh.capacity(), capacity);
+    let wh = wh.into_writer();
+    wh.push(capacity);
+    assert_eq!(rh.len(), capacity + 1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn push_uninit() {
+    extern crate std;
+    let (wh, rh) = Stele::new();
+    wh.push(0);
+    let slots = (1..4)
+        .map(|_| wh.push_uninit())
+        .collect::<alloc::vec::Vec<_>>();
+    wh.push(4);
+    assert_eq!(rh.len(), 5);
+    assert_eq!(rh.initialized_len(), 1);
+    assert!(rh.try_read(1).is_none());
+    assert_eq!(rh.try_read(4), Some(&4));
+    assert!(rh.iter().copied().eq(0..1));
+    //Fill the reservations out of order from other threads
+    let fillers = slots
+        .into_iter()
+        .rev()
+        .map(|slot| {
+            std::thread::spawn(move || {
+                let idx = slot.index();
+                slot.fill(idx);
+            })
+        })
+        .collect::<alloc::vec::Vec<_>>();
+    for filler in fillers {
+        filler.join().unwrap();
+    }
+    assert_eq!(rh.initialized_len(), 5);
+    assert!(rh.iter().copied().eq(0..5));
+    assert_eq!(wh.try_into_vec(rh).unwrap(), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn push_uninit_dropped() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    let drops = AtomicUsize::new(0);
+    let (wh, rh) = Stele::new();
+    let filled = wh.push_uninit();
+    //A dropped reservation stays empty for good and is never dropped itself
+    drop(wh.push_uninit());
+    wh.push(DropCounter(&drops));
+    filled.fill(DropCounter(&drops));
+    assert!(rh.try_read(0).is_some());
+    assert!(rh.try_read(1).is_none());
+    assert!(rh.try_read(2).is_some());
+    assert_eq!(rh.initialized_len(), 1);
+    assert_eq!(rh.iter().count(), 1);
+    let v = wh.try_into_vec(rh).unwrap();
+    assert_eq!(v.len(), 2);
+    drop(v);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+
+    let (wh, rh) = Stele::new();
+    drop(wh.push_uninit());
+    wh.push(DropCounter(&drops));
+    drop((wh, rh));
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+#[should_panic(expected = "Read a reserved element that has not been filled")]
+fn read_unfilled() {
+    let (wh, rh) = Stele::<u32>::new();
+    let _slot = wh.push_uninit();
+    let _ = rh.read(0);
+}
+
+#[test]
+fn push_uninit_recycle() {
+    let (mut wh, rh) = Stele::new();
+    //Elements pushed into a block before its first reservation stay readable
+    (0..5).for_each(|n| wh.push(n));
+    let slot = wh.push_uninit();
+    wh.push(6);
+    assert_eq!(rh.initialized_len(), 5);
+    slot.fill(5);
+    assert!(rh.iter().copied().eq(0..7));
+    drop(rh);
+    assert!(wh.try_recycle());
+    assert!(wh.is_empty());
+    (0..8).for_each(|n| wh.push(n));
+    assert!(wh.new_read_handle().iter().copied().eq(0..8));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn work_queue() {
+    extern crate std;
+    use crate::queue::WorkQueue;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    let (wh, rh) = Stele::new();
+    let queue = WorkQueue::new(rh);
+    let counts = alloc::sync::Arc::new(
+        (0..10_000)
+            .map(|_| AtomicUsize::new(0))
+            .collect::<alloc::vec::Vec<_>>(),
+    );
+    let workers = (0..4)
+        .map(|worker| {
+            let queue = queue.clone();
+            let counts = alloc::sync::Arc::clone(&counts);
+            std::thread::spawn(move || {
+                while queue.claimed() < 10_000 {
+                    //Half of the workers claim in batches
+                    let batch = if worker % 2 == 0 { 1 } else { 16 };
+                    for (idx, &val) in queue.steal_batch(batch) {
+                        assert_eq!(idx, val);
+                        counts[idx].fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            })
+        })
+        .collect::<alloc::vec::Vec<_>>();
+    (0..10_000).for_each(|n| wh.push(n));
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    assert!(counts
+        .iter()
+        .all(|count| count.load(Ordering::Relaxed) == 1));
+    assert_eq!(queue.claimed(), 10_000);
+    assert_eq!(queue.unclaimed(), 0);
+    assert!(queue.claim().is_none());
+    wh.push(10_000);
+    assert_eq!(queue.claim(), Some((10_000, &10_000)));
+    assert_eq!(queue.handle().len(), 10_001);
+}
+
+#[test]
+fn local_push_read() {
+    use crate::local::LocalStele;
+
+    let (wh, rh) = LocalStele::new();
+    (0..1000).for_each(|n| wh.push(n));
+    assert_eq!(rh.len(), 1000);
+    assert_eq!(rh[999], 999);
+    assert_eq!(rh.try_read(1000), None);
+    assert!(rh.iter().copied().eq(0..1000));
+    assert!(rh.clone().into_iter().eq(0..1000));
+    assert_eq!(wh.capacity(), rh.capacity());
+    //Blocks are laid out exactly like those of a Stele
+    let (swh, srh) = Stele::new();
+    (0..1000).for_each(|n| swh.push(n));
+    assert_eq!(rh.capacity(), srh.capacity());
+    assert!(wh.push_within_capacity(1000).is_ok());
+}
+
+#[test]
+fn local_owned_build_mutate_share() {
+    use crate::local::LocalStele;
+    use alloc::string::{String, ToString};
+
+    let mut s = (0..10_usize)
+        .map(|n| n.to_string())
+        .collect::<LocalStele<_>>();
+    assert!(!s.is_empty());
+    for n in 10..100_usize {
+        s.push_mut(n.to_string());
+    }
+    assert_eq!(s.len(), 100);
+    assert_eq!(s.read(42).map(String::as_str), Some("42"));
+    assert_eq!(s.read(100), None);
+    s.get_mut(3).unwrap().push('!');
+    assert!(s.get_mut(100).is_none());
+    //Spans several blocks
+    for val in &mut s {
+        val.push('s');
+    }
+    assert_eq!(s.iter_mut().len(), 100);
+    assert_eq!(s.iter().nth(3).map(String::as_str), Some("3!s"));
+    assert!((&s)
+        .into_iter()
+        .skip(4)
+        .enumerate()
+        .all(|(idx, val)| *val == alloc::format!("{}s", idx + 4)));
+    let (wh, rh) = s.to_handles();
+    assert_eq!(rh.read(3), "3!s");
+    wh.push("100".into());
+    assert_eq!(rh.len(), 101);
+}
+
+#[test]
+fn local_push_while_iterating() {
+    use crate::local::LocalStele;
+    use alloc::{string::ToString, vec::Vec};
+
+    let (wh, rh) = LocalStele::new();
+    (0..4).for_each(|n: usize| wh.push(n.to_string()));
+    let first = rh.read(0);
+    //The iterator only yields what was pushed before it was created, while the pushes cross into several new blocks
+    for (idx, val) in rh.iter().enumerate() {
+        assert_eq!(*val, idx.to_string());
+        (0..100).for_each(|_| wh.push(wh.len().to_string()));
+    }
+    assert_eq!(rh.len(), 404);
+    assert_eq!(first, "0");
+    //No element ever moves, so references taken before later pushes stay valid
+    let refs: Vec<_> = rh.iter().collect();
+    (0..5000).for_each(|_| wh.push(wh.len().to_string()));
+    assert!(refs
+        .iter()
+        .enumerate()
+        .all(|(idx, val)| **val == idx.to_string()));
+    assert!(rh
+        .iter()
+        .enumerate()
+        .all(|(idx, val)| *val == idx.to_string()));
+}
+
+#[test]
+fn local_reentrant_storage() {
+    use crate::local::{LocalStele, LocalWriteHandle};
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    //A storage that pushes to the LocalStele it is allocating for
+    #[derive(Debug, Default, Clone)]
+    struct Reentrant(alloc::rc::Rc<RefCell<Option<LocalWriteHandle<usize, Reentrant>>>>);
+
+    impl Storage for Reentrant {
+        fn allocate_block(&self, layout: Layout) -> *mut u8 {
+            if let Some(wh) = self.0.borrow_mut().take() {
+                wh.push(wh.len() + 100);
+                *self.0.borrow_mut() = Some(wh);
+            }
+            DefaultStorage::default().allocate_block(layout)
+        }
+
+        unsafe fn deallocate_block(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { DefaultStorage::default().deallocate_block(ptr, layout) }
+        }
+    }
+
+    let storage = Reentrant::default();
+    let (wh, rh) = LocalStele::new_in(storage.clone());
+    *storage.0.borrow_mut() = Some(wh);
+    let push = |val| {
+        let wh = storage.0.borrow_mut().take().unwrap();
+        wh.push(val);
+        *storage.0.borrow_mut() = Some(wh);
+    };
+    (0..40).for_each(push);
+    //Pushes from inside the storage land before the push that triggered the allocation, and nothing is lost
+    let vals: Vec<_> = rh.iter().copied().collect();
+    assert!(vals.iter().filter(|&&n| n < 100).copied().eq(0..40));
+    assert!(vals
+        .iter()
+        .enumerate()
+        .all(|(idx, &n)| n < 100 || n == idx + 100));
+    //Break the cycle between the storage and the LocalStele
+    drop(storage.0.borrow_mut().take());
+}
+
+#[cfg(feature = "defmt")]
+#[test]
+fn defmt_format() {
+    use alloc::vec::Vec;
+    use core::convert::TryFrom;
+    use defmt::export::{fetch_bytes, fetch_string_index};
+
+    //The mock encoder hands out the next string index for every interned string, and writes everything else as is
+    struct Expected {
+        index: u16,
+        bytes: Vec<u8>,
+    }
+
+    impl Expected {
+        fn istr(&mut self) -> &mut Self {
+            self.bytes.extend(self.index.to_le_bytes());
+            self.index += 1;
+            self
+        }
+
+        fn usize(&mut self, n: usize) -> &mut Self {
+            self.bytes.extend(u32::try_from(n).unwrap().to_le_bytes());
+            self
+        }
+
+        fn str(&mut self, s: &str) -> &mut Self {
+            self.usize(s.len());
+            self.bytes.extend(s.as_bytes());
+            self
+        }
+
+        fn element(&mut self, n: u8) -> &mut Self {
+            self.istr().istr();
+            self.bytes.push(n);
+            self
+        }
+
+        fn end(&mut self) -> Vec<u8> {
+            self.bytes.extend(0_u16.to_le_bytes());
+            core::mem::take(&mut self.bytes)
+        }
+    }
+
+    let (wh, rh) = Stele::new();
+    (0..3_u8).for_each(|n| wh.push(n));
+    let mut expected = Expected {
+        index: fetch_string_index(),
+        bytes: Vec::new(),
+    };
+    defmt::export::fmt(&rh);
+    expected.istr().istr().str("ReadHandle").usize(3).element(0);
+    expected.istr().element(1).istr().element(2).istr();
+    assert_eq!(fetch_bytes(), expected.end());
+
+    //Only the first few elements of a long Stele are formatted
+    (3..100_u8).for_each(|n| wh.push(n));
+    expected.index = fetch_string_index();
+    defmt::export::fmt(&wh);
+    expected
+        .istr()
+        .istr()
+        .str("WriteHandle")
+        .usize(100)
+        .element(0);
+    (1..8).for_each(|n| {
+        expected.istr().element(n);
+    });
+    expected.istr().istr();
+    assert_eq!(fetch_bytes(), expected.end());
+
+    expected.index = fetch_string_index();
+    defmt::export::fmt(&crate::Full(()));
+    expected.istr().istr();
+    assert_eq!(fetch_bytes(), expected.end());
+}
+
+#[test]
+fn records_round_trip() {
+    use crate::records::{RecordReader, RecordWriter};
+    use alloc::{borrow::Cow, vec::Vec};
+
+    let (wh, rh) = Stele::new();
+    let writer = RecordWriter::new(wh);
+    let reader = RecordReader::new(rh);
+    let records = (0..300_usize)
+        .map(|n| (0..=255_u8).cycle().skip(n).take(n).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let ids = records
+        .iter()
+        .map(|record| writer.append(record))
+        .collect::<Vec<_>>();
+    //Lengths from 128 on need a two byte prefix
+    assert_eq!(ids[1].offset(), 1);
+    assert_eq!(ids[129].offset() - ids[128].offset(), 130);
+    for (id, record) in ids.iter().zip(&records) {
+        assert_eq!(reader.get(*id).unwrap(), record.as_slice());
+    }
+    let mut straddling = 0;
+    for (found, (id, record)) in reader.iter().zip(ids.iter().zip(&records)) {
+        let (found_id, payload) = found.unwrap();
+        assert_eq!(found_id, *id);
+        assert_eq!(payload, record.as_slice());
+        if let Cow::Owned(_) = payload {
+            straddling += 1;
+        }
+    }
+    //Blocks double in size, so only a handful of records straddle a block boundary and have to be copied
+    assert!(straddling > 0 && straddling < 20);
+    assert_eq!((&reader).into_iter().count(), 300);
+    assert_eq!(reader.get(writer.append(&[])).unwrap(), &[] as &[u8]);
+}
+
+#[test]
+fn records_tail() {
+    use crate::records::{RecordReader, RecordWriter};
+
+    let (wh, rh) = Stele::new();
+    let writer = RecordWriter::new(wh);
+    let _ = writer.append(b"old");
+    let reader = RecordReader::new(rh);
+    let mut tail = reader.tail();
+    assert!(tail.next().is_none());
+    let id = writer.append(b"new");
+    let (found, payload) = tail.next().unwrap().unwrap();
+    assert_eq!((found, &*payload), (id, &b"new"[..]));
+    assert!(tail.next().is_none());
+    let _ = writer.append(b"newer");
+    assert_eq!(&*tail.next().unwrap().unwrap().1, b"newer");
+}
+
+#[test]
+fn records_malformed() {
+    use crate::{
+        records::{RecordReader, RecordWriter},
+        SteleError,
+    };
+
+    let (wh, rh) = Stele::new();
+    let writer = RecordWriter::new(wh);
+    let id = writer.append(b"complete");
+    let reader = RecordReader::new(rh);
+    //A writer that died halfway through a record leaves a prefix promising more bytes than follow it
+    let wh = writer.into_inner();
+    let torn = wh.len();
+    for &b in &[10, b'a', b'b'] {
+        wh.push(b);
+    }
+    let mut records = reader.iter();
+    assert_eq!(&*records.next().unwrap().unwrap().1, b"complete");
+    assert_eq!(
+        records.next(),
+        Some(Err(SteleError::MalformedRecord { offset: torn }))
+    );
+    assert_eq!(records.next(), None);
+    assert_eq!(&*reader.get(id).unwrap(), b"complete");
+
+    //A prefix that never ends, or that overflows a usize, is caught as well
+    let (_, rh) = [0xFF; 20]
+        .iter()
+        .copied()
+        .collect::<Stele<u8>>()
+        .to_handles();
+    let reader = RecordReader::new(rh);
+    assert_eq!(
+        reader.iter().next(),
+        Some(Err(SteleError::MalformedRecord { offset: 0 }))
+    );
+    let (_, rh) = [0xFF; 9]
+        .iter()
+        .chain(&[0x7F])
+        .copied()
+        .collect::<Stele<u8>>()
+        .to_handles();
+    let reader = RecordReader::new(rh);
+    assert_eq!(
+        reader.iter().next(),
+        Some(Err(SteleError::MalformedRecord { offset: 0 }))
+    );
+}
+
+#[cfg(all(feature = "std", feature = "bytemuck"))]
+#[test]
+fn snapshot_pod() {
+    use crate::snapshot::SnapshotError;
+    use alloc::vec::Vec;
+
+    let (wh, rh) = Stele::new();
+    (0..1000_u32).for_each(|n| wh.push(n));
+    let mut snapshot = Vec::new();
+    let written = rh.write_snapshot(&mut snapshot).unwrap();
+    assert_eq!(written, snapshot.len() as u64);
+    let (wh2, rh2) = Stele::<u32>::read_snapshot(snapshot.as_slice()).unwrap();
+    assert!(rh2.iter().copied().eq(0..1000));
+    //The rebuilt Stele can be pushed to as usual
+    wh2.push(1000);
+    assert_eq!(rh2.len(), 1001);
+
+    //Zero sized elements have no contents, only a length
+    let (wh, rh) = Stele::new();
+    (0..10).for_each(|_| wh.push(()));
+    let mut units = Vec::new();
+    rh.write_snapshot(&mut units).unwrap();
+    let (_, rh) = Stele::<()>::read_snapshot(units.as_slice()).unwrap();
+    assert_eq!(rh.len(), 10);
+
+    //Flipping a byte of the contents is caught by the checksum
+    let mut corrupted = snapshot.clone();
+    *corrupted.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        Stele::<u32>::read_snapshot(corrupted.as_slice()),
+        Err(SnapshotError::ChecksumMismatch { .. })
+    ));
+    let mut newer = snapshot.clone();
+    newer[8] = 2;
+    assert!(matches!(
+        Stele::<u32>::read_snapshot(newer.as_slice()),
+        Err(SnapshotError::UnsupportedVersion { found: 2 })
+    ));
+    for len in [0, 10, 42, snapshot.len() - 1] {
+        assert!(matches!(
+            Stele::<u32>::read_snapshot(&snapshot[..len]),
+            Err(SnapshotError::Truncated)
+        ));
+    }
+    assert!(matches!(
+        Stele::<u64>::read_snapshot(snapshot.as_slice()),
+        Err(SnapshotError::ElementSizeMismatch {
+            expected: 8,
+            found: 4
+        })
+    ));
+    assert!(matches!(
+        Stele::<u32>::read_snapshot(&[b'x'; 64][..]),
+        Err(SnapshotError::BadMagic)
+    ));
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+#[test]
+fn snapshot_serde() {
+    use crate::snapshot::SnapshotError;
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    let (wh, rh) = Stele::new();
+    (0..300).for_each(|n| wh.push((n, n.to_string())));
+    let mut snapshot = Vec::new();
+    let written = rh.write_snapshot_serde(&mut snapshot).unwrap();
+    assert_eq!(written, snapshot.len() as u64);
+    let (_, rh2) = Stele::<(u32, String)>::read_snapshot_serde(snapshot.as_slice()).unwrap();
+    assert_eq!(rh2.len(), 300);
+    assert!(rh2.iter().eq(rh.iter()));
+
+    let mut corrupted = snapshot.clone();
+    let last = corrupted.len() - 1;
+    corrupted[last] ^= 1;
+    assert!(matches!(
+        Stele::<(u32, String)>::read_snapshot_serde(corrupted.as_slice()),
+        Err(SnapshotError::ChecksumMismatch { .. })
+    ));
+    assert!(matches!(
+        Stele::<(u32, String)>::read_snapshot_serde(&snapshot[..snapshot.len() - 1]),
+        Err(SnapshotError::Truncated)
+    ));
+    //Elements that do not decode as the requested type are rejected even though the checksum matches
+    assert!(matches!(
+        Stele::<(String, u32)>::read_snapshot_serde(snapshot.as_slice()),
+        Err(SnapshotError::Corrupted)
+    ));
+    #[cfg(feature = "bytemuck")]
+    assert!(matches!(
+        Stele::<u32>::read_snapshot(snapshot.as_slice()),
+        Err(SnapshotError::EncodingMismatch)
+    ));
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn digest_increments() {
+    use crate::{
+        digest::{Checkpoint, ContentHasher, Crc32, Digester},
+        GrowthPolicy,
+    };
+
+    fn check<H: ContentHasher + Clone>(hasher: H)
+    where
+        H::Output: PartialEq + core::fmt::Debug,
+    {
+        let (writer, reader) = Stele::new();
+        let mut digester = Digester::new(reader.clone(), hasher.clone());
+        let mut bytes = hasher.clone();
+        //Cut at arbitrary points that do not line up with the blocks
+        for &end in &[37, 38, 300] {
+            for n in writer.len()..end {
+                let val = n as u64 * 7;
+                writer.push(val);
+                bytes.update(&val.to_ne_bytes());
+            }
+            let checkpoint = digester.update();
+            assert_eq!(checkpoint.len, end);
+            assert_eq!(checkpoint.digest, bytes.digest());
+        }
+        assert_eq!(digester.update(), digester.checkpoint());
+
+        //Hashing everything at once, from a Stele with other blocks, gives the same digest
+        let (other, other_reader) = Stele::with_growth(GrowthPolicy::uniform(10));
+        (0..300_u64).for_each(|n| other.push(n * 7));
+        let mut all_at_once = Digester::new(other_reader, hasher);
+        assert_eq!(all_at_once.update(), digester.checkpoint());
+    }
+
+    //The check value of CRC-32
+    let mut crc = Crc32::new();
+    crc.update(b"123456789");
+    assert_eq!(crc.digest(), 0xcbf4_3926);
+    check(Crc32::new());
+    #[cfg(feature = "sha2")]
+    {
+        let mut sha = crate::digest::Sha256::new();
+        sha.update(b"abc");
+        assert_eq!(
+            sha.digest()[..4],
+            [0xba, 0x78, 0x16, 0xbf],
+            "The SHA-256 of \"abc\" starts with ba7816bf"
+        );
+        check(crate::digest::Sha256::new());
+    }
+
+    //Zero sized elements have no bytes to hash, but are still covered
+    let (writer, reader) = Stele::new();
+    (0..5).for_each(|_| writer.push(()));
+    let mut digester = Digester::new(reader, Crc32::new());
+    assert_eq!(
+        digester.update(),
+        Checkpoint {
+            len: 5,
+            digest: Crc32::new().digest()
+        }
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn digest_serde() {
+    use crate::digest::{ContentHasher, Crc32, Digester};
+    use alloc::format;
+
+    //Only encodes when it holds `true`
+    struct Picky(bool);
+
+    impl serde::Serialize for Picky {
+        fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            if self.0 {
+                s.serialize_bool(true)
+            } else {
+                Err(serde::ser::Error::custom("refused to encode"))
+            }
+        }
+    }
+
+    let (writer, reader) = Stele::new();
+    let mut digester = Digester::new(reader.clone(), Crc32::new());
+    let mut encoded = Crc32::new();
+    for &end in &[1, 20, 100] {
+        for n in writer.len()..end {
+            let val = (n as u64, format!("entry {n}"));
+            encoded.update(&postcard::to_allocvec(&val).unwrap());
+            writer.push(val);
+        }
+        let checkpoint = digester.update_serde().unwrap();
+        assert_eq!(checkpoint.len, end);
+        assert_eq!(checkpoint.digest, encoded.digest());
+    }
+    let mut all_at_once = Digester::new(reader, Crc32::new());
+    assert_eq!(all_at_once.update_serde(), Ok(digester.checkpoint()));
+
+    let (writer, reader) = Stele::new();
+    for &b in &[true, true, false, true] {
+        writer.push(Picky(b));
+    }
+    let mut digester = Digester::new(reader, Crc32::new());
+    assert_eq!(
+        digester.update_serde(),
+        Err(crate::SteleError::SerializeFailed { index: 2 })
+    );
+    //The elements before the failure stay hashed
+    assert_eq!(digester.checkpoint().len, 2);
+    assert_eq!(
+        digester.update_serde(),
+        Err(crate::SteleError::SerializeFailed { index: 2 })
+    );
+}
+
+#[cfg(feature = "shmem")]
+#[test]
+fn shared_memory() {
+    extern crate std;
+    use crate::shm::{ShmError, ShmReadHandle, ShmStele};
+    use memmap2::{Mmap, MmapMut};
+
+    //Two mappings of the same file stand in for the writing and the reading process
+    let path = std::env::temp_dir().join(std::format!("stele-shm-{}", std::process::id()));
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    file.set_len(4096).unwrap();
+    let map = || unsafe { Mmap::map(&file).unwrap() };
+    assert_eq!(
+        ShmReadHandle::<u64>::open(map()).unwrap_err(),
+        ShmError::BadMagic
+    );
+    let mut writer = ShmStele::<u64>::create(unsafe { MmapMut::map_mut(&file).unwrap() }).unwrap();
+    let reader = ShmReadHandle::<u64>::open(map()).unwrap();
+    assert!(matches!(
+        ShmReadHandle::<u32>::open(map()),
+        Err(ShmError::ElementMismatch { size: 8, align: 8 })
+    ));
+    assert!(reader.is_empty());
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            while reader.len() < 256 {
+                //Every element the reader can see has been written completely
+                let len = reader.len();
+                assert!(reader.iter().copied().eq(0..len as u64));
+            }
+        });
+        (0..256).for_each(|n| writer.push(n));
+    });
+    assert_eq!(reader.len(), 256);
+    assert_eq!(*reader.read(255), 255);
+    assert_eq!(writer.get(7), Some(7));
+    assert!(reader.try_read(256).is_none());
+    //The header and 256 elements fill most of the 4 KiB, leaving no room for the next block of 256
+    assert!(writer.try_push(256).is_err());
+}
+
+#[cfg(all(feature = "mmap", unix))]
+#[test]
+fn mmap_reopen() {
+    extern crate std;
+    use crate::mmap::{MmapError, MmapStorage};
+
+    let path = std::env::temp_dir().join(std::format!("stele-mmap-{}", std::process::id()));
+    let (writer, reader) = Stele::with_first_block_exp_in(2, MmapStorage::create(&path).unwrap());
+    (0..1000_u32).for_each(|n| writer.push(n));
+    writer.flush().unwrap();
+    //Pushed but never flushed, so not committed
+    (1000..1100).for_each(|n| writer.push(n));
+    drop((writer, reader));
+
+    let reopened = MmapStorage::reopen::<u32>(&path).unwrap();
+    assert_eq!(reopened.len(), 1000);
+    assert!(reopened.iter().copied().eq(0..1000));
+    assert!(matches!(
+        MmapStorage::reopen::<u64>(&path),
+        Err(MmapError::ElementSizeMismatch {
+            expected: 8,
+            found: 4
+        })
+    ));
+    //A promoted writer only pushes to memory, and cannot flush to the file
+    let writer = reopened.try_promote().unwrap();
+    (1000..5000).for_each(|n| writer.push(n));
+    assert_eq!(writer.read(4999), &4999);
+    assert!(writer.flush().is_err());
+    drop(writer);
+    assert_eq!(MmapStorage::reopen::<u32>(&path).unwrap().len(), 1000);
+
+    std::fs::write(&path, [0; 64]).unwrap();
+    assert!(matches!(
+        MmapStorage::reopen::<u32>(&path),
+        Err(MmapError::BadMagic)
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(all(feature = "mmap", unix))]
+#[test]
+fn mmap_shrink_reopen() {
+    extern crate std;
+    use crate::mmap::MmapStorage;
+
+    let path = std::env::temp_dir().join(std::format!("stele-mmap-shrink-{}", std::process::id()));
+    let (writer, reader) = Stele::with_first_block_exp_in(2, MmapStorage::create(&path).unwrap());
+    (0..100_u32).for_each(|n| writer.push(n));
+    writer.reserve(2000);
+    let blocks = writer.block_count();
+    writer.flush().unwrap();
+    let file_len = std::fs::metadata(&path).unwrap().len();
+    //Freeing the reserved blocks and then allocating them again reuses the same regions of the file
+    drop(reader);
+    let writer = writer.shrink_unused().try_promote().unwrap();
+    assert!(writer.block_count() < blocks);
+    (100..1000).for_each(|n| writer.push(n));
+    writer.flush().unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), file_len);
+    drop(writer);
+
+    let reopened = MmapStorage::reopen::<u32>(&path).unwrap();
+    assert_eq!(reopened.len(), 1000);
+    assert!(reopened.iter().copied().eq(0..1000));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn tracing_events() {
+    extern crate std;
+    use alloc::{boxed::Box, vec::Vec};
+    use std::sync::Mutex;
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+
+    //The name and integer fields of an event
+    type Recorded = (&'static str, Vec<(&'static str, u64)>);
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<Recorded>>);
+
+    struct Fields<'a>(&'a mut Vec<(&'static str, u64)>);
+
+    impl Visit for Fields<'_> {
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            self.0.push((field.name(), value));
+        }
+
+        fn record_debug(&mut self, _: &Field, _: &dyn core::fmt::Debug) {}
+    }
+
+    impl Subscriber for &'static Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Vec::new();
+            event.record(&mut Fields(&mut fields));
+            self.0
+                .lock()
+                .unwrap()
+                .push((event.metadata().name(), fields));
+        }
+
+        fn enter(&self, _: &span::Id) {}
+
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    let recorder: &'static Recorder = Box::leak(Box::default());
+    tracing::subscriber::with_default(recorder, || {
+        let (mut writer, reader) = Stele::<u32>::new();
+        //The first, second, third and fifth push each allocate the block they land in
+        (0..5).for_each(|n| writer.push(n));
+        drop(reader);
+        assert!(writer.try_recycle());
+        drop(writer);
+        //A second Stele gets its own id
+        Stele::<u32>::new().0.push(0);
+    });
+    let events = recorder.0.lock().unwrap();
+    let id = events[0].1[0].1;
+    let expected = [
+        (
+            "stele.alloc",
+            [("block", 0), ("elements", 1), ("bytes", 4), ("capacity", 1)].as_slice(),
+        ),
+        (
+            "stele.alloc",
+            &[("block", 1), ("elements", 1), ("bytes", 4), ("capacity", 2)],
+        ),
+        (
+            "stele.alloc",
+            &[("block", 2), ("elements", 2), ("bytes", 8), ("capacity", 4)],
+        ),
+        (
+            "stele.alloc",
+            &[
+                ("block", 3),
+                ("elements", 4),
+                ("bytes", 16),
+                ("capacity", 8),
+            ],
+        ),
+        ("stele.recycle", &[("len", 5), ("capacity", 8)]),
+        ("stele.drop", &[("blocks", 4), ("bytes", 32), ("len", 0)]),
+    ];
+    assert_eq!(events.len(), expected.len() + 2);
+    for ((name, fields), (expected_name, expected_fields)) in events.iter().zip(expected.iter()) {
+        assert_eq!(name, expected_name);
+        assert_eq!(fields[0], ("id", id));
+        assert_eq!(&fields[1..], *expected_fields);
+    }
+    assert_ne!(events[expected.len()].1[0], ("id", id));
+}
+
+#[cfg(all(feature = "deepsize", not(feature = "portable-atomic")))]
+#[test]
+fn deep_size() {
+    use crate::{ReadHandle, WriteHandle};
+    use alloc::string::{String, ToString};
+    use core::mem::size_of;
+    use deepsize::DeepSizeOf;
+
+    let stele_size = size_of::<Stele<u32>>();
+    //Ten elements fill the first five blocks, which hold 16
+    let s = (0..10_u32).collect::<Stele<_>>();
+    assert_eq!(s.deep_size_of(), stele_size + 16 * 4);
+    let (wh, rh) = s.to_handles();
+    assert_eq!(
+        rh.deep_size_of(),
+        size_of::<ReadHandle<u32>>() + stele_size + 16 * 4
+    );
+    //Every handle to the same Stele counts it only once
+    let handles = (wh, rh.clone(), rh);
+    assert_eq!(
+        handles.deep_size_of(),
+        size_of::<(WriteHandle<u32>, ReadHandle<u32>, ReadHandle<u32>)>() + stele_size + 16 * 4
+    );
+    //A reservation allocates one flag per element of its block, one word at a time
+    let slot = handles.0.push_uninit();
+    slot.fill(10);
+    assert_eq!(
+        handles.1.deep_size_of(),
+        size_of::<ReadHandle<u32>>() + stele_size + 16 * 4 + size_of::<usize>()
+    );
+
+    //The heap memory of every element is counted on top of the blocks
+    let (wh, rh) = Stele::new();
+    let strings = ["", "a", "stele", "append only"];
+    for s in strings {
+        wh.push(s.to_string());
+    }
+    let owned = strings
+        .iter()
+        .map(|s| s.to_string().capacity())
+        .sum::<usize>();
+    assert_eq!(
+        wh.deep_size_of(),
+        size_of::<WriteHandle<String>>()
+            + size_of::<Stele<String>>()
+            + 4 * size_of::<String>()
+            + owned
+    );
+    drop(rh);
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn metrics_emitted() {
+    use alloc::{string::String, vec::Vec};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
+
+    //The value of every metric by name and label, if it has one
+    fn snapshot(snapshotter: &Snapshotter) -> Vec<(String, Option<String>, DebugValue)> {
+        snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let key = key.key();
+                let mut labels = key.labels();
+                let label = labels.next().map(|label| {
+                    assert_eq!(label.key(), "name");
+                    label.value().into()
+                });
+                assert!(labels.next().is_none());
+                (key.name().into(), label, value)
+            })
+            .collect()
+    }
+
+    fn find<'a>(
+        values: &'a [(String, Option<String>, DebugValue)],
+        name: &str,
+        label: Option<&str>,
+    ) -> &'a DebugValue {
+        &values
+            .iter()
+            .find(|(n, l, _)| n == name && l.as_deref() == label)
+            .unwrap_or_else(|| panic!("{} was not emitted with the label {:?}", name, label))
+            .2
+    }
+
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let (mut writer, reader) = metrics::with_local_recorder(&recorder, || {
+        Stele::<u32>::builder()
+            .metrics_label("events")
+            .build()
+            .unwrap()
+    });
+    //The first, second, third and fifth push each allocate the block they land in
+    (0..5).for_each(|n| writer.push(n));
+    let values = snapshot(&snapshotter);
+    //Pushes are reported as each block starts, so the fifth is not counted until the next report
+    assert_eq!(
+        find(&values, "stele.pushes", Some("events")),
+        &DebugValue::Counter(4)
+    );
+    assert_eq!(
+        find(&values, "stele.blocks_allocated", Some("events")),
+        &DebugValue::Counter(4)
+    );
+    assert_eq!(
+        find(&values, "stele.allocated_bytes", Some("events")),
+        &DebugValue::Gauge(32.0.into())
+    );
+    assert_eq!(
+        find(&values, "stele.block_alloc_bytes", Some("events")),
+        &DebugValue::Histogram([4.0, 4.0, 8.0, 16.0].map(Into::into).to_vec())
+    );
+
+    drop(reader);
+    //Recycling reports the rest, and the kept blocks are not allocated again
+    assert!(writer.try_recycle());
+    (0..3).for_each(|n| writer.push(n));
+    drop(writer);
+    //A Stele without a label emits its own metrics
+    metrics::with_local_recorder(&recorder, || Stele::<u64>::new().0.push(0));
+    //Counters and gauges start over from zero after every snapshot, so these are the changes since the last one
+    let values = snapshot(&snapshotter);
+    assert_eq!(
+        find(&values, "stele.pushes", Some("events")),
+        &DebugValue::Counter(4)
+    );
+    assert_eq!(
+        find(&values, "stele.blocks_allocated", Some("events")),
+        &DebugValue::Counter(0)
+    );
+    assert_eq!(
+        find(&values, "stele.allocated_bytes", Some("events")),
+        &DebugValue::Gauge((-32.0).into())
+    );
+    assert_eq!(find(&values, "stele.pushes", None), &DebugValue::Counter(1));
+    assert_eq!(
+        find(&values, "stele.allocated_bytes", None),
+        &DebugValue::Gauge(0.0.into())
+    );
+    assert_eq!(
+        find(&values, "stele.block_alloc_bytes", None),
+        &DebugValue::Histogram([8.0].map(Into::into).to_vec())
+    );
+}
+
+#[cfg(feature = "nightly-simd")]
+#[test]
+fn simd_reductions() {
+    use alloc::vec::Vec;
+    use core::simd::{num::SimdUint, Simd};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(0x51d);
+    //Lengths that end inside a block, right at the end of one, and several blocks in
+    for len in [0_u32, 1, 7, 8, 9, 63, 64, 100, 1000, 4099] {
+        let (int_wh, ints) = Stele::new();
+        let (float_wh, floats) = Stele::new();
+        for _ in 0..len {
+            int_wh.push(rng.gen::<u64>());
+            float_wh.push(rng.gen_range(-1000.0_f32..1000.0));
+        }
+        //Integers match bit for bit, even though the random values overflow
+        assert_eq!(
+            ints.sum_simd(),
+            ints.iter().fold(0_u64, |acc, &n| acc.wrapping_add(n))
+        );
+        assert_eq!(
+            ints.min_max_simd(),
+            ints.iter().copied().min().zip(ints.iter().copied().max())
+        );
+
+        let exact = floats.iter().map(|&x| f64::from(x)).sum::<f64>();
+        let magnitude = floats.iter().map(|&x| f64::from(x.abs())).sum::<f64>();
+        let tolerance = f64::from(len) * f64::from(f32::EPSILON) * magnitude;
+        assert!((f64::from(floats.sum_simd()) - exact).abs() <= tolerance);
+        let min = floats.iter().copied().reduce(f32::min);
+        let max = floats.iter().copied().reduce(f32::max);
+        assert_eq!(floats.min_max_simd(), min.zip(max));
+    }
+
+    //A dot product against a slice, keeping track of where each block starts
+    let (wh, values) = Stele::new();
+    let weights = (0..777_u64)
+        .map(|_| rng.gen_range(0..1000))
+        .collect::<Vec<_>>();
+    for _ in 0..weights.len() {
+        wh.push(rng.gen_range(0..1000_u64));
+    }
+    let (dot, covered) =
+        values.fold_blocks_simd::<_, 4>((0, 0), |(mut dot, mut idx), head, body, tail| {
+            for &x in head {
+                dot += x * weights[idx];
+                idx += 1;
+            }
+            for &v in body {
+                dot += (v * Simd::from_slice(&weights[idx..idx + 4])).reduce_sum();
+                idx += 4;
+            }
+            for &x in tail {
+                dot += x * weights[idx];
+                idx += 1;
+            }
+            (dot, idx)
+        });
+    assert_eq!(covered, weights.len());
+    assert_eq!(
+        dot,
+        values.iter().zip(&weights).map(|(a, b)| a * b).sum::<u64>()
+    );
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn random_sampling() {
+    use alloc::vec::Vec;
+    use rand::{distributions::Uniform, rngs::StdRng, SeedableRng};
+
+    let (writer, reader) = Stele::new();
+    let mut rng = StdRng::seed_from_u64(0x57e1e);
+    assert!(reader.choose(&mut rng).is_none());
+    assert!(reader.choose_multiple(&mut rng, 3).is_empty());
+    assert!(reader.index_distribution().is_none());
+    (0..10_usize).for_each(|n| writer.push(n));
+
+    //Chi-squared over 10 buckets has 9 degrees of freedom, and exceeds 30 with a probability of about 0.0004
+    let chi_squared = |counts: &[u32], expected: f64| {
+        counts
+            .iter()
+            .map(|&count| {
+                let diff = f64::from(count) - expected;
+                diff * diff / expected
+            })
+            .sum::<f64>()
+    };
+    let mut counts = [0; 10];
+    (0..10_000).for_each(|_| counts[*reader.choose(&mut rng).unwrap()] += 1);
+    assert!(chi_squared(&counts, 1000.0) < 30.0, "{:?}", counts);
+
+    let mut counts = [0; 10];
+    for _ in 0..2_000 {
+        let chosen = reader.choose_multiple(&mut rng, 5);
+        assert_eq!(chosen.len(), 5);
+        assert!(chosen.windows(2).all(|w| w[0] < w[1]));
+        for idx in chosen {
+            counts[idx] += 1;
+        }
+    }
+    assert!(chi_squared(&counts, 1000.0) < 30.0, "{:?}", counts);
+    assert_eq!(
+        reader.sample_copied(&mut rng, 20),
+        (0..10).collect::<Vec<_>>()
+    );
+
+    //The same seed always makes the same choices
+    let sample = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (
+            reader.choose(&mut rng).copied(),
+            reader.sample_copied(&mut rng, 4),
+        )
+    };
+    assert_eq!(sample(7), sample(7));
+
+    let indices = reader.index_distribution().unwrap();
+    assert!(reader.choose_with(&mut rng, &indices).is_some());
+    assert!(reader
+        .choose_with(&mut rng, &Uniform::new(10, 20))
+        .is_none());
+}
+
+#[test]
+fn common_prefix() {
+    use crate::GrowthPolicy;
+    use alloc::vec::Vec;
+
+    let leader = (0..100_u32).collect::<Stele<_>>();
+    let (_leader_writer, leader) = leader.to_handles();
+    //Different block sizes, so that the block slices of both sides rarely line up
+    let follower = |contents: &[u32]| {
+        let (writer, reader) = Stele::with_growth(GrowthPolicy::uniform(7));
+        for &n in contents {
+            writer.push(n);
+        }
+        reader
+    };
+    let all = (0..100).collect::<Vec<_>>();
+
+    //Identical contents
+    assert_eq!(leader.common_prefix_len(&follower(&all)), 100);
+    assert_eq!(leader.common_prefix_len_slice(&all), 100);
+    assert_eq!(leader.diff_suffix(&follower(&all)).count(), 0);
+
+    //A strict prefix, from either side
+    let prefix = follower(&all[..40]);
+    assert_eq!(leader.common_prefix_len(&prefix), 40);
+    assert_eq!(prefix.common_prefix_len(&leader), 40);
+    assert_eq!(leader.common_prefix_len_slice(&all[..40]), 40);
+    assert!(leader.diff_suffix(&prefix).copied().eq(40..100));
+    assert_eq!(prefix.diff_suffix(&leader).count(), 0);
+
+    //Diverging exactly where the leader's fifth block starts
+    let mut diverged = all.clone();
+    diverged[8] = 1000;
+    assert_eq!(leader.common_prefix_len(&follower(&diverged)), 8);
+    assert_eq!(leader.common_prefix_len_slice(&diverged), 8);
+    assert!(leader.diff_suffix(&follower(&diverged)).copied().eq(8..100));
+    //And where the follower's third block starts
+    let mut diverged = all.clone();
+    diverged[14] = 1000;
+    assert_eq!(leader.common_prefix_len(&follower(&diverged)), 14);
+    diverged[0] = 1000;
+    assert_eq!(leader.common_prefix_len(&follower(&diverged)), 0);
+
+    //Empty inputs
+    let (_empty_writer, empty) = Stele::<u32>::new();
+    assert_eq!(leader.common_prefix_len(&empty), 0);
+    assert_eq!(empty.common_prefix_len(&leader), 0);
+    assert_eq!(empty.common_prefix_len(&empty), 0);
+    assert_eq!(leader.common_prefix_len_slice(&[]), 0);
+    assert!(leader.diff_suffix(&empty).copied().eq(0..100));
+    assert_eq!(empty.diff_suffix(&leader).count(), 0);
+}
+
+#[test]
+fn chunk_by() {
+    use alloc::vec::Vec;
+
+    let (writer, reader) = Stele::new();
+    assert_eq!(reader.chunk_by(|a: &u32, b| a == b).count(), 0);
+    //Every element is its own group
+    (0..5).for_each(|n| writer.push(n));
+    assert!(reader
+        .chunk_by(|a, b| a == b)
+        .map(|group| (group.len(), *group.first()))
+        .eq((0..5).map(|n| (1, n))));
+    //Indices 5 to 19 hold 7s, covering the rest of block 3, all of block 4 and the start of block 5
+    (5..20).for_each(|_| writer.push(7));
+    (20..24).for_each(|n| writer.push(n));
+    let groups = reader.chunk_by(|a, b| a == b).collect::<Vec<_>>();
+    assert_eq!(groups.len(), 10);
+    let long = &groups[5];
+    assert_eq!(long.range(), 5..20);
+    assert_eq!((*long.first(), *long.last()), (7, 7));
+    assert!(long.iter().all(|&n| n == 7));
+    assert_eq!(long.into_iter().count(), 15);
+
+    //Only the window is grouped, so the long group is cut short at both ends
+    let window = reader
+        .iter_range(3..22)
+        .chunk_by(|a, b| a == b)
+        .map(|group| group.range())
+        .collect::<Vec<_>>();
+    assert_eq!(window, [3..4, 4..5, 5..20, 20..21, 21..22]);
+    assert_eq!(reader.iter_range(20..).count(), 4);
+    assert_eq!(reader.iter_range(..=1).count(), 2);
+    assert_eq!(reader.iter_range(30..40).count(), 0);
+}
+
+#[test]
+fn split_bytes() {
+    use alloc::vec::Vec;
+
+    let (writer, reader) = Stele::new();
+    //Blocks hold [0], [1], [2, 3], [4, 8) and [8, 16), so the delimiters at 3 and 7 end a block and the one at 16 starts one
+    b"abc,d,,efghijklm,".iter().for_each(|&b| writer.push(b));
+    let fields = reader.split(b',').collect::<Vec<_>>();
+    assert_eq!(
+        fields
+            .iter()
+            .map(crate::append::iter::SegSlice::range)
+            .collect::<Vec<_>>(),
+        [0..3, 4..5, 6..6, 7..16, 17..17]
+    );
+    let expected: [&[u8]; 5] = [b"abc", b"d", b"", b"efghijklm", b""];
+    for (field, expected) in fields.iter().zip(expected.iter()) {
+        assert_eq!(*field, **expected);
+        assert_eq!(&field.to_vec(), expected);
+        assert!(field.iter_bytes().eq(expected.iter().copied()));
+    }
+    //"abc" and "efghijklm" cross block boundaries while the rest lie within one block
+    assert_eq!(fields[0].as_slice(), None);
+    assert_eq!(fields[1].as_slice(), Some(&b"d"[..]));
+    assert_eq!(fields[2].as_slice(), Some(&b""[..]));
+    assert_eq!(fields[3].as_slice(), None);
+    assert_eq!(fields[3].chunks().count(), 2);
+    assert_eq!(fields[4].as_slice(), Some(&b""[..]));
+
+    let (writer, reader) = Stele::new();
+    //Like `slice::split`, nothing to split still yields one empty field
+    assert!(reader
+        .split(b',')
+        .map(|field| field.to_vec())
+        .eq([Vec::new()]));
+    b"no delimiter".iter().for_each(|&b| writer.push(b));
+    assert!(reader
+        .split(b',')
+        .map(|field| field.to_vec())
+        .eq([b"no delimiter".to_vec()]));
+}
+
+#[test]
+fn write_bytes_to() {
+    extern crate std;
+    use alloc::vec::Vec;
+    use std::io::{self, Write};
+
+    //Accepts a few bytes at a time, is interrupted every other call and fails for good after `limit` bytes
+    struct Flaky {
+        written: Vec<u8>,
+        interrupt: bool,
+        limit: usize,
+    }
+
+    impl Write for Flaky {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.interrupt = !self.interrupt;
+            if !self.interrupt {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            if self.written.len() >= self.limit {
+                return Err(io::Error::other("disk full"));
+            }
+            let len = buf.len().min(3).min(self.limit - self.written.len());
+            self.written.extend_from_slice(&buf[..len]);
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let (writer, reader) = Stele::new();
+    //Ends partway into the ninth block
+    let bytes = (0..200_u32).map(|n| (n % 251) as u8).collect::<Vec<_>>();
+    for &b in &bytes {
+        writer.push(b);
+    }
+
+    let mut sink = Vec::new();
+    assert_eq!(reader.write_all_to(&mut sink).unwrap(), 200);
+    assert_eq!(sink, bytes);
+    let mut sink = Vec::new();
+    assert_eq!(reader.write_range_to(5..130, &mut sink).unwrap(), 125);
+    assert_eq!(sink, bytes[5..130]);
+    let mut sink = Vec::new();
+    assert_eq!(reader.write_range_to(150..1000, &mut sink).unwrap(), 50);
+    assert_eq!(sink, bytes[150..]);
+
+    let mut flaky = Flaky {
+        written: Vec::new(),
+        interrupt: false,
+        limit: usize::MAX,
+    };
+    assert_eq!(reader.write_all_to(&mut flaky).unwrap(), 200);
+    assert_eq!(flaky.written, bytes);
+
+    let mut failing = Flaky {
+        written: Vec::new(),
+        interrupt: false,
+        limit: 70,
+    };
+    let err = reader.write_all_to(&mut failing).unwrap_err();
+    assert_eq!(err.to_string(), "disk full");
+    assert_eq!(failing.written, bytes[..70]);
+}
+
+#[test]
+fn utf8_lines() {
+    use alloc::{borrow::Cow, vec::Vec};
+
+    let (writer, reader) = Stele::new();
+    let push = |bytes: &[u8]| bytes.iter().for_each(|&b| writer.push(b));
+    //Blocks hold [0], [1], [2, 3], [4, 8) and [8, 16), so "ab" crosses blocks and the two bytes of 'é' sit at 7 and 8
+    push(b"ab\r\nxyz\xc3\xa9\nok\n\xffa\ntail");
+    let lines = reader.lines().collect::<Vec<_>>();
+    assert_eq!(lines, ["ab", "xyz\u{e9}", "ok", "\u{fffd}a", "tail"]);
+    assert!(matches!(lines[2], Cow::Borrowed("ok")));
+    assert!(matches!(lines[3], Cow::Owned(_)));
+
+    //The unfinished last line is left for the next tick
+    let mut tail = reader.lines_from(0);
+    assert_eq!(tail.by_ref().count(), 4);
+    assert_eq!(tail.position(), 16);
+    push(b"ing\n");
+    let mut tail = reader.lines_from(tail.position());
+    assert_eq!(tail.next().as_deref(), Some("tailing"));
+    assert_eq!(tail.next(), None);
+    assert_eq!(tail.position(), 24);
+    assert_eq!(reader.lines_from(24).count(), 0);
+
+    //Empty lines and a trailing line ending behave like `str::lines`
+    let text = "first\n\n\r\nlast\n";
+    let (writer, reader) = Stele::new();
+    text.bytes().for_each(|b| writer.push(b));
+    assert!(reader.lines().eq(text.lines()));
+}
+
+#[test]
+fn utf8_chunks() {
+    use crate::{
+        append::iter::{Straddling, Utf8Chunk},
+        GrowthPolicy,
+    };
+    use alloc::{borrow::Cow, string::String, vec::Vec};
+
+    let (writer, reader) = Stele::new();
+    //Blocks hold [0], [1], [2, 4), [4, 8), [8, 16) and [16, 32), so the emoji sits at [6, 10) across a boundary
+    //and the trailing "\xe2\x82" is cut short by the end of the Stele after crossing another one
+    b"abcdef\xf0\x9f\x98\x80gh\xffij\xe2\x82"
+        .iter()
+        .for_each(|&b| writer.push(b));
+    let chunks = reader.utf8_chunks().collect::<Vec<_>>();
+    assert_eq!(
+        chunks[..4],
+        [
+            Utf8Chunk::Valid("a"),
+            Utf8Chunk::Valid("b"),
+            Utf8Chunk::Valid("cd"),
+            Utf8Chunk::Valid("ef")
+        ]
+    );
+    let straddling = |chunk: Utf8Chunk| match chunk {
+        Utf8Chunk::Straddling(seq) => seq,
+        chunk => panic!("{:?} does not cross a block boundary", chunk),
+    };
+    let emoji: Straddling = straddling(chunks[4]);
+    assert_eq!(emoji.as_bytes(), "\u{1f600}".as_bytes());
+    assert_eq!(emoji.to_char(), Some('\u{1f600}'));
+    assert_eq!(
+        chunks[5..8],
+        [
+            Utf8Chunk::Valid("gh"),
+            Utf8Chunk::Invalid(b"\xff"),
+            Utf8Chunk::Valid("ij")
+        ]
+    );
+    let truncated = straddling(chunks[8]);
+    assert_eq!(truncated.as_bytes(), b"\xe2\x82");
+    assert_eq!(truncated.to_char(), None);
+    assert_eq!(chunks.len(), 9);
+    assert_eq!(
+        reader.to_string_lossy(),
+        "abcdef\u{1f600}gh\u{fffd}ij\u{fffd}"
+    );
+
+    //A truncated sequence within the last block is borrowed, and text within one block is not copied
+    let (writer, reader) = Stele::with_growth(GrowthPolicy::uniform(8));
+    b"xyz\xf0\x9f\x98".iter().for_each(|&b| writer.push(b));
+    assert!(reader
+        .utf8_chunks()
+        .eq([Utf8Chunk::Valid("xyz"), Utf8Chunk::Invalid(b"\xf0\x9f\x98")]));
+    let (writer, reader) = Stele::with_growth(GrowthPolicy::uniform(8));
+    "h\u{e9}llo".bytes().for_each(|b| writer.push(b));
+    assert!(matches!(
+        reader.to_string_lossy(),
+        Cow::Borrowed("h\u{e9}llo")
+    ));
+    assert!(matches!(
+        Stele::<u8>::new().1.to_string_lossy(),
+        Cow::Borrowed("")
+    ));
+
+    //Splitting at block boundaries never changes where the replacement characters go
+    let texts: [&[u8]; 5] = [
+        "\u{1f600}\u{e9}\u{20ac}a\u{10ffff}".as_bytes(),
+        b"abc\xe2\x82A\xed\xa0\x80z",
+        b"\xf0\x9f\x98\xf0\x9f\x98\x80\xc3",
+        b"\x80\x80\xc0\xaf\xf4\x90\x80\x80",
+        b"",
+    ];
+    for &text in &texts {
+        for block_len in 1..=5 {
+            let (writer, reader) = Stele::with_growth(GrowthPolicy::uniform(block_len));
+            for &b in text {
+                writer.push(b);
+            }
+            assert_eq!(reader.to_string_lossy(), String::from_utf8_lossy(text));
+            let bytes = reader
+                .utf8_chunks()
+                .flat_map(|chunk| match chunk {
+                    Utf8Chunk::Valid(valid) => valid.as_bytes().to_vec(),
+                    Utf8Chunk::Invalid(invalid) => invalid.to_vec(),
+                    Utf8Chunk::Straddling(seq) => seq.as_bytes().to_vec(),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(bytes, text);
+        }
+    }
+}
+
+#[test]
+fn flat_records() {
+    use alloc::vec::Vec;
+    use core::convert::TryFrom;
+
+    fn check<const N: usize>() {
+        let (writer, reader) = Stele::<[u32; N]>::new();
+        //Enough records to span several blocks
+        let scalars = (0..u32::try_from(N * 100).unwrap()).collect::<Vec<_>>();
+        writer.push_flat(&scalars).unwrap();
+        assert_eq!(reader.len(), 100);
+        assert_eq!(reader.flat_len(), N * 100);
+        let nested = reader
+            .iter()
+            .flat_map(|record| record.iter())
+            .collect::<Vec<_>>();
+        assert!(reader.iter_flat().eq(nested.iter().copied()));
+        assert_eq!(reader.iter_flat().len(), nested.len());
+        assert_eq!(
+            reader.iter_flat().copied().sum::<u32>(),
+            scalars.iter().sum()
+        );
+        let mut iter = reader.iter_flat();
+        iter.nth(N + 1);
+        assert_eq!(iter.len(), (N * 100).saturating_sub(N + 2));
+        assert!(iter.copied().eq(scalars.iter().copied().skip(N + 2)));
+        for (i, &scalar) in scalars.iter().enumerate() {
+            assert_eq!(reader.flat_get(i), scalar);
+            assert_eq!(*reader.flat_read(i), reader.read(i / N)[i % N]);
+        }
+        if N > 1 {
+            assert_eq!(
+                writer.push_flat(&scalars[1..]),
+                Err(crate::SteleError::RaggedLength {
+                    len: N * 100 - 1,
+                    record_len: N
+                })
+            );
+            assert_eq!(reader.len(), 100);
+        }
+    }
+
+    check::<1>();
+    check::<3>();
+    check::<8>();
+    check::<13>();
+
+    let (writer, reader) = Stele::<[u8; 0]>::new();
+    assert_eq!(writer.push_flat(&[]), Ok(()));
+    assert_eq!(
+        writer.push_flat(&[1]),
+        Err(crate::SteleError::RaggedLength {
+            len: 1,
+            record_len: 0
+        })
+    );
+    assert_eq!(reader.flat_len(), 0);
+    assert_eq!(reader.iter_flat().count(), 0);
+
+    //Bounded Steles push every record or none of them
+    let (writer, reader) = Stele::<[u8; 2]>::bounded(3);
+    writer.push_flat(&[1, 2, 3, 4]).unwrap();
+    assert_eq!(
+        writer.push_flat(&[5, 6, 7, 8]),
+        Err(crate::SteleError::CapacityExceeded {
+            requested: 4,
+            max: 3
+        })
+    );
+    assert!(reader.iter_flat().eq(&[1, 2, 3, 4]));
+
+    //The length is published once for the whole batch
+    let notify = MockNotify::default();
+    let notifications = alloc::sync::Arc::clone(&notify.notifications);
+    let mut s = core::iter::empty().collect::<Stele<[u32; 3]>>();
+    s.set_notifier(notify);
+    let (writer, reader) = s.to_handles();
+    let scalars = (0..300).collect::<Vec<_>>();
+    writer.push_flat(&scalars).unwrap();
+    assert_eq!(notifications.load(core::sync::atomic::Ordering::Relaxed), 1);
+    assert!(reader.iter_flat().eq(&scalars));
+}
+
+#[test]
+#[should_panic(expected = "Flat index 6 is out of bounds for a Stele with 6 scalars")]
+fn flat_read_out_of_bounds() {
+    let (writer, reader) = Stele::<[u8; 3]>::new();
+    writer.push_flat(&[1, 2, 3, 4, 5, 6]).unwrap();
+    let _ = reader.flat_read(6);
+}
+
+#[test]
+fn pinned_self_referential() {
+    use core::{
+        marker::PhantomPinned,
+        pin::Pin,
+        ptr,
+        sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+    };
+
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    //Remembers its own address once pinned, which is only sound if it never moves afterwards
+    struct SelfRef {
+        this: AtomicPtr<SelfRef>,
+        _pinned: PhantomPinned,
+    }
+
+    impl SelfRef {
+        fn bind(self: Pin<&Self>) {
+            self.this
+                .store(ptr::from_ref(self.get_ref()).cast_mut(), Ordering::Relaxed);
+        }
+
+        fn is_bound(&self) -> bool {
+            ptr::eq(self.this.load(Ordering::Relaxed), self)
+        }
+    }
+
+    impl Drop for SelfRef {
+        fn drop(&mut self) {
+            assert!(
+                self.is_bound(),
+                "Dropped somewhere other than where it was pinned"
+            );
+            DROPPED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let (writer, reader) = Stele::new();
+    for _ in 0..10 {
+        writer
+            .push_pinned(SelfRef {
+                this: AtomicPtr::new(ptr::null_mut()),
+                _pinned: PhantomPinned,
+            })
+            .bind();
+    }
+    assert!(reader.read_pinned(10).is_none());
+    //Later pushes allocate new blocks without moving the elements in the earlier ones
+    for idx in 10..100 {
+        writer.push(SelfRef {
+            this: AtomicPtr::new(ptr::null_mut()),
+            _pinned: PhantomPinned,
+        });
+        reader.read_pinned(idx).unwrap().bind();
+    }
+    assert!(reader.iter().all(SelfRef::is_bound));
+    drop(writer);
+    drop(reader);
+    assert_eq!(DROPPED.load(Ordering::Relaxed), 100);
+}
+
+#[test]
+fn address_of() {
+    use core::ptr::{self, NonNull};
+
+    //An intrusive list threaded backwards through the elements, each linking to the one pushed before it
+    struct Node {
+        val: usize,
+        prev: Option<NonNull<Node>>,
+    }
+
+    let (wh, rh) = Stele::new();
+    let mut addresses = Vec::new();
+    for val in 0..100_usize {
+        let prev = val.checked_sub(1).and_then(|idx| wh.address_of(idx));
+        wh.push(Node { val, prev });
+        addresses.push(rh.address_of(val).unwrap());
+    }
+    assert!(rh.address_of(100).is_none());
+    //The addresses taken before later blocks were allocated still point at the same elements
+    for (idx, &addr) in addresses.iter().enumerate() {
+        assert!(ptr::eq(addr.as_ptr(), rh.read(idx)));
+        assert_eq!(wh.address_of(idx), Some(addr));
+    }
+    let mut sum = 0;
+    let mut node = addresses.last().copied();
+    while let Some(ptr) = node {
+        //SAFETY: Every link was returned by address_of on a handle to this Stele
+        let current = unsafe { rh.element_at(ptr) };
+        sum += current.val;
+        node = current.prev;
+    }
+    assert_eq!(sum, (0..100).sum());
+    //SAFETY: As above
+    assert_eq!(unsafe { wh.element_at(addresses[42]) }.val, 42);
+}
+
+#[test]
+fn address_of_recycle() {
+    use alloc::string::String;
+
+    let (mut wh, rh) = Stele::<String>::new();
+    wh.push(String::from("first"));
+    let before = wh.address_of(0).unwrap();
+    //SAFETY: The address was just taken from this Stele, which has not been recycled since
+    assert_eq!(unsafe { rh.element_at(before) }, "first");
+    drop(rh);
+    //Recycling drops the element behind `before`, so it must not be passed to `element_at` again,
+    //but the block is kept and the next element pushed lives at the same address
+    assert!(wh.try_recycle());
+    wh.push(String::from("second"));
+    let after = wh.address_of(0).unwrap();
+    assert_eq!(after, before);
+    //SAFETY: The address was taken after the Stele was recycled
+    assert_eq!(unsafe { wh.element_at(after) }, "second");
+}
+
+#[test]
+fn assumed_len() {
+    use alloc::vec::Vec;
+
+    let (writer, reader) = Stele::new();
+    for n in 0..100_u32 {
+        writer.push(n);
+    }
+    let n = reader.len();
+    //SAFETY: Every element below the length just observed was pushed
+    let view = unsafe { reader.assume_len(n) };
+    assert_eq!(view.len(), n);
+    assert_eq!(view.get(n - 1), 99);
+    assert_eq!(*view.read(0), 0);
+    //Pushes after the length was observed are not part of the view
+    writer.push(100);
+    assert!(view.iter().eq(reader.iter_range(..n)));
+    assert_eq!(view.iter().copied().sum::<u32>(), (0..100).sum());
+    assert_eq!((&view).into_iter().count(), n);
+    //The safe APIs still see everything and still check their bounds
+    assert_eq!(reader.len(), 101);
+    assert_eq!(reader.try_read(100), Some(&100));
+    assert_eq!(reader.try_read(101), None);
+    assert_eq!(
+        reader.iter().copied().collect::<Vec<_>>(),
+        (0..=100).collect::<Vec<_>>()
+    );
+
+    //SAFETY: Nothing is assumed to be initialized
+    let empty = unsafe { reader.assume_len(0) };
+    assert!(empty.is_empty());
+    assert_eq!(empty.iter().count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Read index 3 of a view assumed to hold 3 elements")]
+fn assumed_len_out_of_bounds() {
+    let (writer, reader) = Stele::new();
+    for n in 0..10_u8 {
+        writer.push(n);
+    }
+    //SAFETY: More than three elements were pushed
+    let view = unsafe { reader.assume_len(3) };
+    let _ = view.read(3);
+}
+
+#[test]
+fn raw_parts_export() {
+    use crate::{ffi::RawParts, GrowthPolicy};
+    use alloc::vec::Vec;
+
+    //Reads every element the way `stele_locate` in `include/stele.h` does
+    fn read_foreign(parts: &RawParts<u64>) -> Vec<u64> {
+        (0..parts.len)
+            .map(|idx| {
+                let mut offset = idx;
+                for block in &parts.blocks[..parts.block_count] {
+                    if offset < block.len {
+                        //SAFETY: Every slot below `parts.len` is initialized and the Stele is still alive
+                        return unsafe { *block.ptr.add(offset) };
+                    }
+                    offset -= block.len;
+                }
+                unreachable!("Element {} lies past the exported blocks", idx)
+            })
+            .collect()
+    }
+
+    for growth in [
+        GrowthPolicy::default(),
+        GrowthPolicy::doubling(3),
+        GrowthPolicy::uniform(7),
+    ] {
+        let (writer, reader) = Stele::with_growth(growth);
+        let empty = reader.as_raw_parts();
+        assert_eq!((empty.len, empty.block_count), (0, 0));
+        for n in 0..100 {
+            writer.push(n * 3);
+        }
+        let parts = reader.as_raw_parts();
+        assert_eq!(parts.len, 100);
+        assert_eq!(parts.block_count, growth.blocks_for_len(100));
+        assert!(parts.blocks[parts.block_count..]
+            .iter()
+            .all(|block| block.ptr.is_null() && block.len == 0));
+        assert_eq!(
+            read_foreign(&parts),
+            reader.iter().copied().collect::<Vec<_>>()
+        );
+    }
+
+    let (writer, reader) = Stele::new();
+    writer.push(1_u64);
+    let leaked = reader.leak_raw();
+    let before = leaked.as_raw_parts();
+    for n in 2..=20 {
+        writer.push(n);
+    }
+    drop(writer);
+    //The earlier snapshot still points at the same elements, and a new one sees the rest
+    assert_eq!(read_foreign(&before), [1]);
+    assert_eq!(
+        read_foreign(&leaked.as_raw_parts()),
+        (1..=20).collect::<Vec<_>>()
+    );
+    //SAFETY: The leaked reference count is taken back once, after the last use of the leaked handle,
+    //so that Miri's leak check passes
+    drop(unsafe { crate::sync::Arc::from_raw(core::ptr::from_ref(leaked.handle)) });
+}
+
+#[test]
+fn concat_join() {
+    use alloc::{
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    let words = ["alpha", "", "beta", "gamma"];
+    let (writer, reader) = Stele::new();
+    assert_eq!(reader.concat(), "");
+    assert_eq!(reader.join(", "), "");
+    for &word in &words {
+        writer.push(word);
+    }
+    assert_eq!(reader.concat(), words.concat());
+    assert_eq!(reader.join(", "), words.join(", "));
+    assert_eq!(reader.join(""), words.join(""));
+
+    let (writer, reader) = Stele::new();
+    writer.push(String::from("only"));
+    assert_eq!(reader.join(", "), "only");
+    for word in &words {
+        writer.push(word.to_string());
+    }
+    let owned = reader.iter().cloned().collect::<Vec<_>>();
+    assert_eq!(reader.concat(), owned.concat());
+    assert_eq!(reader.join("--"), owned.join("--"));
+
+    let lists = [vec![1, 2], vec![], vec![3]];
+    let (writer, reader) = Stele::new();
+    for list in &lists {
+        writer.push(list.clone());
+    }
+    assert_eq!(reader.concat(), lists.concat());
+    assert_eq!(reader.join(&[0, 0][..]), lists.join(&[0, 0][..]));
+    let joined = reader.join(&[9][..]);
+    assert_eq!(joined.capacity(), joined.len());
+
+    let (writer, reader) = Stele::new();
+    for list in &lists {
+        writer.push(list.as_slice());
+    }
+    assert_eq!(reader.concat(), lists.concat());
+    assert_eq!(reader.join(&[][..]), lists.join(&[][..]));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn append_map() {
+    extern crate std;
+    use crate::map::{AppendMap, Occupied};
+    use alloc::{format, string::String, vec::Vec};
+
+    let map = AppendMap::new();
+    let first = map.insert(String::from("one"), 1).unwrap();
+    assert_eq!(first.index(), 0);
+    assert_eq!(
+        map.insert(String::from("one"), 2),
+        Err(Occupied {
+            key: String::from("one"),
+            value: 2,
+            existing: first
+        })
+    );
+    assert_eq!(map.get("one"), Some(&1));
+    assert_eq!(map.len(), 1);
+
+    let reader = map.reader();
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            let reader = reader.clone();
+            s.spawn(move || {
+                //Every key found must resolve to its entry, whatever the writer is doing meanwhile
+                while reader.len() < 1001 {
+                    let len = reader.len();
+                    let key = format!("{}", len.saturating_sub(2));
+                    if let Some(found) = reader.find(key.as_str()) {
+                        assert_eq!(reader.entry(found), (&key, &len.saturating_sub(2)));
+                    }
+                }
+            });
+        }
+        for n in 0..1000 {
+            map.insert(format!("{n}"), n).unwrap();
+        }
+    });
+    assert_eq!(reader.get("999"), Some(&999));
+    assert!(reader.get("1000").is_none());
+
+    //Iteration follows insertion order, however the keys hash
+    let keys = reader.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>();
+    assert_eq!(keys[0], "one");
+    assert!(keys[1..]
+        .iter()
+        .cloned()
+        .eq((0..1000).map(|n| format!("{n}"))));
+    assert!(map.iter().map(|(k, _)| k).eq(keys.iter()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "Read a key past the entries in the map")]
+fn append_map_foreign_key() {
+    use crate::map::AppendMap;
+
+    let small = AppendMap::new();
+    small.insert(0_u32, "zero").unwrap();
+    let large = AppendMap::new();
+    let mut foreign = large.insert(0_u32, "zero").unwrap();
+    for n in 1..100 {
+        foreign = large.insert(n, "many").unwrap();
+    }
+    //A key from another map is checked against the entries of this one in every build
+    let _ = small.reader().entry(foreign);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn parallel_bulk_fill() {
+    use crate::append::bulk::BlockWriter;
+    use core::sync::atomic::Ordering;
+    use rayon::prelude::*;
+
+    let notify = MockNotify::default();
+    let notifications = alloc::sync::Arc::clone(&notify.notifications);
+    let mut s = (0..3_usize).collect::<Stele<_>>();
+    s.set_notifier(notify);
+    let (mut wh, rh) = s.to_handles();
+    let mut writers = wh.reserve_blocks(10_000);
+    //Every writer lies within one block and together they cover the reservation in order
+    assert!(writers.len() > 1);
+    assert_eq!(writers[0].range().start, 3);
+    assert!(writers
+        .windows(2)
+        .all(|w| w[0].range().end == w[1].range().start));
+    assert_eq!(writers.last().unwrap().range().end, 10_003);
+    writers.par_iter_mut().for_each(|writer| {
+        let range = writer.range();
+        if writer.len() > 1 {
+            //Write the first element twice, which replaces the first value
+            writer.write(0, usize::MAX);
+            assert_eq!(writer.fill_from_iter(range.clone()), range.len());
+        } else {
+            writer.write(0, range.start);
+        }
+        assert!(writer.is_finished());
+    });
+    //Nothing is visible until the writers are published
+    assert_eq!(rh.len(), 3);
+    assert_eq!(notifications.load(Ordering::Relaxed), 0);
+    writers.reverse();
+    assert_eq!(BlockWriter::publish(writers), Ok(()));
+    assert_eq!(notifications.load(Ordering::Relaxed), 1);
+    assert_eq!(rh.len(), 10_003);
+    assert_eq!(rh.initialized_len(), 10_003);
+    assert!(rh.iter().copied().eq(0..10_003));
+    wh.push(10_003);
+    assert_eq!(rh.len(), 10_004);
+    assert!(wh.reserve_blocks(0).is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn unfinished_bulk_fill() {
+    extern crate std;
+    use crate::{append::bulk::BlockWriter, SteleError};
+    use core::sync::atomic::AtomicUsize;
+
+    let drops = AtomicUsize::new(0);
+    let (mut wh, rh) = Stele::new();
+    wh.push(DropCounter(&drops));
+    let slot = wh.push_uninit();
+
+    //A writer that is dropped before finishing, here by panicking, stops the rest from being published
+    let writers = wh.reserve_blocks(100);
+    let count = writers.len();
+    let counter = &drops;
+    let writers = std::thread::scope(|s| {
+        writers
+            .into_iter()
+            .enumerate()
+            .map(|(n, mut writer)| {
+                s.spawn(move || {
+                    let len = writer.len();
+                    writer.fill_from_iter((0..len).map(|_| DropCounter(counter)));
+                    assert_ne!(n, count - 1, "The last writer fails");
+                    writer
+                })
+            })
+            .collect::<alloc::vec::Vec<_>>()
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .collect::<alloc::vec::Vec<_>>()
+    });
+    assert_eq!(writers.len(), count - 1);
+    let written = writers.iter().map(BlockWriter::len).sum::<usize>();
+    assert_eq!(
+        BlockWriter::publish(writers),
+        Err(SteleError::Unfilled {
+            filled: written,
+            reserved: 100
+        })
+    );
+    //Every element that was written has been dropped and none were published
+    assert_eq!(drops.load(core::sync::atomic::Ordering::Relaxed), 100);
+    assert_eq!(rh.len(), 2);
+
+    //Unfinished writers are not published either, and the reservation can be made again
+    let mut writers = wh.reserve_blocks(3);
+    writers[0].write(0, DropCounter(&drops));
+    assert!(!writers[0].is_finished());
+    assert!(matches!(
+        BlockWriter::publish(writers),
+        Err(SteleError::Unfilled { filled: 1, .. })
+    ));
+    let mut writers = wh.reserve_blocks(3);
+    for writer in &mut writers {
+        let len = writer.len();
+        writer.fill_from_iter((0..len).map(|_| DropCounter(&drops)));
+    }
+    assert_eq!(BlockWriter::publish(writers), Ok(()));
+    //Published elements in a block with a pending reservation are readable, while the reservation itself is still empty
+    assert_eq!(rh.len(), 5);
+    assert!(rh.try_read(1).is_none());
+    assert!(rh.try_read(2).is_some());
+    slot.fill(DropCounter(&drops));
+    assert_eq!(rh.initialized_len(), 5);
+    drop((wh, rh));
+    assert_eq!(drops.load(core::sync::atomic::Ordering::Relaxed), 106);
+}
+
+#[test]
+fn spare_blocks() {
+    let (mut wh, rh) = Stele::<u8>::new();
+    wh.push(0);
+    let spare = wh.spare_blocks_mut(20);
+    assert_eq!((spare.start(), spare.len()), (1, 20));
+    //The slots after the first element run through blocks of 1, 2, 4, 8 and 16 elements
+    let mut written = 0;
+    for slots in spare {
+        //Written through raw pointers, the way a foreign function would fill a buffer
+        let ptr = slots.as_mut_ptr().cast::<u8>();
+        for offset in 0..slots.len() {
+            //SAFETY: `offset` is within the slice
+            unsafe { ptr.add(offset).write(1 + written) };
+            written += 1;
+        }
+    }
+    assert_eq!(written, 20);
+    assert_eq!(rh.len(), 1);
+    //Only the first 12 are published, leaving the rest as spare slots again
+    //SAFETY: Every slot up to the 20th was initialized above
+    unsafe { wh.publish_initialized(12) };
+    assert_eq!(rh.len(), 13);
+    assert!(rh.iter().copied().eq(0..13));
+
+    //The blocks are already allocated, and the slots straddle the end of the one holding 8 to 15
+    let capacity = rh.capacity();
+    let spare = wh.spare_blocks_mut(4);
+    assert_eq!(spare.size_hint(), (2, Some(2)));
+    let blocks = spare.collect::<Vec<_>>();
+    assert_eq!(
+        blocks.iter().map(|slots| slots.len()).collect::<Vec<_>>(),
+        [3, 1]
+    );
+    for slots in blocks {
+        for slot in slots {
+            slot.write(u8::MAX);
+        }
+    }
+    assert_eq!(rh.capacity(), capacity);
+    //SAFETY: The 4 slots were initialized above
+    unsafe { wh.publish_initialized(4) };
+    assert_eq!(rh.len(), 17);
+    assert!(rh.iter().skip(13).all(|&val| val == u8::MAX));
+
+    //Spare slots in a block with a pending reservation are tracked like pushed elements once published
+    let (mut wh, rh) = Stele::<u32>::new();
+    (0..2).for_each(|n| wh.push(n));
+    let slot = wh.push_uninit();
+    wh.spare_blocks_mut(1).next().unwrap()[0].write(3);
+    //SAFETY: The slot was initialized above
+    unsafe { wh.publish_initialized(1) };
+    assert_eq!(rh.try_read(2), None);
+    assert_eq!(rh.try_read(3), Some(&3));
+    slot.fill(2);
+    assert!(rh.iter().copied().eq(0..4));
+}
+
+#[test]
+#[should_panic(expected = "Published 9 elements past the end, but only 8 slots are spare")]
+fn publish_beyond_spare() {
+    let (mut wh, _rh) = Stele::<u64>::new();
+    let _ = wh.spare_blocks_mut(8);
+    //SAFETY: Never reached, as there are not enough slots
+    unsafe { wh.publish_initialized(9) };
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn block_pool() {
+    extern crate std;
+    use crate::pool::BlockPool;
+
+    let counter = CountingAllocator::new();
+    let pool = BlockPool::new_in(&counter);
+    let (wh, rh) = Stele::new_in(&pool);
+    for n in 0..100_u32 {
+        wh.push(n);
+    }
+    let blocks = rh.block_count();
+    let bytes = rh.allocated_bytes();
+    assert_eq!(counter.allocations(), blocks);
+    drop((wh, rh));
+    //The blocks went back to the pool instead of the allocator
+    assert_eq!(counter.deallocations(), 0);
+    assert_eq!(pool.pooled_blocks(), blocks);
+    assert_eq!(pool.pooled_bytes(), bytes);
+
+    //Steles with the same element size reuse every block, even from several threads at once
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..50 {
+                    let (wh, rh) = Stele::new_in(&pool);
+                    for n in 0..100_u32 {
+                        wh.push(n);
+                    }
+                    assert!(rh.iter().copied().eq(0..100));
+                }
+            });
+        }
+    });
+    assert!(counter.allocations() <= blocks * 4);
+    assert_eq!(counter.deallocations(), 0);
+    assert_eq!(counter.live_bytes(), pool.pooled_bytes());
+
+    //Blocks of another size come from the allocator and join the pool in their own size class
+    let (wh, rh) = Stele::new_in(&pool);
+    wh.push(0_u64);
+    let allocated = counter.allocations();
+    drop((wh, rh));
+    let (wh, rh) = Stele::new_in(&pool);
+    wh.push(0_u64);
+    assert_eq!(counter.allocations(), allocated);
+    drop((wh, rh));
+
+    pool.trim();
+    assert_eq!(pool.pooled_blocks(), 0);
+    assert_eq!(pool.pooled_bytes(), 0);
+    counter.assert_empty();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn interner() {
+    extern crate std;
+    use crate::intern::{Interner, Symbol};
+    use alloc::{format, vec::Vec};
+    use core::convert::TryFrom;
+
+    let mut interner = Interner::new();
+    //"abc" does not fit in the first three blocks, so it is moved to block 3 which starts at byte 4
+    let abc = interner.intern("abc");
+    assert_eq!(interner.resolve(abc), "abc");
+    assert_eq!(interner.byte_len(), 7);
+    //Only one byte of block 3 is left, so "defgh" moves on to block 4
+    let defgh = interner.intern("defgh");
+    assert_eq!(interner.resolve(defgh), "defgh");
+    assert_eq!(interner.intern("abc"), abc);
+    assert_eq!(interner.intern("defgh"), defgh);
+    let empty = interner.intern("");
+    assert_eq!(interner.intern(""), empty);
+    assert_eq!(interner.resolve(empty), "");
+    assert_eq!(interner.len(), 3);
+    assert_eq!(Symbol::from_u32(defgh.as_u32()), defgh);
+
+    let resolver = interner.resolver();
+    assert!(resolver.try_resolve(Symbol::from_u32(3)).is_none());
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            let resolver = resolver.clone();
+            s.spawn(move || {
+                while resolver.len() < 503 {
+                    //Symbols 3 and up are "word0", "word1" and so on
+                    let newest = resolver.len() - 1;
+                    if newest >= 3 {
+                        let symbol = Symbol::from_u32(u32::try_from(newest).unwrap());
+                        assert_eq!(resolver.resolve(symbol), format!("word{}", newest - 3));
+                    }
+                }
+            });
+        }
+        let symbols = (0..500)
+            .map(|n| interner.intern(&format!("word{n}")))
+            .collect::<Vec<_>>();
+        //Interning again only finds the existing symbols
+        assert!((0..500)
+            .map(|n| interner.intern(&format!("word{n}")))
+            .eq(symbols));
+    });
+    assert_eq!(resolver.resolve(abc), "abc");
+
+    #[cfg(feature = "serde")]
+    {
+        let bytes = postcard::to_allocvec(&defgh).unwrap();
+        assert_eq!(postcard::from_bytes::<Symbol>(&bytes).unwrap(), defgh);
+    }
+}
+
+#[test]
+fn error_messages() {
+    use crate::{PushError, SteleError};
+    use alloc::string::ToString;
+    use core::error::Error;
+
+    let cases = [
+        (
+            SteleError::AllocationFailed {
+                layout: Layout::new::<[u64; 4]>(),
+            },
+            "failed to allocate 32 bytes aligned to 8",
+        ),
+        (
+            SteleError::CapacityExceeded {
+                requested: 5,
+                max: 4,
+            },
+            "room for 5 elements was needed but the Stele can hold 4",
+        ),
+        (
+            SteleError::OutOfBounds { index: 3, len: 2 },
+            "index 3 is out of bounds for a Stele of length 2",
+        ),
+        (
+            SteleError::MalformedRecord { offset: 9 },
+            "the bytes at offset 9 do not hold a complete record",
+        ),
+        (
+            SteleError::RaggedLength {
+                len: 7,
+                record_len: 3,
+            },
+            "7 values do not split into whole records of 3",
+        ),
+        (
+            SteleError::SerializeFailed { index: 4 },
+            "the element at index 4 could not be serialized",
+        ),
+        (
+            SteleError::InvalidOptions {
+                reason: "`align` must be a power of two",
+            },
+            "invalid Stele options: `align` must be a power of two",
+        ),
+        (SteleError::WriterExists, "the Stele already has a writer"),
+        (SteleError::WouldBlock, "the operation would block"),
+        (
+            SteleError::TimedOut { next: 6 },
+            "timed out waiting for a match after checking every element before index 6",
+        ),
+    ];
+    for (error, msg) in cases {
+        assert_eq!(error.to_string(), msg);
+        assert!(error.source().is_none());
+    }
+
+    //A PushError hands back the value and points at the reason through `source`
+    let (wh, _rh) = Stele::bounded(0);
+    let err: PushError<u32> = wh.push_within_capacity(7).unwrap_err();
+    assert_eq!(err.to_string(), "failed to push to the Stele");
+    let source = err.source().unwrap();
+    assert_eq!(
+        source.to_string(),
+        "room for 1 elements was needed but the Stele can hold 0"
+    );
+    assert_eq!(
+        source.downcast_ref::<SteleError>(),
+        Some(&SteleError::CapacityExceeded {
+            requested: 1,
+            max: 0
+        })
+    );
+    assert!(source.source().is_none());
+    assert_eq!(err.into_inner(), 7);
+
+    let full: &dyn Error = &crate::Full(1);
+    assert_eq!(full.to_string(), "the Stele is full");
+}
+
+#[cfg(all(feature = "bytemuck", not(any(loom, feature = "loom", shuttle))))]
+#[test]
+fn transparent_cast() {
+    use alloc::{string::String, sync::Arc};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, PartialEq)]
+    #[repr(transparent)]
+    struct Meters(f64);
+    //SAFETY: `Meters` is transparent over `f64`
+    unsafe impl bytemuck::TransparentWrapper<f64> for Meters {}
+
+    #[repr(transparent)]
+    struct Name(String);
+    //SAFETY: `Name` is transparent over `String`
+    unsafe impl bytemuck::TransparentWrapper<String> for Name {}
+
+    //Pushes through either view of the writer are read through either view of the readers
+    let (wh, rh) = Stele::<f64>::new();
+    for n in 0..100_u32 {
+        if n % 2 == 0 {
+            wh.push(f64::from(n));
+        } else {
+            wh.cast_ref::<Meters>().push(Meters(f64::from(n)));
+        }
+    }
+    let meters = rh.clone().cast::<Meters>();
+    assert_eq!(meters.len(), 100);
+    assert_eq!(meters.read(7), &Meters(7.0));
+    assert!(rh.iter().copied().eq((0..100_u32).map(f64::from)));
+    assert!(rh
+        .cast_ref::<Meters>()
+        .iter()
+        .map(|m| m.0)
+        .eq(rh.iter().copied()));
+
+    //Casting the writer hands it over rather than closing the Stele, and peeling hands it back
+    let wh = wh.cast::<Meters>();
+    wh.push(Meters(100.0));
+    let wh = wh.peel::<f64>();
+    wh.push(101.0);
+    assert_eq!(meters.read(101), &Meters(101.0));
+    assert!(meters
+        .peel_ref::<f64>()
+        .iter()
+        .copied()
+        .eq((0..102_u32).map(f64::from)));
+    drop(wh);
+    let rh = meters.peel::<f64>();
+    assert_eq!(rh.len(), 102);
+
+    //Elements that own memory are dropped exactly once whichever type the last handle has,
+    //and the drop hook is called with the type it was set through
+    let hooked = Arc::new(AtomicUsize::new(0));
+    let (wh, rh) = Stele::<String>::new();
+    let counter = Arc::clone(&hooked);
+    wh.cast_ref::<Name>().set_drop_hook(move |name: &Name| {
+        counter.fetch_add(name.0.len(), Ordering::Relaxed);
+    });
+    for word in ["granite", "basalt", "slate"] {
+        wh.cast_ref::<Name>().push(Name(String::from(word)));
+    }
+    wh.push(String::from("marble"));
+    assert_eq!(rh.read(0), "granite");
+    let names = rh.cast::<Name>();
+    drop(wh);
+    assert_eq!(names.read(3).0, "marble");
+    drop(names);
+    assert_eq!(hooked.load(Ordering::Relaxed), 24);
 }
@@ -0,0 +1,165 @@
+extern crate std;
+
+use alloc::{alloc::Layout, vec::Vec};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+use crate::mem::{AllocError, Allocator, Global};
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+use crate::mem::{GlobalStorage, Storage};
+
+/// An allocator that forwards to the global allocator while keeping track of every allocation made through it
+///
+/// It can be passed to [`Stele::new_in`](crate::Stele::new_in) either by value or, to inspect it after the Stele is gone, by reference.
+/// Freeing memory it did not hand out, freeing it twice or freeing it with a different layout panics.
+///
+/// ```
+/// use stele::{testing::CountingAllocator, Stele};
+///
+/// let counter = CountingAllocator::new();
+/// let (wh, rh) = Stele::new_in(&counter);
+/// wh.push(42_u32);
+/// assert_eq!(counter.live_allocations(), rh.block_count());
+/// drop((wh, rh));
+/// counter.assert_empty();
+/// ```
+#[derive(Debug, Default)]
+pub struct CountingAllocator {
+    allocations: AtomicUsize,
+    deallocations: AtomicUsize,
+    total_bytes: AtomicUsize,
+    layouts: Mutex<Vec<Layout>>,
+    live: Mutex<Vec<(usize, Layout)>>,
+}
+
+impl CountingAllocator {
+    /// Creates a new `CountingAllocator` that has not allocated anything
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of allocations made so far
+    pub fn allocations(&self) -> usize {
+        self.allocations.load(Ordering::Acquire)
+    }
+
+    /// The number of allocations freed so far
+    pub fn deallocations(&self) -> usize {
+        self.deallocations.load(Ordering::Acquire)
+    }
+
+    /// The number of allocations that have not been freed yet
+    ///
+    /// # Panics
+    /// Panics if a thread panicked while updating the allocator
+    pub fn live_allocations(&self) -> usize {
+        self.live.lock().unwrap().len()
+    }
+
+    /// The number of bytes that have been allocated and not freed yet
+    ///
+    /// # Panics
+    /// Panics if a thread panicked while updating the allocator
+    pub fn live_bytes(&self) -> usize {
+        self.live
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, layout)| layout.size())
+            .sum()
+    }
+
+    /// The number of bytes allocated so far, including those that have been freed since
+    pub fn total_bytes(&self) -> usize {
+        self.total_bytes.load(Ordering::Acquire)
+    }
+
+    /// The layout of every allocation made so far, in the order they were made
+    ///
+    /// # Panics
+    /// Panics if a thread panicked while updating the allocator
+    pub fn layouts(&self) -> Vec<Layout> {
+        self.layouts.lock().unwrap().clone()
+    }
+
+    /// Asserts that every allocation made through this allocator has been freed
+    ///
+    /// # Panics
+    /// Panics if any allocation is still live
+    pub fn assert_empty(&self) {
+        //The lock is released before asserting so that a failure does not poison it for the frees that follow
+        let (count, bytes) = (self.live_allocations(), self.live_bytes());
+        assert!(
+            count == 0,
+            "{} allocations totalling {} bytes were never freed",
+            count,
+            bytes
+        );
+    }
+
+    //Also used by the tests to hand the allocator memory the global allocator made elsewhere, such as a Vec's buffer
+    pub(crate) fn record_allocation(&self, ptr: *mut u8, layout: Layout) {
+        if ptr.is_null() {
+            return;
+        }
+        self.live.lock().unwrap().push((ptr as usize, layout));
+        self.layouts.lock().unwrap().push(layout);
+        self.total_bytes.fetch_add(layout.size(), Ordering::AcqRel);
+        self.allocations.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn record_deallocation(&self, ptr: *mut u8, layout: Layout) {
+        let mut live = self.live.lock().unwrap();
+        let pos = live
+            .iter()
+            .position(|&(live_ptr, _)| live_ptr == ptr as usize)
+            .expect("Freed memory that is not live, either because it was already freed or was never allocated here");
+        let (_, live_layout) = live.swap_remove(pos);
+        assert_eq!(
+            live_layout, layout,
+            "Freed memory with a different layout than it was allocated with"
+        );
+        self.deallocations.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
+unsafe impl Allocator for CountingAllocator {
+    fn allocate(&self, layout: Layout) -> Result<core::ptr::NonNull<[u8]>, AllocError> {
+        let ptr = Global.allocate(layout)?;
+        self.record_allocation(ptr.as_ptr().cast(), layout);
+        Ok(ptr)
+    }
+
+    unsafe fn deallocate(&self, ptr: core::ptr::NonNull<u8>, layout: Layout) {
+        self.record_deallocation(ptr.as_ptr(), layout);
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+impl Storage for CountingAllocator {
+    fn allocate_block(&self, layout: Layout) -> *mut u8 {
+        let ptr = GlobalStorage.allocate_block(layout);
+        self.record_allocation(ptr, layout);
+        ptr
+    }
+
+    unsafe fn deallocate_block(&self, ptr: *mut u8, layout: Layout) {
+        self.record_deallocation(ptr, layout);
+        unsafe { GlobalStorage.deallocate_block(ptr, layout) }
+    }
+}
+
+#[cfg(not(any(feature = "allocator_api", feature = "allocator-api2")))]
+impl Storage for &CountingAllocator {
+    fn allocate_block(&self, layout: Layout) -> *mut u8 {
+        (**self).allocate_block(layout)
+    }
+
+    unsafe fn deallocate_block(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { (**self).deallocate_block(ptr, layout) }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{queue::WorkQueue, Stele};
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn push_and_read() {
+    let (wh, rh) = Stele::new();
+    (0..100).for_each(|n| wh.push(n));
+    assert_eq!(rh.len(), 100);
+    assert_eq!(rh.read(99), &99);
+    assert_eq!(rh.try_read(100), None);
+    let slot = wh.push_uninit();
+    assert_eq!(rh.initialized_len(), 100);
+    slot.fill(100);
+    assert_eq!(rh.get(100), 100);
+}
+
+#[wasm_bindgen_test]
+fn iterate() {
+    let (wh, rh) = Stele::new();
+    (0..1000).for_each(|n| wh.push(n));
+    assert!(rh.iter().copied().eq(0..1000));
+    for (idx, val) in (&rh).into_iter().enumerate() {
+        assert_eq!(idx, *val);
+    }
+    assert!(rh.into_iter().eq(0..1000));
+    assert_eq!(wh.new_read_handle().iter().nth(500), Some(&500));
+}
+
+#[wasm_bindgen_test]
+fn static_stele() {
+    static STELE: Stele<u32> = Stele::const_new();
+    let wh = STELE.claim_writer().unwrap();
+    assert!(STELE.claim_writer().is_err());
+    (0..10).for_each(|n| wh.push(n));
+    assert!(STELE.reader().iter().copied().eq(0..10));
+}
+
+#[wasm_bindgen_test]
+fn work_queue() {
+    //With a single thread nothing else can push, so an empty queue has to report it instead of waiting
+    let (wh, rh) = Stele::new();
+    let queue = WorkQueue::new(rh);
+    assert!(queue.claim().is_none());
+    (0..10).for_each(|n| wh.push(n));
+    assert_eq!(queue.steal_batch(4).indices(), 0..4);
+    assert_eq!(queue.claim(), Some((4, &4)));
+    assert_eq!(queue.unclaimed(), 5);
+}
+
+#[wasm_bindgen_test]
+fn promote() {
+    let (wh, rh) = Stele::new();
+    wh.push(0);
+    let rh = rh.try_promote().unwrap_err();
+    drop(wh);
+    let wh = rh.try_promote().unwrap();
+    wh.push(1);
+    assert_eq!(wh.len(), 2);
+}
+
+#[cfg(feature = "std")]
+#[wasm_bindgen_test]
+fn channel() {
+    use crate::channel::{broadcast, TryRecvError};
+
+    let (tx, rx) = broadcast();
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+    tx.send(1);
+    assert_eq!(rx.try_recv(), Ok(&1));
+    drop(tx);
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[cfg(feature = "wasm-singlethread")]
+#[wasm_bindgen_test]
+fn singlethread_backend() {
+    //The handles must be reference counted without atomics
+    let (wh, rh) = Stele::<u32>::new();
+    let _: &alloc::rc::Rc<Stele<u32>> = &rh.handle;
+    let _: &alloc::rc::Rc<Stele<u32>> = &wh.handle;
+}